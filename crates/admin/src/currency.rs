@@ -6,6 +6,7 @@ use app_108jobs_db::{
     currency::{Currency, CurrencyInsertForm, CurrencyUpdateForm},
     currency_rate_history::{CurrencyRateHistory, CurrencyRateHistoryInsertForm},
     pricing_config::{PricingConfig, PricingConfigInsertForm, PricingConfigUpdateForm},
+    pricing_rule::{PricingRule, PricingRuleInsertForm, PricingRuleUpdateForm},
   },
   traits::Crud,
 };
@@ -13,20 +14,28 @@ use app_108jobs_db_views_currency::{
   api::{
     CreateCurrencyRequest,
     CreatePricingConfigRequest,
+    CreatePricingRuleRequest,
     CurrencyListResponse,
     CurrencyResponse,
+    DeletePricingRule,
     GetCurrency,
     GetPricingConfig,
     ListPricingConfigs,
+    ListPricingRules,
     PricingConfigListResponse,
     PricingConfigResponse,
+    PricingRuleListResponse,
+    PricingRuleResponse,
     UpdateCurrencyRequest,
     UpdatePricingConfigRequest,
+    UpdatePricingRuleRequest,
   },
+  validator::validate_pricing_rule,
   CurrencyView,
   PricingConfigView,
 };
 use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_site::api::SuccessResponse;
 use chrono::Utc;
 
 // ============================================================================
@@ -256,6 +265,119 @@ pub async fn admin_update_pricing_config(
   }))
 }
 
+// ============================================================================
+// Pricing Rule Admin Endpoints
+// ============================================================================
+
+pub async fn admin_list_pricing_rules(
+  data: Json<ListPricingRules>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<PricingRuleListResponse>> {
+  is_admin(&local_user_view)?;
+
+  let pricing_rules =
+    PricingRule::list_for_config(&mut context.pool(), data.pricing_config_id).await?;
+
+  Ok(Json(PricingRuleListResponse { pricing_rules }))
+}
+
+pub async fn admin_create_pricing_rule(
+  data: Json<CreatePricingRuleRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<PricingRuleResponse>> {
+  is_admin(&local_user_view)?;
+
+  // Verify pricing config exists
+  let _config = PricingConfig::read(&mut context.pool(), data.pricing_config_id).await?;
+
+  let form = PricingRuleInsertForm {
+    pricing_config_id: data.pricing_config_id,
+    name: data.name.clone(),
+    kind: data.kind,
+    multiplier: data.multiplier,
+    start_minute: data.start_minute,
+    end_minute: data.end_minute,
+    days_of_week: data.days_of_week.clone(),
+    holiday_date: data.holiday_date,
+    zone_lat: data.zone_lat,
+    zone_lng: data.zone_lng,
+    zone_radius_km: data.zone_radius_km,
+    demand_ratio_threshold: data.demand_ratio_threshold,
+    is_active: data.is_active,
+    service_area_id: data.service_area_id,
+  };
+  validate_pricing_rule(&form)?;
+
+  let pricing_rule = PricingRule::create(&mut context.pool(), &form).await?;
+
+  Ok(Json(PricingRuleResponse { pricing_rule }))
+}
+
+pub async fn admin_update_pricing_rule(
+  data: Json<UpdatePricingRuleRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<PricingRuleResponse>> {
+  is_admin(&local_user_view)?;
+
+  let existing = PricingRule::read(&mut context.pool(), data.rule_id).await?;
+
+  // Validate the rule as it will look after the update
+  let merged = PricingRuleInsertForm {
+    pricing_config_id: existing.pricing_config_id,
+    name: data.name.clone().unwrap_or(existing.name),
+    kind: existing.kind,
+    multiplier: data.multiplier.unwrap_or(existing.multiplier),
+    start_minute: data.start_minute.or(existing.start_minute),
+    end_minute: data.end_minute.or(existing.end_minute),
+    days_of_week: data.days_of_week.clone().or(existing.days_of_week),
+    holiday_date: data.holiday_date.or(existing.holiday_date),
+    zone_lat: data.zone_lat.or(existing.zone_lat),
+    zone_lng: data.zone_lng.or(existing.zone_lng),
+    zone_radius_km: data.zone_radius_km.or(existing.zone_radius_km),
+    demand_ratio_threshold: data
+      .demand_ratio_threshold
+      .or(existing.demand_ratio_threshold),
+    is_active: Some(data.is_active.unwrap_or(existing.is_active)),
+    service_area_id: data.service_area_id.or(existing.service_area_id),
+  };
+  validate_pricing_rule(&merged)?;
+
+  let form = PricingRuleUpdateForm {
+    name: data.name.clone(),
+    multiplier: data.multiplier,
+    start_minute: data.start_minute.map(Some),
+    end_minute: data.end_minute.map(Some),
+    days_of_week: data.days_of_week.clone().map(Some),
+    holiday_date: data.holiday_date.map(Some),
+    zone_lat: data.zone_lat.map(Some),
+    zone_lng: data.zone_lng.map(Some),
+    zone_radius_km: data.zone_radius_km.map(Some),
+    demand_ratio_threshold: data.demand_ratio_threshold.map(Some),
+    is_active: data.is_active,
+    updated_at: Some(Some(Utc::now())),
+    service_area_id: data.service_area_id.map(Some),
+  };
+
+  let pricing_rule = PricingRule::update(&mut context.pool(), data.rule_id, &form).await?;
+
+  Ok(Json(PricingRuleResponse { pricing_rule }))
+}
+
+pub async fn admin_delete_pricing_rule(
+  data: Json<DeletePricingRule>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  PricingRule::delete(&mut context.pool(), data.rule_id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
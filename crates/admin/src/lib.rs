@@ -1,5 +1,6 @@
 pub mod bank_account;
 pub mod currency;
 pub mod platform;
+pub mod post_boost;
 pub mod site;
 pub mod wallet;
@@ -0,0 +1,75 @@
+use actix_web::web::{Data, Json};
+use app_108jobs_api_utils::{context::FastJobContext, utils::is_admin};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  source::post_boost::{PostBoostPackage, PostBoostPackageInsertForm, PostBoostPackageUpdateForm},
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_post::api::{
+  CreatePostBoostPackage,
+  ListPostBoostPackagesResponse,
+  PostBoostPackageResponse,
+  UpdatePostBoostPackage,
+};
+use chrono::Utc;
+
+pub async fn admin_list_post_boost_packages(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListPostBoostPackagesResponse>> {
+  is_admin(&local_user_view)?;
+
+  let packages = PostBoostPackage::list(&mut context.pool(), false).await?;
+  Ok(Json(ListPostBoostPackagesResponse { packages }))
+}
+
+pub async fn admin_create_post_boost_package(
+  data: Json<CreatePostBoostPackage>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<PostBoostPackageResponse>> {
+  is_admin(&local_user_view)?;
+  validate_package(Some(data.duration_days), Some(data.price_coin))?;
+
+  let mut form = PostBoostPackageInsertForm::new(
+    data.name.trim().to_string(),
+    data.kind,
+    data.duration_days,
+    data.price_coin,
+  );
+  form.is_active = data.is_active;
+
+  let package = PostBoostPackage::create(&mut context.pool(), &form).await?;
+  Ok(Json(PostBoostPackageResponse { package }))
+}
+
+pub async fn admin_update_post_boost_package(
+  data: Json<UpdatePostBoostPackage>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<PostBoostPackageResponse>> {
+  is_admin(&local_user_view)?;
+  validate_package(data.duration_days, data.price_coin)?;
+
+  let form = PostBoostPackageUpdateForm {
+    name: data.name.as_ref().map(|n| n.trim().to_string()),
+    duration_days: data.duration_days,
+    price_coin: data.price_coin,
+    is_active: data.is_active,
+    updated_at: Some(Some(Utc::now())),
+  };
+
+  let package = PostBoostPackage::update(&mut context.pool(), data.package_id, &form).await?;
+  Ok(Json(PostBoostPackageResponse { package }))
+}
+
+fn validate_package(duration_days: Option<i32>, price_coin: Option<i32>) -> FastJobResult<()> {
+  if duration_days.is_some_and(|d| d <= 0) {
+    return Err(FastJobErrorType::InvalidField("durationDays".to_string()).into());
+  }
+  if price_coin.is_some_and(|p| p <= 0) {
+    return Err(FastJobErrorType::NegativeAmount.into());
+  }
+  Ok(())
+}
@@ -51,6 +51,7 @@ pub async fn confirm_delivery_completion(
     employer_person_id,
     coin_id,
     platform_wallet_id,
+    context.settings(),
   )
   .await?;
 
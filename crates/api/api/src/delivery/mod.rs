@@ -3,5 +3,4 @@ pub mod confirm;
 pub mod list;
 pub mod location;
 pub mod rate;
-pub mod ride;
 pub mod status;
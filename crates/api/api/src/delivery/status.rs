@@ -72,6 +72,7 @@ pub async fn update_delivery_status(
       status: new_status,
       cancellation_reason: current_delivery.cancellation_reason,
       updated_at: current_delivery.updated_at,
+      eta: None,
     }));
   }
 
@@ -90,6 +91,7 @@ pub async fn update_delivery_status(
     status: new_status,
     cancellation_reason: updated_delivery.cancellation_reason,
     updated_at: updated_delivery.updated_at,
+    eta: None,
   };
 
   // Publish status change event to Redis for WebSocket listeners
@@ -0,0 +1,123 @@
+use crate::context::FastJobContext;
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  utils::geo::haversine_km,
+};
+use app_108jobs_db::newtypes::{LocalUserId, PricingConfigId};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+/// Audience of quote tokens, so they can never pass as login tokens and the
+/// other way round.
+const FARE_QUOTE_AUDIENCE: &str = "fare-quote";
+
+/// What a fare quote token commits to. Signed with the site JWT secret.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FareQuoteClaims {
+  /// local_user_id of the employer the quote was issued to
+  pub sub: String,
+  pub aud: String,
+  pub iat: i64,
+  pub exp: i64,
+  pub pricing_config_id: PricingConfigId,
+  pub pickup_lat: f64,
+  pub pickup_lng: f64,
+  pub dropoff_lat: f64,
+  pub dropoff_lng: f64,
+  pub price_multiplier: f64,
+  /// Top of the quoted range; the fare is capped here
+  pub max_price_coin: i32,
+}
+
+impl FareQuoteClaims {
+  pub fn new(
+    employer_id: LocalUserId,
+    pricing_config_id: PricingConfigId,
+    pickup: (f64, f64),
+    dropoff: (f64, f64),
+    price_multiplier: f64,
+    max_price_coin: i32,
+    ttl_minutes: i64,
+  ) -> Self {
+    let now = Utc::now();
+    FareQuoteClaims {
+      sub: employer_id.0.to_string(),
+      aud: FARE_QUOTE_AUDIENCE.to_string(),
+      iat: now.timestamp(),
+      exp: (now + Duration::minutes(ttl_minutes)).timestamp(),
+      pricing_config_id,
+      pickup_lat: pickup.0,
+      pickup_lng: pickup.1,
+      dropoff_lat: dropoff.0,
+      dropoff_lng: dropoff.1,
+      price_multiplier,
+      max_price_coin,
+    }
+  }
+
+  pub fn sign(&self, context: &FastJobContext) -> FastJobResult<String> {
+    let secret = &context.secret().jwt_secret;
+    let key = EncodingKey::from_secret(secret.as_ref());
+    Ok(encode(&Header::default(), self, &key)?)
+  }
+
+  /// Decode a quote token and check it was issued to `employer_id` and has
+  /// not expired.
+  pub fn verify(
+    token: &str,
+    employer_id: LocalUserId,
+    context: &FastJobContext,
+  ) -> FastJobResult<Self> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[FARE_QUOTE_AUDIENCE]);
+    let secret = &context.secret().jwt_secret;
+    let key = DecodingKey::from_secret(secret.as_ref());
+    let claims = decode::<FareQuoteClaims>(token, &key, &validation)
+      .with_fastjob_type(FastJobErrorType::FareQuoteInvalid)?
+      .claims;
+
+    if claims.sub != employer_id.0.to_string() {
+      return Err(FastJobErrorType::FareQuoteInvalid.into());
+    }
+    Ok(claims)
+  }
+
+  /// Whether a booking's pickup and dropoff are the ones that were quoted,
+  /// within `tolerance_km`.
+  pub fn matches_route(
+    &self,
+    pickup: Option<(f64, f64)>,
+    dropoff: Option<(f64, f64)>,
+    tolerance_km: f64,
+  ) -> bool {
+    let near = |point: Option<(f64, f64)>, lat: f64, lng: f64| {
+      point.is_some_and(|(p_lat, p_lng)| haversine_km(p_lat, p_lng, lat, lng) <= tolerance_km)
+    };
+    near(pickup, self.pickup_lat, self.pickup_lng)
+      && near(dropoff, self.dropoff_lat, self.dropoff_lng)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn route_must_match_within_tolerance() {
+    let claims = FareQuoteClaims::new(
+      LocalUserId(1),
+      PricingConfigId(1),
+      (13.7563, 100.5018),
+      (13.7367, 100.5231),
+      1.0,
+      20000,
+      10,
+    );
+
+    assert!(claims.matches_route(Some((13.7565, 100.5020)), Some((13.7367, 100.5231)), 0.5));
+    assert!(!claims.matches_route(Some((13.80, 100.55)), Some((13.7367, 100.5231)), 0.5));
+    assert!(!claims.matches_route(None, Some((13.7367, 100.5231)), 0.5));
+  }
+}
@@ -1,6 +1,7 @@
 pub mod build_response;
 pub mod claims;
 pub mod context;
+pub mod fare_quote;
 pub mod geolocation;
 pub mod listing_defaults;
 pub mod plugins;
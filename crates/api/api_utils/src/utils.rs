@@ -26,6 +26,7 @@ use app_108jobs_db::{
     BankId,
     CategoryId,
     ChatRoomId,
+    Coin,
     DbUrl,
     InstanceId,
     LanguageId,
@@ -39,6 +40,7 @@ use app_108jobs_db::{
     category::{Category, CategoryActions},
     chat_message::{ChatMessage, ChatMessageInsertForm},
     chat_room::{ChatRoom, ChatRoomUpdateForm},
+    dispatch::DispatchOffer,
     images::{ImageDetails, RemoteImage},
     language::Language,
     local_site::LocalSite,
@@ -54,6 +56,7 @@ use app_108jobs_db::{
     person::{Person, PersonUpdateForm},
     post::{Post, PostActions, PostReadProposalsForm},
     proposal::{Proposal, ProposalActions},
+    referral::Referral,
     registration_application::RegistrationApplication,
     user_bank_account::BankAccount,
   },
@@ -1025,6 +1028,74 @@ pub async fn get_active_rider_by_person(
     .ok_or(FastJobErrorType::NotAnActiveRider.into())
 }
 
+/// Pay referral rewards for the participants of a job that was just paid out.
+///
+/// Called after an escrow release (workflow approval, delivery confirmation).
+/// Only a participant's first paid job pays their referrer; later calls are
+/// no-ops. Failures are logged and never surface to the caller, since the
+/// payout they follow has already been committed.
+pub async fn reward_referrals(
+  context: &FastJobContext,
+  participants: &[LocalUserId],
+  reference_type: &str,
+  reference_id: i32,
+) {
+  let config = &context.settings().referral;
+  if !config.enabled || config.reward_coin <= 0 {
+    return;
+  }
+  let (coin_id, platform_wallet_id) = match (
+    context.get_coin_id().await,
+    context.get_platform_wallet_id().await,
+  ) {
+    (Ok(coin_id), Ok(platform_wallet_id)) => (coin_id, platform_wallet_id),
+    _ => {
+      tracing::warn!("Skipping referral rewards: coin or platform wallet not configured");
+      return;
+    }
+  };
+  for &local_user_id in participants {
+    if let Err(e) = Referral::reward_first_completion(
+      &mut context.pool(),
+      local_user_id,
+      reference_type,
+      reference_id,
+      Coin(config.reward_coin),
+      coin_id,
+      platform_wallet_id,
+    )
+    .await
+    {
+      tracing::warn!(
+        ?e,
+        local_user_id = local_user_id.0,
+        reference_type,
+        reference_id,
+        "Failed to pay referral reward"
+      );
+    }
+  }
+}
+
+/// Notify riders of new dispatch offers on `dispatch:rider:{riderId}`.
+/// Publishing is best-effort; riders can still poll their open offers.
+pub async fn publish_dispatch_offers(context: &FastJobContext, offers: &[DispatchOffer]) {
+  let mut redis = context.redis().clone();
+  for offer in offers {
+    let Ok(json) = serde_json::to_string(offer) else {
+      continue;
+    };
+    let channel = format!("dispatch:rider:{}", offer.rider_id.0);
+    if let Err(e) = redis.publish(&channel, &json).await {
+      tracing::warn!(
+        ?e,
+        rider_id = offer.rider_id.0,
+        "Failed to publish dispatch offer"
+      );
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -634,8 +634,6 @@ mod rider {
       "rating": 0,
       "completedJobs": 0,
       "totalJobs": 0,
-      "totalEarnings": 0,
-      "pendingEarnings": 0,
       "isOnline": false,
       "acceptingJobs": false,
       "createdAt": "2026-01-01T00:00:00Z"
@@ -382,6 +382,7 @@ pub enum FastJobErrorType {
   CannotUnassignFromStatus,
   OnlyAssignerCanConfirm,
   CannotConfirmNonDeliveredDelivery,
+  CannotCancelCompletedDelivery,
   NoRiderAssigned,
   // Ride session errors
   RiderAlreadyHasActiveRide,
@@ -424,6 +425,119 @@ pub enum FastJobErrorType {
   CouldntCreateRideSession,
   CouldntUpdateRideSession,
   RideSessionNotFound,
+  // Referral related errors
+  CouldntCreateReferral,
+  CouldntUpdateReferral,
+  InvalidReferralCode,
+  // Post boost related errors
+  CouldntCreatePostBoost,
+  CouldntUpdatePostBoost,
+  PostBoostPackageNotFound,
+  PostBoostNotAllowed,
+  // Cash-on-delivery related errors
+  CodCollectionNotConfirmed,
+  CodCashLimitExceeded,
+  CouldntCreateCodCollection,
+  CouldntSettleCod,
+  NothingToSettle,
+  // Rider earnings related errors
+  CouldntCreateRiderEarningHold,
+  CouldntReleaseRiderEarningHold,
+  // Dispatch related errors
+  DispatchAlreadyRunning,
+  DispatchNotFound,
+  DispatchOfferNotFound,
+  DispatchOfferNoLongerAvailable,
+  DispatchJobNoLongerOpen,
+  DispatchPickupLocationRequired,
+  CouldntCreateDispatch,
+  CouldntUpdateDispatch,
+  // Pricing rule related errors
+  CouldntCreatePricingRule,
+  CouldntUpdatePricingRule,
+  // Fare quote related errors
+  FareQuoteInvalid,
+  FareQuoteMismatch,
+  // Meter verification related errors
+  CouldntCreateRideMeterFlag,
+  CouldntUpdateRideMeterFlag,
+  MeterReadingRejected,
+  RideMeterFlagAlreadyReviewed,
+  // Routing related errors
+  RoutingFailed,
+  // Service area related errors
+  CouldntCreateServiceArea,
+  CouldntUpdateServiceArea,
+  InvalidServiceAreaGeometry,
+  OutsideServiceArea,
+  VehicleNotAllowedInServiceArea,
+  // Proof of delivery related errors
+  CouldntCreateDeliveryProof,
+  CouldntUpdateDeliveryProof,
+  DeliveryPhotoRequired,
+  DeliverySignatureRequired,
+  DeliveryOtpRequired,
+  DeliveryOtpInvalid,
+  DeliveryOtpAttemptsExceeded,
+  // Delivery stop related errors
+  CouldntCreateDeliveryStop,
+  CouldntUpdateDeliveryStop,
+  InvalidDeliveryStops,
+  DeliveryStopsLocked,
+  DeliveryStopOutOfOrder,
+  DeliveryStopSkipReasonRequired,
+  DeliveryStopsNotFinished,
+  // Scheduled trip related errors
+  InvalidScheduledPickupTime,
+  NotAScheduledJob,
+  ScheduledJobNotOpenYet,
+  ScheduledJobAlreadyReserved,
+  // Rider document related errors
+  RiderLicenseExpired,
+  RiderDocumentsExpired,
+  // Trip track export related errors
+  TripTrackNotAvailableYet,
+  // Tracking link related errors
+  CouldntCreateTrackingLink,
+  TrackingLinkNotFound,
+  TrackingLinkTripFinished,
+  // Employer rating related errors
+  CouldntRateEmployer,
+  EmployerRatingTripNotFinished,
+  // Cancellation related errors
+  CouldntCreateCancellationPolicy,
+  CouldntUpdateCancellationPolicy,
+  CouldntRecordTripCancellation,
+  RiderAcceptBanned,
+  CouldntCreateCargoTariff,
+  CouldntUpdateCargoTariff,
+  InvalidCargoTariff,
+  CargoTooHeavy,
+  /// The lowest delivery fee allowed for the cargo, in coins
+  DeliveryFeeBelowMinimum(i32),
+  CouldntCreateShiftSlot,
+  CouldntUpdateShiftSlot,
+  CouldntClaimShift,
+  CouldntUpdateShift,
+  ShiftSlotFull,
+  ShiftSlotAlreadyStarted,
+  ShiftOverlapsClaimedShift,
+  ShiftReleaseTooLate,
+  RiderShiftClaimBanned,
+  CouldntCreateIncident,
+  CouldntUpdateIncident,
+  CouldntSaveEmergencyContact,
+  IncidentTripNotActive,
+  DeliveryNotBatchable,
+  DeliveryBatchTooLarge,
+  CouldntCreateFleet,
+  CouldntUpdateFleet,
+  FleetAlreadyExists,
+  FleetJoinCodeInvalid,
+  RiderAlreadyInFleet,
+  RiderNotInFleet,
+  CouldntCreateFleetDocument,
+  CouldntUpdateFleetDocument,
 }
 
 cfg_if! {
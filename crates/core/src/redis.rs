@@ -96,6 +96,33 @@ impl RedisClient {
     }
   }
 
+  /// Read several keys in one MGET, in the order given; missing keys are
+  /// `None`.
+  pub async fn get_values<T: serde::de::DeserializeOwned>(
+    &mut self,
+    keys: &[String],
+  ) -> FastJobResult<Vec<Option<T>>> {
+    if keys.is_empty() {
+      return Ok(Vec::new());
+    }
+    let values: Vec<Option<String>> = redis::cmd("MGET")
+      .arg(keys)
+      .query_async(&mut self.connection)
+      .await
+      .with_fastjob_type(FastJobErrorType::RedisGetFailed)?;
+
+    values
+      .into_iter()
+      .map(|value| {
+        value
+          .map(|v| {
+            serde_json::from_str(&v).with_fastjob_type(FastJobErrorType::DeserializationFailed)
+          })
+          .transpose()
+      })
+      .collect()
+  }
+
   // Fire-and-forget delete (don't error if key missing)
   pub async fn delete_key(&mut self, key: &str) -> FastJobResult<()> {
     let _: i64 = self
@@ -57,6 +57,38 @@ pub struct Settings {
   #[default(1_000_000_000)]
   pub supply_minted_total: i32,
   pub scb: SCBConfig,
+  /// Referral program rewards and anti-abuse limits
+  pub referral: ReferralConfig,
+  /// Cash-on-delivery limits and settlement
+  pub cod: CodConfig,
+  /// Clearing period for rider delivery earnings
+  pub rider_earnings: RiderEarningsConfig,
+  /// Automatic nearest-rider dispatch
+  pub dispatch: DispatchConfig,
+  /// Time-of-day and surge multipliers on ride fares
+  pub surge_pricing: SurgePricingConfig,
+  /// Fare estimates before a ride or delivery is booked
+  pub fare_quote: FareQuoteConfig,
+  /// Checks of rider-reported meter readings against recorded GPS
+  pub meter_verification: MeterVerificationConfig,
+  /// Route distance and duration for fares, dispatch and ETAs
+  pub routing: RoutingConfig,
+  /// Rides and deliveries booked for a later pickup time
+  pub scheduling: SchedulingConfig,
+  /// Partitioning, downsampling and retention of recorded trip locations
+  pub trip_history: TripHistoryConfig,
+  /// Public share links for following a delivery or ride
+  pub tracking_link: TrackingLinkConfig,
+  /// Scoring employers from rider ratings, and what a low score means
+  pub employer_reliability: EmployerReliabilityConfig,
+  /// Penalties for riders who cancel trips they accepted
+  pub cancellation: CancellationConfig,
+  /// Rider shift slots and no-show tracking
+  pub shifts: ShiftConfig,
+  /// Planning the visit order of several deliveries for one rider
+  pub batching: BatchingConfig,
+  /// Courier companies managing several riders
+  pub fleets: FleetConfig,
 }
 
 impl Settings {
@@ -264,3 +296,338 @@ pub struct SCBConfig {
   pub merchant_id: String,
   pub terminal_id: String,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReferralConfig {
+  /// Whether referral codes are accepted at signup
+  #[default(true)]
+  pub enabled: bool,
+  /// Coins paid from the platform wallet to the referrer once the referred user completes their
+  /// first paid workflow or delivery
+  #[default(500)]
+  #[doku(example = "500")]
+  pub reward_coin: i32,
+  /// Maximum number of referrals accepted from the same signup IP address before further
+  /// referrals from it are rejected
+  #[default(3)]
+  #[doku(example = "3")]
+  pub max_referrals_per_ip: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct CodConfig {
+  /// Coins credited per unit of collected cash when a COD amount is recorded
+  #[default(1.0)]
+  #[doku(example = "1.0")]
+  pub coins_per_cash_unit: f64,
+  /// Riders holding at least this many coins of unsettled COD cash cannot be assigned new COD
+  /// deliveries
+  #[default(5000)]
+  #[doku(example = "5000")]
+  pub rider_cash_limit_coin: i64,
+  /// Automatically settle outstanding COD cash out of a rider's delivery payout
+  #[default(true)]
+  pub deduct_from_earnings: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiderEarningsConfig {
+  /// Hours a delivery payout stays pending in the rider's wallet before it can be withdrawn.
+  /// Set to 0 to make earnings withdrawable immediately.
+  #[default(72)]
+  #[doku(example = "72")]
+  pub clearing_period_hours: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct DispatchConfig {
+  /// Riders offered the job at once in each wave
+  #[default(3)]
+  #[doku(example = "3")]
+  pub wave_size: i64,
+  /// Seconds a rider has to accept before the next wave goes out
+  #[default(30)]
+  #[doku(example = "30")]
+  pub offer_timeout_seconds: i64,
+  /// Waves sent before the job is left on the open list
+  #[default(3)]
+  #[doku(example = "3")]
+  pub max_waves: i32,
+  /// Riders further than this from the pickup are not offered the job
+  #[default(10.0)]
+  #[doku(example = "10.0")]
+  pub max_radius_km: f64,
+  /// Rider locations older than this are ignored
+  #[default(30)]
+  #[doku(example = "30")]
+  pub location_max_age_minutes: i64,
+  /// Only offer jobs to riders connected to the WebSocket, who are pushed
+  /// the offer as soon as it is made
+  #[default(true)]
+  #[doku(example = "true")]
+  pub require_presence: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurgePricingConfig {
+  /// Offset from UTC used for time windows, weekdays and holidays
+  #[default(420)]
+  #[doku(example = "420")]
+  pub utc_offset_minutes: i32,
+  /// Upper bound on the combined multiplier of all matching rules
+  #[default(3.0)]
+  #[doku(example = "3.0")]
+  pub max_multiplier: f64,
+  /// Rider locations older than this do not count towards supply
+  #[default(30)]
+  #[doku(example = "30")]
+  pub location_max_age_minutes: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct FareQuoteConfig {
+  /// Quoted range is the estimate plus or minus this fraction
+  #[default(0.15)]
+  #[doku(example = "0.15")]
+  pub range_spread: f64,
+  /// Minutes a quote token stays valid
+  #[default(10)]
+  #[doku(example = "10")]
+  pub token_ttl_minutes: i64,
+  /// Packages heavier than this pay the weight surcharge
+  #[default(10.0)]
+  #[doku(example = "10.0")]
+  pub cargo_weight_step_kg: f64,
+  /// Surcharge per started weight step above the first, as a fraction
+  #[default(0.1)]
+  #[doku(example = "0.1")]
+  pub cargo_weight_step_rate: f64,
+  /// Surcharge for packages of size "large", as a fraction
+  #[default(0.2)]
+  #[doku(example = "0.2")]
+  pub large_package_rate: f64,
+  /// How far the booked pickup or dropoff may be from the quoted one
+  #[default(0.5)]
+  #[doku(example = "0.5")]
+  pub location_tolerance_km: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct MeterVerificationConfig {
+  /// Reported distance may differ from the GPS distance by this fraction
+  #[default(0.2)]
+  #[doku(example = "0.2")]
+  pub distance_tolerance_ratio: f64,
+  /// Differences below this many kilometres are always accepted
+  #[default(0.5)]
+  #[doku(example = "0.5")]
+  pub distance_tolerance_km: f64,
+  /// Reported elapsed time may differ from the server clock by this much
+  #[default(2)]
+  #[doku(example = "2")]
+  pub time_tolerance_minutes: i32,
+  /// GPS jumps implying a faster speed are treated as noise
+  #[default(150.0)]
+  #[doku(example = "150.0")]
+  pub max_speed_kmh: f64,
+  /// GPS fixes less accurate than this are ignored
+  #[default(100.0)]
+  #[doku(example = "100.0")]
+  pub max_accuracy_m: f64,
+  /// Reject divergent meter updates instead of only flagging them
+  #[default(false)]
+  #[doku(example = "false")]
+  pub reject_divergent: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct RoutingConfig {
+  /// Base URL of a self-hosted OSRM server. Straight-line estimates are used
+  /// when unset or when the server does not answer.
+  #[doku(example = "http://localhost:5000")]
+  pub osrm_url: Option<Url>,
+  /// OSRM routing profile
+  #[default("driving")]
+  #[doku(example = "driving")]
+  pub osrm_profile: String,
+  /// Give up on OSRM after this long and fall back to straight-line
+  #[default(2000)]
+  #[doku(example = "2000")]
+  pub request_timeout_ms: u64,
+  /// Straight-line distance is multiplied by this to approximate roads
+  #[default(1.3)]
+  #[doku(example = "1.3")]
+  pub road_distance_factor: f64,
+  /// Average speed used for straight-line duration estimates
+  #[default(25.0)]
+  #[doku(example = "25.0")]
+  pub average_speed_kmh: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulingConfig {
+  /// Pickups must be at least this far ahead to be booked as scheduled
+  #[default(60)]
+  #[doku(example = "60")]
+  pub min_lead_minutes: i64,
+  /// How far ahead a pickup may be booked
+  #[default(7)]
+  #[doku(example = "7")]
+  pub max_days_ahead: i64,
+  /// Scheduled jobs join the open list and may be dispatched this long
+  /// before pickup
+  #[default(30)]
+  #[doku(example = "30")]
+  pub pre_dispatch_minutes: i64,
+  /// Riders holding a reservation are reminded this long before pickup
+  #[default(60)]
+  #[doku(example = "60")]
+  pub reminder_minutes: i64,
+  /// Jobs without a confirmed rider this long before pickup are cancelled
+  /// and any escrow refunded
+  #[default(15)]
+  #[doku(example = "15")]
+  pub confirm_deadline_minutes: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct TripHistoryConfig {
+  /// Monthly partitions are created this many months ahead
+  #[default(3)]
+  #[doku(example = "3")]
+  pub partition_months_ahead: i32,
+  /// Tracks of finished trips are thinned out this long after the trip ends
+  #[default(7)]
+  #[doku(example = "7")]
+  pub downsample_after_days: i64,
+  /// Thinned tracks keep at most one fix per this many seconds, plus the
+  /// fixes around each status change
+  #[default(30)]
+  #[doku(example = "30")]
+  pub downsample_interval_seconds: i64,
+  /// How many trips to thin out per run
+  #[default(500)]
+  #[doku(example = "500")]
+  pub downsample_batch_size: i64,
+  /// Recorded locations older than this are deleted
+  #[default(180)]
+  #[doku(example = "180")]
+  pub retention_days: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrackingLinkConfig {
+  /// Links stop working this long after they are created, even if the trip
+  /// is not finished
+  #[default(24)]
+  #[doku(example = "24")]
+  pub ttl_hours: i64,
+  /// How often the live stream checks for a new location or status
+  #[default(3)]
+  #[doku(example = "3")]
+  pub stream_interval_seconds: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmployerReliabilityConfig {
+  /// Employers scoring below this (out of 100) are flagged for admins
+  #[default(50.0)]
+  #[doku(example = "50.0")]
+  pub flag_below_score: f64,
+  /// Ratings an employer needs before they can be flagged
+  #[default(5)]
+  #[doku(example = "5")]
+  pub min_ratings: i32,
+  /// Hold back the first dispatch wave for jobs from flagged employers, so
+  /// riders are offered other jobs first
+  #[default(false)]
+  #[doku(example = "false")]
+  pub deprioritize_in_dispatch: bool,
+  /// How long the first wave is held back
+  #[default(60)]
+  #[doku(example = "60")]
+  pub dispatch_delay_seconds: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct CancellationConfig {
+  /// Rider cancellations are counted over this many past hours
+  #[default(168)]
+  #[doku(example = "168")]
+  pub rider_offence_window_hours: i64,
+  /// Cancellations within the window before the rider may not accept jobs
+  #[default(3)]
+  #[doku(example = "3")]
+  pub rider_offences_before_ban: i64,
+  /// Length of the first ban; every further cancellation in the window adds
+  /// another ban of this length
+  #[default(60)]
+  #[doku(example = "60")]
+  pub rider_ban_minutes: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShiftConfig {
+  /// A rider who has not been active this many minutes into a started shift
+  /// is a no-show
+  #[default(15)]
+  #[doku(example = "15")]
+  pub no_show_grace_minutes: i64,
+  /// Claimed shifts can be released until this many minutes before they start
+  #[default(120)]
+  #[doku(example = "120")]
+  pub release_cutoff_minutes: i64,
+  /// No-shows are counted over this many past days
+  #[default(30)]
+  #[doku(example = "30")]
+  pub no_show_window_days: i64,
+  /// No-shows within the window before the rider may not claim shifts; 0
+  /// never blocks
+  #[default(3)]
+  #[doku(example = "3")]
+  pub no_shows_before_claim_ban: i64,
+  /// Longest window a coverage report may span
+  #[default(14)]
+  #[doku(example = "14")]
+  pub max_report_days: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatchingConfig {
+  /// Most deliveries one plan may cover
+  #[default(8)]
+  #[doku(example = "8")]
+  pub max_deliveries: usize,
+  /// Minutes spent at each pickup or dropoff
+  #[default(3.0)]
+  #[doku(example = "3.0")]
+  pub stop_service_minutes: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct FleetConfig {
+  /// Largest share of a rider's delivery payout a fleet may take, in percent
+  #[default(30.0)]
+  #[doku(example = "30.0")]
+  pub max_share_percent: f64,
+  /// Most documents a fleet may submit for review
+  #[default(20)]
+  #[doku(example = "20")]
+  pub max_documents: usize,
+}
@@ -0,0 +1,354 @@
+/// Mean Earth radius in kilometres.
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two WGS84 points, in kilometres.
+pub fn haversine_km(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+  let d_lat = (lat2 - lat1).to_radians();
+  let d_lng = (lng2 - lng1).to_radians();
+  let a = (d_lat / 2.0).sin().powi(2)
+    + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+  2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// A recorded GPS fix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+  pub lat: f64,
+  pub lng: f64,
+  /// Unix time in seconds
+  pub timestamp: f64,
+  pub accuracy_m: Option<f64>,
+}
+
+/// Distance travelled along a GPS track once bad fixes are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TrackDistance {
+  pub distance_km: f64,
+  pub points_used: usize,
+  /// Fixes dropped as inaccurate or as jumps faster than the speed limit
+  pub points_dropped: usize,
+  /// Fastest speed between two kept fixes
+  pub max_speed_kmh: f64,
+}
+
+/// Sum the great-circle distance between consecutive fixes, which must be in
+/// time order. Fixes less accurate than `max_accuracy_m` are skipped, as is
+/// any fix that could only be reached from the previous kept one by going
+/// faster than `max_speed_kmh` (a GPS teleport).
+pub fn track_distance(
+  points: &[TrackPoint],
+  max_speed_kmh: f64,
+  max_accuracy_m: f64,
+) -> TrackDistance {
+  let mut result = TrackDistance::default();
+  let mut last: Option<&TrackPoint> = None;
+
+  for point in points {
+    if point.accuracy_m.is_some_and(|a| a > max_accuracy_m) {
+      result.points_dropped += 1;
+      continue;
+    }
+    let Some(prev) = last else {
+      last = Some(point);
+      result.points_used += 1;
+      continue;
+    };
+
+    let step_km = haversine_km(prev.lat, prev.lng, point.lat, point.lng);
+    let hours = (point.timestamp - prev.timestamp) / 3600.0;
+    let speed_kmh = if hours > 0.0 {
+      step_km / hours
+    } else if step_km > 0.0 {
+      f64::INFINITY
+    } else {
+      0.0
+    };
+    if speed_kmh > max_speed_kmh {
+      result.points_dropped += 1;
+      continue;
+    }
+
+    result.distance_km += step_km;
+    result.max_speed_kmh = result.max_speed_kmh.max(speed_kmh);
+    result.points_used += 1;
+    last = Some(point);
+  }
+  result
+}
+
+/// Which fixes of a time-ordered track to keep when thinning it out. Each
+/// kept fix is at least `interval_s` seconds after the previous one; the
+/// first and last fixes are always kept, as are the fixes either side of
+/// every time in `keep_at` (Unix seconds), such as status changes. Running
+/// it again on the kept fixes keeps them all.
+pub fn downsample_track(points: &[TrackPoint], interval_s: f64, keep_at: &[f64]) -> Vec<bool> {
+  let mut keep = vec![false; points.len()];
+  let mut last_kept: Option<f64> = None;
+  for (i, point) in points.iter().enumerate() {
+    if !last_kept.is_some_and(|t| point.timestamp - t < interval_s) {
+      keep[i] = true;
+      last_kept = Some(point.timestamp);
+    }
+  }
+  if let Some(last) = keep.last_mut() {
+    *last = true;
+  }
+
+  for &at in keep_at {
+    let after = points.partition_point(|p| p.timestamp <= at);
+    if after > 0 {
+      keep[after - 1] = true;
+    }
+    if after < points.len() {
+      keep[after] = true;
+    }
+  }
+  keep
+}
+
+/// Encode `(lat, lng)` points in the Google encoded polyline format with
+/// five decimal places, as produced by OSRM and most map SDKs.
+pub fn encode_polyline(points: &[(f64, f64)]) -> String {
+  fn push_value(out: &mut String, value: i64) {
+    let mut v = if value < 0 { !(value << 1) } else { value << 1 };
+    while v >= 0x20 {
+      out.push(char::from((((v & 0x1f) | 0x20) + 63) as u8));
+      v >>= 5;
+    }
+    out.push(char::from((v + 63) as u8));
+  }
+
+  let mut out = String::new();
+  let (mut prev_lat, mut prev_lng) = (0i64, 0i64);
+  for &(lat, lng) in points {
+    let lat = (lat * 1e5).round() as i64;
+    let lng = (lng * 1e5).round() as i64;
+    push_value(&mut out, lat - prev_lat);
+    push_value(&mut out, lng - prev_lng);
+    prev_lat = lat;
+    prev_lng = lng;
+  }
+  out
+}
+
+/// Simplify a track of `(lat, lng)` points with the Douglas–Peucker
+/// algorithm, dropping every point that lies within `tolerance_m` metres of
+/// the line kept around it. The first and last points are always kept.
+/// Points are projected onto a flat plane around the track's mean latitude,
+/// which is accurate enough for city-scale trips.
+pub fn simplify_polyline(points: &[(f64, f64)], tolerance_m: f64) -> Vec<(f64, f64)> {
+  if points.len() <= 2 {
+    return points.to_vec();
+  }
+
+  let mean_lat = points.iter().map(|&(lat, _)| lat).sum::<f64>() / points.len() as f64;
+  let x_scale = mean_lat.to_radians().cos();
+  let planar: Vec<(f64, f64)> = points
+    .iter()
+    .map(|&(lat, lng)| {
+      (
+        lng.to_radians() * x_scale * EARTH_RADIUS_KM * 1000.0,
+        lat.to_radians() * EARTH_RADIUS_KM * 1000.0,
+      )
+    })
+    .collect();
+
+  let mut keep = vec![false; points.len()];
+  keep[0] = true;
+  keep[points.len() - 1] = true;
+  let mut stack = vec![(0, points.len() - 1)];
+  while let Some((start, end)) = stack.pop() {
+    let mut farthest = (0.0, start);
+    for i in start + 1..end {
+      let d = distance_to_segment(planar[i], planar[start], planar[end]);
+      if d > farthest.0 {
+        farthest = (d, i);
+      }
+    }
+    let (d, index) = farthest;
+    if d > tolerance_m {
+      keep[index] = true;
+      stack.push((start, index));
+      stack.push((index, end));
+    }
+  }
+
+  points
+    .iter()
+    .zip(keep)
+    .filter_map(|(&point, keep)| keep.then_some(point))
+    .collect()
+}
+
+/// Distance from `p` to the segment `a`-`b` on a plane.
+fn distance_to_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+  let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+  let len_sq = dx * dx + dy * dy;
+  let t = if len_sq > 0.0 {
+    (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0.0, 1.0)
+  } else {
+    0.0
+  };
+  let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+  ((p.0 - x).powi(2) + (p.1 - y).powi(2)).sqrt()
+}
+
+/// Whether `(lat, lng)` lies inside a polygon given as rings of `(lat, lng)`
+/// vertices. The first ring is the outer boundary and any further rings are
+/// holes; rings may be open or closed. Uses the even-odd rule, which is
+/// accurate enough for city-sized areas away from the poles and antimeridian.
+pub fn point_in_polygon(point: (f64, f64), rings: &[Vec<(f64, f64)>]) -> bool {
+  let (lat, lng) = point;
+  let mut inside = false;
+  for ring in rings {
+    let Some(&last) = ring.last().filter(|_| ring.len() >= 3) else {
+      continue;
+    };
+    let mut prev = last;
+    for &(lat_i, lng_i) in ring {
+      let (lat_j, lng_j) = prev;
+      if (lat_i > lat) != (lat_j > lat)
+        && lng < (lng_j - lng_i) * (lat - lat_i) / (lat_j - lat_i) + lng_i
+      {
+        inside = !inside;
+      }
+      prev = (lat_i, lng_i);
+    }
+  }
+  inside
+}
+
+#[cfg(test)]
+mod test {
+  use crate::utils::geo::{
+    downsample_track,
+    encode_polyline,
+    haversine_km,
+    point_in_polygon,
+    simplify_polyline,
+    track_distance,
+    TrackPoint,
+  };
+
+  #[test]
+  fn test_haversine_km() {
+    assert!(haversine_km(13.7563, 100.5018, 13.7563, 100.5018).abs() < 1e-9);
+    // Bangkok to Chiang Mai is roughly 585 km as the crow flies
+    let d = haversine_km(13.7563, 100.5018, 18.7883, 98.9853);
+    assert!((d - 585.0).abs() < 5.0, "got {d}");
+  }
+
+  fn point(lat: f64, lng: f64, timestamp: f64) -> TrackPoint {
+    TrackPoint {
+      lat,
+      lng,
+      timestamp,
+      accuracy_m: Some(10.0),
+    }
+  }
+
+  #[test]
+  fn test_track_distance_drops_teleports_and_inaccurate_fixes() {
+    // Heading north about 1.1 km per minute (~67 km/h)
+    let mut points = vec![
+      point(13.70, 100.50, 0.0),
+      point(13.71, 100.50, 60.0),
+      // Jumps 50 km in a minute
+      point(14.15, 100.50, 90.0),
+      point(13.72, 100.50, 120.0),
+    ];
+    points.push(TrackPoint {
+      accuracy_m: Some(500.0),
+      ..point(13.80, 100.50, 150.0)
+    });
+    points.push(point(13.73, 100.50, 180.0));
+
+    let track = track_distance(&points, 150.0, 100.0);
+    let expected = haversine_km(13.70, 100.50, 13.73, 100.50);
+    assert!((track.distance_km - expected).abs() < 1e-6, "got {track:?}");
+    assert_eq!(track.points_used, 4);
+    assert_eq!(track.points_dropped, 2);
+    assert!(track.max_speed_kmh < 150.0);
+  }
+
+  #[test]
+  fn test_encode_polyline() {
+    // Reference example from the format documentation
+    let points = [(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)];
+    assert_eq!(encode_polyline(&points), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    assert_eq!(encode_polyline(&[]), "");
+  }
+
+  #[test]
+  fn test_point_in_polygon_with_hole() {
+    let outer = vec![(13.0, 100.0), (13.0, 101.0), (14.0, 101.0), (14.0, 100.0)];
+    let hole = vec![
+      (13.4, 100.4),
+      (13.4, 100.6),
+      (13.6, 100.6),
+      (13.6, 100.4),
+      (13.4, 100.4),
+    ];
+    let rings = vec![outer, hole];
+
+    assert!(point_in_polygon((13.2, 100.2), &rings));
+    assert!(!point_in_polygon((13.5, 100.5), &rings));
+    assert!(!point_in_polygon((15.0, 100.5), &rings));
+    assert!(!point_in_polygon((13.5, 100.5), &[]));
+  }
+
+  #[test]
+  fn test_simplify_polyline() {
+    // Heading north with a few metres of GPS jitter, then turning east
+    let points = [
+      (13.7000, 100.5000),
+      (13.7010, 100.50002),
+      (13.7020, 100.49998),
+      (13.7030, 100.5000),
+      (13.7030, 100.5010),
+      (13.7030, 100.5020),
+    ];
+    let simplified = simplify_polyline(&points, 10.0);
+    assert_eq!(
+      simplified,
+      vec![
+        (13.7000, 100.5000),
+        (13.7030, 100.5000),
+        (13.7030, 100.5020)
+      ]
+    );
+
+    // A tolerance wider than the whole detour keeps only the end points
+    assert_eq!(simplify_polyline(&points, 1000.0).len(), 2);
+    assert_eq!(
+      simplify_polyline(&points[..1], 10.0),
+      vec![(13.7000, 100.5000)]
+    );
+  }
+
+  #[test]
+  fn test_downsample_track() {
+    // A fix every 5 seconds for a minute, with a status change at 32s
+    let points: Vec<TrackPoint> = (0..=12)
+      .map(|i| point(13.70 + i as f64 * 0.0001, 100.50, i as f64 * 5.0))
+      .collect();
+    let keep = downsample_track(&points, 20.0, &[32.0]);
+    let kept: Vec<f64> = points
+      .iter()
+      .zip(&keep)
+      .filter(|(_, &keep)| keep)
+      .map(|(p, _)| p.timestamp)
+      .collect();
+    assert_eq!(kept, vec![0.0, 20.0, 30.0, 35.0, 40.0, 60.0]);
+
+    // Thinning the result again keeps every fix
+    let thinned: Vec<TrackPoint> = points
+      .iter()
+      .zip(&keep)
+      .filter(|(_, &keep)| keep)
+      .map(|(p, _)| *p)
+      .collect();
+    assert!(downsample_track(&thinned, 20.0, &[32.0]).iter().all(|&k| k));
+    assert!(downsample_track(&[], 20.0, &[32.0]).is_empty());
+  }
+}
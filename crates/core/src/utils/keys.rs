@@ -33,3 +33,13 @@ pub fn user_events_topic(user_id: &str) -> String {
 pub fn room_topic(room_id: &str) -> String {
   format!("room:{}", room_id)
 }
+
+#[inline]
+pub fn rider_offers_topic(user_id: i32) -> String {
+  format!("rider:{}:offers", user_id)
+}
+
+#[inline]
+pub fn admin_incidents_topic() -> String {
+  "admin:incidents".to_string()
+}
@@ -1,3 +1,4 @@
+pub mod geo;
 pub mod helper;
 pub mod keys;
 pub mod locale;
@@ -4,3 +4,8 @@
 pub fn rand_number5() -> Option<String> {
   Some(format!("{:05}", fastrand::u32(..100_000)))
 }
+
+/// Generate a random 4-digit one-time code as a string (0000-9999).
+pub fn rand_number4() -> String {
+  format!("{:04}", fastrand::u32(..10_000))
+}
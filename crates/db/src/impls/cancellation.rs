@@ -0,0 +1,181 @@
+use crate::{
+  enums::{CancellationFeeKind, CancellationParty, PostKind},
+  newtypes::{CancellationPolicyId, PricingConfigId, RiderId},
+  schema::{cancellation_policy, trip_cancellation},
+  source::cancellation::{
+    CancellationPolicy,
+    CancellationPolicyInsertForm,
+    CancellationPolicyUpdateForm,
+    TripCancellation,
+    TripCancellationInsertForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{count_star, insert_into, update},
+  BoolExpressionMethods,
+  ExpressionMethods,
+  NullableExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl Crud for CancellationPolicy {
+  type InsertForm = CancellationPolicyInsertForm;
+  type UpdateForm = CancellationPolicyUpdateForm;
+  type IdType = CancellationPolicyId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(cancellation_policy::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateCancellationPolicy)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    policy_id: CancellationPolicyId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(cancellation_policy::table.find(policy_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateCancellationPolicy)
+  }
+}
+
+impl CancellationPolicy {
+  pub async fn list(pool: &mut DbPool<'_>) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cancellation_policy::table
+      .order((cancellation_policy::post_kind, cancellation_policy::id))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// The active policy for a trip: the one for its pricing config if there
+  /// is one, else the default for its post kind.
+  pub async fn find_for(
+    pool: &mut DbPool<'_>,
+    post_kind: PostKind,
+    pricing_config_id: Option<PricingConfigId>,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let scope = cancellation_policy::pricing_config_id.is_null().or(
+      cancellation_policy::pricing_config_id
+        .nullable()
+        .eq(pricing_config_id),
+    );
+    cancellation_policy::table
+      .filter(cancellation_policy::post_kind.eq(post_kind))
+      .filter(cancellation_policy::is_active.eq(true))
+      .filter(scope)
+      .order(cancellation_policy::pricing_config_id.desc().nulls_last())
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Fee owed to the rider for a cancellation this long after assignment,
+  /// with the rider having driven `distance_km` since.
+  pub fn fee(&self, minutes_since_assigned: i64, distance_km: f64) -> i32 {
+    if minutes_since_assigned < i64::from(self.free_minutes) {
+      return 0;
+    }
+    let distance_fee = match self.fee_kind {
+      CancellationFeeKind::Flat => 0.0,
+      CancellationFeeKind::Distance => f64::from(self.fee_per_km_coin) * distance_km.max(0.0),
+    };
+    // Whole coins; the float to int cast saturates
+    let fee = (f64::from(self.flat_fee_coin) + distance_fee).round() as i32;
+    match self.max_fee_coin {
+      Some(max) => fee.min(max),
+      None => fee,
+    }
+  }
+}
+
+impl TripCancellation {
+  pub async fn create(
+    pool: &mut DbPool<'_>,
+    form: &TripCancellationInsertForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(trip_cancellation::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntRecordTripCancellation)
+  }
+
+  /// How many trips the rider cancelled themselves since `since`.
+  pub async fn count_rider_cancellations_since(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+    since: DateTime<Utc>,
+  ) -> FastJobResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+
+    trip_cancellation::table
+      .filter(trip_cancellation::rider_id.eq(rider_id))
+      .filter(trip_cancellation::cancelled_by.eq(CancellationParty::Rider))
+      .filter(trip_cancellation::created_at.ge(since))
+      .select(count_star())
+      .first::<i64>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn policy(fee_kind: CancellationFeeKind, max_fee_coin: Option<i32>) -> CancellationPolicy {
+    CancellationPolicy {
+      id: CancellationPolicyId(1),
+      post_kind: PostKind::RideTaxi,
+      pricing_config_id: None,
+      free_minutes: 5,
+      fee_kind,
+      flat_fee_coin: 20,
+      fee_per_km_coin: 10,
+      max_fee_coin,
+      is_active: true,
+      created_at: Utc::now(),
+      updated_at: None,
+    }
+  }
+
+  #[test]
+  fn free_within_grace_period() {
+    let policy = policy(CancellationFeeKind::Distance, None);
+    assert_eq!(policy.fee(4, 3.0), 0);
+    assert_eq!(policy.fee(5, 0.0), 20);
+  }
+
+  #[test]
+  fn distance_fee_grows_with_distance_up_to_the_cap() {
+    assert_eq!(policy(CancellationFeeKind::Flat, None).fee(10, 5.0), 20);
+    assert_eq!(policy(CancellationFeeKind::Distance, None).fee(10, 5.0), 70);
+    assert_eq!(
+      policy(CancellationFeeKind::Distance, Some(50)).fee(10, 5.0),
+      50
+    );
+  }
+}
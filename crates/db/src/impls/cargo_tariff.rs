@@ -0,0 +1,338 @@
+use crate::{
+  enums::VehicleType,
+  newtypes::{CargoTariffId, PricingConfigId},
+  schema::cargo_tariff,
+  source::{
+    cargo_tariff::{
+      CargoCharges,
+      CargoSpec,
+      CargoTariff,
+      CargoTariffInsertForm,
+      CargoTariffUpdateForm,
+      SizeClass,
+      WeightBracket,
+    },
+    currency::Currency,
+    pricing_config::PricingConfig,
+    service_area::ServiceArea,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use diesel::{
+  dsl::{insert_into, update},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use serde_json::Value as JsonValue;
+
+impl Crud for CargoTariff {
+  type InsertForm = CargoTariffInsertForm;
+  type UpdateForm = CargoTariffUpdateForm;
+  type IdType = CargoTariffId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(cargo_tariff::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateCargoTariff)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    tariff_id: CargoTariffId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(cargo_tariff::table.find(tariff_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateCargoTariff)
+  }
+}
+
+impl CargoTariff {
+  pub async fn list(pool: &mut DbPool<'_>) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cargo_tariff::table
+      .order(cargo_tariff::pricing_config_id)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// The active tariff of a pricing config.
+  pub async fn find_for_config(
+    pool: &mut DbPool<'_>,
+    pricing_config_id: PricingConfigId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cargo_tariff::table
+      .filter(cargo_tariff::pricing_config_id.eq(pricing_config_id))
+      .filter(cargo_tariff::is_active.eq(true))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// The pricing config a delivery from `area` is priced with, and its
+  /// tariff: the area's config if it is active, else the active config of
+  /// the default currency. `None` when that config has no active tariff.
+  pub async fn find_for_area(
+    pool: &mut DbPool<'_>,
+    area: Option<&ServiceArea>,
+  ) -> FastJobResult<Option<(PricingConfig, Self)>> {
+    let area_config = match area.and_then(|a| a.pricing_config_id) {
+      Some(config_id) => Some(PricingConfig::read(pool, config_id).await?),
+      None => None,
+    }
+    .filter(|c| c.is_active);
+    let config = match area_config {
+      Some(config) => config,
+      None => {
+        let Some(currency) = Currency::get_default(pool).await? else {
+          return Ok(None);
+        };
+        let Some(config) = PricingConfig::get_active_for_currency(pool, currency.id).await? else {
+          return Ok(None);
+        };
+        config
+      }
+    };
+
+    Ok(
+      Self::find_for_config(pool, config.id)
+        .await?
+        .map(|tariff| (config, tariff)),
+    )
+  }
+
+  /// Parse and check weight brackets and size classes. Fees must not be
+  /// negative, brackets must grow in weight with only the last one open,
+  /// and size class names must be distinct.
+  pub fn parse_rates(
+    weight_brackets: &JsonValue,
+    size_classes: &JsonValue,
+  ) -> FastJobResult<(Vec<WeightBracket>, Vec<SizeClass>)> {
+    let brackets: Vec<WeightBracket> = serde_json::from_value(weight_brackets.clone())
+      .with_fastjob_type(FastJobErrorType::InvalidCargoTariff)?;
+    let classes: Vec<SizeClass> = serde_json::from_value(size_classes.clone())
+      .with_fastjob_type(FastJobErrorType::InvalidCargoTariff)?;
+
+    let mut previous_kg = 0.0;
+    for (i, bracket) in brackets.iter().enumerate() {
+      let valid = bracket.fee_coin >= 0
+        && match bracket.up_to_kg {
+          Some(kg) => kg.is_finite() && kg > previous_kg,
+          None => i + 1 == brackets.len(),
+        };
+      if !valid {
+        return Err(FastJobErrorType::InvalidCargoTariff.into());
+      }
+      previous_kg = bracket.up_to_kg.unwrap_or(f64::INFINITY);
+    }
+    for (i, class) in classes.iter().enumerate() {
+      let name = class.name.trim();
+      let duplicate = classes[..i]
+        .iter()
+        .any(|c| c.name.trim().eq_ignore_ascii_case(name));
+      if name.is_empty() || duplicate || class.fee_coin < 0 {
+        return Err(FastJobErrorType::InvalidCargoTariff.into());
+      }
+    }
+    Ok((brackets, classes))
+  }
+
+  /// Multiplier for the vehicle a package needs. Without a required vehicle
+  /// any rider can take it, so the cheapest vehicle applies.
+  pub fn vehicle_multiplier(&self, vehicle: Option<VehicleType>) -> f64 {
+    match vehicle {
+      Some(VehicleType::Motorcycle) => self.motorcycle_multiplier,
+      Some(VehicleType::Bicycle) => self.bicycle_multiplier,
+      Some(VehicleType::Car) => self.car_multiplier,
+      None => self
+        .motorcycle_multiplier
+        .min(self.bicycle_multiplier)
+        .min(self.car_multiplier),
+    }
+  }
+
+  /// Charges for the cargo. A package without a weight falls in the lightest
+  /// bracket; one heavier than every bracket is refused. Sizes outside the
+  /// tariff's classes are refused unless it has none.
+  pub fn charges(&self, cargo: &CargoSpec) -> FastJobResult<CargoCharges> {
+    let (brackets, classes) = Self::parse_rates(&self.weight_brackets, &self.size_classes)?;
+
+    let weight_fee_coin = match cargo.weight_kg {
+      _ if brackets.is_empty() => 0,
+      None => brackets[0].fee_coin,
+      Some(kg) => {
+        brackets
+          .iter()
+          .find(|b| b.up_to_kg.map_or(true, |max| kg <= max))
+          .ok_or(FastJobErrorType::CargoTooHeavy)?
+          .fee_coin
+      }
+    };
+    let size_fee_coin = match cargo.size.as_deref().map(str::trim) {
+      Some(size) if !size.is_empty() && !classes.is_empty() => {
+        classes
+          .iter()
+          .find(|c| c.name.trim().eq_ignore_ascii_case(size))
+          .ok_or_else(|| FastJobErrorType::InvalidField(format!("unknown package size {size}")))?
+          .fee_coin
+      }
+      _ => 0,
+    };
+
+    Ok(CargoCharges {
+      weight_fee_coin,
+      size_fee_coin,
+      fragile_surcharge_coin: if cargo.fragile {
+        self.fragile_surcharge_coin
+      } else {
+        0
+      },
+      signature_surcharge_coin: if cargo.requires_signature {
+        self.signature_surcharge_coin
+      } else {
+        0
+      },
+      vehicle_multiplier: self.vehicle_multiplier(cargo.vehicle),
+    })
+  }
+
+  /// Lowest fee an employer may offer for the delivery: the config's base
+  /// fare and distance charge plus the cargo charges, times the vehicle
+  /// multiplier.
+  pub fn minimum_fee(
+    &self,
+    config: &PricingConfig,
+    distance_km: f64,
+    cargo: &CargoSpec,
+  ) -> FastJobResult<i32> {
+    let charges = self.charges(cargo)?;
+    let fare = f64::from(config.base_fare_coin)
+      + distance_km.max(0.0) * f64::from(config.distance_charge_per_km_coin)
+      + f64::from(charges.surcharges_coin());
+    // Whole coins; the float to int cast saturates
+    Ok((fare * charges.vehicle_multiplier).round() as i32)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::Utc;
+  use serde_json::json;
+
+  fn tariff() -> CargoTariff {
+    CargoTariff {
+      id: CargoTariffId(1),
+      pricing_config_id: PricingConfigId(1),
+      weight_brackets: json!([
+        {"upToKg": 5, "feeCoin": 0},
+        {"upToKg": 20, "feeCoin": 300},
+        {"upToKg": 50, "feeCoin": 800}
+      ]),
+      size_classes: json!([
+        {"name": "small", "feeCoin": 0},
+        {"name": "large", "feeCoin": 200}
+      ]),
+      fragile_surcharge_coin: 150,
+      signature_surcharge_coin: 50,
+      motorcycle_multiplier: 1.0,
+      bicycle_multiplier: 0.8,
+      car_multiplier: 1.5,
+      is_active: true,
+      created_at: Utc::now(),
+      updated_at: None,
+    }
+  }
+
+  fn config() -> PricingConfig {
+    PricingConfig {
+      id: PricingConfigId(1),
+      currency_id: Default::default(),
+      name: "default".to_string(),
+      base_fare_coin: 1000,
+      time_charge_per_minute_coin: 10,
+      minimum_charge_minutes: 10,
+      distance_charge_per_km_coin: 100,
+      accepts_cash: true,
+      accepts_coin: true,
+      is_active: true,
+      created_at: Utc::now(),
+      updated_at: None,
+    }
+  }
+
+  #[test]
+  fn charges_add_up_brackets_classes_and_surcharges() {
+    let cargo = CargoSpec {
+      weight_kg: Some(12.0),
+      size: Some(" Large ".to_string()),
+      fragile: true,
+      requires_signature: true,
+      vehicle: Some(VehicleType::Car),
+    };
+    let charges = tariff().charges(&cargo).unwrap();
+    assert_eq!(charges.surcharges_coin(), 300 + 200 + 150 + 50);
+    assert_eq!(charges.vehicle_multiplier, 1.5);
+    // (1000 + 3 km * 100 + 700) * 1.5
+    assert_eq!(tariff().minimum_fee(&config(), 3.0, &cargo).unwrap(), 3000);
+  }
+
+  #[test]
+  fn no_required_vehicle_uses_the_cheapest_one() {
+    let cargo = CargoSpec::default();
+    assert_eq!(tariff().charges(&cargo).unwrap().vehicle_multiplier, 0.8);
+    assert_eq!(tariff().minimum_fee(&config(), 0.0, &cargo).unwrap(), 800);
+  }
+
+  #[test]
+  fn refuses_heavy_packages_and_unknown_sizes() {
+    let heavy = CargoSpec {
+      weight_kg: Some(60.0),
+      ..Default::default()
+    };
+    assert!(tariff().charges(&heavy).is_err());
+    let odd_size = CargoSpec {
+      size: Some("huge".to_string()),
+      ..Default::default()
+    };
+    assert!(tariff().charges(&odd_size).is_err());
+  }
+
+  #[test]
+  fn brackets_must_grow_with_only_the_last_open() {
+    let classes = json!([]);
+    assert!(CargoTariff::parse_rates(&json!([{"upToKg": null, "feeCoin": 0}]), &classes).is_ok());
+    assert!(CargoTariff::parse_rates(
+      &json!([{"upToKg": null, "feeCoin": 0}, {"upToKg": 5, "feeCoin": 10}]),
+      &classes
+    )
+    .is_err());
+    assert!(CargoTariff::parse_rates(
+      &json!([{"upToKg": 10, "feeCoin": 0}, {"upToKg": 5, "feeCoin": 10}]),
+      &classes
+    )
+    .is_err());
+    assert!(CargoTariff::parse_rates(
+      &json!([]),
+      &json!([{"name": "Large", "feeCoin": 1}, {"name": "large", "feeCoin": 2}])
+    )
+    .is_err());
+  }
+}
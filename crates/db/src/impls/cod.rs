@@ -0,0 +1,318 @@
+use crate::{
+  enums::CodSettlementMethod,
+  newtypes::{Coin, CoinId, LocalUserId, PersonId, PostId, RiderId, WalletId},
+  schema::{cod_collection, cod_settlement},
+  source::{
+    cod::{
+      CodCollection,
+      CodCollectionInsertForm,
+      CodRemittanceSummary,
+      CodSettlement,
+      CodSettlementInsertForm,
+      RiderCodOutstanding,
+    },
+    rider::Rider,
+    wallet::{TxKind, WalletModel, WalletTransactionInsertForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::Utc;
+use diesel::{
+  dsl::{count_star, insert_into, sum, update},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+
+impl CodCollection {
+  /// Record the cash collected at drop-off. Recording the same delivery twice
+  /// returns the existing row.
+  pub async fn record(
+    pool: &mut DbPool<'_>,
+    form: &CodCollectionInsertForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    let inserted = insert_into(cod_collection::table)
+      .values(form)
+      .on_conflict(cod_collection::post_id)
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::CouldntCreateCodCollection)?;
+    match inserted {
+      Some(collection) => Ok(collection),
+      None => cod_collection::table
+        .filter(cod_collection::post_id.eq(form.post_id))
+        .first::<Self>(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::NotFound),
+    }
+  }
+
+  pub async fn get_by_post_id(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cod_collection::table
+      .filter(cod_collection::post_id.eq(post_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Coins the rider collected but has not settled yet.
+  pub async fn outstanding_for_rider(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+  ) -> FastJobResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+
+    let outstanding: Option<i64> = cod_collection::table
+      .filter(cod_collection::rider_id.eq(rider_id))
+      .filter(cod_collection::settled_at.is_null())
+      .select(sum(
+        cod_collection::amount_coin - cod_collection::settled_coin,
+      ))
+      .first(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    Ok(outstanding.unwrap_or(0))
+  }
+
+  pub async fn list_open_for_rider(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cod_collection::table
+      .filter(cod_collection::rider_id.eq(rider_id))
+      .filter(cod_collection::settled_at.is_null())
+      .order(cod_collection::collected_at.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  pub async fn list_for_employer(
+    pool: &mut DbPool<'_>,
+    employer_id: LocalUserId,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cod_collection::table
+      .filter(cod_collection::employer_id.eq(employer_id))
+      .order(cod_collection::collected_at.desc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  pub async fn remittance_summary(
+    pool: &mut DbPool<'_>,
+    employer_id: LocalUserId,
+  ) -> FastJobResult<CodRemittanceSummary> {
+    let conn = &mut get_conn(pool).await?;
+
+    let (collected, remitted): (Option<i64>, Option<i64>) = cod_collection::table
+      .filter(cod_collection::employer_id.eq(employer_id))
+      .select((
+        sum(cod_collection::amount_coin),
+        sum(cod_collection::settled_coin),
+      ))
+      .first(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let collected_coin = collected.unwrap_or(0);
+    let remitted_coin = remitted.unwrap_or(0);
+    Ok(CodRemittanceSummary {
+      collected_coin,
+      remitted_coin,
+      outstanding_coin: collected_coin - remitted_coin,
+    })
+  }
+
+  /// Riders currently holding unsettled cash, largest balance first.
+  pub async fn list_outstanding_riders(
+    pool: &mut DbPool<'_>,
+  ) -> FastJobResult<Vec<RiderCodOutstanding>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let rows: Vec<(RiderId, Option<i64>, i64)> = cod_collection::table
+      .filter(cod_collection::settled_at.is_null())
+      .group_by(cod_collection::rider_id)
+      .select((
+        cod_collection::rider_id,
+        sum(cod_collection::amount_coin - cod_collection::settled_coin),
+        count_star(),
+      ))
+      .order(sum(cod_collection::amount_coin - cod_collection::settled_coin).desc())
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(
+      rows
+        .into_iter()
+        .map(
+          |(rider_id, outstanding, open_collections)| RiderCodOutstanding {
+            rider_id,
+            outstanding_coin: outstanding.unwrap_or(0),
+            open_collections,
+          },
+        )
+        .collect(),
+    )
+  }
+}
+
+impl CodSettlement {
+  /// Settle up to `amount_coin` of the rider's outstanding cash, oldest
+  /// collection first, and remit each share to the employer's wallet.
+  ///
+  /// `EarningsDeduction` and `WalletPayment` take the coins from the rider's
+  /// wallet. `CashHandover` means staff received the cash, so the employer is
+  /// paid from the platform wallet instead.
+  #[allow(clippy::too_many_arguments)]
+  pub async fn settle(
+    pool: &mut DbPool<'_>,
+    rider: &Rider,
+    method: CodSettlementMethod,
+    amount_coin: i32,
+    recorded_by_person_id: Option<PersonId>,
+    note: Option<String>,
+    coin_id: CoinId,
+    platform_wallet_id: WalletId,
+  ) -> FastJobResult<Self> {
+    let rider_id = rider.id;
+    let rider_user_id = rider.user_id;
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let open = cod_collection::table
+            .filter(cod_collection::rider_id.eq(rider_id))
+            .filter(cod_collection::settled_at.is_null())
+            .order((cod_collection::collected_at.asc(), cod_collection::id.asc()))
+            .for_update()
+            .load::<CodCollection>(conn)
+            .await?;
+
+          let outstanding: i64 = open
+            .iter()
+            .map(|c| i64::from(c.amount_coin - c.settled_coin))
+            .sum();
+          let amount = i64::from(amount_coin).min(outstanding);
+          if amount <= 0 {
+            return Err(FastJobErrorType::NothingToSettle.into());
+          }
+          // Bounded by `amount_coin`, so this cannot truncate
+          let amount = amount as i32;
+
+          let mut form = CodSettlementInsertForm::new(rider_id, method, amount);
+          form.recorded_by_person_id = recorded_by_person_id;
+          form.note = note;
+          let settlement = insert_into(cod_settlement::table)
+            .values(&form)
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntSettleCod)?;
+
+          if method != CodSettlementMethod::CashHandover {
+            let tx_form = WalletTransactionInsertForm {
+              wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, rider_user_id).await?,
+              reference_type: "cod_settlement".to_string(),
+              reference_id: settlement.id.0,
+              kind: TxKind::Transfer,
+              amount: Coin(amount),
+              description: format!("cod settlement: rider {}", rider_id.0),
+              counter_user_id: None,
+              idempotency_key: format!("cod-settle:{}", settlement.id.0),
+            };
+            WalletModel::hold_on_conn(conn, &tx_form).await?;
+          }
+
+          let now = Utc::now();
+          let mut remaining = amount;
+          for collection in open {
+            if remaining == 0 {
+              break;
+            }
+            let share = remaining.min(collection.amount_coin - collection.settled_coin);
+            if share <= 0 {
+              continue;
+            }
+            remaining -= share;
+
+            let settled_coin = collection.settled_coin + share;
+            let settled_at = (settled_coin == collection.amount_coin).then_some(now);
+            update(cod_collection::table.find(collection.id))
+              .set((
+                cod_collection::settled_coin.eq(settled_coin),
+                cod_collection::settled_at.eq(settled_at),
+              ))
+              .execute(conn)
+              .await
+              .with_fastjob_type(FastJobErrorType::CouldntSettleCod)?;
+
+            let remit_form = WalletTransactionInsertForm {
+              wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, collection.employer_id)
+                .await?,
+              reference_type: "cod_collection".to_string(),
+              reference_id: collection.id.0,
+              kind: if method == CodSettlementMethod::CashHandover {
+                TxKind::Deposit
+              } else {
+                TxKind::Transfer
+              },
+              amount: Coin(share),
+              description: format!("cod remittance: post {}", collection.post_id.0),
+              counter_user_id: Some(rider_user_id),
+              idempotency_key: format!("cod-remit:{}:{}", settlement.id.0, collection.id.0),
+            };
+            if method == CodSettlementMethod::CashHandover {
+              WalletModel::deposit_from_platform_on_conn(
+                conn,
+                &remit_form,
+                coin_id,
+                platform_wallet_id,
+              )
+              .await?;
+            } else {
+              WalletModel::refund_from_platform_on_conn(conn, &remit_form).await?;
+            }
+          }
+
+          Ok(settlement)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn list_for_rider(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    cod_settlement::table
+      .filter(cod_settlement::rider_id.eq(rider_id))
+      .order(cod_settlement::created_at.desc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
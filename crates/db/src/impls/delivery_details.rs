@@ -1,6 +1,7 @@
 use crate::{
-  enums::{PostKind, RiderVerificationStatus, TripStatus},
+  enums::{CodSettlementMethod, PostKind, RiderVerificationStatus, TripStatus},
   newtypes::{
+    Coin,
     CoinId,
     DeliveryDetailsId,
     LocalUserId,
@@ -12,20 +13,30 @@ use crate::{
   },
   schema::{delivery_details, local_user as local_user_tbl, post as post_tbl, rider as rider_tbl},
   source::{
+    cod::{CodCollection, CodCollectionInsertForm, CodSettlement},
     delivery_details::{DeliveryDetails, DeliveryDetailsInsertForm, DeliveryDetailsUpdateForm},
+    delivery_proof::{DeliveryProof, DeliveryProofInsertForm},
     local_user::LocalUser,
     post::Post,
     rider::Rider,
+    rider_earning::RiderEarningHold,
+    rider_fleet::RiderFleet,
     wallet::{TxKind, WalletModel, WalletTransactionInsertForm},
   },
   traits::Crud,
   utils::{get_conn, DbPool},
 };
-use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
-use chrono::{DateTime, Utc};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::Settings,
+};
+use chrono::{DateTime, Duration, Utc};
 use diesel::{
   dsl::{insert_into, update},
+  BoolExpressionMethods,
   ExpressionMethods,
+  JoinOnDsl,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
@@ -205,6 +216,36 @@ impl DeliveryDetails {
     Ok(updated_delivery)
   }
 
+  /// Mark a delivery Delivered, store the drop-off proof and add the cash
+  /// collected to the rider's COD ledger in the same transaction, so a
+  /// delivery is never Delivered without its proof or its cash on record.
+  pub async fn mark_delivered(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    proof: Option<&DeliveryProofInsertForm>,
+    cod: Option<&CodCollectionInsertForm>,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let mut pool: DbPool<'_> = conn.into();
+          let delivered =
+            Self::update_status(&mut pool, post_id, TripStatus::Delivered, None).await?;
+          if let Some(form) = proof {
+            DeliveryProof::record_delivery(&mut pool, form).await?;
+          }
+          if let Some(form) = cod {
+            CodCollection::record(&mut pool, form).await?;
+          }
+          Ok(delivered)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
   /// Get delivery details by post_id.
   pub async fn get_by_post_id(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Self> {
     let conn = &mut get_conn(pool).await?;
@@ -407,6 +448,67 @@ impl DeliveryDetails {
       .await
   }
 
+  /// Connection-scoped assignment used by automatic dispatch. Holds the
+  /// delivery fee in escrow exactly like a manual assignment, using the
+  /// sender/receiver contacts stored when dispatch started.
+  ///
+  /// Only succeeds while the delivery is still Pending with no rider.
+  pub async fn assign_dispatched_on_conn(
+    conn: &mut diesel_async::AsyncPgConnection,
+    post_id: PostId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let current_delivery = delivery_details::dsl::delivery_details
+      .filter(delivery_details::dsl::post_id.eq(post_id.0))
+      .for_update()
+      .first::<Self>(conn)
+      .await
+      .map_err(|_| FastJobErrorType::NotFound)?;
+    if current_delivery.status != TripStatus::Pending
+      || current_delivery.assigned_rider_id.is_some()
+    {
+      return Err(FastJobErrorType::DispatchJobNoLongerOpen.into());
+    }
+
+    let post = post_tbl::table.find(post_id).first::<Post>(conn).await?;
+    let employer = local_user_tbl::table
+      .filter(local_user_tbl::person_id.eq(post.creator_id))
+      .first::<LocalUser>(conn)
+      .await
+      .map_err(|_| FastJobErrorType::NotFound)?;
+
+    // Hold the delivery fee in escrow (employer -> platform)
+    let delivery_fee = post.budget;
+    let tx_form = WalletTransactionInsertForm {
+      wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, employer.id).await?,
+      reference_type: "delivery".to_string(),
+      reference_id: post_id.0,
+      kind: TxKind::Transfer,
+      amount: delivery_fee,
+      description: format!("escrow hold for delivery assignment: post {}", post_id.0),
+      counter_user_id: Some(employer.id),
+      // Same key as a manual assignment, so the fee can only be held once.
+      idempotency_key: format!("assign:{}:{}", post_id.0, employer.id.0),
+    };
+    WalletModel::hold_on_conn(conn, &tx_form).await?;
+
+    let now = Utc::now();
+    update(
+      delivery_details::dsl::delivery_details.filter(delivery_details::dsl::post_id.eq(post_id.0)),
+    )
+    .set((
+      delivery_details::dsl::assigned_rider_id.eq(rider_id.0),
+      delivery_details::dsl::assigned_at.eq(now),
+      delivery_details::dsl::assigned_by_person_id.eq(post.creator_id.0),
+      delivery_details::dsl::delivery_fee.eq(delivery_fee),
+      delivery_details::dsl::status.eq(TripStatus::Assigned),
+      delivery_details::dsl::updated_at.eq(now),
+    ))
+    .get_result::<Self>(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateDeliveryDetails)
+  }
+
   /// Unassign a rider from a delivery, returning it to Pending status.
   /// Only the assigner (employer) or an admin can unassign.
   pub async fn unassign_rider(
@@ -461,14 +563,21 @@ impl DeliveryDetails {
   /// This method:
   /// 1. Verifies the delivery is Delivered
   /// 2. Verifies the caller is the employer
-  /// 3. Releases escrow funds from platform to rider's wallet
-  /// 4. Updates the employer_confirmed_at timestamp
+  /// 3. Releases escrow funds from platform to rider's wallet, less the share of the rider's fleet,
+  ///    which goes to the fleet owner's wallet
+  /// 4. Settles the rider's outstanding COD cash out of the payout, if enabled
+  /// 5. Holds the rest of the payout until the clearing period ends
+  /// 6. Updates the employer_confirmed_at timestamp
+  ///
+  /// The delivery row is locked throughout, so the payout is made once even
+  /// when the employer confirms twice at the same time.
   pub async fn confirm_completion_and_release_payment(
     pool: &mut DbPool<'_>,
     post_id: PostId,
     employer_person_id: PersonId,
     coin_id: CoinId,
     platform_wallet_id: WalletId,
+    settings: &Settings,
   ) -> FastJobResult<Self> {
     use diesel::QueryDsl;
 
@@ -477,9 +586,11 @@ impl DeliveryDetails {
     conn
       .run_transaction(|conn| {
         async move {
-          // Fetch current delivery with post
+          // Fetch current delivery with post, locked so that concurrent
+          // confirmations pay out once
           let current_delivery = delivery_details::dsl::delivery_details
             .filter(delivery_details::dsl::post_id.eq(post_id.0))
+            .for_update()
             .first::<Self>(conn)
             .await
             .map_err(|_| FastJobErrorType::NotFound)?;
@@ -525,21 +636,74 @@ impl DeliveryDetails {
           // Get rider's wallet
           let rider_wallet = WalletModel::get_by_user(&mut pool, rider_local_user_id).await?;
 
-          // Release the delivery fee from escrow to rider (platform -> rider)
+          // Pay the fleet's share from escrow to the fleet owner
           let delivery_fee = current_delivery.delivery_fee;
-          let tx_form = WalletTransactionInsertForm {
-            wallet_id: rider_wallet.id,
-            reference_type: "delivery".to_string(),
-            reference_id: post_id.0,
-            kind: TxKind::Transfer,
-            amount: delivery_fee,
-            description: format!("delivery payment released: post {}", post_id.0),
-            counter_user_id: Some(rider_local_user_id),
-            // Deterministic key: retrying payment release is idempotent.
-            idempotency_key: format!("release:{}:{}", post_id.0, rider_local_user_id.0),
+          let fleet = match rider.fleet_id {
+            Some(fleet_id) => Some(RiderFleet::read(&mut pool, fleet_id).await?),
+            None => None,
+          };
+          let fleet_share = fleet.as_ref().map_or(0, |f| {
+            f.payout_share(
+              rider_local_user_id,
+              delivery_fee,
+              settings.fleets.max_share_percent,
+            )
+          });
+          if let Some(fleet) = fleet.filter(|_| fleet_share > 0) {
+            let owner_wallet = WalletModel::get_by_user(&mut pool, fleet.owner_id).await?;
+            let share_form = WalletTransactionInsertForm {
+              wallet_id: owner_wallet.id,
+              reference_type: "fleet_share".to_string(),
+              reference_id: post_id.0,
+              kind: TxKind::Transfer,
+              amount: Coin(fleet_share),
+              description: format!("fleet share of delivery payment: post {}", post_id.0),
+              counter_user_id: Some(rider_local_user_id),
+              idempotency_key: format!("fleet-share:{}", post_id.0),
+            };
+            WalletModel::deposit_from_platform(&mut pool, &share_form, coin_id, platform_wallet_id)
+              .await?;
+          }
+
+          // Release the rest of the delivery fee from escrow to rider (platform -> rider)
+          let rider_payout = Coin(delivery_fee.0 - fleet_share);
+          if rider_payout.0 > 0 {
+            let tx_form = WalletTransactionInsertForm {
+              wallet_id: rider_wallet.id,
+              reference_type: "delivery".to_string(),
+              reference_id: post_id.0,
+              kind: TxKind::Transfer,
+              amount: rider_payout,
+              description: format!("delivery payment released: post {}", post_id.0),
+              counter_user_id: Some(rider_local_user_id),
+              // Deterministic key: retrying payment release is idempotent.
+              idempotency_key: format!("release:{}:{}", post_id.0, rider_local_user_id.0),
+            };
+            WalletModel::deposit_from_platform(&mut pool, &tx_form, coin_id, platform_wallet_id)
+              .await?;
+          }
+
+          let cod_deducted = if settings.cod.deduct_from_earnings {
+            Self::deduct_cod_from_payout(
+              &mut pool,
+              &rider,
+              rider_payout,
+              coin_id,
+              platform_wallet_id,
+            )
+            .await?
+          } else {
+            0
           };
-          WalletModel::deposit_from_platform(&mut pool, &tx_form, coin_id, platform_wallet_id)
-            .await?;
+
+          // Keep the rest pending until the clearing period ends
+          let held = rider_payout.0 - cod_deducted;
+          let clearing_hours = settings.rider_earnings.clearing_period_hours;
+          if clearing_hours > 0 && held > 0 {
+            let clears_at = Utc::now() + Duration::hours(clearing_hours);
+            let conn = &mut get_conn(&mut pool).await?;
+            RiderEarningHold::place_on_conn(conn, &rider, post_id, Coin(held), clears_at).await?;
+          }
 
           // Update the delivery with confirmation timestamp
           let conn = &mut get_conn(&mut pool).await?;
@@ -562,6 +726,32 @@ impl DeliveryDetails {
       .await
   }
 
+  /// Use a fresh delivery payout to pay down the rider's unsettled COD cash
+  /// and return the coins deducted.
+  async fn deduct_cod_from_payout(
+    pool: &mut DbPool<'_>,
+    rider: &Rider,
+    payout: Coin,
+    coin_id: CoinId,
+    platform_wallet_id: WalletId,
+  ) -> FastJobResult<i32> {
+    if payout.0 <= 0 || CodCollection::outstanding_for_rider(pool, rider.id).await? <= 0 {
+      return Ok(0);
+    }
+    let settlement = CodSettlement::settle(
+      pool,
+      rider,
+      CodSettlementMethod::EarningsDeduction,
+      payout.0,
+      None,
+      None,
+      coin_id,
+      platform_wallet_id,
+    )
+    .await?;
+    Ok(settlement.amount_coin)
+  }
+
   /// Cancel a delivery and refund any held escrow back to the employer.
   ///
   /// Guard: if no rider is assigned (`assigned_rider_id.is_none()`) or the
@@ -641,6 +831,111 @@ impl DeliveryDetails {
       .await
   }
 
+  /// Cancel a delivery that is not yet Delivered or Cancelled. With a rider
+  /// assigned, the rider is paid `rider_fee` out of the held escrow and the
+  /// rest is refunded to the employer. The fee is capped at the escrowed
+  /// delivery fee; the fee actually paid is returned with the delivery.
+  ///
+  /// The status is changed first and only if the delivery is still open, so
+  /// a cancel racing a confirmation cannot pay out the escrow a second time.
+  /// Idempotency keys `cancel-fee:{post_id}:{rider_local_user_id}` and
+  /// `cancel-refund:{post_id}:{employer_local_user_id}` make retried
+  /// cancellations safe.
+  pub async fn cancel_with_rider_fee(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    reason: Option<String>,
+    rider_fee: Coin,
+    coin_id: CoinId,
+    platform_wallet_id: WalletId,
+  ) -> FastJobResult<(Self, Coin)> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let cancelled = update(
+            delivery_details::table
+              .filter(delivery_details::post_id.eq(post_id.0))
+              .filter(
+                delivery_details::status.ne_all([TripStatus::Delivered, TripStatus::Cancelled]),
+              ),
+          )
+          .set((
+            delivery_details::status.eq(TripStatus::Cancelled),
+            delivery_details::cancellation_reason.eq(reason),
+            delivery_details::updated_at.eq(Utc::now()),
+          ))
+          .get_result::<Self>(conn)
+          .await
+          .optional()
+          .with_fastjob_type(FastJobErrorType::CouldntUpdateDeliveryDetails)?
+          .ok_or(FastJobErrorType::CannotCancelCompletedDelivery)?;
+
+          // Escrow is only held once a rider is assigned
+          let escrow = cancelled.delivery_fee.0.max(0);
+          let Some(rider_id) = cancelled.assigned_rider_id.filter(|_| escrow > 0) else {
+            return Ok((cancelled, Coin(0)));
+          };
+          let rider_fee = Coin(rider_fee.0.clamp(0, escrow));
+          let refund = Coin(escrow) - rider_fee;
+
+          if rider_fee.0 > 0 {
+            let rider_user_id = rider_tbl::table
+              .find(rider_id)
+              .select(rider_tbl::user_id)
+              .first::<LocalUserId>(conn)
+              .await
+              .map_err(|_| FastJobErrorType::NotFound)?;
+            let fee_form = WalletTransactionInsertForm {
+              wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, rider_user_id).await?,
+              reference_type: "delivery".to_string(),
+              reference_id: post_id.0,
+              kind: TxKind::Transfer,
+              amount: rider_fee,
+              description: format!("cancellation fee for delivery: post {}", post_id.0),
+              counter_user_id: Some(rider_user_id),
+              idempotency_key: format!("cancel-fee:{}:{}", post_id.0, rider_user_id.0),
+            };
+            WalletModel::deposit_from_platform_on_conn(
+              conn,
+              &fee_form,
+              coin_id,
+              platform_wallet_id,
+            )
+            .await?;
+          }
+
+          if refund.0 > 0 {
+            let employer_user_id = post_tbl::table
+              .find(post_id)
+              .inner_join(
+                local_user_tbl::table.on(local_user_tbl::person_id.eq(post_tbl::creator_id)),
+              )
+              .select(local_user_tbl::id)
+              .first::<LocalUserId>(conn)
+              .await
+              .map_err(|_| FastJobErrorType::NotFound)?;
+            let refund_form = WalletTransactionInsertForm {
+              wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, employer_user_id).await?,
+              reference_type: "delivery".to_string(),
+              reference_id: post_id.0,
+              kind: TxKind::Transfer,
+              amount: refund,
+              description: format!("escrow refund for cancelled delivery: post {}", post_id.0),
+              counter_user_id: Some(employer_user_id),
+              idempotency_key: format!("cancel-refund:{}:{}", post_id.0, employer_user_id.0),
+            };
+            WalletModel::refund_from_platform_on_conn(conn, &refund_form).await?;
+          }
+
+          Ok((cancelled, rider_fee))
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
   /// Get the active delivery assignment for a specific rider.
   /// Returns the delivery if the rider has an active assignment.
   pub async fn get_active_for_rider(
@@ -730,6 +1025,108 @@ impl DeliveryDetails {
       .await
       .map_err(|_| FastJobErrorType::NotFound.into())
   }
+
+  /// Scheduled deliveries that no rider has reserved yet, soonest pickup first.
+  pub async fn list_scheduled_open(
+    pool: &mut DbPool<'_>,
+    limit: Option<i64>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let limit = limit.unwrap_or(20);
+
+    delivery_details::table
+      .filter(delivery_details::status.eq(TripStatus::Pending))
+      .filter(delivery_details::assigned_rider_id.is_null())
+      .filter(delivery_details::scheduled_pickup_at.gt(Utc::now()))
+      .order(delivery_details::scheduled_pickup_at.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Reserve a scheduled delivery for a rider. The delivery fee is held in
+  /// escrow straight away, as for a dispatched assignment; the rider still
+  /// has to confirm before the deadline.
+  pub async fn reserve_scheduled(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move { Self::assign_dispatched_on_conn(conn, post_id, rider_id).await }.scope_boxed()
+      })
+      .await
+  }
+
+  /// The reserving rider confirms they will do the scheduled delivery.
+  pub async fn confirm_reservation(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let now = Utc::now();
+
+    update(
+      delivery_details::table
+        .filter(delivery_details::post_id.eq(post_id))
+        .filter(delivery_details::assigned_rider_id.eq(rider_id))
+        .filter(delivery_details::status.eq(TripStatus::Assigned))
+        .filter(delivery_details::scheduled_pickup_at.is_not_null()),
+    )
+    .set((
+      delivery_details::rider_confirmed_at.eq(now),
+      delivery_details::updated_at.eq(now),
+    ))
+    .get_result::<Self>(conn)
+    .await
+    .map_err(|_| FastJobErrorType::DeliveryIsNotActive.into())
+  }
+
+  /// Reserved deliveries whose pickup is within `before` and whose rider has
+  /// not been reminded yet.
+  pub async fn list_due_reminders(
+    pool: &mut DbPool<'_>,
+    before: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    delivery_details::table
+      .filter(delivery_details::scheduled_pickup_at.le(before))
+      .filter(delivery_details::scheduled_pickup_at.gt(Utc::now()))
+      .filter(delivery_details::reminder_sent_at.is_null())
+      .filter(delivery_details::assigned_rider_id.is_not_null())
+      .filter(delivery_details::status.eq(TripStatus::Assigned))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Scheduled deliveries still without a confirmed rider once their pickup
+  /// is within `before`.
+  pub async fn list_unconfirmed_scheduled(
+    pool: &mut DbPool<'_>,
+    before: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    delivery_details::table
+      .filter(delivery_details::scheduled_pickup_at.le(before))
+      .filter(
+        delivery_details::status.eq(TripStatus::Pending).or(
+          delivery_details::status
+            .eq(TripStatus::Assigned)
+            .and(delivery_details::rider_confirmed_at.is_null()),
+        ),
+      )
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
 }
 
 // ============================================================================
@@ -928,6 +1325,50 @@ mod tests {
     cleanup(pool, instance_id).await;
   }
 
+  /// A delivery without escrow cancels for free, and only once; a finished
+  /// delivery cannot be cancelled at all.
+  #[tokio::test]
+  #[serial]
+  async fn cancel_with_rider_fee_only_cancels_once() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let (instance_id, post_id, _) = fixture_with_status(pool, TripStatus::Pending).await;
+    let (delivered_instance_id, delivered_post_id, _) =
+      fixture_with_status(pool, TripStatus::Delivered).await;
+
+    let (cancelled, fee_paid) = DeliveryDetails::cancel_with_rider_fee(
+      pool,
+      post_id,
+      Some("changed plans".to_string()),
+      Coin(50),
+      CoinId(1),
+      WalletId(1),
+    )
+    .await
+    .expect("cancel");
+    assert_eq!(cancelled.status, TripStatus::Cancelled);
+    assert_eq!(fee_paid, Coin(0));
+
+    for post_id in [post_id, delivered_post_id] {
+      assert!(DeliveryDetails::cancel_with_rider_fee(
+        pool,
+        post_id,
+        None,
+        Coin(50),
+        CoinId(1),
+        WalletId(1),
+      )
+      .await
+      .is_err());
+    }
+    let delivered = DeliveryDetails::get_by_post_id(pool, delivered_post_id)
+      .await
+      .expect("get");
+    assert_eq!(delivered.status, TripStatus::Delivered);
+    cleanup(pool, instance_id).await;
+    cleanup(pool, delivered_instance_id).await;
+  }
+
   /// Non-cancel transitions clear `cancellation_reason` (per update_status:185).
   #[tokio::test]
   #[serial]
@@ -0,0 +1,265 @@
+use crate::{
+  newtypes::{PostId, RiderId},
+  schema::delivery_proof,
+  source::{
+    delivery_details::DeliveryDetails,
+    delivery_proof::{DeliveryEvidence, DeliveryProof, DeliveryProofInsertForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  utils::random::rand_number4,
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{insert_into, update},
+  upsert::excluded,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+/// Wrong codes a rider may enter before the employer has to issue a new one.
+const MAX_OTP_ATTEMPTS: i32 = 5;
+
+impl DeliveryProof {
+  pub async fn get_by_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    delivery_proof::table
+      .filter(delivery_proof::post_id.eq(post_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Issue a fresh receiver code for the delivery, replacing any earlier one
+  /// and resetting the attempt counter.
+  pub async fn issue_otp(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let form = DeliveryProofInsertForm {
+      otp_code: Some(rand_number4()),
+      ..DeliveryProofInsertForm::new(post_id, rider_id)
+    };
+
+    insert_into(delivery_proof::table)
+      .values(&form)
+      .on_conflict(delivery_proof::post_id)
+      .do_update()
+      .set((
+        delivery_proof::rider_id.eq(excluded(delivery_proof::rider_id)),
+        delivery_proof::otp_code.eq(excluded(delivery_proof::otp_code)),
+        delivery_proof::otp_attempts.eq(0),
+        delivery_proof::otp_verified_at.eq(None::<DateTime<Utc>>),
+        delivery_proof::updated_at.eq(Utc::now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateDeliveryProof)
+  }
+
+  /// Check the code the rider entered. A wrong code counts as an attempt;
+  /// once the attempts are used up even the right code is refused.
+  pub async fn verify_otp(pool: &mut DbPool<'_>, post_id: PostId, code: &str) -> FastJobResult<()> {
+    let conn = &mut get_conn(pool).await?;
+
+    let verified = update(
+      delivery_proof::table
+        .filter(delivery_proof::post_id.eq(post_id))
+        .filter(delivery_proof::otp_code.eq(code.trim()))
+        .filter(delivery_proof::otp_attempts.lt(MAX_OTP_ATTEMPTS)),
+    )
+    .set(delivery_proof::otp_verified_at.eq(Utc::now()))
+    .execute(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateDeliveryProof)?;
+    if verified > 0 {
+      return Ok(());
+    }
+
+    let counted = update(
+      delivery_proof::table
+        .filter(delivery_proof::post_id.eq(post_id))
+        .filter(delivery_proof::otp_code.is_not_null())
+        .filter(delivery_proof::otp_attempts.lt(MAX_OTP_ATTEMPTS)),
+    )
+    .set(delivery_proof::otp_attempts.eq(delivery_proof::otp_attempts + 1))
+    .execute(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateDeliveryProof)?;
+
+    if counted > 0 {
+      Err(FastJobErrorType::DeliveryOtpInvalid.into())
+    } else if delivery_proof::table
+      .filter(delivery_proof::post_id.eq(post_id))
+      .filter(delivery_proof::otp_code.is_not_null())
+      .first::<Self>(conn)
+      .await
+      .optional()?
+      .is_some()
+    {
+      Err(FastJobErrorType::DeliveryOtpAttemptsExceeded.into())
+    } else {
+      // No code was ever issued for this delivery
+      Err(FastJobErrorType::DeliveryOtpInvalid.into())
+    }
+  }
+
+  /// Store the evidence submitted with the `Delivered` status, keeping the
+  /// receiver code and its verification.
+  pub async fn record_delivery(
+    pool: &mut DbPool<'_>,
+    form: &DeliveryProofInsertForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(delivery_proof::table)
+      .values(form)
+      .on_conflict(delivery_proof::post_id)
+      .do_update()
+      .set((
+        delivery_proof::rider_id.eq(excluded(delivery_proof::rider_id)),
+        delivery_proof::photo_url.eq(excluded(delivery_proof::photo_url)),
+        delivery_proof::signature_url.eq(excluded(delivery_proof::signature_url)),
+        delivery_proof::delivered_lat.eq(excluded(delivery_proof::delivered_lat)),
+        delivery_proof::delivered_lng.eq(excluded(delivery_proof::delivered_lng)),
+        delivery_proof::submitted_at.eq(excluded(delivery_proof::submitted_at)),
+        delivery_proof::updated_at.eq(Utc::now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateDeliveryProof)
+  }
+}
+
+impl DeliveryDetails {
+  /// Whether the evidence covers what this delivery requires. Only checks
+  /// presence; the receiver code is verified separately.
+  pub fn check_delivery_evidence(&self, evidence: &DeliveryEvidence) -> FastJobResult<()> {
+    if self.requires_photo && evidence.photo_url.is_none() {
+      return Err(FastJobErrorType::DeliveryPhotoRequired.into());
+    }
+    if self.requires_signature && evidence.signature_url.is_none() {
+      return Err(FastJobErrorType::DeliverySignatureRequired.into());
+    }
+    if self.requires_otp
+      && evidence
+        .otp
+        .as_deref()
+        .map_or(true, |c| c.trim().is_empty())
+    {
+      return Err(FastJobErrorType::DeliveryOtpRequired.into());
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    enums::TripStatus,
+    newtypes::{Coin, DbUrl, DeliveryDetailsId},
+  };
+
+  fn delivery() -> DeliveryDetails {
+    DeliveryDetails {
+      id: DeliveryDetailsId(1),
+      post_id: PostId(1),
+      pickup_address: "A".to_string(),
+      pickup_lat: None,
+      pickup_lng: None,
+      dropoff_address: "B".to_string(),
+      dropoff_lat: None,
+      dropoff_lng: None,
+      package_description: None,
+      package_weight_kg: None,
+      package_size: None,
+      fragile: false,
+      requires_signature: false,
+      vehicle_required: None,
+      latest_pickup_at: None,
+      latest_dropoff_at: None,
+      sender_name: None,
+      sender_phone: None,
+      receiver_name: None,
+      receiver_phone: None,
+      cash_on_delivery: false,
+      cod_amount: None,
+      status: TripStatus::EnRouteToDropoff,
+      cancellation_reason: None,
+      assigned_rider_id: Some(RiderId(1)),
+      assigned_at: None,
+      assigned_by_person_id: None,
+      linked_proposal_id: None,
+      delivery_fee: Coin(0),
+      employer_confirmed_at: None,
+      employer_wallet_transaction_id: None,
+      rider_wallet_transaction_id: None,
+      created_at: Utc::now(),
+      updated_at: Utc::now(),
+      requires_photo: false,
+      requires_otp: false,
+      scheduled_pickup_at: None,
+      rider_confirmed_at: None,
+      reminder_sent_at: None,
+    }
+  }
+
+  fn image() -> Option<DbUrl> {
+    "https://example.com/api/v4/image/abc.jpg".parse().ok()
+  }
+
+  #[test]
+  fn nothing_required_accepts_empty_evidence() {
+    assert!(delivery()
+      .check_delivery_evidence(&DeliveryEvidence::default())
+      .is_ok());
+  }
+
+  #[test]
+  fn each_flag_requires_its_evidence() {
+    let strict = DeliveryDetails {
+      requires_photo: true,
+      requires_signature: true,
+      requires_otp: true,
+      ..delivery()
+    };
+    let complete = DeliveryEvidence {
+      photo_url: image(),
+      signature_url: image(),
+      otp: Some("0421".to_string()),
+    };
+    assert!(strict.check_delivery_evidence(&complete).is_ok());
+
+    let no_photo = DeliveryEvidence {
+      photo_url: None,
+      ..complete.clone()
+    };
+    let no_signature = DeliveryEvidence {
+      signature_url: None,
+      ..complete.clone()
+    };
+    let blank_otp = DeliveryEvidence {
+      otp: Some(" ".to_string()),
+      ..complete.clone()
+    };
+    for (evidence, expected) in [
+      (no_photo, FastJobErrorType::DeliveryPhotoRequired),
+      (no_signature, FastJobErrorType::DeliverySignatureRequired),
+      (blank_otp, FastJobErrorType::DeliveryOtpRequired),
+    ] {
+      let err = strict
+        .check_delivery_evidence(&evidence)
+        .expect_err("missing evidence");
+      assert_eq!(err.error_type, expected);
+    }
+  }
+}
@@ -0,0 +1,302 @@
+use crate::{
+  enums::{DeliveryStopStatus, TripStatus},
+  newtypes::{Coin, DeliveryStopId, PostId},
+  schema::{delivery_details, delivery_stop, post},
+  source::{
+    cod::CodCollectionInsertForm,
+    delivery_details::DeliveryDetails,
+    delivery_proof::DeliveryEvidence,
+    delivery_stop::{DeliveryStop, DeliveryStopInsertForm, DeliveryStopUpdateForm},
+    pricing_config::PricingConfig,
+  },
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::Utc;
+use diesel::{
+  dsl::{delete, insert_into, update},
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+
+/// Most drop-offs a single delivery may have.
+pub const MAX_DELIVERY_STOPS: usize = 10;
+
+impl DeliveryStop {
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    delivery_stop::table
+      .filter(delivery_stop::post_id.eq(post_id))
+      .order_by(delivery_stop::stop_order.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Replace the stops of a delivery that has no rider yet. The delivery's
+  /// dropoff mirrors the last stop and its cash on delivery the sum over all
+  /// stops. The post budget becomes the sum of the leg fees, so that is what
+  /// is held in escrow on assignment. An empty list turns it back into a
+  /// single drop-off delivery.
+  pub async fn replace_for_post(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    forms: Vec<DeliveryStopInsertForm>,
+  ) -> FastJobResult<Vec<Self>> {
+    if forms.len() > MAX_DELIVERY_STOPS {
+      return Err(FastJobErrorType::InvalidDeliveryStops.into());
+    }
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let delivery = delivery_details::table
+            .filter(delivery_details::post_id.eq(post_id))
+            .for_update()
+            .first::<DeliveryDetails>(conn)
+            .await
+            .map_err(|_| FastJobErrorType::NotFound)?;
+          if delivery.status != TripStatus::Pending || delivery.assigned_rider_id.is_some() {
+            return Err(FastJobErrorType::DeliveryStopsLocked.into());
+          }
+
+          delete(delivery_stop::table.filter(delivery_stop::post_id.eq(post_id)))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntCreateDeliveryStop)?;
+          if forms.is_empty() {
+            return Ok(Vec::new());
+          }
+          let mut stops = insert_into(delivery_stop::table)
+            .values(&forms)
+            .get_results::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntCreateDeliveryStop)?;
+
+          if let Some(last) = forms.iter().max_by_key(|f| f.stop_order) {
+            let cod_total: f64 = forms.iter().filter_map(|f| f.cod_amount).sum();
+            update(delivery_details::table.filter(delivery_details::post_id.eq(post_id)))
+              .set((
+                delivery_details::dropoff_address.eq(&last.address),
+                delivery_details::dropoff_lat.eq(Some(last.lat)),
+                delivery_details::dropoff_lng.eq(Some(last.lng)),
+                delivery_details::cash_on_delivery.eq(cod_total > 0.0),
+                delivery_details::cod_amount.eq((cod_total > 0.0).then_some(cod_total)),
+                delivery_details::updated_at.eq(Utc::now()),
+              ))
+              .execute(conn)
+              .await
+              .with_fastjob_type(FastJobErrorType::CouldntUpdateDeliveryDetails)?;
+          }
+          let total_fee = Coin(stops.iter().map(|s| s.leg_fee.0).sum());
+          update(post::table.find(post_id))
+            .set(post::budget.eq(total_fee))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdatePost)?;
+
+          stops.sort_by_key(|s| s.stop_order);
+          Ok(stops)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Update the stop the rider is working on. Stops are done strictly in
+  /// order, so only the first stop that is not finished can change.
+  pub async fn advance(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    stop_id: DeliveryStopId,
+    form: &DeliveryStopUpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let stops = delivery_stop::table
+            .filter(delivery_stop::post_id.eq(post_id))
+            .order_by(delivery_stop::stop_order.asc())
+            .for_update()
+            .load::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+          let next = Self::next_open(&stops).ok_or(FastJobErrorType::DeliveryStopOutOfOrder)?;
+          if next.id != stop_id {
+            return Err(FastJobErrorType::DeliveryStopOutOfOrder.into());
+          }
+
+          update(delivery_stop::table.find(stop_id))
+            .set(form)
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdateDeliveryStop)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Update the stop the rider is working on and move the delivery along in
+  /// the same transaction: on its way to the drop-offs with the first stop,
+  /// and Delivered once no stop is left open. `cod` goes to the rider's COD
+  /// ledger only when this update finishes the delivery.
+  pub async fn advance_delivery(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    stop_id: DeliveryStopId,
+    form: &DeliveryStopUpdateForm,
+    cod: Option<&CodCollectionInsertForm>,
+  ) -> FastJobResult<(Self, DeliveryDetails)> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let mut pool: DbPool<'_> = conn.into();
+          let stop = Self::advance(&mut pool, post_id, stop_id, form).await?;
+
+          let mut delivery = DeliveryDetails::get_by_post_id(&mut pool, post_id).await?;
+          if delivery.status == TripStatus::PickedUp {
+            delivery = DeliveryDetails::update_status(
+              &mut pool,
+              post_id,
+              TripStatus::EnRouteToDropoff,
+              None,
+            )
+            .await?;
+          }
+          let stops = Self::list_for_post(&mut pool, post_id).await?;
+          if Self::next_open(&stops).is_none() {
+            delivery = DeliveryDetails::mark_delivered(&mut pool, post_id, None, cod).await?;
+          }
+          Ok((stop, delivery))
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// The stop the rider should go to next.
+  pub fn next_open(stops: &[Self]) -> Option<&Self> {
+    stops.iter().find(|s| !s.is_finished())
+  }
+
+  pub fn is_finished(&self) -> bool {
+    matches!(
+      self.status,
+      DeliveryStopStatus::Completed | DeliveryStopStatus::Skipped
+    )
+  }
+
+  /// Whether the evidence covers the photo and signature the delivery asks
+  /// for. The receiver code only applies to single drop-off deliveries.
+  pub fn check_evidence(
+    delivery: &DeliveryDetails,
+    evidence: &DeliveryEvidence,
+  ) -> FastJobResult<()> {
+    if delivery.requires_photo && evidence.photo_url.is_none() {
+      return Err(FastJobErrorType::DeliveryPhotoRequired.into());
+    }
+    if delivery.requires_signature && evidence.signature_url.is_none() {
+      return Err(FastJobErrorType::DeliverySignatureRequired.into());
+    }
+    Ok(())
+  }
+
+  /// Price each leg as its own metered trip. The base fare is charged once,
+  /// on the first leg. `legs` holds the distance in km and the minutes of
+  /// each leg in stop order.
+  pub fn leg_fees(config: &PricingConfig, legs: &[(f64, i32)]) -> Vec<Coin> {
+    legs
+      .iter()
+      .enumerate()
+      .map(|(i, &(distance_km, minutes))| {
+        let fare = config.metered_fare(minutes, distance_km);
+        let base = if i == 0 { 0 } else { config.base_fare_coin };
+        Coin(fare.subtotal_coin - base)
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::newtypes::{CurrencyId, PricingConfigId};
+
+  fn stop(id: i32, status: DeliveryStopStatus) -> DeliveryStop {
+    DeliveryStop {
+      id: DeliveryStopId(id),
+      post_id: PostId(1),
+      stop_order: id,
+      address: format!("Stop {id}"),
+      lat: 13.75,
+      lng: 100.5,
+      contact_name: None,
+      contact_phone: None,
+      cod_amount: None,
+      status,
+      skip_reason: None,
+      leg_distance_km: 1.0,
+      leg_fee: Coin(0),
+      photo_url: None,
+      signature_url: None,
+      completed_lat: None,
+      completed_lng: None,
+      arrived_at: None,
+      completed_at: None,
+      created_at: Utc::now(),
+      updated_at: None,
+    }
+  }
+
+  #[test]
+  fn next_open_skips_finished_stops() {
+    let stops = vec![
+      stop(1, DeliveryStopStatus::Completed),
+      stop(2, DeliveryStopStatus::Skipped),
+      stop(3, DeliveryStopStatus::Arrived),
+      stop(4, DeliveryStopStatus::Pending),
+    ];
+    assert_eq!(
+      DeliveryStop::next_open(&stops).map(|s| s.id),
+      Some(DeliveryStopId(3))
+    );
+
+    let done = vec![
+      stop(1, DeliveryStopStatus::Completed),
+      stop(2, DeliveryStopStatus::Skipped),
+    ];
+    assert!(DeliveryStop::next_open(&done).is_none());
+  }
+
+  #[test]
+  fn base_fare_is_charged_on_the_first_leg_only() {
+    let config = PricingConfig {
+      id: PricingConfigId(1),
+      currency_id: CurrencyId(1),
+      name: "Default".to_string(),
+      base_fare_coin: 5000,
+      time_charge_per_minute_coin: 100,
+      minimum_charge_minutes: 10,
+      distance_charge_per_km_coin: 1000,
+      accepts_cash: true,
+      accepts_coin: true,
+      is_active: true,
+      created_at: Utc::now(),
+      updated_at: None,
+    };
+
+    let fees = DeliveryStop::leg_fees(&config, &[(2.0, 8), (3.0, 12)]);
+    // 5000 base + 1 time block + 2 km
+    assert_eq!(fees[0], Coin(5000 + 100 + 2000));
+    // 2 time blocks + 3 km, no base fare
+    assert_eq!(fees[1], Coin(200 + 3000));
+  }
+}
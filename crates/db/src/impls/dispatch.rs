@@ -0,0 +1,868 @@
+use crate::{
+  enums::{DispatchJobKind, DispatchOfferStatus, DispatchStatus, TripStatus},
+  newtypes::{DispatchOfferId, DispatchRequestId, LocalUserId, PostId, RiderId},
+  schema::{delivery_details, dispatch_offer, dispatch_request, ride_session, rider, service_area},
+  source::{
+    delivery_details::DeliveryDetails,
+    dispatch::{
+      DispatchCandidate,
+      DispatchDistances,
+      DispatchOffer,
+      DispatchOfferInsertForm,
+      DispatchOfferView,
+      DispatchPresence,
+      DispatchRequest,
+      DispatchRequestInsertForm,
+    },
+    ride_session::RideSession,
+    service_area::ServiceArea,
+  },
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::DispatchConfig,
+  utils::geo::haversine_km,
+};
+use chrono::{Duration, Utc};
+use diesel::{
+  dsl::{exists, insert_into, select, update},
+  sql_query,
+  sql_types::{BigInt, Double, Integer, Nullable, Timestamptz},
+  BoolExpressionMethods,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncPgConnection, RunQueryDsl};
+use tracing::warn;
+
+/// Ranking weights; they sum to one so scores stay within 0..=1.
+const DISTANCE_WEIGHT: f64 = 0.6;
+const RATING_WEIGHT: f64 = 0.25;
+const ACCEPTANCE_WEIGHT: f64 = 0.15;
+const MAX_RATING: f64 = 5.0;
+
+/// Roughly one degree of latitude, used for the bounding-box prefilter.
+const KM_PER_DEGREE: f64 = 111.0;
+
+/// Dispatches advanced per scheduler run.
+const ADVANCE_BATCH: i64 = 100;
+
+impl DispatchCandidate {
+  /// Rank score, higher is better. Riders with no rating yet count as
+  /// average so new riders still get offers.
+  pub fn score(&self, max_radius_km: f64) -> f64 {
+    let distance_km = self.road_distance_km.unwrap_or(self.distance_km);
+    let proximity = if max_radius_km > 0.0 {
+      (1.0 - distance_km / max_radius_km).clamp(0.0, 1.0)
+    } else {
+      0.0
+    };
+    let rating = if self.rating > 0.0 {
+      (self.rating / MAX_RATING).clamp(0.0, 1.0)
+    } else {
+      0.5
+    };
+    DISTANCE_WEIGHT * proximity
+      + RATING_WEIGHT * rating
+      + ACCEPTANCE_WEIGHT * self.acceptance_rate.clamp(0.0, 1.0)
+  }
+}
+
+/// Keep candidates within `max_radius_km` and return the best `take` of them
+/// with their scores.
+pub fn rank_candidates(
+  candidates: Vec<DispatchCandidate>,
+  max_radius_km: f64,
+  take: usize,
+) -> Vec<(DispatchCandidate, f64)> {
+  let mut ranked: Vec<(DispatchCandidate, f64)> = candidates
+    .into_iter()
+    .filter(|c| c.distance_km <= max_radius_km)
+    .map(|c| {
+      let score = c.score(max_radius_km);
+      (c, score)
+    })
+    .collect();
+  ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+  ranked.truncate(take);
+  ranked
+}
+
+#[derive(QueryableByName)]
+struct CandidateRow {
+  #[diesel(sql_type = Integer)]
+  rider_id: i32,
+  #[diesel(sql_type = Integer)]
+  user_id: i32,
+  #[diesel(sql_type = Double)]
+  rating: f64,
+  #[diesel(sql_type = Double)]
+  lat: f64,
+  #[diesel(sql_type = Double)]
+  lng: f64,
+  #[diesel(sql_type = BigInt)]
+  accepted: i64,
+  #[diesel(sql_type = BigInt)]
+  answered: i64,
+}
+
+impl DispatchRequest {
+  /// Start looking for a rider and send the first wave of offers. A post can
+  /// be dispatched again once its previous search ended; riders who were
+  /// already offered the job are not asked twice. If the form holds the first
+  /// wave back, no offers are sent now and the scheduler sends them once it
+  /// is due.
+  pub async fn start(
+    pool: &mut DbPool<'_>,
+    form: &DispatchRequestInsertForm,
+    config: &DispatchConfig,
+    distances: &dyn DispatchDistances,
+    presence: &dyn DispatchPresence,
+  ) -> FastJobResult<(Self, Vec<DispatchOffer>)> {
+    let request = {
+      let conn = &mut get_conn(pool).await?;
+      conn
+        .run_transaction(|conn| {
+          async move {
+            let existing = dispatch_request::table
+              .filter(dispatch_request::post_id.eq(form.post_id))
+              .for_update()
+              .first::<Self>(conn)
+              .await
+              .optional()?;
+
+            match existing {
+              Some(r)
+                if matches!(
+                  r.status,
+                  DispatchStatus::Searching | DispatchStatus::Accepted
+                ) =>
+              {
+                Err(FastJobErrorType::DispatchAlreadyRunning.into())
+              }
+              Some(r) => update(dispatch_request::table.find(r.id))
+                .set((
+                  dispatch_request::vehicle_type.eq(form.vehicle_type),
+                  dispatch_request::service_area_id.eq(form.service_area_id),
+                  dispatch_request::pickup_lat.eq(form.pickup_lat),
+                  dispatch_request::pickup_lng.eq(form.pickup_lng),
+                  dispatch_request::status.eq(DispatchStatus::Searching),
+                  dispatch_request::wave.eq(0),
+                  dispatch_request::next_wave_at.eq(form.next_wave_at.unwrap_or_else(Utc::now)),
+                  dispatch_request::updated_at.eq(Utc::now()),
+                ))
+                .get_result::<Self>(conn)
+                .await
+                .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch),
+              None => insert_into(dispatch_request::table)
+                .values(form)
+                .get_result::<Self>(conn)
+                .await
+                .with_fastjob_type(FastJobErrorType::CouldntCreateDispatch),
+            }
+          }
+          .scope_boxed()
+        })
+        .await?
+    };
+    if request.next_wave_at > Utc::now() {
+      return Ok((request, Vec::new()));
+    }
+
+    let ranked = Self::rank_wave(pool, &request, config, distances, presence).await?;
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let current = Self::lock(conn, request.id).await?;
+          // The scheduler may have sent this wave while it was being ranked
+          if !current.awaits_wave(request.wave) {
+            return Ok((current, Vec::new()));
+          }
+          Self::send_wave_on_conn(conn, current, config, ranked).await
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Expire the current wave of every search whose timeout passed and send
+  /// the next one. Returns the new offers so the caller can notify riders.
+  pub async fn advance_due(
+    pool: &mut DbPool<'_>,
+    config: &DispatchConfig,
+    distances: &dyn DispatchDistances,
+    presence: &dyn DispatchPresence,
+  ) -> FastJobResult<Vec<DispatchOffer>> {
+    let due: Vec<DispatchRequestId> = {
+      let conn = &mut get_conn(pool).await?;
+      dispatch_request::table
+        .filter(dispatch_request::status.eq(DispatchStatus::Searching))
+        .filter(dispatch_request::next_wave_at.le(Utc::now()))
+        .order(dispatch_request::next_wave_at.asc())
+        .select(dispatch_request::id)
+        .limit(ADVANCE_BATCH)
+        .load(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?
+    };
+
+    let mut offers = Vec::new();
+    for request_id in due {
+      match Self::advance(pool, request_id, config, distances, presence).await {
+        Ok(mut sent) => offers.append(&mut sent),
+        Err(e) => warn!(?e, request_id = request_id.0, "Failed to advance dispatch"),
+      }
+    }
+    Ok(offers)
+  }
+
+  async fn advance(
+    pool: &mut DbPool<'_>,
+    request_id: DispatchRequestId,
+    config: &DispatchConfig,
+    distances: &dyn DispatchDistances,
+    presence: &dyn DispatchPresence,
+  ) -> FastJobResult<Vec<DispatchOffer>> {
+    let request = {
+      let conn = &mut get_conn(pool).await?;
+      dispatch_request::table
+        .find(request_id)
+        .first::<Self>(conn)
+        .await?
+    };
+    if !request.awaits_wave(request.wave) {
+      return Ok(Vec::new());
+    }
+
+    let ranked = Self::rank_wave(pool, &request, config, distances, presence).await?;
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let current = Self::lock(conn, request.id).await?;
+          if !current.awaits_wave(request.wave) {
+            return Ok(Vec::new());
+          }
+
+          Self::close_open_offers(conn, current.id, DispatchOfferStatus::Expired).await?;
+
+          // Someone may have assigned the job by hand in the meantime
+          if !Self::job_is_open(conn, &current).await? {
+            Self::set_status(conn, current.id, DispatchStatus::Cancelled).await?;
+            return Ok(Vec::new());
+          }
+
+          let (_, offers) = Self::send_wave_on_conn(conn, current, config, ranked).await?;
+          Ok(offers)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  async fn lock(
+    conn: &mut AsyncPgConnection,
+    request_id: DispatchRequestId,
+  ) -> FastJobResult<Self> {
+    Ok(
+      dispatch_request::table
+        .find(request_id)
+        .for_update()
+        .first::<Self>(conn)
+        .await?,
+    )
+  }
+
+  /// Whether the search is still due to send the wave after `wave`.
+  fn awaits_wave(&self, wave: i32) -> bool {
+    self.status == DispatchStatus::Searching && self.wave == wave && self.next_wave_at <= Utc::now()
+  }
+
+  /// The best riders for the request's next wave with their scores, empty
+  /// once the wave limit is reached. Presence and road distances are network
+  /// calls, so this runs before the request row is locked.
+  async fn rank_wave(
+    pool: &mut DbPool<'_>,
+    request: &Self,
+    config: &DispatchConfig,
+    distances: &dyn DispatchDistances,
+    presence: &dyn DispatchPresence,
+  ) -> FastJobResult<Vec<(DispatchCandidate, f64)>> {
+    if request.wave >= config.max_waves {
+      return Ok(Vec::new());
+    }
+
+    let mut candidates = {
+      let conn = &mut get_conn(pool).await?;
+      let mut candidates = Self::find_candidates(conn, request, config).await?;
+      candidates.retain(|c| c.distance_km <= config.max_radius_km);
+      if let Some(area_id) = request.service_area_id {
+        let shape = service_area::table
+          .find(area_id)
+          .first::<ServiceArea>(conn)
+          .await
+          .optional()?
+          .map(|area| area.shape())
+          .transpose()?;
+        if let Some(shape) = shape {
+          candidates.retain(|c| shape.contains(c.lat, c.lng));
+        }
+      }
+      candidates
+    };
+    let users: Vec<LocalUserId> = candidates.iter().map(|c| c.local_user_id).collect();
+    let mut online = presence.online(&users).await.into_iter();
+    candidates.retain(|_| online.next().unwrap_or(false));
+    let riders: Vec<(f64, f64)> = candidates.iter().map(|c| (c.lat, c.lng)).collect();
+    let road = distances
+      .to_pickup(&riders, (request.pickup_lat, request.pickup_lng))
+      .await;
+    for (candidate, road_km) in candidates.iter_mut().zip(road) {
+      candidate.road_distance_km = road_km;
+    }
+    let take = usize::try_from(config.wave_size).unwrap_or(0);
+    Ok(rank_candidates(candidates, config.max_radius_km, take))
+  }
+
+  /// Offer the job to the ranked riders, or mark the search exhausted when
+  /// nobody is left to ask. Riders who got an offer while the wave was
+  /// ranked are skipped.
+  async fn send_wave_on_conn(
+    conn: &mut AsyncPgConnection,
+    request: Self,
+    config: &DispatchConfig,
+    ranked: Vec<(DispatchCandidate, f64)>,
+  ) -> FastJobResult<(Self, Vec<DispatchOffer>)> {
+    let rider_ids: Vec<RiderId> = ranked.iter().map(|(c, _)| c.rider_id).collect();
+    let busy: Vec<RiderId> = dispatch_offer::table
+      .filter(dispatch_offer::rider_id.eq_any(&rider_ids))
+      .filter(
+        dispatch_offer::dispatch_request_id.eq(request.id).or(
+          dispatch_offer::status
+            .eq(DispatchOfferStatus::Offered)
+            .and(dispatch_offer::expires_at.gt(Utc::now())),
+        ),
+      )
+      .select(dispatch_offer::rider_id)
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    let candidates: Vec<(DispatchCandidate, f64)> = ranked
+      .into_iter()
+      .filter(|(c, _)| !busy.contains(&c.rider_id))
+      .collect();
+
+    if candidates.is_empty() {
+      let request = Self::set_status(conn, request.id, DispatchStatus::Exhausted).await?;
+      return Ok((request, Vec::new()));
+    }
+
+    let wave = request.wave + 1;
+    let expires_at = Utc::now() + Duration::seconds(config.offer_timeout_seconds);
+    let forms: Vec<DispatchOfferInsertForm> = candidates
+      .into_iter()
+      .map(|(c, score)| {
+        DispatchOfferInsertForm::new(
+          request.id,
+          c.rider_id,
+          wave,
+          c.distance_km,
+          score,
+          expires_at,
+        )
+      })
+      .collect();
+    let offers = insert_into(dispatch_offer::table)
+      .values(&forms)
+      .get_results::<DispatchOffer>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateDispatch)?;
+
+    let request = update(dispatch_request::table.find(request.id))
+      .set((
+        dispatch_request::wave.eq(wave),
+        dispatch_request::next_wave_at.eq(expires_at),
+        dispatch_request::updated_at.eq(Utc::now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)?;
+    Ok((request, offers))
+  }
+
+  /// Online riders accepting jobs, with a fresh location inside the search
+  /// radius's bounding box, who have not been offered this job and are not
+  /// holding another open offer. With a service area, riders must also be
+  /// inside its bounding box and drive a vehicle it allows.
+  async fn find_candidates(
+    conn: &mut AsyncPgConnection,
+    request: &Self,
+    config: &DispatchConfig,
+  ) -> FastJobResult<Vec<DispatchCandidate>> {
+    let lat_delta = config.max_radius_km / KM_PER_DEGREE;
+    let lng_delta =
+      config.max_radius_km / (KM_PER_DEGREE * request.pickup_lat.to_radians().cos().max(0.01));
+    let located_since = Utc::now() - Duration::minutes(config.location_max_age_minutes);
+
+    let rows: Vec<CandidateRow> = sql_query(
+      r#"
+      SELECT r.id AS rider_id,
+             r.user_id,
+             r.rating,
+             loc.lat,
+             loc.lng,
+             hist.accepted,
+             hist.answered
+      FROM rider r
+      JOIN LATERAL (
+        SELECT t.lat, t.lng
+        FROM trip_location_current t
+        WHERE t.rider_id = r.id
+          AND t.updated_at >= $1
+        ORDER BY t.updated_at DESC
+        LIMIT 1
+      ) loc ON true
+      JOIN LATERAL (
+        SELECT COUNT(*) FILTER (WHERE o.status = 'Accepted') AS accepted,
+               COUNT(*)                                     AS answered
+        FROM dispatch_offer o
+        WHERE o.rider_id = r.id
+          AND o.status IN ('Accepted', 'Declined', 'Expired')
+      ) hist ON true
+      WHERE r.is_online
+        AND r.accepting_jobs
+        AND r.is_active
+        AND r.is_verified
+        AND (r.accept_banned_until IS NULL OR r.accept_banned_until <= now())
+        AND ($2::vehicle_type IS NULL OR r.vehicle_type = $2)
+        AND loc.lat BETWEEN $3 AND $4
+        AND loc.lng BETWEEN $5 AND $6
+        AND NOT EXISTS (
+          SELECT 1 FROM dispatch_offer x
+          WHERE x.dispatch_request_id = $7 AND x.rider_id = r.id
+        )
+        AND NOT EXISTS (
+          SELECT 1 FROM dispatch_offer y
+          WHERE y.rider_id = r.id AND y.status = 'Offered' AND y.expires_at > now()
+        )
+        AND ($8::int4 IS NULL OR EXISTS (
+          SELECT 1 FROM service_area a
+          WHERE a.id = $8
+            AND loc.lat BETWEEN a.min_lat AND a.max_lat
+            AND loc.lng BETWEEN a.min_lng AND a.max_lng
+            AND (COALESCE(cardinality(a.allowed_vehicle_types), 0) = 0
+                 OR r.vehicle_type = ANY(a.allowed_vehicle_types))
+        ))
+      "#,
+    )
+    .bind::<Timestamptz, _>(located_since)
+    .bind::<Nullable<crate::schema::sql_types::VehicleType>, _>(request.vehicle_type)
+    .bind::<Double, _>(request.pickup_lat - lat_delta)
+    .bind::<Double, _>(request.pickup_lat + lat_delta)
+    .bind::<Double, _>(request.pickup_lng - lng_delta)
+    .bind::<Double, _>(request.pickup_lng + lng_delta)
+    .bind::<Integer, _>(request.id.0)
+    .bind::<Nullable<Integer>, _>(request.service_area_id.map(|id| id.0))
+    .load(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(
+      rows
+        .into_iter()
+        .map(|r| DispatchCandidate {
+          rider_id: RiderId(r.rider_id),
+          local_user_id: LocalUserId(r.user_id),
+          lat: r.lat,
+          lng: r.lng,
+          distance_km: haversine_km(r.lat, r.lng, request.pickup_lat, request.pickup_lng),
+          road_distance_km: None,
+          rating: r.rating,
+          acceptance_rate: if r.answered > 0 {
+            r.accepted as f64 / r.answered as f64
+          } else {
+            1.0
+          },
+        })
+        .collect(),
+    )
+  }
+
+  /// Whether the job still has no rider and is waiting in Pending.
+  async fn job_is_open(conn: &mut AsyncPgConnection, request: &Self) -> FastJobResult<bool> {
+    let open = match request.job_kind {
+      DispatchJobKind::Delivery => {
+        select(exists(
+          delivery_details::table
+            .filter(delivery_details::post_id.eq(request.post_id.0))
+            .filter(delivery_details::status.eq(TripStatus::Pending))
+            .filter(delivery_details::assigned_rider_id.is_null()),
+        ))
+        .get_result(conn)
+        .await?
+      }
+      DispatchJobKind::Ride => {
+        select(exists(
+          ride_session::table
+            .filter(ride_session::post_id.eq(request.post_id))
+            .filter(ride_session::status.eq(TripStatus::Pending))
+            .filter(ride_session::rider_id.is_null()),
+        ))
+        .get_result(conn)
+        .await?
+      }
+    };
+    Ok(open)
+  }
+
+  async fn set_status(
+    conn: &mut AsyncPgConnection,
+    request_id: DispatchRequestId,
+    status: DispatchStatus,
+  ) -> FastJobResult<Self> {
+    update(dispatch_request::table.find(request_id))
+      .set((
+        dispatch_request::status.eq(status),
+        dispatch_request::updated_at.eq(Utc::now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)
+  }
+
+  async fn close_open_offers(
+    conn: &mut AsyncPgConnection,
+    request_id: DispatchRequestId,
+    status: DispatchOfferStatus,
+  ) -> FastJobResult<usize> {
+    update(
+      dispatch_offer::table
+        .filter(dispatch_offer::dispatch_request_id.eq(request_id))
+        .filter(dispatch_offer::status.eq(DispatchOfferStatus::Offered)),
+    )
+    .set(dispatch_offer::status.eq(status))
+    .execute(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)
+  }
+
+  /// Accept an offer. The request row is locked, so when several riders
+  /// accept at once exactly one of them is assigned; the rest get
+  /// `DispatchOfferNoLongerAvailable`.
+  pub async fn accept_offer(
+    pool: &mut DbPool<'_>,
+    offer_id: DispatchOfferId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let offer = dispatch_offer::table
+            .find(offer_id)
+            .filter(dispatch_offer::rider_id.eq(rider_id))
+            .first::<DispatchOffer>(conn)
+            .await
+            .optional()?
+            .ok_or(FastJobErrorType::DispatchOfferNotFound)?;
+          let request = dispatch_request::table
+            .find(offer.dispatch_request_id)
+            .for_update()
+            .first::<Self>(conn)
+            .await?;
+
+          let now = Utc::now();
+          if request.status != DispatchStatus::Searching
+            || offer.status != DispatchOfferStatus::Offered
+            || offer.expires_at <= now
+          {
+            return Err(FastJobErrorType::DispatchOfferNoLongerAvailable.into());
+          }
+
+          match request.job_kind {
+            DispatchJobKind::Delivery => {
+              DeliveryDetails::assign_dispatched_on_conn(conn, request.post_id, rider_id).await?;
+            }
+            DispatchJobKind::Ride => {
+              RideSession::assign_dispatched_on_conn(conn, request.post_id, rider_id).await?;
+              // A rider takes one ride at a time
+              update(rider::table.find(rider_id))
+                .set(rider::accepting_jobs.eq(false))
+                .execute(conn)
+                .await?;
+            }
+          }
+
+          update(dispatch_offer::table.find(offer.id))
+            .set((
+              dispatch_offer::status.eq(DispatchOfferStatus::Accepted),
+              dispatch_offer::responded_at.eq(now),
+            ))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)?;
+          Self::close_open_offers(conn, request.id, DispatchOfferStatus::Superseded).await?;
+
+          update(dispatch_request::table.find(request.id))
+            .set((
+              dispatch_request::status.eq(DispatchStatus::Accepted),
+              dispatch_request::accepted_rider_id.eq(rider_id),
+              dispatch_request::updated_at.eq(now),
+            ))
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Decline an offer. Once everyone in the wave has answered, the next wave
+  /// goes out on the following scheduler run instead of waiting for the
+  /// timeout.
+  pub async fn decline_offer(
+    pool: &mut DbPool<'_>,
+    offer_id: DispatchOfferId,
+    rider_id: RiderId,
+  ) -> FastJobResult<DispatchOffer> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let offer = update(
+            dispatch_offer::table
+              .find(offer_id)
+              .filter(dispatch_offer::rider_id.eq(rider_id))
+              .filter(dispatch_offer::status.eq(DispatchOfferStatus::Offered)),
+          )
+          .set((
+            dispatch_offer::status.eq(DispatchOfferStatus::Declined),
+            dispatch_offer::responded_at.eq(Utc::now()),
+          ))
+          .get_result::<DispatchOffer>(conn)
+          .await
+          .optional()?
+          .ok_or(FastJobErrorType::DispatchOfferNotFound)?;
+
+          let still_open: bool = select(exists(
+            dispatch_offer::table
+              .filter(dispatch_offer::dispatch_request_id.eq(offer.dispatch_request_id))
+              .filter(dispatch_offer::status.eq(DispatchOfferStatus::Offered)),
+          ))
+          .get_result(conn)
+          .await?;
+          if !still_open {
+            update(
+              dispatch_request::table
+                .find(offer.dispatch_request_id)
+                .filter(dispatch_request::status.eq(DispatchStatus::Searching)),
+            )
+            .set(dispatch_request::next_wave_at.eq(Utc::now()))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)?;
+          }
+          Ok(offer)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Stop a running search, e.g. because the employer assigned a rider by
+  /// hand. Returns `None` if nothing was running.
+  pub async fn cancel_for_post(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let cancelled = update(
+            dispatch_request::table
+              .filter(dispatch_request::post_id.eq(post_id))
+              .filter(dispatch_request::status.eq(DispatchStatus::Searching)),
+          )
+          .set((
+            dispatch_request::status.eq(DispatchStatus::Cancelled),
+            dispatch_request::updated_at.eq(Utc::now()),
+          ))
+          .get_result::<Self>(conn)
+          .await
+          .optional()
+          .with_fastjob_type(FastJobErrorType::CouldntUpdateDispatch)?;
+          if let Some(request) = &cancelled {
+            Self::close_open_offers(conn, request.id, DispatchOfferStatus::Superseded).await?;
+          }
+          Ok(cancelled)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn get_by_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    dispatch_request::table
+      .filter(dispatch_request::post_id.eq(post_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+impl DispatchOffer {
+  pub async fn list_for_request(
+    pool: &mut DbPool<'_>,
+    request_id: DispatchRequestId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    dispatch_offer::table
+      .filter(dispatch_offer::dispatch_request_id.eq(request_id))
+      .order((dispatch_offer::wave.asc(), dispatch_offer::score.desc()))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Offers a rider can still accept, soonest to expire first.
+  pub async fn list_open_for_rider(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+  ) -> FastJobResult<Vec<DispatchOfferView>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let rows: Vec<(DispatchOffer, DispatchRequest)> = dispatch_offer::table
+      .inner_join(dispatch_request::table)
+      .filter(dispatch_offer::rider_id.eq(rider_id))
+      .filter(dispatch_offer::status.eq(DispatchOfferStatus::Offered))
+      .filter(dispatch_offer::expires_at.gt(Utc::now()))
+      .filter(dispatch_request::status.eq(DispatchStatus::Searching))
+      .order(dispatch_offer::expires_at.asc())
+      .select((DispatchOffer::as_select(), DispatchRequest::as_select()))
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+  }
+
+  /// The given offers with their jobs and the user account of each rider, to
+  /// notify the riders.
+  pub async fn list_recipients(
+    pool: &mut DbPool<'_>,
+    offer_ids: &[DispatchOfferId],
+  ) -> FastJobResult<Vec<(DispatchOfferView, LocalUserId)>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let rows: Vec<(DispatchOffer, DispatchRequest, LocalUserId)> = dispatch_offer::table
+      .inner_join(dispatch_request::table)
+      .inner_join(rider::table)
+      .filter(dispatch_offer::id.eq_any(offer_ids))
+      .select((
+        DispatchOffer::as_select(),
+        DispatchRequest::as_select(),
+        rider::user_id,
+      ))
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(
+      rows
+        .into_iter()
+        .map(|(offer, request, user_id)| ((offer, request).into(), user_id))
+        .collect(),
+    )
+  }
+}
+
+impl From<(DispatchOffer, DispatchRequest)> for DispatchOfferView {
+  fn from((offer, request): (DispatchOffer, DispatchRequest)) -> Self {
+    Self {
+      offer,
+      post_id: request.post_id,
+      job_kind: request.job_kind,
+      pickup_lat: request.pickup_lat,
+      pickup_lng: request.pickup_lng,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn candidate(id: i32, distance_km: f64, rating: f64, acceptance_rate: f64) -> DispatchCandidate {
+    DispatchCandidate {
+      rider_id: RiderId(id),
+      local_user_id: LocalUserId(id),
+      lat: 0.0,
+      lng: 0.0,
+      distance_km,
+      road_distance_km: None,
+      rating,
+      acceptance_rate,
+    }
+  }
+
+  #[test]
+  fn closer_riders_rank_first() {
+    let ranked = rank_candidates(
+      vec![
+        candidate(1, 8.0, 5.0, 1.0),
+        candidate(2, 1.0, 4.0, 0.8),
+        candidate(3, 12.0, 5.0, 1.0),
+      ],
+      10.0,
+      3,
+    );
+    let ids: Vec<i32> = ranked.iter().map(|(c, _)| c.rider_id.0).collect();
+    // Rider 3 is outside the radius
+    assert_eq!(ids, vec![2, 1]);
+  }
+
+  #[test]
+  fn rating_and_acceptance_break_distance_ties() {
+    let ranked = rank_candidates(
+      vec![
+        candidate(1, 2.0, 3.0, 0.2),
+        candidate(2, 2.0, 4.9, 0.9),
+        candidate(3, 2.0, 0.0, 1.0),
+      ],
+      10.0,
+      2,
+    );
+    let ids: Vec<i32> = ranked.iter().map(|(c, _)| c.rider_id.0).collect();
+    assert_eq!(ids, vec![2, 3]);
+  }
+
+  #[test]
+  fn road_distance_ranks_but_radius_uses_straight_line() {
+    // Rider 1 is closer as the crow flies but has to drive around a river
+    let ranked = rank_candidates(
+      vec![
+        DispatchCandidate {
+          road_distance_km: Some(14.0),
+          ..candidate(1, 2.0, 5.0, 1.0)
+        },
+        DispatchCandidate {
+          road_distance_km: Some(4.0),
+          ..candidate(2, 3.0, 5.0, 1.0)
+        },
+      ],
+      10.0,
+      2,
+    );
+    let ids: Vec<i32> = ranked.iter().map(|(c, _)| c.rider_id.0).collect();
+    assert_eq!(ids, vec![2, 1]);
+  }
+}
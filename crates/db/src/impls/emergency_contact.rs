@@ -0,0 +1,63 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::emergency_contact,
+  source::emergency_contact::{EmergencyContact, EmergencyContactForm},
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::Utc;
+use diesel::{
+  dsl::{delete, insert_into},
+  upsert::excluded,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl EmergencyContact {
+  pub async fn read_for_user(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    emergency_contact::table
+      .find(local_user_id)
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Set the user's contact, replacing the one they had.
+  pub async fn upsert(pool: &mut DbPool<'_>, form: &EmergencyContactForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(emergency_contact::table)
+      .values(form)
+      .on_conflict(emergency_contact::local_user_id)
+      .do_update()
+      .set((
+        emergency_contact::name.eq(excluded(emergency_contact::name)),
+        emergency_contact::phone.eq(excluded(emergency_contact::phone)),
+        emergency_contact::email.eq(excluded(emergency_contact::email)),
+        emergency_contact::updated_at.eq(Utc::now()),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntSaveEmergencyContact)
+  }
+
+  pub async fn delete_for_user(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> FastJobResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+
+    delete(emergency_contact::table.find(local_user_id))
+      .execute(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
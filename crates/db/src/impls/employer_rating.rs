@@ -0,0 +1,206 @@
+use crate::{
+  newtypes::PersonId,
+  schema::{employer_rating, employer_reliability},
+  source::employer_rating::{EmployerRating, EmployerRatingInsertForm, EmployerReliability},
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::Utc;
+use diesel::{
+  dsl::insert_into,
+  sql_query,
+  sql_types::{BigInt, Double, Integer},
+  upsert::excluded,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncPgConnection, RunQueryDsl};
+
+/// A new employer is scored as if they already had this many ratings at
+/// `PRIOR_AVERAGE`, so a single bad trip does not flag them.
+const PRIOR_RATINGS: f64 = 3.0;
+const PRIOR_AVERAGE: f64 = 4.0;
+/// Share of the score lost when every rating carries a negative tag.
+const NEGATIVE_TAG_PENALTY: f64 = 0.5;
+
+/// Reliability score from 0 to 100. The average rating is pulled towards
+/// `PRIOR_AVERAGE` while there are few ratings, then reduced by the share of
+/// ratings tagged no-show, wrong address or rude.
+pub fn reliability_score(rating_count: i64, average_rating: f64, negative_ratings: i64) -> f64 {
+  let count = rating_count.max(0) as f64;
+  let smoothed = (PRIOR_RATINGS * PRIOR_AVERAGE + count * average_rating) / (PRIOR_RATINGS + count);
+  let negative_share = if count > 0.0 {
+    (negative_ratings as f64 / count).clamp(0.0, 1.0)
+  } else {
+    0.0
+  };
+  let base = ((smoothed - 1.0) / 4.0).clamp(0.0, 1.0);
+  base * (1.0 - NEGATIVE_TAG_PENALTY * negative_share) * 100.0
+}
+
+#[derive(QueryableByName)]
+struct AggregateRow {
+  #[diesel(sql_type = BigInt)]
+  rating_count: i64,
+  #[diesel(sql_type = Double)]
+  average_rating: f64,
+  #[diesel(sql_type = BigInt)]
+  negative_count: i64,
+  #[diesel(sql_type = BigInt)]
+  no_show_count: i64,
+  #[diesel(sql_type = BigInt)]
+  wrong_address_count: i64,
+  #[diesel(sql_type = BigInt)]
+  rude_count: i64,
+  #[diesel(sql_type = BigInt)]
+  great_count: i64,
+}
+
+impl EmployerRating {
+  /// Save a rider's rating of a trip, replacing their earlier rating of it,
+  /// and refresh the employer's reliability score.
+  pub async fn upsert(
+    pool: &mut DbPool<'_>,
+    form: &EmployerRatingInsertForm,
+  ) -> FastJobResult<(Self, EmployerReliability)> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let rating = insert_into(employer_rating::table)
+            .values(form)
+            .on_conflict((employer_rating::post_id, employer_rating::rider_id))
+            .do_update()
+            .set((
+              employer_rating::rating.eq(excluded(employer_rating::rating)),
+              employer_rating::tags.eq(excluded(employer_rating::tags)),
+              employer_rating::comment.eq(excluded(employer_rating::comment)),
+              employer_rating::updated_at.eq(Utc::now()),
+            ))
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntRateEmployer)?;
+
+          let reliability = EmployerReliability::refresh_on_conn(conn, rating.employer_id).await?;
+          Ok((rating, reliability))
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+}
+
+impl EmployerReliability {
+  /// Recompute an employer's aggregate from all their ratings.
+  async fn refresh_on_conn(
+    conn: &mut AsyncPgConnection,
+    employer_id: PersonId,
+  ) -> FastJobResult<Self> {
+    let row: AggregateRow = sql_query(
+      r#"
+      SELECT COUNT(*)                                          AS rating_count,
+             COALESCE(AVG(rating), 0)::float8                  AS average_rating,
+             COUNT(*) FILTER (
+               WHERE tags && ARRAY['NoShow', 'WrongAddress', 'Rude']::employer_rating_tag[]
+             )                                                 AS negative_count,
+             COUNT(*) FILTER (WHERE 'NoShow' = ANY(tags))       AS no_show_count,
+             COUNT(*) FILTER (WHERE 'WrongAddress' = ANY(tags)) AS wrong_address_count,
+             COUNT(*) FILTER (WHERE 'Rude' = ANY(tags))         AS rude_count,
+             COUNT(*) FILTER (WHERE 'Great' = ANY(tags))        AS great_count
+      FROM employer_rating
+      WHERE employer_id = $1
+      "#,
+    )
+    .bind::<Integer, _>(employer_id.0)
+    .get_result(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let to_i32 = |n: i64| i32::try_from(n).unwrap_or(i32::MAX);
+    let reliability = Self {
+      employer_id,
+      rating_count: to_i32(row.rating_count),
+      average_rating: row.average_rating,
+      no_show_count: to_i32(row.no_show_count),
+      wrong_address_count: to_i32(row.wrong_address_count),
+      rude_count: to_i32(row.rude_count),
+      great_count: to_i32(row.great_count),
+      score: reliability_score(row.rating_count, row.average_rating, row.negative_count),
+      updated_at: Utc::now(),
+    };
+
+    insert_into(employer_reliability::table)
+      .values(&reliability)
+      .on_conflict(employer_reliability::employer_id)
+      .do_update()
+      .set(&reliability)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntRateEmployer)
+  }
+
+  pub async fn read_for_employer(
+    pool: &mut DbPool<'_>,
+    employer_id: PersonId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    employer_reliability::table
+      .find(employer_id)
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Whether riders have rated the employer often enough, and badly enough,
+  /// for the score to count against them.
+  pub fn is_flagged(&self, flag_below_score: f64, min_ratings: i32) -> bool {
+    self.rating_count >= min_ratings && self.score < flag_below_score
+  }
+
+  /// Flagged employers, lowest score first.
+  pub async fn list_flagged(
+    pool: &mut DbPool<'_>,
+    flag_below_score: f64,
+    min_ratings: i32,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    employer_reliability::table
+      .filter(employer_reliability::rating_count.ge(min_ratings))
+      .filter(employer_reliability::score.lt(flag_below_score))
+      .order((
+        employer_reliability::score.asc(),
+        employer_reliability::rating_count.desc(),
+      ))
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn new_employers_start_near_the_top() {
+    let score = reliability_score(0, 0.0, 0);
+    assert!((score - 75.0).abs() < 1e-9);
+    // One bad trip is not enough to drop far
+    assert!(reliability_score(1, 1.0, 0) > 50.0);
+  }
+
+  #[test]
+  fn negative_tags_lower_the_score() {
+    let clean = reliability_score(20, 4.0, 0);
+    let tagged = reliability_score(20, 4.0, 10);
+    assert!(tagged < clean);
+    assert!(reliability_score(50, 5.0, 0) > 95.0);
+    assert!(reliability_score(50, 1.0, 50) < 10.0);
+  }
+}
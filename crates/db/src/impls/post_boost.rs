@@ -0,0 +1,381 @@
+use crate::{
+  enums::{PostBoostKind, PostBoostStatus},
+  newtypes::{Coin, CoinId, LocalUserId, PostBoostPackageId, PostId, WalletId},
+  schema::{mod_feature_post, post, post_boost, post_boost_package, search_combined},
+  source::{
+    post_boost::{
+      PostBoost,
+      PostBoostInsertForm,
+      PostBoostPackage,
+      PostBoostPackageInsertForm,
+      PostBoostPackageUpdateForm,
+      PostBoostUpdateForm,
+    },
+    wallet::{TxKind, WalletModel, WalletTransactionInsertForm},
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::{DateTime, Duration, Utc};
+use diesel::{
+  dsl::{insert_into, max, update},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncPgConnection, RunQueryDsl};
+use std::collections::HashMap;
+
+impl Crud for PostBoostPackage {
+  type InsertForm = PostBoostPackageInsertForm;
+  type UpdateForm = PostBoostPackageUpdateForm;
+  type IdType = PostBoostPackageId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(post_boost_package::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreatePostBoost)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    package_id: PostBoostPackageId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(post_boost_package::table.find(package_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdatePostBoost)
+  }
+}
+
+impl PostBoostPackage {
+  pub async fn list(pool: &mut DbPool<'_>, active_only: bool) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let mut query = post_boost_package::table.into_boxed();
+    if active_only {
+      query = query.filter(post_boost_package::is_active.eq(true));
+    }
+    query
+      .order(post_boost_package::kind.asc())
+      .then_order_by(post_boost_package::duration_days.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+impl PostBoost {
+  /// Charge the buyer's wallet and activate a boost on the post. Buying the
+  /// same kind again while one is running queues the new window right after
+  /// the current one instead of overlapping it.
+  pub async fn purchase(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    package: &PostBoostPackage,
+    buyer_id: LocalUserId,
+    coin_id: CoinId,
+    platform_wallet_id: WalletId,
+  ) -> FastJobResult<Self> {
+    let package = package.clone();
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          // Serialise purchases on the same post so stacked windows line up.
+          post::table
+            .find(post_id)
+            .select(post::id)
+            .for_update()
+            .first::<PostId>(conn)
+            .await?;
+
+          let running_until: Option<DateTime<Utc>> = post_boost::table
+            .filter(post_boost::post_id.eq(post_id))
+            .filter(post_boost::kind.eq(package.kind))
+            .filter(post_boost::status.eq(PostBoostStatus::Active))
+            .select(max(post_boost::ends_at))
+            .first(conn)
+            .await?;
+
+          let now = Utc::now();
+          let starts_at = running_until.filter(|t| *t > now).unwrap_or(now);
+          let ends_at = starts_at + Duration::days(package.duration_days.into());
+
+          let form = PostBoostInsertForm::new(
+            post_id,
+            package.id,
+            buyer_id,
+            package.kind,
+            package.price_coin,
+            starts_at,
+            ends_at,
+          );
+          let boost = insert_into(post_boost::table)
+            .values(&form)
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntCreatePostBoost)?;
+
+          let tx_form = WalletTransactionInsertForm {
+            wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, buyer_id).await?,
+            reference_type: "post_boost".to_string(),
+            reference_id: boost.id.0,
+            kind: TxKind::Withdraw,
+            amount: Coin(package.price_coin),
+            description: format!("post boost: {} for post {}", package.name, post_id.0),
+            counter_user_id: None,
+            idempotency_key: format!("post-boost:{}", boost.id.0),
+          };
+          WalletModel::withdraw_to_platform_on_conn(conn, &tx_form, coin_id, platform_wallet_id)
+            .await?;
+
+          Self::sync_post_flags(conn, post_id, &[package.kind]).await?;
+          Ok(boost)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    post_boost::table
+      .filter(post_boost::post_id.eq(post_id))
+      .order(post_boost::created_at.desc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Mark every boost whose window has passed as expired and clear the flags
+  /// it was holding. Returns the number of boosts expired.
+  pub async fn expire_due(pool: &mut DbPool<'_>) -> FastJobResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let now = Utc::now();
+          let expired: Vec<(PostId, PostBoostKind)> = update(
+            post_boost::table
+              .filter(post_boost::status.eq(PostBoostStatus::Active))
+              .filter(post_boost::ends_at.le(now)),
+          )
+          .set((
+            post_boost::status.eq(PostBoostStatus::Expired),
+            post_boost::updated_at.eq(now),
+          ))
+          .returning((post_boost::post_id, post_boost::kind))
+          .get_results(conn)
+          .await
+          .with_fastjob_type(FastJobErrorType::CouldntUpdatePostBoost)?;
+
+          let mut by_post: HashMap<PostId, Vec<PostBoostKind>> = HashMap::new();
+          for (post_id, kind) in &expired {
+            by_post.entry(*post_id).or_default().push(*kind);
+          }
+          for (post_id, kinds) in by_post {
+            Self::sync_post_flags(conn, post_id, &kinds).await?;
+          }
+          Ok(expired.len())
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Remove a post by moderation and, in the same transaction, refund the
+  /// unused part of every running boost on it. Boosts queued after the
+  /// current one are refunded in full.
+  pub async fn remove_post_and_refund(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    coin_id: CoinId,
+    platform_wallet_id: WalletId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          update(post::table.find(post_id))
+            .set(post::removed.eq(true))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdatePost)?;
+
+          let active = post_boost::table
+            .filter(post_boost::post_id.eq(post_id))
+            .filter(post_boost::status.eq(PostBoostStatus::Active))
+            .for_update()
+            .load::<Self>(conn)
+            .await?;
+
+          let now = Utc::now();
+          let mut refunded = Vec::with_capacity(active.len());
+          let mut kinds = Vec::with_capacity(active.len());
+          for boost in active {
+            let amount = unused_refund(boost.price_coin, boost.starts_at, boost.ends_at, now);
+            if amount > 0 {
+              let tx_form = WalletTransactionInsertForm {
+                wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, boost.local_user_id)
+                  .await?,
+                reference_type: "post_boost".to_string(),
+                reference_id: boost.id.0,
+                kind: TxKind::Refund,
+                amount: Coin(amount),
+                description: format!("post boost refund for post {}", post_id.0),
+                counter_user_id: None,
+                idempotency_key: format!("post-boost-refund:{}", boost.id.0),
+              };
+              WalletModel::deposit_from_platform_on_conn(
+                conn,
+                &tx_form,
+                coin_id,
+                platform_wallet_id,
+              )
+              .await?;
+            }
+
+            let form = PostBoostUpdateForm {
+              status: Some(PostBoostStatus::Refunded),
+              refunded_coin: Some(Some(amount)),
+              updated_at: Some(Some(now)),
+            };
+            let boost = update(post_boost::table.find(boost.id))
+              .set(&form)
+              .get_result::<Self>(conn)
+              .await
+              .with_fastjob_type(FastJobErrorType::CouldntUpdatePostBoost)?;
+            kinds.push(boost.kind);
+            refunded.push(boost);
+          }
+
+          if !kinds.is_empty() {
+            Self::sync_post_flags(conn, post_id, &kinds).await?;
+          }
+          Ok(refunded)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Bring the post's featured flags and search boost in line with its active
+  /// boosts. Only the featured flags for `kinds` are touched. Such a flag is
+  /// on while a boost of its kind runs; otherwise it follows the moderators'
+  /// last feature action on the post, so a post they featured stays featured
+  /// when a boost on it ends.
+  async fn sync_post_flags(
+    conn: &mut AsyncPgConnection,
+    post_id: PostId,
+    kinds: &[PostBoostKind],
+  ) -> FastJobResult<()> {
+    let active: Vec<PostBoostKind> = post_boost::table
+      .filter(post_boost::post_id.eq(post_id))
+      .filter(post_boost::status.eq(PostBoostStatus::Active))
+      .select(post_boost::kind)
+      .distinct()
+      .load(conn)
+      .await?;
+
+    if kinds.contains(&PostBoostKind::FeaturedLocal) {
+      let featured = active.contains(&PostBoostKind::FeaturedLocal)
+        || Self::featured_by_moderator(conn, post_id, false).await?;
+      update(post::table.find(post_id))
+        .set(post::featured_local.eq(featured))
+        .execute(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::CouldntUpdatePost)?;
+    }
+    if kinds.contains(&PostBoostKind::FeaturedCategory) {
+      let featured = active.contains(&PostBoostKind::FeaturedCategory)
+        || Self::featured_by_moderator(conn, post_id, true).await?;
+      update(post::table.find(post_id))
+        .set(post::featured_category.eq(featured))
+        .execute(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::CouldntUpdatePost)?;
+    }
+
+    update(post::table.find(post_id))
+      .set(post::boosted.eq(!active.is_empty()))
+      .execute(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdatePost)?;
+    update(search_combined::table.filter(search_combined::post_id.eq(post_id)))
+      .set(search_combined::boosted.eq(!active.is_empty()))
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+
+  /// Whether the moderators' last feature action on the post, to its
+  /// category or to the site, left it featured.
+  async fn featured_by_moderator(
+    conn: &mut AsyncPgConnection,
+    post_id: PostId,
+    category: bool,
+  ) -> FastJobResult<bool> {
+    let featured = mod_feature_post::table
+      .filter(mod_feature_post::post_id.eq(post_id))
+      .filter(mod_feature_post::is_featured_category.eq(category))
+      .order((
+        mod_feature_post::published_at.desc(),
+        mod_feature_post::id.desc(),
+      ))
+      .select(mod_feature_post::featured)
+      .first::<bool>(conn)
+      .await
+      .optional()?;
+    Ok(featured.unwrap_or(false))
+  }
+}
+
+/// Pro-rata share of `price` for the part of the window that has not been
+/// used yet. A window that has not started is refunded in full.
+fn unused_refund(
+  price: i32,
+  starts_at: DateTime<Utc>,
+  ends_at: DateTime<Utc>,
+  now: DateTime<Utc>,
+) -> i32 {
+  let total = (ends_at - starts_at).num_seconds();
+  let remaining = (ends_at - now.max(starts_at)).num_seconds();
+  if total <= 0 || remaining <= 0 {
+    return 0;
+  }
+  let remaining = remaining.min(total);
+  (i64::from(price) * remaining / total) as i32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn refunds_unused_share_of_window() {
+    let starts_at = Utc::now();
+    let ends_at = starts_at + Duration::days(4);
+
+    assert_eq!(unused_refund(400, starts_at, ends_at, starts_at), 400);
+    assert_eq!(
+      unused_refund(400, starts_at, ends_at, starts_at + Duration::days(1)),
+      300
+    );
+    assert_eq!(unused_refund(400, starts_at, ends_at, ends_at), 0);
+    assert_eq!(
+      unused_refund(400, starts_at, ends_at, starts_at - Duration::days(2)),
+      400
+    );
+  }
+}
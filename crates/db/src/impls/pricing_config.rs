@@ -1,6 +1,11 @@
 use crate::{
   newtypes::{CurrencyId, PricingConfigId},
-  source::pricing_config::{PricingConfig, PricingConfigInsertForm, PricingConfigUpdateForm},
+  source::pricing_config::{
+    MeteredFare,
+    PricingConfig,
+    PricingConfigInsertForm,
+    PricingConfigUpdateForm,
+  },
   traits::Crud,
   utils::{get_conn, DbPool},
 };
@@ -86,4 +91,24 @@ impl PricingConfig {
 
     Ok(result)
   }
+
+  /// Base fare plus time and distance charges. Time is billed per started
+  /// block of `minimum_charge_minutes`, with at least one block.
+  pub fn metered_fare(&self, elapsed_minutes: i32, distance_km: f64) -> MeteredFare {
+    let min_block = self.minimum_charge_minutes as f64;
+    let intervals = if min_block > 0.0 {
+      (elapsed_minutes as f64 / min_block).ceil().max(1.0)
+    } else {
+      1.0
+    };
+    let time_charge_coin = (intervals * self.time_charge_per_minute_coin as f64).round() as i32;
+    let distance_charge_coin =
+      (distance_km * self.distance_charge_per_km_coin as f64).round() as i32;
+
+    MeteredFare {
+      time_charge_coin,
+      distance_charge_coin,
+      subtotal_coin: self.base_fare_coin + time_charge_coin + distance_charge_coin,
+    }
+  }
 }
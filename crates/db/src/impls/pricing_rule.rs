@@ -0,0 +1,363 @@
+use crate::{
+  enums::PricingRuleKind,
+  newtypes::{PricingConfigId, PricingRuleId, ServiceAreaId},
+  schema::pricing_rule,
+  source::{
+    pricing_rule::{PriceMultiplier, PricingRule, PricingRuleInsertForm, PricingRuleUpdateForm},
+    service_area::ServiceArea,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::SurgePricingConfig,
+  utils::geo::haversine_km,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Timelike, Utc};
+use diesel::{
+  dsl::{insert_into, update},
+  sql_query,
+  sql_types::{BigInt, Double, Nullable, Timestamptz},
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+/// Rough kilometres per degree of latitude, for zone bounding boxes.
+const KM_PER_DEGREE: f64 = 111.0;
+
+impl Crud for PricingRule {
+  type InsertForm = PricingRuleInsertForm;
+  type UpdateForm = PricingRuleUpdateForm;
+  type IdType = PricingRuleId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(pricing_rule::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreatePricingRule)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    rule_id: PricingRuleId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(pricing_rule::table.find(rule_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdatePricingRule)
+  }
+}
+
+impl PricingRule {
+  pub async fn list_for_config(
+    pool: &mut DbPool<'_>,
+    config_id: PricingConfigId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    pricing_rule::table
+      .filter(pricing_rule::pricing_config_id.eq(config_id))
+      .order((pricing_rule::kind.asc(), pricing_rule::id.asc()))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Whether the time window, weekday and holiday criteria hold at the given
+  /// local time.
+  pub fn applies_at(&self, local: NaiveDateTime) -> bool {
+    let minute = (local.hour() * 60 + local.minute()) as i32;
+    let in_window = match (self.start_minute, self.end_minute) {
+      (Some(start), Some(end)) if start <= end => (start..end).contains(&minute),
+      // The window runs past midnight
+      (Some(start), Some(end)) => minute >= start || minute < end,
+      _ => true,
+    };
+    let weekday = local.weekday().number_from_monday() as i32;
+    let on_day = self
+      .days_of_week
+      .as_ref()
+      .map_or(true, |days| days.contains(&weekday));
+    let on_holiday = self.holiday_date.map_or(true, |date| date == local.date());
+
+    in_window && on_day && on_holiday
+  }
+
+  /// Whether the pickup lies inside the rule's zone and service area. A
+  /// zoned rule never covers a ride without a pickup location.
+  pub fn covers(&self, pickup: Option<(f64, f64)>, pickup_area: Option<ServiceAreaId>) -> bool {
+    let in_zone = match (self.zone_lat, self.zone_lng, self.zone_radius_km) {
+      (Some(lat), Some(lng), Some(radius_km)) => {
+        pickup.is_some_and(|(p_lat, p_lng)| haversine_km(lat, lng, p_lat, p_lng) <= radius_km)
+      }
+      _ => true,
+    };
+    let in_area = self
+      .service_area_id
+      .map_or(true, |area_id| pickup_area == Some(area_id));
+    in_zone && in_area
+  }
+
+  /// Open requests per online rider inside the rule's zone or service area,
+  /// or platform-wide for a rule without either. Both are approximated by
+  /// their bounding box.
+  async fn demand_ratio(
+    &self,
+    conn: &mut AsyncPgConnection,
+    located_since: DateTime<Utc>,
+    pickup_area: Option<&ServiceArea>,
+  ) -> FastJobResult<f64> {
+    let bbox = match (self.zone_lat, self.zone_lng, self.zone_radius_km) {
+      (Some(lat), Some(lng), Some(radius_km)) => {
+        let lat_delta = radius_km / KM_PER_DEGREE;
+        let lng_delta = radius_km / (KM_PER_DEGREE * lat.to_radians().cos().max(0.01));
+        Some((
+          lat - lat_delta,
+          lat + lat_delta,
+          lng - lng_delta,
+          lng + lng_delta,
+        ))
+      }
+      _ => pickup_area
+        .filter(|area| self.service_area_id == Some(area.id))
+        .map(|area| (area.min_lat, area.max_lat, area.min_lng, area.max_lng)),
+    };
+
+    let row: DemandRow = sql_query(
+      r#"
+      SELECT
+        (SELECT COUNT(*) FROM ride_session s
+         WHERE s.status = 'Pending' AND s.rider_id IS NULL
+           AND ($1::float8 IS NULL
+                OR (s.pickup_lat BETWEEN $1 AND $2 AND s.pickup_lng BETWEEN $3 AND $4)))
+        + (SELECT COUNT(*) FROM delivery_details d
+           WHERE d.status = 'Pending' AND d.assigned_rider_id IS NULL
+             AND ($1::float8 IS NULL
+                  OR (d.pickup_lat BETWEEN $1 AND $2 AND d.pickup_lng BETWEEN $3 AND $4)))
+          AS open_requests,
+        (SELECT COUNT(*) FROM rider r
+         WHERE r.is_online AND r.accepting_jobs AND r.is_active
+           AND ($1::float8 IS NULL OR EXISTS (
+             SELECT 1 FROM trip_location_current t
+             WHERE t.rider_id = r.id
+               AND t.updated_at >= $5
+               AND t.lat BETWEEN $1 AND $2
+               AND t.lng BETWEEN $3 AND $4)))
+          AS online_riders
+      "#,
+    )
+    .bind::<Nullable<Double>, _>(bbox.map(|b| b.0))
+    .bind::<Nullable<Double>, _>(bbox.map(|b| b.1))
+    .bind::<Nullable<Double>, _>(bbox.map(|b| b.2))
+    .bind::<Nullable<Double>, _>(bbox.map(|b| b.3))
+    .bind::<Timestamptz, _>(located_since)
+    .get_result(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(row.open_requests as f64 / row.online_riders.max(1) as f64)
+  }
+}
+
+#[derive(QueryableByName)]
+struct DemandRow {
+  #[diesel(sql_type = BigInt)]
+  open_requests: i64,
+  #[diesel(sql_type = BigInt)]
+  online_riders: i64,
+}
+
+impl PriceMultiplier {
+  /// The multiplier for a ride on `config_id` requested at `now`. Surge
+  /// rules are only checked against demand once their time, zone and
+  /// service area match.
+  pub async fn for_ride(
+    pool: &mut DbPool<'_>,
+    config_id: PricingConfigId,
+    now: DateTime<Utc>,
+    pickup: Option<(f64, f64)>,
+    config: &SurgePricingConfig,
+  ) -> FastJobResult<Self> {
+    let pickup_area = match pickup {
+      Some((lat, lng)) => ServiceArea::find_for_point(pool, lat, lng).await?,
+      None => None,
+    };
+    let pickup_area_id = pickup_area.as_ref().map(|area| area.id);
+
+    let conn = &mut get_conn(pool).await?;
+    let rules = pricing_rule::table
+      .filter(pricing_rule::pricing_config_id.eq(config_id))
+      .filter(pricing_rule::is_active.eq(true))
+      .load::<PricingRule>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let local = (now + Duration::minutes(config.utc_offset_minutes.into())).naive_utc();
+    let located_since = now - Duration::minutes(config.location_max_age_minutes);
+
+    let mut matching = Vec::new();
+    for rule in rules {
+      if !rule.applies_at(local) || !rule.covers(pickup, pickup_area_id) {
+        continue;
+      }
+      if rule.kind == PricingRuleKind::Surge {
+        let threshold = rule.demand_ratio_threshold.unwrap_or(0.0);
+        let ratio = rule
+          .demand_ratio(conn, located_since, pickup_area.as_ref())
+          .await?;
+        if ratio < threshold {
+          continue;
+        }
+      }
+      matching.push(rule);
+    }
+
+    Ok(Self::combine(&matching, config.max_multiplier))
+  }
+
+  /// Time, weekday and holiday multipliers stack; of the surge rules only the
+  /// highest applies. The product is capped at `max_multiplier`.
+  pub fn combine(matching: &[PricingRule], max_multiplier: f64) -> Self {
+    let mut multiplier = 1.0;
+    let mut applied_rules = Vec::new();
+    let mut surge: Option<&PricingRule> = None;
+
+    for rule in matching {
+      if rule.kind == PricingRuleKind::Surge {
+        if surge.map_or(true, |s| rule.multiplier > s.multiplier) {
+          surge = Some(rule);
+        }
+      } else {
+        multiplier *= rule.multiplier;
+        applied_rules.push(rule.id);
+      }
+    }
+    if let Some(rule) = surge {
+      multiplier *= rule.multiplier;
+      applied_rules.push(rule.id);
+    }
+
+    Self {
+      multiplier: multiplier.min(max_multiplier),
+      applied_rules,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::NaiveDate;
+
+  fn rule(id: i32, kind: PricingRuleKind, multiplier: f64) -> PricingRule {
+    PricingRule {
+      id: PricingRuleId(id),
+      pricing_config_id: PricingConfigId(1),
+      name: format!("rule {id}"),
+      kind,
+      multiplier,
+      start_minute: None,
+      end_minute: None,
+      days_of_week: None,
+      holiday_date: None,
+      zone_lat: None,
+      zone_lng: None,
+      zone_radius_km: None,
+      demand_ratio_threshold: None,
+      is_active: true,
+      created_at: Utc::now(),
+      updated_at: None,
+      service_area_id: None,
+    }
+  }
+
+  fn at(date: (i32, u32, u32), hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+      .and_then(|d| d.and_hms_opt(hour, minute, 0))
+      .expect("valid date")
+  }
+
+  #[test]
+  fn night_window_wraps_midnight() {
+    let night = PricingRule {
+      start_minute: Some(22 * 60),
+      end_minute: Some(5 * 60),
+      ..rule(1, PricingRuleKind::TimeWindow, 1.5)
+    };
+
+    assert!(night.applies_at(at((2026, 3, 2), 2, 0)));
+    assert!(night.applies_at(at((2026, 3, 2), 23, 30)));
+    assert!(!night.applies_at(at((2026, 3, 2), 12, 0)));
+    assert!(!night.applies_at(at((2026, 3, 2), 5, 0)));
+  }
+
+  #[test]
+  fn rush_hour_only_on_listed_weekdays() {
+    let rush = PricingRule {
+      start_minute: Some(7 * 60),
+      end_minute: Some(9 * 60),
+      days_of_week: Some(vec![1, 2, 3, 4, 5]),
+      ..rule(1, PricingRuleKind::TimeWindow, 1.3)
+    };
+
+    // 2026-03-02 is a Monday, 2026-03-07 a Saturday
+    assert!(rush.applies_at(at((2026, 3, 2), 8, 0)));
+    assert!(!rush.applies_at(at((2026, 3, 7), 8, 0)));
+  }
+
+  #[test]
+  fn zoned_rule_needs_pickup_inside_radius() {
+    let zoned = PricingRule {
+      zone_lat: Some(13.7563),
+      zone_lng: Some(100.5018),
+      zone_radius_km: Some(5.0),
+      ..rule(1, PricingRuleKind::Surge, 2.0)
+    };
+
+    assert!(zoned.covers(Some((13.76, 100.50)), None));
+    assert!(!zoned.covers(Some((18.7883, 98.9853)), None));
+    assert!(!zoned.covers(None, None));
+  }
+
+  #[test]
+  fn area_rule_needs_pickup_in_that_area() {
+    let area_rule = PricingRule {
+      service_area_id: Some(ServiceAreaId(2)),
+      ..rule(1, PricingRuleKind::Surge, 1.5)
+    };
+
+    assert!(area_rule.covers(Some((13.76, 100.50)), Some(ServiceAreaId(2))));
+    assert!(!area_rule.covers(Some((13.76, 100.50)), Some(ServiceAreaId(3))));
+    assert!(!area_rule.covers(Some((13.76, 100.50)), None));
+    assert!(rule(2, PricingRuleKind::Surge, 1.5).covers(None, None));
+  }
+
+  #[test]
+  fn rules_stack_but_only_highest_surge_applies() {
+    let matching = vec![
+      rule(1, PricingRuleKind::TimeWindow, 1.5),
+      rule(2, PricingRuleKind::Holiday, 1.2),
+      rule(3, PricingRuleKind::Surge, 1.4),
+      rule(4, PricingRuleKind::Surge, 1.8),
+    ];
+
+    let combined = PriceMultiplier::combine(&matching, 10.0);
+    assert!((combined.multiplier - 1.5 * 1.2 * 1.8).abs() < 1e-9);
+    assert_eq!(
+      combined.applied_rules,
+      vec![PricingRuleId(1), PricingRuleId(2), PricingRuleId(4)]
+    );
+
+    let capped = PriceMultiplier::combine(&matching, 2.5);
+    assert!((capped.multiplier - 2.5).abs() < 1e-9);
+  }
+}
@@ -0,0 +1,458 @@
+use crate::{
+  enums::ReferralStatus,
+  newtypes::{Coin, CoinId, LocalUserId, WalletId},
+  schema::{local_user, login_token, person, referral, referral_code, user_bank_accounts},
+  source::{
+    referral::{
+      Referral,
+      ReferralCode,
+      ReferralCodeInsertForm,
+      ReferralInsertForm,
+      ReferralSummary,
+      ReferralUpdateForm,
+    },
+    wallet::{TxKind, WalletModel, WalletTransactionInsertForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::Utc;
+use diesel::{
+  dsl::{count_star, insert_into, sum, update},
+  ExpressionMethods,
+  JoinOnDsl,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncPgConnection, RunQueryDsl};
+
+/// Length of generated invite codes.
+const REFERRAL_CODE_LEN: usize = 8;
+
+/// Reasons recorded on rejected referrals. Kept short so the dashboard can
+/// map them to translated strings.
+const REJECT_SAME_DEVICE: &str = "same_device";
+const REJECT_SAME_IP: &str = "same_ip";
+const REJECT_IP_LIMIT: &str = "ip_limit";
+const REJECT_SAME_BANK_ACCOUNT: &str = "same_bank_account";
+
+impl ReferralCode {
+  /// Return the user's invite code, creating one on first use.
+  pub async fn get_or_create(
+    pool: &mut DbPool<'_>,
+    local_user_id: LocalUserId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    let existing = referral_code::table
+      .find(local_user_id)
+      .first::<Self>(conn)
+      .await
+      .optional()?;
+    if let Some(code) = existing {
+      return Ok(code);
+    }
+
+    let form = ReferralCodeInsertForm::new(local_user_id, generate_code());
+    insert_into(referral_code::table)
+      .values(&form)
+      .on_conflict(referral_code::local_user_id)
+      .do_nothing()
+      .execute(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateReferral)?;
+
+    // Re-read so a concurrent insert for the same user wins consistently.
+    referral_code::table
+      .find(local_user_id)
+      .first::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateReferral)
+  }
+
+  /// Look up an invite code (case-insensitive) on the supplied connection.
+  pub async fn find_by_code_on_conn(
+    conn: &mut AsyncPgConnection,
+    code: &str,
+  ) -> FastJobResult<Option<Self>> {
+    referral_code::table
+      .filter(referral_code::code.eq(code.trim().to_uppercase()))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+impl Referral {
+  /// Attribute a freshly registered user to the owner of `code`.
+  ///
+  /// Runs on the registration transaction's connection. An unknown or
+  /// self-referral code fails with `InvalidReferralCode` before anything is
+  /// written. Anti-abuse checks do not fail; they store the referral as
+  /// `Rejected` with a reason so it never pays out.
+  pub async fn create_on_signup(
+    conn: &mut AsyncPgConnection,
+    referred_id: LocalUserId,
+    code: &str,
+    signup_ip: Option<String>,
+    device_id: Option<String>,
+    max_referrals_per_ip: i64,
+  ) -> FastJobResult<Self> {
+    let owner = ReferralCode::find_by_code_on_conn(conn, code)
+      .await?
+      .ok_or(FastJobErrorType::InvalidReferralCode)?;
+    if owner.local_user_id == referred_id {
+      return Err(FastJobErrorType::InvalidReferralCode.into());
+    }
+    let referrer_id = owner.local_user_id;
+
+    let rejected_reason = Self::signup_abuse_reason(
+      conn,
+      referrer_id,
+      signup_ip.as_deref(),
+      device_id.as_deref(),
+      max_referrals_per_ip,
+    )
+    .await?;
+
+    let form = ReferralInsertForm {
+      status: Some(if rejected_reason.is_some() {
+        ReferralStatus::Rejected
+      } else {
+        ReferralStatus::Pending
+      }),
+      signup_ip,
+      device_id,
+      rejected_reason: rejected_reason.map(str::to_string),
+      ..ReferralInsertForm::new(referrer_id, referred_id, owner.code)
+    };
+
+    insert_into(referral::table)
+      .values(&form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateReferral)
+  }
+
+  /// Signup-time checks: a device may only be referred once, the signup IP
+  /// must not be one the referrer logged in from, and a single IP may only
+  /// produce a handful of referrals.
+  async fn signup_abuse_reason(
+    conn: &mut AsyncPgConnection,
+    referrer_id: LocalUserId,
+    signup_ip: Option<&str>,
+    device_id: Option<&str>,
+    max_referrals_per_ip: i64,
+  ) -> FastJobResult<Option<&'static str>> {
+    if let Some(device_id) = device_id {
+      // One referral per device, whoever the referrer is.
+      let reused: i64 = referral::table
+        .filter(referral::device_id.eq(device_id))
+        .select(count_star())
+        .first(conn)
+        .await?;
+      if reused > 0 {
+        return Ok(Some(REJECT_SAME_DEVICE));
+      }
+    }
+
+    if let Some(ip) = signup_ip {
+      let referrer_logins: i64 = login_token::table
+        .filter(login_token::user_id.eq(referrer_id))
+        .filter(login_token::ip.eq(ip))
+        .select(count_star())
+        .first(conn)
+        .await?;
+      if referrer_logins > 0 {
+        return Ok(Some(REJECT_SAME_IP));
+      }
+
+      let from_ip: i64 = referral::table
+        .filter(referral::signup_ip.eq(ip))
+        .select(count_star())
+        .first(conn)
+        .await?;
+      if from_ip >= max_referrals_per_ip {
+        return Ok(Some(REJECT_IP_LIMIT));
+      }
+    }
+
+    Ok(None)
+  }
+
+  /// Pay the referrer once the referred user completes their first paid
+  /// workflow or delivery.
+  ///
+  /// Only a `Pending` referral qualifies, so this is a no-op (`Ok(None)`) for
+  /// users without a referral or whose referral was already settled. The
+  /// reward is credited from the platform wallet with the deterministic key
+  /// `referral-reward:{referral_id}`, making retries safe.
+  pub async fn reward_first_completion(
+    pool: &mut DbPool<'_>,
+    referred_id: LocalUserId,
+    reference_type: &str,
+    reference_id: i32,
+    reward: Coin,
+    coin_id: CoinId,
+    platform_wallet_id: WalletId,
+  ) -> FastJobResult<Option<Self>> {
+    let reference_type = reference_type.to_string();
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let pending = referral::table
+            .filter(referral::referred_id.eq(referred_id))
+            .filter(referral::status.eq(ReferralStatus::Pending))
+            .for_update()
+            .first::<Self>(conn)
+            .await
+            .optional()?;
+          let Some(pending) = pending else {
+            return Ok(None);
+          };
+
+          // Payout-time check: both sides withdrawing to the same bank
+          // account means it is the same person.
+          if Self::share_bank_account(conn, pending.referrer_id, referred_id).await? {
+            let form = ReferralUpdateForm {
+              status: Some(ReferralStatus::Rejected),
+              rejected_reason: Some(Some(REJECT_SAME_BANK_ACCOUNT.to_string())),
+              ..Default::default()
+            };
+            return Self::update_on_conn(conn, &pending, &form).await.map(Some);
+          }
+
+          let referrer_wallet_id: WalletId = person::table
+            .inner_join(local_user::table.on(person::id.eq(local_user::person_id)))
+            .filter(local_user::id.eq(pending.referrer_id))
+            .select(person::wallet_id)
+            .first(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntFindWalletByUser)?;
+
+          let tx_form = WalletTransactionInsertForm {
+            wallet_id: referrer_wallet_id,
+            reference_type: "referral".to_string(),
+            reference_id: pending.id.0,
+            kind: TxKind::Deposit,
+            amount: reward,
+            description: format!("referral reward: user {}", referred_id.0),
+            counter_user_id: Some(referred_id),
+            idempotency_key: format!("referral-reward:{}", pending.id.0),
+          };
+          WalletModel::deposit_from_platform_on_conn(conn, &tx_form, coin_id, platform_wallet_id)
+            .await?;
+
+          let form = ReferralUpdateForm {
+            status: Some(ReferralStatus::Rewarded),
+            reward_coin: Some(Some(reward.0)),
+            reference_type: Some(Some(reference_type)),
+            reference_id: Some(Some(reference_id)),
+            rewarded_at: Some(Some(Utc::now())),
+            ..Default::default()
+          };
+          Self::update_on_conn(conn, &pending, &form).await.map(Some)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  async fn share_bank_account(
+    conn: &mut AsyncPgConnection,
+    referrer_id: LocalUserId,
+    referred_id: LocalUserId,
+  ) -> FastJobResult<bool> {
+    let referrer_accounts = user_bank_accounts::table
+      .filter(user_bank_accounts::local_user_id.eq(referrer_id))
+      .select(user_bank_accounts::account_number);
+
+    let shared: i64 = user_bank_accounts::table
+      .filter(user_bank_accounts::local_user_id.eq(referred_id))
+      .filter(user_bank_accounts::account_number.eq_any(referrer_accounts))
+      .select(count_star())
+      .first(conn)
+      .await?;
+    Ok(shared > 0)
+  }
+
+  async fn update_on_conn(
+    conn: &mut AsyncPgConnection,
+    current: &Self,
+    form: &ReferralUpdateForm,
+  ) -> FastJobResult<Self> {
+    update(referral::table.find(current.id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateReferral)
+  }
+
+  /// All referrals made by a user, newest first.
+  pub async fn list_for_referrer(
+    pool: &mut DbPool<'_>,
+    referrer_id: LocalUserId,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    referral::table
+      .filter(referral::referrer_id.eq(referrer_id))
+      .order(referral::created_at.desc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Per-status counters plus the total coins earned by a referrer.
+  pub async fn summary_for_referrer(
+    pool: &mut DbPool<'_>,
+    referrer_id: LocalUserId,
+  ) -> FastJobResult<ReferralSummary> {
+    let conn = &mut get_conn(pool).await?;
+
+    let counts: Vec<(ReferralStatus, i64)> = referral::table
+      .filter(referral::referrer_id.eq(referrer_id))
+      .group_by(referral::status)
+      .select((referral::status, count_star()))
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let earned: Option<i64> = referral::table
+      .filter(referral::referrer_id.eq(referrer_id))
+      .filter(referral::status.eq(ReferralStatus::Rewarded))
+      .select(sum(referral::reward_coin))
+      .first(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let mut summary = ReferralSummary {
+      earned_coin: earned.unwrap_or(0),
+      ..Default::default()
+    };
+    for (status, count) in counts {
+      match status {
+        ReferralStatus::Pending => summary.pending_count = count,
+        ReferralStatus::Rewarded => summary.rewarded_count = count,
+        ReferralStatus::Rejected => summary.rejected_count = count,
+      }
+    }
+    Ok(summary)
+  }
+}
+
+fn generate_code() -> String {
+  uuid::Uuid::new_v4().simple().to_string()[..REFERRAL_CODE_LEN].to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    test_data::{pool_for_tests, unique_test_domain},
+  };
+  use serial_test::serial;
+
+  async fn make_user(pool: &mut DbPool<'_>, inst: &Instance, name: &str) -> LocalUserId {
+    let (person_form, _) = PersonInsertForm::test_form_with_wallet(pool, inst.id, name)
+      .await
+      .expect("test_form_with_wallet");
+    let person = Person::create(pool, &person_form)
+      .await
+      .expect("create person");
+    LocalUser::create(pool, &LocalUserInsertForm::test_form(person.id), vec![])
+      .await
+      .expect("create local_user")
+      .id
+  }
+
+  /// A user's code is stable across calls and resolves case-insensitively.
+  #[tokio::test]
+  #[serial]
+  async fn code_is_created_once() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let inst = Instance::read_or_create(pool, unique_test_domain("referral"))
+      .await
+      .expect("create instance");
+    let user = make_user(pool, &inst, "ref_owner").await;
+
+    let first = ReferralCode::get_or_create(pool, user)
+      .await
+      .expect("create");
+    let second = ReferralCode::get_or_create(pool, user).await.expect("read");
+    assert_eq!(first.code, second.code);
+    assert_eq!(first.code.len(), REFERRAL_CODE_LEN);
+
+    let found = {
+      let conn = &mut get_conn(pool).await.expect("get conn");
+      ReferralCode::find_by_code_on_conn(conn, &first.code.to_lowercase())
+        .await
+        .expect("lookup")
+    };
+    assert_eq!(found.map(|c| c.local_user_id), Some(user));
+
+    let _ = Instance::delete(pool, inst.id).await;
+  }
+
+  /// Signups from the referrer's own login IP are stored as Rejected and
+  /// never show up as pending.
+  #[tokio::test]
+  #[serial]
+  async fn same_ip_signup_is_rejected() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let inst = Instance::read_or_create(pool, unique_test_domain("referral"))
+      .await
+      .expect("create instance");
+    let referrer = make_user(pool, &inst, "ref_referrer").await;
+    let honest = make_user(pool, &inst, "ref_honest").await;
+    let puppet = make_user(pool, &inst, "ref_puppet").await;
+    let code = ReferralCode::get_or_create(pool, referrer)
+      .await
+      .expect("code")
+      .code;
+
+    let (ok, bad) = {
+      let conn = &mut get_conn(pool).await.expect("get conn");
+      insert_into(login_token::table)
+        .values((
+          login_token::token.eq(uuid::Uuid::new_v4().to_string()),
+          login_token::user_id.eq(referrer),
+          login_token::ip.eq("10.0.0.1"),
+        ))
+        .execute(conn)
+        .await
+        .expect("insert login");
+
+      let ok = Referral::create_on_signup(conn, honest, &code, Some("10.0.0.2".into()), None, 3)
+        .await
+        .expect("honest referral");
+      let bad = Referral::create_on_signup(conn, puppet, &code, Some("10.0.0.1".into()), None, 3)
+        .await
+        .expect("puppet referral");
+      (ok, bad)
+    };
+    assert_eq!(ok.status, ReferralStatus::Pending);
+    assert_eq!(bad.status, ReferralStatus::Rejected);
+    assert_eq!(bad.rejected_reason.as_deref(), Some(REJECT_SAME_IP));
+
+    let summary = Referral::summary_for_referrer(pool, referrer)
+      .await
+      .expect("summary");
+    assert_eq!(summary.pending_count, 1);
+    assert_eq!(summary.rejected_count, 1);
+    assert_eq!(summary.earned_coin, 0);
+
+    let _ = Instance::delete(pool, inst.id).await;
+  }
+}
@@ -0,0 +1,145 @@
+use crate::{
+  newtypes::{PersonId, RideMeterFlagId, RideSessionId},
+  schema::ride_meter_flag,
+  source::ride_meter_flag::{RideMeterFlag, RideMeterFlagInsertForm, RideMeterFlagUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::MeterVerificationConfig,
+};
+use chrono::Utc;
+use diesel::{
+  dsl::{insert_into, update},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl Crud for RideMeterFlag {
+  type InsertForm = RideMeterFlagInsertForm;
+  type UpdateForm = RideMeterFlagUpdateForm;
+  type IdType = RideMeterFlagId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(ride_meter_flag::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateRideMeterFlag)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    flag_id: RideMeterFlagId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(ride_meter_flag::table.find(flag_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateRideMeterFlag)
+  }
+}
+
+impl RideMeterFlag {
+  /// Whether a reported reading is too far from the server's. Distances
+  /// within the absolute tolerance always pass; above it the relative
+  /// tolerance applies.
+  pub fn diverges(
+    reported_km: f64,
+    server_km: f64,
+    reported_minutes: i32,
+    server_minutes: i32,
+    config: &MeterVerificationConfig,
+  ) -> bool {
+    let distance_diff = (reported_km - server_km).abs();
+    let distance_off = distance_diff > config.distance_tolerance_km
+      && distance_diff > server_km * config.distance_tolerance_ratio;
+    let time_off = (reported_minutes - server_minutes).abs() > config.time_tolerance_minutes;
+
+    distance_off || time_off
+  }
+
+  /// Flags nobody has reviewed yet, oldest first.
+  pub async fn list_unreviewed(pool: &mut DbPool<'_>, limit: i64) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    ride_meter_flag::table
+      .filter(ride_meter_flag::reviewed_at.is_null())
+      .order(ride_meter_flag::created_at.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  pub async fn list_for_session(
+    pool: &mut DbPool<'_>,
+    session_id: RideSessionId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    ride_meter_flag::table
+      .filter(ride_meter_flag::ride_session_id.eq(session_id))
+      .order(ride_meter_flag::created_at.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Close a flag. Fails if another admin already reviewed it.
+  pub async fn mark_reviewed(
+    pool: &mut DbPool<'_>,
+    flag_id: RideMeterFlagId,
+    person_id: PersonId,
+    note: Option<String>,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(
+      ride_meter_flag::table
+        .find(flag_id)
+        .filter(ride_meter_flag::reviewed_at.is_null()),
+    )
+    .set((
+      ride_meter_flag::reviewed_at.eq(Utc::now()),
+      ride_meter_flag::reviewed_by_person_id.eq(person_id),
+      ride_meter_flag::resolution_note.eq(note),
+    ))
+    .get_result::<Self>(conn)
+    .await
+    .optional()
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateRideMeterFlag)?
+    .ok_or(FastJobErrorType::RideMeterFlagAlreadyReviewed.into())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn short_trips_use_absolute_tolerance() {
+    let config = MeterVerificationConfig::default();
+
+    // 0.4 km off is 40% of a 1 km trip but still under 0.5 km
+    assert!(!RideMeterFlag::diverges(1.4, 1.0, 5, 5, &config));
+    assert!(RideMeterFlag::diverges(2.0, 1.0, 5, 5, &config));
+  }
+
+  #[test]
+  fn long_trips_use_relative_tolerance() {
+    let config = MeterVerificationConfig::default();
+
+    assert!(!RideMeterFlag::diverges(11.5, 10.0, 30, 30, &config));
+    assert!(RideMeterFlag::diverges(13.0, 10.0, 30, 30, &config));
+    assert!(RideMeterFlag::diverges(10.0, 10.0, 40, 30, &config));
+  }
+}
@@ -1,19 +1,27 @@
 use crate::{
-  enums::TripStatus,
-  newtypes::{LocalUserId, PostId, RideSessionId, RiderId},
-  schema::ride_session,
-  source::ride_session::{RideSession, RideSessionInsertForm, RideSessionUpdateForm},
+  enums::{TripStatus, TxKind},
+  newtypes::{Coin, LocalUserId, PostId, RideSessionId, RiderId},
+  schema::{ride_session, rider},
+  source::{
+    ride_session::{RideSession, RideSessionInsertForm, RideSessionUpdateForm},
+    wallet::{WalletModel, WalletTransactionInsertForm},
+  },
   traits::Crud,
   utils::{get_conn, DbPool},
 };
-use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::SchedulingConfig,
+};
+use chrono::{DateTime, Duration, Utc};
 use diesel::{
   dsl::{insert_into, update},
+  BoolExpressionMethods,
   ExpressionMethods,
   OptionalExtension,
   QueryDsl,
 };
-use diesel_async::RunQueryDsl;
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
 
 impl Crud for RideSession {
   type InsertForm = RideSessionInsertForm;
@@ -127,17 +135,25 @@ impl RideSession {
       .with_fastjob_type(FastJobErrorType::DatabaseError)
   }
 
-  /// List available ride sessions that riders can accept (Pending status, no rider assigned)
+  /// List available ride sessions that riders can accept (Pending status, no rider assigned).
+  /// Scheduled rides only show up once they are inside the pre-dispatch window.
   pub async fn list_available_for_rider(
     pool: &mut DbPool<'_>,
     limit: Option<i64>,
+    scheduling: &SchedulingConfig,
   ) -> FastJobResult<Vec<Self>> {
     let conn = &mut get_conn(pool).await?;
     let limit = limit.unwrap_or(20);
+    let open_before = Utc::now() + Duration::minutes(scheduling.pre_dispatch_minutes);
 
     ride_session::table
       .filter(ride_session::status.eq(TripStatus::Pending))
       .filter(ride_session::rider_id.is_null())
+      .filter(
+        ride_session::scheduled_pickup_at
+          .is_null()
+          .or(ride_session::scheduled_pickup_at.le(open_before)),
+      )
       .order(ride_session::created_at.desc())
       .limit(limit)
       .load::<Self>(conn)
@@ -145,11 +161,149 @@ impl RideSession {
       .with_fastjob_type(FastJobErrorType::DatabaseError)
   }
 
+  /// Scheduled rides that no rider has reserved yet, soonest pickup first.
+  pub async fn list_scheduled_open(
+    pool: &mut DbPool<'_>,
+    limit: Option<i64>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let limit = limit.unwrap_or(20);
+
+    ride_session::table
+      .filter(ride_session::status.eq(TripStatus::Pending))
+      .filter(ride_session::rider_id.is_null())
+      .filter(ride_session::scheduled_pickup_at.gt(Utc::now()))
+      .order(ride_session::scheduled_pickup_at.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Reserve a scheduled ride for a rider. Only succeeds while the ride is
+  /// still Pending with no rider; the rider then confirms it as usual.
+  pub async fn reserve(
+    pool: &mut DbPool<'_>,
+    session_id: RideSessionId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let now = Utc::now();
+
+    let session = update(
+      ride_session::table
+        .find(session_id)
+        .filter(ride_session::scheduled_pickup_at.is_not_null())
+        .filter(ride_session::status.eq(TripStatus::Pending))
+        .filter(ride_session::rider_id.is_null()),
+    )
+    .set((
+      ride_session::rider_id.eq(rider_id),
+      ride_session::status.eq(TripStatus::Assigned),
+      ride_session::rider_assigned_at.eq(now),
+      ride_session::updated_at.eq(now),
+    ))
+    .get_result::<Self>(conn)
+    .await
+    .optional()
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateRideSession)?
+    .ok_or(FastJobErrorType::ScheduledJobAlreadyReserved)?;
+    Ok(session)
+  }
+
+  /// Reserved rides whose pickup is within `before` and whose rider has not
+  /// been reminded yet.
+  pub async fn list_due_reminders(
+    pool: &mut DbPool<'_>,
+    before: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    ride_session::table
+      .filter(ride_session::scheduled_pickup_at.le(before))
+      .filter(ride_session::scheduled_pickup_at.gt(Utc::now()))
+      .filter(ride_session::reminder_sent_at.is_null())
+      .filter(ride_session::rider_id.is_not_null())
+      .filter(ride_session::status.eq_any([TripStatus::Assigned, TripStatus::RiderConfirmed]))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Scheduled rides still without a confirmed rider once their pickup is
+  /// within `before`.
+  pub async fn list_unconfirmed_scheduled(
+    pool: &mut DbPool<'_>,
+    before: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    ride_session::table
+      .filter(ride_session::scheduled_pickup_at.le(before))
+      .filter(ride_session::status.eq_any([TripStatus::Pending, TripStatus::Assigned]))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Cancel a scheduled ride that still has no confirmed rider, freeing a
+  /// reserved rider to accept jobs again. `None` when the ride was confirmed
+  /// or cancelled since it was listed.
+  pub async fn expire_unconfirmed(
+    pool: &mut DbPool<'_>,
+    session_id: RideSessionId,
+    reason: &str,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let Some(session) = update(
+            ride_session::table
+              .find(session_id)
+              .filter(ride_session::status.eq_any([TripStatus::Pending, TripStatus::Assigned])),
+          )
+          .set((
+            ride_session::status.eq(TripStatus::Cancelled),
+            ride_session::cancellation_reason.eq(reason),
+            ride_session::updated_at.eq(Utc::now()),
+          ))
+          .get_result::<Self>(conn)
+          .await
+          .optional()
+          .with_fastjob_type(FastJobErrorType::CouldntUpdateRideSession)?
+          else {
+            return Ok(None);
+          };
+
+          if let Some(rider_id) = session.rider_id {
+            update(rider::table.find(rider_id))
+              .set(rider::accepting_jobs.eq(true))
+              .execute(conn)
+              .await
+              .with_fastjob_type(FastJobErrorType::CouldntUpdateRider)?;
+          }
+          Ok(Some(session))
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
   /// Check if a rider has any active (non-terminal) ride sessions
   /// Active statuses: Pending, Assigned, EnRouteToPickup, PickedUp, EnRouteToDropoff,
   /// RiderConfirmed Terminal statuses: Delivered, Cancelled
-  pub async fn has_active_session(pool: &mut DbPool<'_>, rider_id: RiderId) -> FastJobResult<bool> {
+  ///
+  /// A reserved scheduled ride only keeps the rider busy once its pickup is
+  /// inside the pre-dispatch window.
+  pub async fn has_active_session(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+    scheduling: &SchedulingConfig,
+  ) -> FastJobResult<bool> {
     let conn = &mut get_conn(pool).await?;
+    let busy_from = Utc::now() + Duration::minutes(scheduling.pre_dispatch_minutes);
 
     let active_statuses = vec![
       TripStatus::Pending,
@@ -163,6 +317,11 @@ impl RideSession {
     let count: i64 = ride_session::table
       .filter(ride_session::rider_id.eq(rider_id))
       .filter(ride_session::status.eq_any(active_statuses))
+      .filter(
+        ride_session::scheduled_pickup_at
+          .is_null()
+          .or(ride_session::scheduled_pickup_at.le(busy_from)),
+      )
       .count()
       .get_result(conn)
       .await
@@ -171,6 +330,101 @@ impl RideSession {
     Ok(count > 0)
   }
 
+  /// Connection-scoped assignment used by automatic dispatch. Only succeeds
+  /// while the ride is still Pending with no rider, so a manual assignment
+  /// and an accepted offer cannot both win.
+  pub async fn assign_dispatched_on_conn(
+    conn: &mut diesel_async::AsyncPgConnection,
+    post_id: PostId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let now = Utc::now();
+    let session = update(
+      ride_session::table
+        .filter(ride_session::post_id.eq(post_id))
+        .filter(ride_session::status.eq(TripStatus::Pending))
+        .filter(ride_session::rider_id.is_null()),
+    )
+    .set((
+      ride_session::rider_id.eq(rider_id),
+      ride_session::status.eq(TripStatus::Assigned),
+      ride_session::rider_assigned_at.eq(now),
+      ride_session::updated_at.eq(now),
+    ))
+    .get_result::<Self>(conn)
+    .await
+    .optional()
+    .with_fastjob_type(FastJobErrorType::CouldntUpdateRideSession)?
+    .ok_or(FastJobErrorType::DispatchJobNoLongerOpen)?;
+    Ok(session)
+  }
+
+  /// Cancel a ride that is not yet Delivered or Cancelled, moving the
+  /// employer's cancellation `fee` to the assigned rider's wallet in the
+  /// same transaction. A failed transfer, e.g. on a short balance, leaves
+  /// the ride as it was.
+  ///
+  /// Idempotency key `ride-cancel-fee:{session_id}` makes a retried
+  /// cancellation safe.
+  pub async fn cancel_with_rider_fee(
+    pool: &mut DbPool<'_>,
+    session_id: RideSessionId,
+    reason: String,
+    fee: Coin,
+    cancelled_at: DateTime<Utc>,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let session = update(ride_session::table.find(session_id).filter(
+            ride_session::status.ne_all(vec![TripStatus::Delivered, TripStatus::Cancelled]),
+          ))
+          .set((
+            ride_session::status.eq(TripStatus::Cancelled),
+            ride_session::cancellation_reason.eq(reason),
+            ride_session::updated_at.eq(cancelled_at),
+          ))
+          .get_result::<Self>(conn)
+          .await
+          .optional()
+          .with_fastjob_type(FastJobErrorType::CouldntUpdateRideSession)?
+          .ok_or(FastJobErrorType::CannotCancelCompletedRide)?;
+
+          if let Some(rider_id) = session.rider_id.filter(|_| fee.0 > 0) {
+            let rider_user_id = rider::table
+              .find(rider_id)
+              .select(rider::user_id)
+              .first::<LocalUserId>(conn)
+              .await
+              .map_err(|_| FastJobErrorType::NotFound)?;
+            let form_out = WalletTransactionInsertForm {
+              wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, session.employer_id).await?,
+              reference_type: "ride_session".to_string(),
+              reference_id: session_id.0,
+              kind: TxKind::Transfer,
+              amount: fee,
+              description: format!("cancellation fee for ride: session {}", session_id.0),
+              counter_user_id: Some(rider_user_id),
+              idempotency_key: format!("ride-cancel-fee:{}", session_id.0),
+            };
+            let form_in = WalletTransactionInsertForm {
+              wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, rider_user_id).await?,
+              counter_user_id: Some(session.employer_id),
+              ..form_out.clone()
+            };
+            let mut pool: DbPool<'_> = conn.into();
+            WalletModel::transfer_between_wallets(&mut pool, &form_out, &form_in).await?;
+          }
+
+          Ok(session)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
   /// Check if a post already has a ride session
   pub async fn exists_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<bool> {
     let conn = &mut get_conn(pool).await?;
@@ -324,6 +578,7 @@ mod tests {
       status: Some(TripStatus::Pending),
       requested_at: Some(Utc::now()),
       current_price_coin: Some(0),
+      scheduled_pickup_at: None,
     }
   }
 
@@ -386,9 +641,12 @@ mod tests {
     .await
     .expect("assign");
 
-    assert!(RideSession::has_active_session(pool, ctx.rider_id)
-      .await
-      .expect("active 1"));
+    let scheduling = SchedulingConfig::default();
+    assert!(
+      RideSession::has_active_session(pool, ctx.rider_id, &scheduling)
+        .await
+        .expect("active 1")
+    );
 
     // Walk through to Delivered.
     for next in [
@@ -412,7 +670,7 @@ mod tests {
     }
 
     assert!(
-      !RideSession::has_active_session(pool, ctx.rider_id)
+      !RideSession::has_active_session(pool, ctx.rider_id, &scheduling)
         .await
         .expect("active 2"),
       "rider should be free once ride is Delivered"
@@ -432,7 +690,8 @@ mod tests {
       .await
       .expect("create");
 
-    let available_before = RideSession::list_available_for_rider(pool, Some(100))
+    let scheduling = SchedulingConfig::default();
+    let available_before = RideSession::list_available_for_rider(pool, Some(100), &scheduling)
       .await
       .expect("list 1");
     assert!(available_before.iter().any(|r| r.id == session.id));
@@ -450,7 +709,7 @@ mod tests {
     .await
     .expect("assign");
 
-    let available_after = RideSession::list_available_for_rider(pool, Some(100))
+    let available_after = RideSession::list_available_for_rider(pool, Some(100), &scheduling)
       .await
       .expect("list 2");
     assert!(
@@ -460,8 +719,76 @@ mod tests {
     cleanup(pool, ctx.instance_id).await;
   }
 
+  /// A ride booked for tomorrow stays out of the available list, can be
+  /// reserved once, and does not keep the rider busy today.
+  #[tokio::test]
+  #[serial]
+  async fn scheduled_ride_reservation() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let ctx = fixture(pool).await;
+    let scheduling = SchedulingConfig::default();
+    let form = RideSessionInsertForm {
+      scheduled_pickup_at: Some(Utc::now() + Duration::days(1)),
+      ..pending_form(&ctx)
+    };
+    let session = RideSession::create(pool, &form).await.expect("create");
+
+    let available = RideSession::list_available_for_rider(pool, Some(100), &scheduling)
+      .await
+      .expect("list available");
+    assert!(!available.iter().any(|r| r.id == session.id));
+    let scheduled = RideSession::list_scheduled_open(pool, Some(100))
+      .await
+      .expect("list scheduled");
+    assert!(scheduled.iter().any(|r| r.id == session.id));
+
+    let reserved = RideSession::reserve(pool, session.id, ctx.rider_id)
+      .await
+      .expect("reserve");
+    assert_eq!(reserved.status, TripStatus::Assigned);
+    assert!(RideSession::reserve(pool, session.id, ctx.rider_id)
+      .await
+      .is_err());
+    assert!(
+      !RideSession::has_active_session(pool, ctx.rider_id, &scheduling)
+        .await
+        .expect("active")
+    );
+    cleanup(pool, ctx.instance_id).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn expire_unconfirmed_frees_rider_once() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let ctx = fixture(pool).await;
+    let form = RideSessionInsertForm {
+      scheduled_pickup_at: Some(Utc::now() + Duration::minutes(10)),
+      ..pending_form(&ctx)
+    };
+    let session = RideSession::create(pool, &form).await.expect("create");
+    RideSession::reserve(pool, session.id, ctx.rider_id)
+      .await
+      .expect("reserve");
+
+    let expired = RideSession::expire_unconfirmed(pool, session.id, "expired")
+      .await
+      .expect("expire")
+      .expect("still unconfirmed");
+    assert_eq!(expired.status, TripStatus::Cancelled);
+    let rider = Rider::read(pool, ctx.rider_id).await.expect("rider");
+    assert!(rider.accepting_jobs);
+    assert!(RideSession::expire_unconfirmed(pool, session.id, "expired")
+      .await
+      .expect("expire again")
+      .is_none());
+    cleanup(pool, ctx.instance_id).await;
+  }
+
   /// Cancellation is reflected by status alone; no rider re-assignment happens
-  /// at this DB layer (cancel_ride_session in api/api/src/delivery/ride.rs
+  /// at this DB layer (cancel_ride_session in logistics/src/handlers/ride.rs
   /// is what flips rider.accepting_jobs back).
   #[tokio::test]
   #[serial]
@@ -493,4 +820,40 @@ mod tests {
     );
     cleanup(pool, ctx.instance_id).await;
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn cancel_with_rider_fee_only_cancels_once() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let ctx = fixture(pool).await;
+    let session = RideSession::create(pool, &pending_form(&ctx))
+      .await
+      .expect("create");
+
+    let cancelled = RideSession::cancel_with_rider_fee(
+      pool,
+      session.id,
+      "changed plans".into(),
+      Coin(0),
+      Utc::now(),
+    )
+    .await
+    .expect("cancel");
+    assert_eq!(cancelled.status, TripStatus::Cancelled);
+    assert_eq!(
+      cancelled.cancellation_reason.as_deref(),
+      Some("changed plans")
+    );
+    assert!(RideSession::cancel_with_rider_fee(
+      pool,
+      session.id,
+      "again".into(),
+      Coin(0),
+      Utc::now()
+    )
+    .await
+    .is_err());
+    cleanup(pool, ctx.instance_id).await;
+  }
 }
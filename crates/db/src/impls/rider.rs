@@ -1,4 +1,5 @@
 use crate::{
+  enums::RiderVerificationStatus,
   newtypes::{LocalUserId, PersonId, RiderId},
   schema::rider,
   source::rider::{Rider, RiderInsertForm, RiderUpdateForm},
@@ -6,6 +7,7 @@ use crate::{
   utils::{get_conn, DbPool},
 };
 use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::{DateTime, Utc};
 use diesel::{
   dsl::{exists, insert_into, select, update},
   ExpressionMethods,
@@ -15,6 +17,9 @@ use diesel::{
 };
 use diesel_async::RunQueryDsl;
 
+/// Days before license expiry at which the rider is emailed.
+pub const LICENSE_REMINDER_DAYS: [i32; 3] = [30, 7, 1];
+
 impl Crud for Rider {
   type InsertForm = RiderInsertForm;
   type UpdateForm = RiderUpdateForm;
@@ -105,6 +110,98 @@ impl Rider {
     .await
     .map_err(|_| FastJobErrorType::DatabaseError.into())
   }
+
+  /// Like `get_by_person_id`, but also finds a deactivated profile, so a
+  /// suspended rider can still submit new documents.
+  pub async fn get_by_person_id_with_inactive(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider::table
+      .filter(rider::person_id.eq(person_id.0))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Active riders whose license expires within `before` but has not yet.
+  pub async fn list_license_expiring(
+    pool: &mut DbPool<'_>,
+    before: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider::table
+      .filter(rider::is_active.eq(true))
+      .filter(rider::license_expiry_date.gt(Utc::now()))
+      .filter(rider::license_expiry_date.le(before))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Riders whose license has expired and who are not suspended for it yet.
+  pub async fn list_license_expired(pool: &mut DbPool<'_>) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider::table
+      .filter(rider::license_expiry_date.le(Utc::now()))
+      .filter(rider::documents_expired_at.is_null())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// The reminder, in days before expiry, that is due for this rider's
+  /// license, if any. Each of `LICENSE_REMINDER_DAYS` is sent at most once
+  /// per license; a rider first seen inside a window only gets that one.
+  pub fn license_reminder_due(&self, now: DateTime<Utc>) -> Option<i32> {
+    let expiry = self.license_expiry_date.filter(|e| *e > now)?;
+    // Round partial days up, so a license expiring in 6.5 days is "7 days"
+    let days_left = ((expiry - now).num_hours() + 23) / 24;
+    let due = LICENSE_REMINDER_DAYS
+      .iter()
+      .copied()
+      .filter(|days| i64::from(*days) >= days_left)
+      .min()?;
+    match self.license_reminder_days {
+      Some(sent) if sent <= due => None,
+      _ => Some(due),
+    }
+  }
+
+  /// Whether the rider may not take jobs until new documents are approved.
+  pub fn documents_expired(&self, now: DateTime<Utc>) -> bool {
+    self.documents_expired_at.is_some() || self.license_expiry_date.is_some_and(|e| e <= now)
+  }
+
+  /// Whether the rider is serving a ban on accepting jobs for cancelling too
+  /// often.
+  pub fn accept_banned(&self, now: DateTime<Utc>) -> bool {
+    self.accept_banned_until.is_some_and(|until| until > now)
+  }
+
+  /// Take a rider with an expired license off the road: inactive, not
+  /// accepting jobs, and back to pending verification until an admin
+  /// approves new documents.
+  pub async fn suspend_for_expired_documents(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let form = RiderUpdateForm {
+      is_active: Some(false),
+      accepting_jobs: Some(false),
+      is_verified: Some(false),
+      verification_status: Some(RiderVerificationStatus::Pending),
+      verified_at: Some(None),
+      documents_expired_at: Some(Some(Utc::now())),
+      ..Default::default()
+    };
+    Self::update(pool, rider_id, &form).await
+  }
 }
 
 // ============================================================================
@@ -283,4 +380,50 @@ mod tests {
     );
     cleanup(pool, ctx.instance_id).await;
   }
+
+  /// Reminders go out once per threshold, and an expired license suspends
+  /// the rider back to pending verification.
+  #[tokio::test]
+  #[serial]
+  async fn license_expiry_reminders_and_suspension() {
+    let pool = pool_for_tests();
+    let pool = &mut (&pool).into();
+    let ctx = make_rider_user(pool).await;
+    let rider = Rider::create(pool, &insert_form(&ctx))
+      .await
+      .expect("create rider");
+    let now = Utc::now();
+
+    let in_20_days = Rider {
+      license_expiry_date: Some(now + chrono::Duration::days(20)),
+      ..rider.clone()
+    };
+    assert_eq!(in_20_days.license_reminder_due(now), Some(30));
+    let reminded = Rider {
+      license_reminder_days: Some(30),
+      ..in_20_days
+    };
+    assert_eq!(reminded.license_reminder_due(now), None);
+    let in_6_days = Rider {
+      license_expiry_date: Some(now + chrono::Duration::hours(6 * 24 + 12)),
+      ..reminded
+    };
+    assert_eq!(in_6_days.license_reminder_due(now), Some(7));
+
+    let suspended = Rider::suspend_for_expired_documents(pool, rider.id)
+      .await
+      .expect("suspend");
+    assert!(!suspended.is_active);
+    assert!(!suspended.accepting_jobs);
+    assert_eq!(
+      suspended.verification_status,
+      RiderVerificationStatus::Pending
+    );
+    assert!(suspended.documents_expired(now));
+    let found = Rider::get_by_person_id_with_inactive(pool, ctx.person_id)
+      .await
+      .expect("get with inactive");
+    assert_eq!(found.map(|r| r.id), Some(rider.id));
+    cleanup(pool, ctx.instance_id).await;
+  }
 }
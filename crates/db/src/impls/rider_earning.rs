@@ -0,0 +1,255 @@
+use crate::{
+  newtypes::{Coin, PostId, RiderEarningHoldId, RiderId, WalletId},
+  schema::{rider, rider_earning_hold, wallet_transaction},
+  source::{
+    rider::Rider,
+    rider_earning::{
+      RiderEarningHold,
+      RiderEarningHoldInsertForm,
+      RiderEarningsBucket,
+      RiderEarningsPeriod,
+      RiderEarningsSummary,
+    },
+    wallet::{TxKind, WalletModel, WalletTransactionInsertForm},
+  },
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{count_star, insert_into, sum, update},
+  sql_query,
+  sql_types::{BigInt, Integer, Text, Timestamptz},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+  TextExpressionMethods,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncPgConnection, RunQueryDsl};
+use tracing::warn;
+
+/// Delivery payouts are journalled on the rider's wallet with this reference
+/// type and a `release:` idempotency key.
+const DELIVERY_REFERENCE: &str = "delivery";
+const DELIVERY_RELEASE_KEY: &str = "release:%";
+
+/// Holds released per scheduler run.
+const RELEASE_BATCH: i64 = 500;
+
+impl RiderEarningHold {
+  /// Keep a delivery payout pending in the rider's wallet until `clears_at`.
+  /// Returns `None` if the payout already has a hold.
+  ///
+  /// Connection-scoped: the caller releases the payout in the same
+  /// transaction, so the rider can never withdraw it before the hold.
+  pub async fn place_on_conn(
+    conn: &mut AsyncPgConnection,
+    rider: &Rider,
+    post_id: PostId,
+    amount: Coin,
+    clears_at: DateTime<Utc>,
+  ) -> FastJobResult<Option<Self>> {
+    let form = RiderEarningHoldInsertForm::new(rider.id, post_id, amount.0, clears_at);
+    let hold = insert_into(rider_earning_hold::table)
+      .values(&form)
+      .on_conflict(rider_earning_hold::post_id)
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::CouldntCreateRiderEarningHold)?;
+    let Some(hold) = hold else {
+      return Ok(None);
+    };
+
+    let tx_form = WalletTransactionInsertForm {
+      wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, rider.user_id).await?,
+      reference_type: "earning_hold".to_string(),
+      reference_id: post_id.0,
+      kind: TxKind::Reserve,
+      amount,
+      description: format!("earnings pending clearance: post {}", post_id.0),
+      counter_user_id: None,
+      idempotency_key: format!("earning-hold:{}", post_id.0),
+    };
+    WalletModel::reserve_on_conn(conn, &tx_form).await?;
+    Ok(Some(hold))
+  }
+
+  /// Make every hold past its clearing time withdrawable. A hold that fails
+  /// to release is logged and retried on the next run.
+  pub async fn release_due(pool: &mut DbPool<'_>) -> FastJobResult<usize> {
+    let due: Vec<RiderEarningHoldId> = {
+      let conn = &mut get_conn(pool).await?;
+      rider_earning_hold::table
+        .filter(rider_earning_hold::released_at.is_null())
+        .filter(rider_earning_hold::clears_at.le(Utc::now()))
+        .order(rider_earning_hold::clears_at.asc())
+        .select(rider_earning_hold::id)
+        .limit(RELEASE_BATCH)
+        .load(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?
+    };
+
+    let mut released = 0;
+    for hold_id in due {
+      match Self::release(pool, hold_id).await {
+        Ok(true) => released += 1,
+        Ok(false) => {}
+        Err(e) => warn!(
+          ?e,
+          hold_id = hold_id.0,
+          "Failed to release rider earning hold"
+        ),
+      }
+    }
+    Ok(released)
+  }
+
+  /// Release one hold. Returns `false` if it was already released.
+  async fn release(pool: &mut DbPool<'_>, hold_id: RiderEarningHoldId) -> FastJobResult<bool> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let hold = rider_earning_hold::table
+            .find(hold_id)
+            .for_update()
+            .first::<Self>(conn)
+            .await?;
+          if hold.released_at.is_some() {
+            return Ok(false);
+          }
+          let rider_user_id = rider::table
+            .find(hold.rider_id)
+            .select(rider::user_id)
+            .first(conn)
+            .await?;
+
+          let tx_form = WalletTransactionInsertForm {
+            wallet_id: WalletModel::wallet_id_for_user_on_conn(conn, rider_user_id).await?,
+            reference_type: "earning_hold".to_string(),
+            reference_id: hold.post_id.0,
+            kind: TxKind::Release,
+            amount: Coin(hold.amount_coin),
+            description: format!("earnings cleared: post {}", hold.post_id.0),
+            counter_user_id: None,
+            idempotency_key: format!("earning-clear:{}", hold.id.0),
+          };
+          WalletModel::release_on_conn(conn, &tx_form).await?;
+
+          update(rider_earning_hold::table.find(hold.id))
+            .set(rider_earning_hold::released_at.eq(Utc::now()))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntReleaseRiderEarningHold)?;
+          Ok(true)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  pub async fn list_pending_for_rider(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider_earning_hold::table
+      .filter(rider_earning_hold::rider_id.eq(rider_id))
+      .filter(rider_earning_hold::released_at.is_null())
+      .order(rider_earning_hold::clears_at.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+impl RiderEarningsSummary {
+  pub async fn for_rider(pool: &mut DbPool<'_>, rider: &Rider) -> FastJobResult<Self> {
+    let wallet = WalletModel::get_by_user(pool, rider.user_id).await?;
+    let conn = &mut get_conn(pool).await?;
+
+    let (total_earned, paid_deliveries): (Option<i64>, i64) = wallet_transaction::table
+      .filter(wallet_transaction::wallet_id.eq(wallet.id))
+      .filter(wallet_transaction::reference_type.eq(DELIVERY_REFERENCE))
+      .filter(wallet_transaction::idempotency_key.like(DELIVERY_RELEASE_KEY))
+      .select((sum(wallet_transaction::amount), count_star()))
+      .first(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let pending: Option<i64> = rider_earning_hold::table
+      .filter(rider_earning_hold::rider_id.eq(rider.id))
+      .filter(rider_earning_hold::released_at.is_null())
+      .select(sum(rider_earning_hold::amount_coin))
+      .first(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(Self {
+      total_earned_coin: total_earned.unwrap_or(0),
+      pending_coin: pending.unwrap_or(0),
+      withdrawable_coin: wallet.balance_available,
+      paid_deliveries,
+    })
+  }
+}
+
+#[derive(QueryableByName)]
+struct BucketRow {
+  #[diesel(sql_type = Timestamptz)]
+  period_start: DateTime<Utc>,
+  #[diesel(sql_type = BigInt)]
+  earned_coin: i64,
+  #[diesel(sql_type = BigInt)]
+  deliveries: i64,
+}
+
+impl RiderEarningsBucket {
+  /// Delivery payouts grouped by day or week, newest first.
+  pub async fn list_for_wallet(
+    pool: &mut DbPool<'_>,
+    wallet_id: WalletId,
+    period: RiderEarningsPeriod,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let rows: Vec<BucketRow> = sql_query(
+      r#"
+      SELECT date_trunc($2, created_at)  AS period_start,
+             SUM(amount)::bigint         AS earned_coin,
+             COUNT(*)                    AS deliveries
+      FROM wallet_transaction
+      WHERE wallet_id = $1
+        AND reference_type = $3
+        AND idempotency_key LIKE $4
+      GROUP BY 1
+      ORDER BY 1 DESC
+      LIMIT $5
+      "#,
+    )
+    .bind::<Integer, _>(wallet_id.0)
+    .bind::<Text, _>(period.trunc_field())
+    .bind::<Text, _>(DELIVERY_REFERENCE)
+    .bind::<Text, _>(DELIVERY_RELEASE_KEY)
+    .bind::<BigInt, _>(limit)
+    .load(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(
+      rows
+        .into_iter()
+        .map(|r| Self {
+          period_start: r.period_start,
+          earned_coin: r.earned_coin,
+          deliveries: r.deliveries,
+        })
+        .collect(),
+    )
+  }
+}
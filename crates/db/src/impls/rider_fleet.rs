@@ -0,0 +1,318 @@
+use crate::{
+  enums::{DispatchJobKind, FleetDocumentStatus, TripStatus},
+  newtypes::{Coin, FleetDocumentId, LocalUserId, PostId, RiderFleetId, RiderId},
+  schema::{delivery_details, fleet_document, ride_session, rider_fleet},
+  source::rider_fleet::{
+    FleetDocument,
+    FleetDocumentInsertForm,
+    FleetDocumentUpdateForm,
+    FleetRiderJob,
+    RiderFleet,
+    RiderFleetInsertForm,
+    RiderFleetUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::Utc;
+use diesel::{
+  dsl::{count_star, insert_into, update},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+/// Length of generated join codes.
+const JOIN_CODE_LEN: usize = 10;
+
+/// Trips that still need their rider.
+const ACTIVE_TRIP_STATUSES: [TripStatus; 5] = [
+  TripStatus::Assigned,
+  TripStatus::RiderConfirmed,
+  TripStatus::EnRouteToPickup,
+  TripStatus::PickedUp,
+  TripStatus::EnRouteToDropoff,
+];
+
+impl Crud for RiderFleet {
+  type InsertForm = RiderFleetInsertForm;
+  type UpdateForm = RiderFleetUpdateForm;
+  type IdType = RiderFleetId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(rider_fleet::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateFleet)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: RiderFleetId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(rider_fleet::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateFleet)
+  }
+}
+
+impl RiderFleet {
+  pub fn generate_join_code() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..JOIN_CODE_LEN].to_uppercase()
+  }
+
+  /// The fleet's cut of a delivery payout of one of its riders, rounded down
+  /// in the rider's favour. Only a verified fleet routing earnings takes a
+  /// cut, and owners driving for their own fleet keep the whole payout.
+  pub fn payout_share(
+    &self,
+    rider_user_id: LocalUserId,
+    payout: Coin,
+    max_share_percent: f64,
+  ) -> i32 {
+    if !self.is_verified || !self.route_earnings || self.owner_id == rider_user_id {
+      return 0;
+    }
+    let share = self
+      .fleet_share_percent
+      .clamp(0.0, max_share_percent.max(0.0));
+    (f64::from(payout.0.max(0)) * share / 100.0).floor() as i32
+  }
+
+  /// The fleet the user owns, if any.
+  pub async fn read_for_owner(
+    pool: &mut DbPool<'_>,
+    owner_id: LocalUserId,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider_fleet::table
+      .filter(rider_fleet::owner_id.eq(owner_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Look up a join code, case-insensitively.
+  pub async fn read_by_join_code(pool: &mut DbPool<'_>, code: &str) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider_fleet::table
+      .filter(rider_fleet::join_code.eq(code.trim().to_uppercase()))
+      .first::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::FleetJoinCodeInvalid)
+  }
+
+  /// The deliveries and rides the given riders are on right now.
+  pub async fn active_jobs(
+    pool: &mut DbPool<'_>,
+    rider_ids: &[RiderId],
+  ) -> FastJobResult<Vec<FleetRiderJob>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let deliveries: Vec<(Option<RiderId>, PostId, TripStatus)> = delivery_details::table
+      .filter(delivery_details::assigned_rider_id.eq_any(rider_ids))
+      .filter(delivery_details::status.eq_any(ACTIVE_TRIP_STATUSES))
+      .select((
+        delivery_details::assigned_rider_id,
+        delivery_details::post_id,
+        delivery_details::status,
+      ))
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    let rides: Vec<(Option<RiderId>, PostId, TripStatus)> = ride_session::table
+      .filter(ride_session::rider_id.eq_any(rider_ids))
+      .filter(ride_session::status.eq_any(ACTIVE_TRIP_STATUSES))
+      .select((
+        ride_session::rider_id,
+        ride_session::post_id,
+        ride_session::status,
+      ))
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let job = |job_kind| {
+      move |(rider_id, post_id, status): (Option<RiderId>, PostId, TripStatus)| {
+        rider_id.map(|rider_id| FleetRiderJob {
+          rider_id,
+          job_kind,
+          post_id,
+          status,
+        })
+      }
+    };
+    Ok(
+      deliveries
+        .into_iter()
+        .filter_map(job(DispatchJobKind::Delivery))
+        .chain(rides.into_iter().filter_map(job(DispatchJobKind::Ride)))
+        .collect(),
+    )
+  }
+
+  /// Mark the fleet verified while it has documents and all are approved,
+  /// and unverified otherwise.
+  pub async fn refresh_verification(
+    pool: &mut DbPool<'_>,
+    fleet_id: RiderFleetId,
+  ) -> FastJobResult<Self> {
+    let (total, approved) = {
+      let conn = &mut get_conn(pool).await?;
+      let total: i64 = fleet_document::table
+        .filter(fleet_document::fleet_id.eq(fleet_id))
+        .select(count_star())
+        .first(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+      let approved: i64 = fleet_document::table
+        .filter(fleet_document::fleet_id.eq(fleet_id))
+        .filter(fleet_document::status.eq(FleetDocumentStatus::Approved))
+        .select(count_star())
+        .first(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+      (total, approved)
+    };
+
+    let fleet = Self::read(pool, fleet_id).await?;
+    let verified = total > 0 && approved == total;
+    if verified == fleet.is_verified {
+      return Ok(fleet);
+    }
+    let now = Utc::now();
+    let form = RiderFleetUpdateForm {
+      is_verified: Some(verified),
+      verified_at: Some(verified.then_some(now)),
+      updated_at: Some(Some(now)),
+      ..Default::default()
+    };
+    Self::update(pool, fleet_id, &form).await
+  }
+}
+
+impl Crud for FleetDocument {
+  type InsertForm = FleetDocumentInsertForm;
+  type UpdateForm = FleetDocumentUpdateForm;
+  type IdType = FleetDocumentId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(fleet_document::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateFleetDocument)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: FleetDocumentId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(fleet_document::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateFleetDocument)
+  }
+}
+
+impl FleetDocument {
+  pub async fn list_for_fleet(
+    pool: &mut DbPool<'_>,
+    fleet_id: RiderFleetId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    fleet_document::table
+      .filter(fleet_document::fleet_id.eq(fleet_id))
+      .order(fleet_document::created_at.desc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Documents with the given status, oldest first so the review queue is
+  /// worked in order. Without a status, those waiting for review.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    status: Option<FleetDocumentStatus>,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    fleet_document::table
+      .filter(fleet_document::status.eq(status.unwrap_or(FleetDocumentStatus::Pending)))
+      .order(fleet_document::created_at.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn fleet() -> RiderFleet {
+    RiderFleet {
+      id: RiderFleetId(1),
+      owner_id: LocalUserId(1),
+      name: "Fleet".to_string(),
+      join_code: "ABCDEFGHIJ".to_string(),
+      route_earnings: true,
+      fleet_share_percent: 15.0,
+      is_verified: true,
+      verified_at: Some(Utc::now()),
+      created_at: Utc::now(),
+      updated_at: None,
+    }
+  }
+
+  #[test]
+  fn payout_share_is_capped_and_rounded_down() {
+    let rider = LocalUserId(2);
+    assert_eq!(fleet().payout_share(rider, Coin(1000), 30.0), 150);
+    assert_eq!(fleet().payout_share(rider, Coin(999), 30.0), 149);
+    assert_eq!(fleet().payout_share(rider, Coin(-10), 30.0), 0);
+    let greedy = RiderFleet {
+      fleet_share_percent: 50.0,
+      ..fleet()
+    };
+    assert_eq!(greedy.payout_share(rider, Coin(1000), 30.0), 300);
+  }
+
+  #[test]
+  fn payout_share_needs_verified_routing_fleet() {
+    let rider = LocalUserId(2);
+    let unverified = RiderFleet {
+      is_verified: false,
+      ..fleet()
+    };
+    assert_eq!(unverified.payout_share(rider, Coin(1000), 30.0), 0);
+    let not_routing = RiderFleet {
+      route_earnings: false,
+      ..fleet()
+    };
+    assert_eq!(not_routing.payout_share(rider, Coin(1000), 30.0), 0);
+    assert_eq!(fleet().payout_share(LocalUserId(1), Coin(1000), 30.0), 0);
+  }
+}
@@ -0,0 +1,567 @@
+use crate::{
+  enums::RiderShiftStatus,
+  newtypes::{RiderId, RiderShiftId, RiderShiftSlotId, ServiceAreaId},
+  schema::{rider, rider_shift, rider_shift_slot},
+  source::rider_shift::{
+    RiderShift,
+    RiderShiftInsertForm,
+    RiderShiftSlot,
+    RiderShiftSlotInsertForm,
+    RiderShiftSlotUpdateForm,
+    RiderShiftView,
+    ShiftCoverageHour,
+    ShiftSlotAvailability,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use diesel::{
+  dsl::{count_star, exists, insert_into, select, update},
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+  SelectableHelper,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+use std::collections::HashMap;
+
+/// Claims still holding a place in their slot.
+const LIVE_STATUSES: [RiderShiftStatus; 4] = [
+  RiderShiftStatus::Claimed,
+  RiderShiftStatus::Started,
+  RiderShiftStatus::Completed,
+  RiderShiftStatus::NoShow,
+];
+
+impl Crud for RiderShiftSlot {
+  type InsertForm = RiderShiftSlotInsertForm;
+  type UpdateForm = RiderShiftSlotUpdateForm;
+  type IdType = RiderShiftSlotId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(rider_shift_slot::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateShiftSlot)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    slot_id: RiderShiftSlotId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(rider_shift_slot::table.find(slot_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateShiftSlot)
+  }
+}
+
+impl RiderShiftSlot {
+  /// Slots overlapping the window, optionally in one area, earliest first.
+  pub async fn list_overlapping(
+    pool: &mut DbPool<'_>,
+    service_area_id: Option<ServiceAreaId>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let mut query = rider_shift_slot::table
+      .filter(rider_shift_slot::starts_at.lt(to))
+      .filter(rider_shift_slot::ends_at.gt(from))
+      .into_boxed();
+    if let Some(area_id) = service_area_id {
+      query = query.filter(rider_shift_slot::service_area_id.eq(area_id));
+    }
+    query
+      .order((rider_shift_slot::starts_at, rider_shift_slot::id))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Slots overlapping the window with how many riders claimed each.
+  pub async fn list_with_claims(
+    pool: &mut DbPool<'_>,
+    service_area_id: Option<ServiceAreaId>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+  ) -> FastJobResult<Vec<ShiftSlotAvailability>> {
+    let slots = Self::list_overlapping(pool, service_area_id, from, to).await?;
+    let slot_ids: Vec<RiderShiftSlotId> = slots.iter().map(|s| s.id).collect();
+
+    let conn = &mut get_conn(pool).await?;
+    let claimed: HashMap<RiderShiftSlotId, i64> = rider_shift::table
+      .filter(rider_shift::slot_id.eq_any(&slot_ids))
+      .filter(rider_shift::status.eq_any(LIVE_STATUSES))
+      .group_by(rider_shift::slot_id)
+      .select((rider_shift::slot_id, count_star()))
+      .load::<(RiderShiftSlotId, i64)>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?
+      .into_iter()
+      .collect();
+
+    Ok(
+      slots
+        .into_iter()
+        .map(|slot| ShiftSlotAvailability {
+          claimed: i32::try_from(claimed.get(&slot.id).copied().unwrap_or(0)).unwrap_or(i32::MAX),
+          slot,
+        })
+        .collect(),
+    )
+  }
+}
+
+impl RiderShift {
+  /// Claim a place in a slot that has not started. Fails when the slot is
+  /// full or overlaps another shift the rider holds.
+  pub async fn claim(
+    pool: &mut DbPool<'_>,
+    slot_id: RiderShiftSlotId,
+    rider_id: RiderId,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .run_transaction(|conn| {
+        async move {
+          // Lock the slot so concurrent claims cannot overfill it
+          let slot = rider_shift_slot::table
+            .find(slot_id)
+            .for_update()
+            .first::<RiderShiftSlot>(conn)
+            .await
+            .optional()?
+            .ok_or(FastJobErrorType::NotFound)?;
+          if slot.starts_at <= Utc::now() {
+            return Err(FastJobErrorType::ShiftSlotAlreadyStarted.into());
+          }
+
+          let claimed = rider_shift::table
+            .filter(rider_shift::slot_id.eq(slot.id))
+            .filter(rider_shift::status.eq_any(LIVE_STATUSES))
+            .select(count_star())
+            .first::<i64>(conn)
+            .await?;
+          if claimed >= i64::from(slot.capacity) {
+            return Err(FastJobErrorType::ShiftSlotFull.into());
+          }
+
+          let overlapping = select(exists(
+            rider_shift::table
+              .inner_join(rider_shift_slot::table)
+              .filter(rider_shift::rider_id.eq(rider_id))
+              .filter(
+                rider_shift::status.eq_any([RiderShiftStatus::Claimed, RiderShiftStatus::Started]),
+              )
+              .filter(rider_shift_slot::starts_at.lt(slot.ends_at))
+              .filter(rider_shift_slot::ends_at.gt(slot.starts_at)),
+          ))
+          .get_result::<bool>(conn)
+          .await?;
+          if overlapping {
+            return Err(FastJobErrorType::ShiftOverlapsClaimedShift.into());
+          }
+
+          insert_into(rider_shift::table)
+            .values(RiderShiftInsertForm::new(slot.id, rider_id))
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntClaimShift)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Give a claimed place back, no later than `cutoff_minutes` before the
+  /// shift starts.
+  pub async fn release(
+    pool: &mut DbPool<'_>,
+    shift_id: RiderShiftId,
+    rider_id: RiderId,
+    cutoff_minutes: i64,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    let (shift, slot) = rider_shift::table
+      .inner_join(rider_shift_slot::table)
+      .filter(rider_shift::id.eq(shift_id))
+      .filter(rider_shift::rider_id.eq(rider_id))
+      .select((RiderShift::as_select(), RiderShiftSlot::as_select()))
+      .first::<(RiderShift, RiderShiftSlot)>(conn)
+      .await
+      .optional()
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?
+      .ok_or(FastJobErrorType::NotFound)?;
+    let now = Utc::now();
+    if shift.status != RiderShiftStatus::Claimed
+      || slot.starts_at - Duration::minutes(cutoff_minutes) < now
+    {
+      return Err(FastJobErrorType::ShiftReleaseTooLate.into());
+    }
+
+    update(rider_shift::table.find(shift.id))
+      .filter(rider_shift::status.eq(RiderShiftStatus::Claimed))
+      .set((
+        rider_shift::status.eq(RiderShiftStatus::Released),
+        rider_shift::updated_at.eq(now),
+      ))
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)
+  }
+
+  /// The rider's shifts ending after `since`, earliest first.
+  pub async fn list_for_rider(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+    since: DateTime<Utc>,
+  ) -> FastJobResult<Vec<RiderShiftView>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let rows = rider_shift::table
+      .inner_join(rider_shift_slot::table)
+      .filter(rider_shift::rider_id.eq(rider_id))
+      .filter(rider_shift_slot::ends_at.gt(since))
+      .order((rider_shift_slot::starts_at, rider_shift::id))
+      .select((RiderShift::as_select(), RiderShiftSlot::as_select()))
+      .load::<(RiderShift, RiderShiftSlot)>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    Ok(
+      rows
+        .into_iter()
+        .map(|(shift, slot)| RiderShiftView { shift, slot })
+        .collect(),
+    )
+  }
+
+  /// Claims on the given slots, released ones included.
+  pub async fn list_for_slots(
+    pool: &mut DbPool<'_>,
+    slot_ids: &[RiderShiftSlotId],
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider_shift::table
+      .filter(rider_shift::slot_id.eq_any(slot_ids))
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Mark claims on slots that are running now as started, and return them.
+  pub async fn start_due(pool: &mut DbPool<'_>, now: DateTime<Utc>) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let running = rider_shift_slot::table
+      .filter(rider_shift_slot::starts_at.le(now))
+      .filter(rider_shift_slot::ends_at.gt(now))
+      .select(rider_shift_slot::id);
+    update(rider_shift::table)
+      .filter(rider_shift::status.eq(RiderShiftStatus::Claimed))
+      .filter(rider_shift::slot_id.eq_any(running))
+      .set((
+        rider_shift::status.eq(RiderShiftStatus::Started),
+        rider_shift::started_at.eq(now),
+        rider_shift::updated_at.eq(now),
+      ))
+      .get_results::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)
+  }
+
+  /// Record that the rider showed up for the shift they are on.
+  pub async fn check_in(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+    now: DateTime<Utc>,
+  ) -> FastJobResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(rider_shift::table)
+      .filter(rider_shift::rider_id.eq(rider_id))
+      .filter(rider_shift::status.eq(RiderShiftStatus::Started))
+      .filter(rider_shift::checked_in_at.is_null())
+      .set((
+        rider_shift::checked_in_at.eq(now),
+        rider_shift::updated_at.eq(now),
+      ))
+      .execute(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)
+  }
+
+  /// Settle started shifts whose grace period is over and that have no
+  /// check-in: a rider active since the shift started is checked in then,
+  /// anyone else is a no-show. Returns the no-shows.
+  pub async fn mark_no_shows(
+    pool: &mut DbPool<'_>,
+    now: DateTime<Utc>,
+    grace_minutes: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let pending = rider_shift::table
+      .inner_join(rider_shift_slot::table)
+      .inner_join(rider::table)
+      .filter(rider_shift::status.eq(RiderShiftStatus::Started))
+      .filter(rider_shift::checked_in_at.is_null())
+      .filter(rider_shift_slot::starts_at.le(now - Duration::minutes(grace_minutes)))
+      .select((RiderShift::as_select(), rider::last_active_at))
+      .load::<(RiderShift, Option<DateTime<Utc>>)>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    let mut no_shows = Vec::new();
+    for (shift, last_active_at) in pending {
+      let started_at = shift.started_at.unwrap_or(shift.created_at);
+      match last_active_at.filter(|at| *at >= started_at) {
+        Some(active_at) => {
+          update(rider_shift::table.find(shift.id))
+            .set((
+              rider_shift::checked_in_at.eq(active_at),
+              rider_shift::updated_at.eq(now),
+            ))
+            .execute(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)?;
+        }
+        None => {
+          let shift = update(rider_shift::table.find(shift.id))
+            .set((
+              rider_shift::status.eq(RiderShiftStatus::NoShow),
+              rider_shift::updated_at.eq(now),
+            ))
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)?;
+          no_shows.push(shift);
+        }
+      }
+    }
+    Ok(no_shows)
+  }
+
+  /// Complete started shifts whose slot is over. Claims on slots that ended
+  /// before they could be started are released rather than held against
+  /// the rider. Returns how many shifts changed.
+  pub async fn finish_ended(pool: &mut DbPool<'_>, now: DateTime<Utc>) -> FastJobResult<usize> {
+    let conn = &mut get_conn(pool).await?;
+
+    let ended = || {
+      rider_shift_slot::table
+        .filter(rider_shift_slot::ends_at.le(now))
+        .select(rider_shift_slot::id)
+    };
+    let completed = update(rider_shift::table)
+      .filter(rider_shift::status.eq(RiderShiftStatus::Started))
+      .filter(rider_shift::slot_id.eq_any(ended()))
+      .set((
+        rider_shift::status.eq(RiderShiftStatus::Completed),
+        rider_shift::updated_at.eq(now),
+      ))
+      .execute(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)?;
+    let released = update(rider_shift::table)
+      .filter(rider_shift::status.eq(RiderShiftStatus::Claimed))
+      .filter(rider_shift::slot_id.eq_any(ended()))
+      .set((
+        rider_shift::status.eq(RiderShiftStatus::Released),
+        rider_shift::updated_at.eq(now),
+      ))
+      .execute(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateShift)?;
+    Ok(completed + released)
+  }
+
+  /// How many shifts starting since `since` the rider did not show up for.
+  pub async fn count_no_shows_since(
+    pool: &mut DbPool<'_>,
+    rider_id: RiderId,
+    since: DateTime<Utc>,
+  ) -> FastJobResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+
+    rider_shift::table
+      .inner_join(rider_shift_slot::table)
+      .filter(rider_shift::rider_id.eq(rider_id))
+      .filter(rider_shift::status.eq(RiderShiftStatus::NoShow))
+      .filter(rider_shift_slot::starts_at.ge(since))
+      .select(count_star())
+      .first::<i64>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+/// Coverage of each area for every hour of `from..to`. Hours without any
+/// slot are listed with zero capacity, so uncovered hours show up too.
+pub fn shift_coverage(
+  area_ids: &[ServiceAreaId],
+  slots: &[RiderShiftSlot],
+  shifts: &[RiderShift],
+  from: DateTime<Utc>,
+  to: DateTime<Utc>,
+) -> Vec<ShiftCoverageHour> {
+  let hour = Duration::hours(1);
+  let first_hour = from.duration_trunc(hour).unwrap_or(from);
+  let hours = ((to - first_hour).num_minutes().max(0) + 59) / 60;
+
+  let mut report: Vec<ShiftCoverageHour> = area_ids
+    .iter()
+    .flat_map(|area_id| {
+      (0..hours).map(move |i| ShiftCoverageHour {
+        service_area_id: *area_id,
+        hour: first_hour + hour * i32::try_from(i).unwrap_or(i32::MAX),
+        capacity: 0,
+        claimed: 0,
+        checked_in: 0,
+        no_shows: 0,
+        gap: 0,
+      })
+    })
+    .collect();
+
+  for slot in slots {
+    let Some(area_index) = area_ids.iter().position(|id| *id == slot.service_area_id) else {
+      continue;
+    };
+    let claims = shifts
+      .iter()
+      .filter(|s| s.slot_id == slot.id && LIVE_STATUSES.contains(&s.status));
+    let claimed = i32::try_from(claims.clone().count()).unwrap_or(i32::MAX);
+    let checked_in = i32::try_from(claims.clone().filter(|s| s.checked_in_at.is_some()).count())
+      .unwrap_or(i32::MAX);
+    let no_shows = i32::try_from(
+      claims
+        .filter(|s| s.status == RiderShiftStatus::NoShow)
+        .count(),
+    )
+    .unwrap_or(i32::MAX);
+
+    let rows = &mut report[area_index * hours as usize..(area_index + 1) * hours as usize];
+    for row in rows
+      .iter_mut()
+      .filter(|row| slot.starts_at < row.hour + hour && slot.ends_at > row.hour)
+    {
+      row.capacity += slot.capacity;
+      row.claimed += claimed;
+      row.checked_in += checked_in;
+      row.no_shows += no_shows;
+    }
+  }
+
+  for row in &mut report {
+    row.gap = (row.capacity - row.claimed).max(0) + row.no_shows;
+  }
+  report
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc
+      .with_ymd_and_hms(2026, 10, 24, hour, minute, 0)
+      .single()
+      .unwrap()
+  }
+
+  fn slot(
+    id: i32,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    capacity: i32,
+  ) -> RiderShiftSlot {
+    RiderShiftSlot {
+      id: RiderShiftSlotId(id),
+      service_area_id: ServiceAreaId(1),
+      starts_at,
+      ends_at,
+      capacity,
+      note: None,
+      created_at: at(0, 0),
+      updated_at: None,
+    }
+  }
+
+  fn shift(slot_id: i32, status: RiderShiftStatus, checked_in: bool) -> RiderShift {
+    RiderShift {
+      id: RiderShiftId(0),
+      slot_id: RiderShiftSlotId(slot_id),
+      rider_id: RiderId(1),
+      status,
+      started_at: None,
+      checked_in_at: checked_in.then(|| at(10, 5)),
+      created_at: at(0, 0),
+      updated_at: None,
+    }
+  }
+
+  #[test]
+  fn coverage_counts_slots_in_every_hour_they_touch() {
+    let slots = [slot(1, at(10, 0), at(12, 30), 3)];
+    let shifts = [
+      shift(1, RiderShiftStatus::Completed, true),
+      shift(1, RiderShiftStatus::NoShow, false),
+      shift(1, RiderShiftStatus::Released, false),
+    ];
+    let report = shift_coverage(&[ServiceAreaId(1)], &slots, &shifts, at(9, 30), at(13, 0));
+
+    assert_eq!(report.len(), 4);
+    assert_eq!(report[0].hour, at(9, 0));
+    assert_eq!(report[0].capacity, 0);
+    assert_eq!(report[0].gap, 0);
+    for row in &report[1..] {
+      assert_eq!(row.capacity, 3);
+      assert_eq!(row.claimed, 2);
+      assert_eq!(row.checked_in, 1);
+      assert_eq!(row.no_shows, 1);
+      // One unclaimed place and one no-show
+      assert_eq!(row.gap, 2);
+    }
+  }
+
+  #[test]
+  fn coverage_adds_up_overlapping_slots_per_area() {
+    let slots = [
+      slot(1, at(10, 0), at(11, 0), 2),
+      slot(2, at(10, 30), at(12, 0), 1),
+    ];
+    let shifts = [shift(2, RiderShiftStatus::Claimed, false)];
+    let report = shift_coverage(
+      &[ServiceAreaId(1), ServiceAreaId(2)],
+      &slots,
+      &shifts,
+      at(10, 0),
+      at(12, 0),
+    );
+
+    assert_eq!(report.len(), 4);
+    assert_eq!(
+      (report[0].capacity, report[0].claimed, report[0].gap),
+      (3, 1, 2)
+    );
+    assert_eq!(
+      (report[1].capacity, report[1].claimed, report[1].gap),
+      (1, 1, 0)
+    );
+    assert!(report[2..].iter().all(|row| row.capacity == 0));
+  }
+}
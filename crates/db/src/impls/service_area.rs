@@ -0,0 +1,387 @@
+use crate::{
+  enums::VehicleType,
+  newtypes::ServiceAreaId,
+  schema::service_area,
+  source::service_area::{
+    ServiceArea,
+    ServiceAreaInsertForm,
+    ServiceAreaShape,
+    ServiceAreaUpdateForm,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  utils::geo::point_in_polygon,
+};
+use diesel::{
+  dsl::{insert_into, update},
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use serde_json::Value as JsonValue;
+
+impl Crud for ServiceArea {
+  type InsertForm = ServiceAreaInsertForm;
+  type UpdateForm = ServiceAreaUpdateForm;
+  type IdType = ServiceAreaId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(service_area::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateServiceArea)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    area_id: ServiceAreaId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(service_area::table.find(area_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateServiceArea)
+  }
+}
+
+impl ServiceAreaInsertForm {
+  pub fn new(name: String, geometry: JsonValue) -> FastJobResult<Self> {
+    let shape = ServiceAreaShape::from_geometry(&geometry)?;
+    Ok(Self {
+      name,
+      geometry,
+      min_lat: shape.min_lat,
+      max_lat: shape.max_lat,
+      min_lng: shape.min_lng,
+      max_lng: shape.max_lng,
+      pricing_config_id: None,
+      allowed_vehicle_types: None,
+      is_active: None,
+    })
+  }
+
+  /// One form per area in a GeoJSON import: a bare geometry, a `Feature`, or
+  /// a `FeatureCollection`. Features are named by their `name` property,
+  /// falling back to `default_name`.
+  pub fn from_geojson(geojson: &JsonValue, default_name: &str) -> FastJobResult<Vec<Self>> {
+    let features = match geojson.get("type").and_then(JsonValue::as_str) {
+      Some("FeatureCollection") => geojson
+        .get("features")
+        .and_then(JsonValue::as_array)
+        .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?
+        .iter()
+        .collect(),
+      Some("Feature") => vec![geojson],
+      _ => {
+        return Ok(vec![Self::new(default_name.to_string(), geojson.clone())?]);
+      }
+    };
+    if features.is_empty() {
+      return Err(FastJobErrorType::InvalidServiceAreaGeometry.into());
+    }
+
+    features
+      .into_iter()
+      .enumerate()
+      .map(|(i, feature)| {
+        let geometry = feature
+          .get("geometry")
+          .filter(|g| !g.is_null())
+          .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?;
+        let name = feature
+          .pointer("/properties/name")
+          .and_then(JsonValue::as_str)
+          .map(str::trim)
+          .filter(|n| !n.is_empty())
+          .map(str::to_string)
+          .unwrap_or_else(|| match i {
+            0 => default_name.to_string(),
+            _ => format!("{default_name} {}", i + 1),
+          });
+        Self::new(name, geometry.clone())
+      })
+      .collect()
+  }
+}
+
+impl ServiceAreaUpdateForm {
+  /// Replace the geometry, keeping the bounding box in step.
+  pub fn set_geometry(&mut self, geometry: JsonValue) -> FastJobResult<()> {
+    let shape = ServiceAreaShape::from_geometry(&geometry)?;
+    self.geometry = Some(geometry);
+    self.min_lat = Some(shape.min_lat);
+    self.max_lat = Some(shape.max_lat);
+    self.min_lng = Some(shape.min_lng);
+    self.max_lng = Some(shape.max_lng);
+    Ok(())
+  }
+}
+
+impl ServiceAreaShape {
+  /// Read a GeoJSON `Polygon` or `MultiPolygon` geometry. GeoJSON positions
+  /// are `[lng, lat]`; the rings come back as `(lat, lng)`.
+  pub fn from_geometry(geometry: &JsonValue) -> FastJobResult<Self> {
+    let coordinates = geometry
+      .get("coordinates")
+      .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?;
+    let polygons = match geometry.get("type").and_then(JsonValue::as_str) {
+      Some("Polygon") => vec![parse_polygon(coordinates)?],
+      Some("MultiPolygon") => coordinates
+        .as_array()
+        .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?
+        .iter()
+        .map(parse_polygon)
+        .collect::<FastJobResult<Vec<_>>>()?,
+      _ => return Err(FastJobErrorType::InvalidServiceAreaGeometry.into()),
+    };
+    if polygons.is_empty() {
+      return Err(FastJobErrorType::InvalidServiceAreaGeometry.into());
+    }
+
+    let outer = polygons.iter().filter_map(|rings| rings.first()).flatten();
+    let (mut min_lat, mut max_lat) = (f64::MAX, f64::MIN);
+    let (mut min_lng, mut max_lng) = (f64::MAX, f64::MIN);
+    for &(lat, lng) in outer {
+      min_lat = min_lat.min(lat);
+      max_lat = max_lat.max(lat);
+      min_lng = min_lng.min(lng);
+      max_lng = max_lng.max(lng);
+    }
+
+    Ok(Self {
+      polygons,
+      min_lat,
+      max_lat,
+      min_lng,
+      max_lng,
+    })
+  }
+
+  pub fn contains(&self, lat: f64, lng: f64) -> bool {
+    (self.min_lat..=self.max_lat).contains(&lat)
+      && (self.min_lng..=self.max_lng).contains(&lng)
+      && self
+        .polygons
+        .iter()
+        .any(|rings| point_in_polygon((lat, lng), rings))
+  }
+}
+
+/// Rings of one polygon. Each needs at least three distinct positions.
+fn parse_polygon(value: &JsonValue) -> FastJobResult<Vec<Vec<(f64, f64)>>> {
+  let rings = value
+    .as_array()
+    .filter(|rings| !rings.is_empty())
+    .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?;
+  rings
+    .iter()
+    .map(|ring| {
+      let ring = ring
+        .as_array()
+        .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?
+        .iter()
+        .map(parse_position)
+        .collect::<FastJobResult<Vec<_>>>()?;
+      let closed = ring.len() > 1 && ring.first() == ring.last();
+      if ring.len() < if closed { 4 } else { 3 } {
+        return Err(FastJobErrorType::InvalidServiceAreaGeometry.into());
+      }
+      Ok(ring)
+    })
+    .collect()
+}
+
+fn parse_position(value: &JsonValue) -> FastJobResult<(f64, f64)> {
+  let position = value
+    .as_array()
+    .ok_or(FastJobErrorType::InvalidServiceAreaGeometry)?;
+  match (
+    position.first().and_then(JsonValue::as_f64),
+    position.get(1).and_then(JsonValue::as_f64),
+  ) {
+    (Some(lng), Some(lat)) if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) => {
+      Ok((lat, lng))
+    }
+    _ => Err(FastJobErrorType::InvalidServiceAreaGeometry.into()),
+  }
+}
+
+impl ServiceArea {
+  pub async fn list(pool: &mut DbPool<'_>, active_only: bool) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let mut query = service_area::table.into_boxed();
+    if active_only {
+      query = query.filter(service_area::is_active.eq(true));
+    }
+    query
+      .order(service_area::id.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  pub fn shape(&self) -> FastJobResult<ServiceAreaShape> {
+    ServiceAreaShape::from_geometry(&self.geometry)
+  }
+
+  pub fn allows_vehicle(&self, vehicle_type: Option<VehicleType>) -> bool {
+    match (&self.allowed_vehicle_types, vehicle_type) {
+      (Some(allowed), Some(vehicle_type)) if !allowed.is_empty() => allowed.contains(&vehicle_type),
+      _ => true,
+    }
+  }
+
+  /// The active area containing the point. Where areas overlap the oldest
+  /// one wins.
+  pub async fn find_for_point(
+    pool: &mut DbPool<'_>,
+    lat: f64,
+    lng: f64,
+  ) -> FastJobResult<Option<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let candidates = service_area::table
+      .filter(service_area::is_active.eq(true))
+      .filter(service_area::min_lat.le(lat))
+      .filter(service_area::max_lat.ge(lat))
+      .filter(service_area::min_lng.le(lng))
+      .filter(service_area::max_lng.ge(lng))
+      .order(service_area::id.asc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(
+      candidates
+        .into_iter()
+        .find(|area| area.shape().is_ok_and(|shape| shape.contains(lat, lng))),
+    )
+  }
+
+  /// Check a trip against the service areas and return the pickup's area.
+  /// Until the first area is set up everything is allowed and `None` is
+  /// returned. Otherwise both ends must lie in an active area and the
+  /// pickup's area must allow the vehicle.
+  pub async fn validate_trip(
+    pool: &mut DbPool<'_>,
+    pickup: (f64, f64),
+    dropoff: Option<(f64, f64)>,
+    vehicle_type: Option<VehicleType>,
+  ) -> FastJobResult<Option<Self>> {
+    if !Self::any_active(pool).await? {
+      return Ok(None);
+    }
+
+    let area = Self::find_for_point(pool, pickup.0, pickup.1)
+      .await?
+      .ok_or(FastJobErrorType::OutsideServiceArea)?;
+    if let Some((lat, lng)) = dropoff {
+      let dropoff_covered =
+        area.shape()?.contains(lat, lng) || Self::find_for_point(pool, lat, lng).await?.is_some();
+      if !dropoff_covered {
+        return Err(FastJobErrorType::OutsideServiceArea.into());
+      }
+    }
+    if !area.allows_vehicle(vehicle_type) {
+      return Err(FastJobErrorType::VehicleNotAllowedInServiceArea.into());
+    }
+    Ok(Some(area))
+  }
+
+  async fn any_active(pool: &mut DbPool<'_>) -> FastJobResult<bool> {
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::select(diesel::dsl::exists(
+      service_area::table.filter(service_area::is_active.eq(true)),
+    ))
+    .get_result::<bool>(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn polygon_is_read_as_lat_lng_with_bbox() {
+    let geometry = json!({
+      "type": "Polygon",
+      "coordinates": [[[100.0, 13.0], [101.0, 13.0], [101.0, 14.0], [100.0, 14.0], [100.0, 13.0]]]
+    });
+    let shape = ServiceAreaShape::from_geometry(&geometry).expect("valid polygon");
+
+    assert_eq!(shape.polygons[0][0][0], (13.0, 100.0));
+    assert_eq!((shape.min_lat, shape.max_lat), (13.0, 14.0));
+    assert_eq!((shape.min_lng, shape.max_lng), (100.0, 101.0));
+    assert!(shape.contains(13.5, 100.5));
+    assert!(!shape.contains(100.5, 13.5));
+  }
+
+  #[test]
+  fn multipolygon_covers_each_part() {
+    let geometry = json!({
+      "type": "MultiPolygon",
+      "coordinates": [
+        [[[100.0, 13.0], [100.2, 13.0], [100.2, 13.2], [100.0, 13.2]]],
+        [[[101.0, 14.0], [101.2, 14.0], [101.2, 14.2], [101.0, 14.2]]]
+      ]
+    });
+    let shape = ServiceAreaShape::from_geometry(&geometry).expect("valid multipolygon");
+
+    assert!(shape.contains(13.1, 100.1));
+    assert!(shape.contains(14.1, 101.1));
+    // Inside the combined bounding box but in neither part
+    assert!(!shape.contains(13.6, 100.6));
+  }
+
+  #[test]
+  fn feature_collection_imports_one_area_per_feature() {
+    let square = |lng: f64| {
+      json!({
+        "type": "Polygon",
+        "coordinates": [[[lng, 13.0], [lng + 0.1, 13.0], [lng + 0.1, 13.1], [lng, 13.1]]]
+      })
+    };
+    let geojson = json!({
+      "type": "FeatureCollection",
+      "features": [
+        { "type": "Feature", "properties": { "name": "Silom" }, "geometry": square(100.5) },
+        { "type": "Feature", "properties": {}, "geometry": square(100.7) }
+      ]
+    });
+    let forms = ServiceAreaInsertForm::from_geojson(&geojson, "Bangkok").expect("valid import");
+
+    assert_eq!(forms.len(), 2);
+    assert_eq!(forms[0].name, "Silom");
+    assert_eq!(forms[1].name, "Bangkok 2");
+    assert_eq!((forms[1].min_lng, forms[1].max_lat), (100.7, 13.1));
+
+    let bare = ServiceAreaInsertForm::from_geojson(&square(100.5), "Bangkok").expect("geometry");
+    assert_eq!(bare[0].name, "Bangkok");
+  }
+
+  #[test]
+  fn invalid_geometry_is_rejected() {
+    for geometry in [
+      json!({ "type": "Point", "coordinates": [100.0, 13.0] }),
+      json!({ "type": "Polygon", "coordinates": [] }),
+      json!({ "type": "Polygon", "coordinates": [[[100.0, 13.0], [101.0, 13.0], [100.0, 13.0]]] }),
+      json!({ "type": "Polygon", "coordinates": [[[100.0, 95.0], [101.0, 13.0], [101.0, 14.0]]] }),
+    ] {
+      assert!(ServiceAreaShape::from_geometry(&geometry).is_err());
+    }
+  }
+}
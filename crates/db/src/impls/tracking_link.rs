@@ -0,0 +1,93 @@
+use crate::{
+  enums::DispatchJobKind,
+  newtypes::PostId,
+  schema::tracking_link,
+  source::tracking_link::{TrackingLink, TrackingLinkInsertForm},
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{insert_into, update},
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+use uuid::Uuid;
+
+impl TrackingLink {
+  /// Issue a new link for a trip, revoking any earlier one so only the
+  /// latest link shared works.
+  pub async fn create_for_post(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    job_kind: DispatchJobKind,
+    expires_at: DateTime<Utc>,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let form = TrackingLinkInsertForm::new(
+      post_id,
+      job_kind,
+      Uuid::new_v4().simple().to_string(),
+      expires_at,
+    );
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          update(
+            tracking_link::table
+              .filter(tracking_link::post_id.eq(post_id))
+              .filter(tracking_link::revoked_at.is_null()),
+          )
+          .set(tracking_link::revoked_at.eq(Utc::now()))
+          .execute(conn)
+          .await?;
+
+          insert_into(tracking_link::table)
+            .values(&form)
+            .get_result::<Self>(conn)
+            .await
+            .with_fastjob_type(FastJobErrorType::CouldntCreateTrackingLink)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Issue an extra link for a trip, e.g. for an emergency contact, leaving
+  /// the links already shared working.
+  pub async fn create_additional(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    job_kind: DispatchJobKind,
+    expires_at: DateTime<Utc>,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+    let form = TrackingLinkInsertForm::new(
+      post_id,
+      job_kind,
+      Uuid::new_v4().simple().to_string(),
+      expires_at,
+    );
+
+    insert_into(tracking_link::table)
+      .values(&form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateTrackingLink)
+  }
+
+  /// The link for a token, as long as it is neither revoked nor expired.
+  pub async fn read_active(pool: &mut DbPool<'_>, token: &str) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    tracking_link::table
+      .filter(tracking_link::token.eq(token))
+      .filter(tracking_link::revoked_at.is_null())
+      .filter(tracking_link::expires_at.gt(Utc::now()))
+      .first::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::TrackingLinkNotFound)
+  }
+}
@@ -0,0 +1,79 @@
+use crate::{
+  enums::IncidentStatus,
+  newtypes::{PostId, TripIncidentId},
+  schema::trip_incident,
+  source::trip_incident::{TripIncident, TripIncidentInsertForm, TripIncidentUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use diesel::{
+  dsl::{insert_into, update},
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl Crud for TripIncident {
+  type InsertForm = TripIncidentInsertForm;
+  type UpdateForm = TripIncidentUpdateForm;
+  type IdType = TripIncidentId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    insert_into(trip_incident::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntCreateIncident)
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    id: TripIncidentId,
+    form: &Self::UpdateForm,
+  ) -> FastJobResult<Self> {
+    let conn = &mut get_conn(pool).await?;
+
+    update(trip_incident::table.find(id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntUpdateIncident)
+  }
+}
+
+impl TripIncident {
+  /// Incidents, newest first. Without a status, only those not resolved.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    status: Option<IncidentStatus>,
+    limit: i64,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = trip_incident::table.into_boxed();
+    query = match status {
+      Some(status) => query.filter(trip_incident::status.eq(status)),
+      None => query.filter(trip_incident::status.ne(IncidentStatus::Resolved)),
+    };
+
+    query
+      .order(trip_incident::created_at.desc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    trip_incident::table
+      .filter(trip_incident::post_id.eq(post_id))
+      .order(trip_incident::created_at.desc())
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
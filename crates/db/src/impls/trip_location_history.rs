@@ -1,12 +1,45 @@
 use crate::{
-  newtypes::{PostId, RiderId},
-  schema::trip_location_history,
-  source::trip_location_history::{TripLocationHistory, TripLocationHistoryInsertForm},
+  newtypes::{PostId, RiderId, TripLocationHistoryId},
+  schema::{trip_location_downsample, trip_location_history},
+  source::{
+    trip_location_history::{TripLocationHistory, TripLocationHistoryInsertForm},
+    trip_status_history::TripStatusHistory,
+  },
   utils::{get_conn, DbPool},
 };
-use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
-use diesel::{dsl::insert_into, ExpressionMethods, QueryDsl, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::MeterVerificationConfig,
+  utils::geo::{downsample_track, track_distance, TrackDistance, TrackPoint},
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{delete, insert_into},
+  sql_query,
+  sql_types::{BigInt, Integer, Timestamptz},
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, RunQueryDsl};
+
+#[derive(QueryableByName)]
+struct CreatedPartitions {
+  #[diesel(sql_type = Integer)]
+  created: i32,
+}
+
+#[derive(QueryableByName)]
+struct DroppedRows {
+  #[diesel(sql_type = BigInt)]
+  dropped: i64,
+}
+
+#[derive(QueryableByName)]
+struct DownsampleCandidate {
+  #[diesel(sql_type = Integer)]
+  post_id: PostId,
+}
 
 impl TripLocationHistory {
   pub async fn create(
@@ -55,4 +88,190 @@ impl TripLocationHistory {
       .await
       .with_fastjob_type(FastJobErrorType::NotFound)
   }
+
+  /// Every fix recorded for a post in the given window, oldest first.
+  pub async fn list_for_post_between(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    trip_location_history::table
+      .filter(trip_location_history::post_id.eq(post_id))
+      .filter(trip_location_history::recorded_at.ge(since))
+      .filter(trip_location_history::recorded_at.le(until))
+      .order(trip_location_history::recorded_at.asc())
+      .select(Self::as_select())
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+
+  /// Distance travelled on a post in the given window, recomputed from the
+  /// recorded fixes with inaccurate fixes and teleports filtered out.
+  pub async fn track_distance_between(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    config: &MeterVerificationConfig,
+  ) -> FastJobResult<TrackDistance> {
+    let points: Vec<TrackPoint> = Self::list_for_post_between(pool, post_id, since, until)
+      .await?
+      .iter()
+      .map(Self::track_point)
+      .collect();
+
+    Ok(track_distance(
+      &points,
+      config.max_speed_kmh,
+      config.max_accuracy_m,
+    ))
+  }
+
+  /// This fix as input for the track distance and simplification helpers.
+  pub fn track_point(&self) -> TrackPoint {
+    TrackPoint {
+      lat: self.lat,
+      lng: self.lng,
+      timestamp: self.recorded_at.timestamp_millis() as f64 / 1000.0,
+      accuracy_m: self.accuracy_m,
+    }
+  }
+
+  /// Create the monthly partitions from the current month to
+  /// `months_ahead` months out. Returns how many were created.
+  pub async fn create_partitions(pool: &mut DbPool<'_>, months_ahead: i32) -> FastJobResult<i32> {
+    let conn = &mut get_conn(pool).await?;
+
+    let row: CreatedPartitions =
+      sql_query("SELECT public.create_trip_location_history_partitions(now(), $1) AS created")
+        .bind::<Integer, _>(months_ahead + 1)
+        .get_result(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    Ok(row.created)
+  }
+
+  /// Posts whose trip was delivered or cancelled within the window and
+  /// whose track has not been thinned out yet.
+  pub async fn list_downsample_candidates(
+    pool: &mut DbPool<'_>,
+    finished_since: DateTime<Utc>,
+    finished_before: DateTime<Utc>,
+    limit: i64,
+  ) -> FastJobResult<Vec<PostId>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let rows: Vec<DownsampleCandidate> = sql_query(
+      r#"
+      SELECT latest.post_id
+      FROM (
+        SELECT DISTINCT ON (post_id) post_id, status, changed_at
+        FROM trip_status_history
+        WHERE changed_at >= $1
+        ORDER BY post_id, changed_at DESC, id DESC
+      ) AS latest
+      WHERE latest.status IN ('Delivered', 'Cancelled')
+        AND latest.changed_at < $2
+        AND NOT EXISTS (
+          SELECT 1 FROM trip_location_downsample d WHERE d.post_id = latest.post_id
+        )
+      ORDER BY latest.changed_at
+      LIMIT $3
+      "#,
+    )
+    .bind::<Timestamptz, _>(finished_since)
+    .bind::<Timestamptz, _>(finished_before)
+    .bind::<BigInt, _>(limit)
+    .load(conn)
+    .await
+    .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(rows.into_iter().map(|r| r.post_id).collect())
+  }
+
+  /// Thin out a finished trip's track to at most one fix per
+  /// `interval_seconds`, keeping the fixes around each status change, and
+  /// remember that the trip is done. Returns how many fixes were deleted.
+  pub async fn downsample_for_post(
+    pool: &mut DbPool<'_>,
+    post_id: PostId,
+    interval_seconds: i64,
+  ) -> FastJobResult<usize> {
+    let status_changes: Vec<f64> = TripStatusHistory::list_for_post(pool, post_id)
+      .await?
+      .iter()
+      .map(|c| c.changed_at.timestamp_millis() as f64 / 1000.0)
+      .collect();
+    let conn = &mut get_conn(pool).await?;
+
+    let points: Vec<Self> = trip_location_history::table
+      .filter(trip_location_history::post_id.eq(post_id))
+      .order(trip_location_history::recorded_at.asc())
+      .select(Self::as_select())
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    let track: Vec<TrackPoint> = points.iter().map(Self::track_point).collect();
+    let keep = downsample_track(&track, interval_seconds as f64, &status_changes);
+    let dropped: Vec<TripLocationHistoryId> = points
+      .iter()
+      .zip(&keep)
+      .filter(|(_, &keep)| !keep)
+      .map(|(p, _)| p.id)
+      .collect();
+    let rows_before = points.len() as i32;
+    let rows_after = (points.len() - dropped.len()) as i32;
+
+    conn
+      .run_transaction(|conn| {
+        async move {
+          let deleted = delete(
+            trip_location_history::table
+              .filter(trip_location_history::post_id.eq(post_id))
+              .filter(trip_location_history::id.eq_any(&dropped)),
+          )
+          .execute(conn)
+          .await?;
+
+          insert_into(trip_location_downsample::table)
+            .values((
+              trip_location_downsample::post_id.eq(post_id),
+              trip_location_downsample::rows_before.eq(rows_before),
+              trip_location_downsample::rows_after.eq(rows_after),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+
+          Ok(deleted)
+        }
+        .scope_boxed()
+      })
+      .await
+  }
+
+  /// Delete every fix recorded before `before`, dropping whole monthly
+  /// partitions where possible. Returns how many fixes were removed.
+  pub async fn purge_before(pool: &mut DbPool<'_>, before: DateTime<Utc>) -> FastJobResult<i64> {
+    let conn = &mut get_conn(pool).await?;
+
+    let partitions: DroppedRows =
+      sql_query("SELECT public.drop_trip_location_history_partitions($1) AS dropped")
+        .bind::<Timestamptz, _>(before)
+        .get_result(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+    // Rows in partly expired months and the default partition
+    let deleted =
+      delete(trip_location_history::table.filter(trip_location_history::recorded_at.lt(before)))
+        .execute(conn)
+        .await
+        .with_fastjob_type(FastJobErrorType::DatabaseError)?;
+
+    Ok(partitions.dropped + deleted as i64)
+  }
 }
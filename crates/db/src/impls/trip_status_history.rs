@@ -0,0 +1,27 @@
+use crate::{
+  newtypes::PostId,
+  schema::trip_status_history,
+  source::trip_status_history::TripStatusHistory,
+  utils::{get_conn, DbPool},
+};
+use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+impl TripStatusHistory {
+  /// Every status change of a post's trip, oldest first.
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    trip_status_history::table
+      .filter(trip_status_history::post_id.eq(post_id))
+      .order((
+        trip_status_history::changed_at.asc(),
+        trip_status_history::id.asc(),
+      ))
+      .select(Self::as_select())
+      .load(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
+}
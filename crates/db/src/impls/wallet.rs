@@ -266,6 +266,21 @@ impl WalletModel {
     Ok(wallet)
   }
 
+  /// Connection-scoped lookup of a user's wallet id, for callers already
+  /// inside a `run_transaction`.
+  pub async fn wallet_id_for_user_on_conn(
+    conn: &mut diesel_async::AsyncPgConnection,
+    local_user_id: LocalUserId,
+  ) -> FastJobResult<WalletId> {
+    person::table
+      .inner_join(local_user::table.on(person::id.eq(local_user::person_id)))
+      .filter(local_user::id.eq(local_user_id))
+      .select(person::wallet_id)
+      .first::<WalletId>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::CouldntFindWalletByUser)
+  }
+
   /// Create a wallet transaction for deposit or withdraw.
   /// For transfers, this facade cannot be used because two mirrored forms are required.
   pub async fn create_transaction(
@@ -598,6 +613,32 @@ impl WalletModel {
     Ok(w)
   }
 
+  /// Connection-scoped variant of `reserve` for callers already inside a
+  /// `run_transaction`. Does NOT open a new transaction.
+  pub async fn reserve_on_conn(
+    conn: &mut diesel_async::AsyncPgConnection,
+    form: &WalletTransactionInsertForm,
+  ) -> FastJobResult<()> {
+    let amount = form.amount;
+    Self::validate_positive_amount(amount)?;
+    let _ = Self::apply_op_on(conn, form.wallet_id, BalanceOp::Reserve, amount).await?;
+    let _ = Self::insert_wallet_tx(conn, form).await?;
+    Ok(())
+  }
+
+  /// Connection-scoped variant of `release` for callers already inside a
+  /// `run_transaction`. Does NOT open a new transaction.
+  pub async fn release_on_conn(
+    conn: &mut diesel_async::AsyncPgConnection,
+    form: &WalletTransactionInsertForm,
+  ) -> FastJobResult<()> {
+    let amount = form.amount;
+    Self::validate_positive_amount(amount)?;
+    let _ = Self::apply_op_on(conn, form.wallet_id, BalanceOp::Release, amount).await?;
+    let _ = Self::insert_wallet_tx(conn, form).await?;
+    Ok(())
+  }
+
   /// Capture part/all of the reserved funds: o -= amt; t -= amt (final debit)
   pub async fn capture(
     pool: &mut DbPool<'_>,
@@ -125,6 +125,78 @@ pub mod sql_types {
   #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
   #[diesel(postgres_type(name = "rider_verification_status"))]
   pub struct RiderVerificationStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "referral_status"))]
+  pub struct ReferralStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "post_boost_kind"))]
+  pub struct PostBoostKind;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "post_boost_status"))]
+  pub struct PostBoostStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "cod_settlement_method"))]
+  pub struct CodSettlementMethod;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "dispatch_job_kind"))]
+  pub struct DispatchJobKind;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "dispatch_status"))]
+  pub struct DispatchStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "dispatch_offer_status"))]
+  pub struct DispatchOfferStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "pricing_rule_kind"))]
+  pub struct PricingRuleKind;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "meter_check_stage"))]
+  pub struct MeterCheckStage;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "delivery_stop_status"))]
+  pub struct DeliveryStopStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "employer_rating_tag"))]
+  pub struct EmployerRatingTag;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "cancellation_fee_kind"))]
+  pub struct CancellationFeeKind;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "cancellation_party"))]
+  pub struct CancellationParty;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "rider_shift_status"))]
+  pub struct RiderShiftStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "incident_kind"))]
+  pub struct IncidentKind;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "incident_status"))]
+  pub struct IncidentStatus;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "fleet_document_kind"))]
+  pub struct FleetDocumentKind;
+
+  #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "fleet_document_status"))]
+  pub struct FleetDocumentStatus;
 }
 
 diesel::table! {
@@ -998,6 +1070,7 @@ diesel::table! {
         is_english_required -> Bool,
         post_kind -> PostKind,
         pending  -> Bool,
+        boosted -> Bool,
     }
 }
 
@@ -1041,6 +1114,11 @@ diesel::table! {
         rider_wallet_transaction_id -> Nullable<Int4>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        requires_photo -> Bool,
+        requires_otp -> Bool,
+        scheduled_pickup_at -> Nullable<Timestamptz>,
+        rider_confirmed_at -> Nullable<Timestamptz>,
+        reminder_sent_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -1172,6 +1250,7 @@ diesel::table! {
         proposal_id -> Nullable<Int4>,
         category_id -> Nullable<Int4>,
         person_id -> Nullable<Int4>,
+        boosted -> Bool,
     }
 }
 
@@ -1266,6 +1345,7 @@ diesel::table! {
         description -> Text,
         counter_user_id -> Nullable<Int4>,
         idempotency_key -> Text,
+        created_at -> Timestamptz,
     }
 }
 
@@ -1498,8 +1578,6 @@ diesel::table! {
         rating -> Float8,
         completed_jobs -> Int4,
         total_jobs -> Int4,
-        total_earnings -> Float8,
-        pending_earnings -> Float8,
 
         // Availability
         is_online -> Bool,
@@ -1509,6 +1587,16 @@ diesel::table! {
         joined_at -> Nullable<Timestamptz>,
         last_active_at -> Nullable<Timestamptz>,
         verified_at -> Nullable<Timestamptz>,
+
+        // Document expiry
+        license_reminder_days -> Nullable<Int4>,
+        documents_expired_at -> Nullable<Timestamptz>,
+
+        // Cancellation penalties
+        accept_banned_until -> Nullable<Timestamptz>,
+
+        // Fleet
+        fleet_id -> Nullable<Int4>,
     }
 }
 
@@ -1661,6 +1749,53 @@ diesel::joinable!(ride_session -> post (post_id));
 diesel::joinable!(ride_session -> rider (rider_id));
 diesel::joinable!(ride_session -> pricing_config (pricing_config_id));
 diesel::joinable!(ride_meter_snapshot -> ride_session (ride_session_id));
+diesel::joinable!(referral_code -> local_user (local_user_id));
+diesel::joinable!(post_boost -> post (post_id));
+diesel::joinable!(post_boost -> post_boost_package (package_id));
+diesel::joinable!(post_boost -> local_user (local_user_id));
+diesel::joinable!(cod_collection -> post (post_id));
+diesel::joinable!(cod_collection -> rider (rider_id));
+diesel::joinable!(cod_collection -> local_user (employer_id));
+diesel::joinable!(cod_settlement -> rider (rider_id));
+diesel::joinable!(cod_settlement -> person (recorded_by_person_id));
+diesel::joinable!(rider_earning_hold -> rider (rider_id));
+diesel::joinable!(rider_earning_hold -> post (post_id));
+diesel::joinable!(dispatch_request -> post (post_id));
+diesel::joinable!(dispatch_request -> rider (accepted_rider_id));
+diesel::joinable!(dispatch_offer -> dispatch_request (dispatch_request_id));
+diesel::joinable!(dispatch_offer -> rider (rider_id));
+diesel::joinable!(pricing_rule -> pricing_config (pricing_config_id));
+diesel::joinable!(ride_meter_flag -> ride_session (ride_session_id));
+diesel::joinable!(ride_meter_flag -> person (reviewed_by_person_id));
+diesel::joinable!(service_area -> pricing_config (pricing_config_id));
+diesel::joinable!(pricing_rule -> service_area (service_area_id));
+diesel::joinable!(dispatch_request -> service_area (service_area_id));
+diesel::joinable!(delivery_proof -> post (post_id));
+diesel::joinable!(delivery_proof -> rider (rider_id));
+diesel::joinable!(delivery_stop -> post (post_id));
+diesel::joinable!(trip_status_history -> post (post_id));
+diesel::joinable!(trip_location_downsample -> post (post_id));
+diesel::joinable!(tracking_link -> post (post_id));
+diesel::joinable!(employer_rating -> post (post_id));
+diesel::joinable!(employer_rating -> rider (rider_id));
+diesel::joinable!(employer_rating -> person (employer_id));
+diesel::joinable!(employer_reliability -> person (employer_id));
+diesel::joinable!(cancellation_policy -> pricing_config (pricing_config_id));
+diesel::joinable!(trip_cancellation -> post (post_id));
+diesel::joinable!(trip_cancellation -> rider (rider_id));
+diesel::joinable!(trip_cancellation -> cancellation_policy (policy_id));
+diesel::joinable!(cargo_tariff -> pricing_config (pricing_config_id));
+diesel::joinable!(rider_shift_slot -> service_area (service_area_id));
+diesel::joinable!(rider_shift -> rider_shift_slot (slot_id));
+diesel::joinable!(rider_shift -> rider (rider_id));
+diesel::joinable!(emergency_contact -> local_user (local_user_id));
+diesel::joinable!(trip_incident -> post (post_id));
+diesel::joinable!(trip_incident -> rider (rider_id));
+diesel::joinable!(trip_incident -> tracking_link (tracking_link_id));
+diesel::joinable!(rider_fleet -> local_user (owner_id));
+diesel::joinable!(rider -> rider_fleet (fleet_id));
+diesel::joinable!(fleet_document -> rider_fleet (fleet_id));
+diesel::joinable!(fleet_document -> person (reviewed_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
   admin_allow_instance,
@@ -1755,7 +1890,35 @@ diesel::allow_tables_to_appear_in_same_query!(
   currency_rate_history,
   pricing_config,
   ride_session,
-  ride_meter_snapshot
+  ride_meter_snapshot,
+  referral_code,
+  referral,
+  post_boost_package,
+  post_boost,
+  cod_collection,
+  cod_settlement,
+  rider_earning_hold,
+  dispatch_request,
+  dispatch_offer,
+  pricing_rule,
+  ride_meter_flag,
+  service_area,
+  delivery_proof,
+  delivery_stop,
+  trip_status_history,
+  trip_location_downsample,
+  tracking_link,
+  employer_rating,
+  employer_reliability,
+  cancellation_policy,
+  trip_cancellation,
+  cargo_tariff,
+  rider_shift_slot,
+  rider_shift,
+  emergency_contact,
+  trip_incident,
+  rider_fleet,
+  fleet_document
 );
 
 // Currency table schema
@@ -1856,6 +2019,10 @@ diesel::table! {
         cancellation_reason -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
+        price_multiplier -> Nullable<Float8>,
+        fare_cap_coin -> Nullable<Int4>,
+        scheduled_pickup_at -> Nullable<Timestamptz>,
+        reminder_sent_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -1873,3 +2040,525 @@ diesel::table! {
         created_at -> Timestamptz,
     }
 }
+
+// Referral code table schema
+diesel::table! {
+    referral_code (local_user_id) {
+        local_user_id -> Int4,
+        code -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+// Referral table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::ReferralStatus;
+
+    referral (id) {
+        id -> Int4,
+        referrer_id -> Int4,
+        referred_id -> Int4,
+        code -> Varchar,
+        status -> ReferralStatus,
+        signup_ip -> Nullable<Text>,
+        device_id -> Nullable<Text>,
+        reward_coin -> Nullable<Int4>,
+        reference_type -> Nullable<Varchar>,
+        reference_id -> Nullable<Int4>,
+        rejected_reason -> Nullable<Text>,
+        created_at -> Timestamptz,
+        rewarded_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Post boost package table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::PostBoostKind;
+
+    post_boost_package (id) {
+        id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        kind -> PostBoostKind,
+        duration_days -> Int4,
+        price_coin -> Int4,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Post boost table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{PostBoostKind, PostBoostStatus};
+
+    post_boost (id) {
+        id -> Int4,
+        post_id -> Int4,
+        package_id -> Int4,
+        local_user_id -> Int4,
+        kind -> PostBoostKind,
+        price_coin -> Int4,
+        status -> PostBoostStatus,
+        starts_at -> Timestamptz,
+        ends_at -> Timestamptz,
+        refunded_coin -> Nullable<Int4>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// COD collection table schema
+diesel::table! {
+    cod_collection (id) {
+        id -> Int4,
+        post_id -> Int4,
+        rider_id -> Int4,
+        employer_id -> Int4,
+        cod_amount -> Float8,
+        amount_coin -> Int4,
+        settled_coin -> Int4,
+        collected_at -> Timestamptz,
+        settled_at -> Nullable<Timestamptz>,
+    }
+}
+
+// COD settlement table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::CodSettlementMethod;
+
+    cod_settlement (id) {
+        id -> Int4,
+        rider_id -> Int4,
+        method -> CodSettlementMethod,
+        amount_coin -> Int4,
+        recorded_by_person_id -> Nullable<Int4>,
+        note -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+// Rider earning hold table schema
+diesel::table! {
+    rider_earning_hold (id) {
+        id -> Int4,
+        rider_id -> Int4,
+        post_id -> Int4,
+        amount_coin -> Int4,
+        clears_at -> Timestamptz,
+        released_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+// Dispatch request table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{DispatchJobKind, DispatchStatus, VehicleType};
+
+    dispatch_request (id) {
+        id -> Int4,
+        post_id -> Int4,
+        job_kind -> DispatchJobKind,
+        vehicle_type -> Nullable<VehicleType>,
+        pickup_lat -> Float8,
+        pickup_lng -> Float8,
+        status -> DispatchStatus,
+        wave -> Int4,
+        accepted_rider_id -> Nullable<Int4>,
+        next_wave_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        service_area_id -> Nullable<Int4>,
+    }
+}
+
+// Dispatch offer table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::DispatchOfferStatus;
+
+    dispatch_offer (id) {
+        id -> Int4,
+        dispatch_request_id -> Int4,
+        rider_id -> Int4,
+        wave -> Int4,
+        distance_km -> Float8,
+        score -> Float8,
+        status -> DispatchOfferStatus,
+        offered_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        responded_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Pricing rule table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::PricingRuleKind;
+
+    pricing_rule (id) {
+        id -> Int4,
+        pricing_config_id -> Int4,
+        name -> Varchar,
+        kind -> PricingRuleKind,
+        multiplier -> Float8,
+        start_minute -> Nullable<Int4>,
+        end_minute -> Nullable<Int4>,
+        days_of_week -> Nullable<Array<Int4>>,
+        holiday_date -> Nullable<Date>,
+        zone_lat -> Nullable<Float8>,
+        zone_lng -> Nullable<Float8>,
+        zone_radius_km -> Nullable<Float8>,
+        demand_ratio_threshold -> Nullable<Float8>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        service_area_id -> Nullable<Int4>,
+    }
+}
+
+// Ride meter flag table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::MeterCheckStage;
+
+    ride_meter_flag (id) {
+        id -> Int4,
+        ride_session_id -> Int4,
+        stage -> MeterCheckStage,
+        reported_distance_km -> Float8,
+        server_distance_km -> Float8,
+        reported_minutes -> Int4,
+        server_minutes -> Int4,
+        points_used -> Int4,
+        points_dropped -> Int4,
+        rejected -> Bool,
+        created_at -> Timestamptz,
+        reviewed_at -> Nullable<Timestamptz>,
+        reviewed_by_person_id -> Nullable<Int4>,
+        resolution_note -> Nullable<Text>,
+    }
+}
+
+// Service area table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::VehicleType;
+
+    service_area (id) {
+        id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        geometry -> Jsonb,
+        min_lat -> Float8,
+        max_lat -> Float8,
+        min_lng -> Float8,
+        max_lng -> Float8,
+        pricing_config_id -> Nullable<Int4>,
+        allowed_vehicle_types -> Nullable<Array<VehicleType>>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Delivery proof table schema
+diesel::table! {
+    delivery_proof (id) {
+        id -> Int4,
+        post_id -> Int4,
+        rider_id -> Int4,
+        photo_url -> Nullable<Text>,
+        signature_url -> Nullable<Text>,
+        #[max_length = 4]
+        otp_code -> Nullable<Varchar>,
+        otp_attempts -> Int4,
+        otp_verified_at -> Nullable<Timestamptz>,
+        delivered_lat -> Nullable<Float8>,
+        delivered_lng -> Nullable<Float8>,
+        submitted_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Delivery stop table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::DeliveryStopStatus;
+
+    delivery_stop (id) {
+        id -> Int4,
+        post_id -> Int4,
+        stop_order -> Int4,
+        address -> Text,
+        lat -> Float8,
+        lng -> Float8,
+        contact_name -> Nullable<Varchar>,
+        contact_phone -> Nullable<Varchar>,
+        cod_amount -> Nullable<Float8>,
+        status -> DeliveryStopStatus,
+        skip_reason -> Nullable<Text>,
+        leg_distance_km -> Float8,
+        leg_fee -> Int4,
+        photo_url -> Nullable<Text>,
+        signature_url -> Nullable<Text>,
+        completed_lat -> Nullable<Float8>,
+        completed_lng -> Nullable<Float8>,
+        arrived_at -> Nullable<Timestamptz>,
+        completed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Trip status history table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::TripStatus;
+
+    trip_status_history (id) {
+        id -> Int4,
+        post_id -> Int4,
+        status -> TripStatus,
+        reason -> Nullable<Text>,
+        changed_at -> Timestamptz,
+    }
+}
+
+// Trip location downsample table schema
+diesel::table! {
+    trip_location_downsample (post_id) {
+        post_id -> Int4,
+        rows_before -> Int4,
+        rows_after -> Int4,
+        downsampled_at -> Timestamptz,
+    }
+}
+
+// Tracking link table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::DispatchJobKind;
+
+    tracking_link (id) {
+        id -> Int4,
+        post_id -> Int4,
+        job_kind -> DispatchJobKind,
+        #[max_length = 32]
+        token -> Varchar,
+        expires_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+// Employer rating table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{DispatchJobKind, EmployerRatingTag};
+
+    employer_rating (id) {
+        id -> Int4,
+        post_id -> Int4,
+        job_kind -> DispatchJobKind,
+        rider_id -> Int4,
+        employer_id -> Int4,
+        rating -> Int2,
+        tags -> Array<EmployerRatingTag>,
+        comment -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Employer reliability table schema
+diesel::table! {
+    employer_reliability (employer_id) {
+        employer_id -> Int4,
+        rating_count -> Int4,
+        average_rating -> Float8,
+        no_show_count -> Int4,
+        wrong_address_count -> Int4,
+        rude_count -> Int4,
+        great_count -> Int4,
+        score -> Float8,
+        updated_at -> Timestamptz,
+    }
+}
+
+// Cancellation policy table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{CancellationFeeKind, PostKind};
+
+    cancellation_policy (id) {
+        id -> Int4,
+        post_kind -> PostKind,
+        pricing_config_id -> Nullable<Int4>,
+        free_minutes -> Int4,
+        fee_kind -> CancellationFeeKind,
+        flat_fee_coin -> Int4,
+        fee_per_km_coin -> Int4,
+        max_fee_coin -> Nullable<Int4>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Trip cancellation table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{CancellationParty, DispatchJobKind};
+
+    trip_cancellation (id) {
+        id -> Int4,
+        post_id -> Int4,
+        job_kind -> DispatchJobKind,
+        rider_id -> Int4,
+        cancelled_by -> CancellationParty,
+        policy_id -> Nullable<Int4>,
+        minutes_since_assigned -> Int4,
+        distance_km -> Float8,
+        fee_coin -> Int4,
+        reason -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+// Cargo tariff table schema
+diesel::table! {
+    cargo_tariff (id) {
+        id -> Int4,
+        pricing_config_id -> Int4,
+        weight_brackets -> Jsonb,
+        size_classes -> Jsonb,
+        fragile_surcharge_coin -> Int4,
+        signature_surcharge_coin -> Int4,
+        motorcycle_multiplier -> Float8,
+        bicycle_multiplier -> Float8,
+        car_multiplier -> Float8,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Rider shift slot table schema
+diesel::table! {
+    rider_shift_slot (id) {
+        id -> Int4,
+        service_area_id -> Int4,
+        starts_at -> Timestamptz,
+        ends_at -> Timestamptz,
+        capacity -> Int4,
+        note -> Nullable<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Rider shift table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::RiderShiftStatus;
+
+    rider_shift (id) {
+        id -> Int4,
+        slot_id -> Int4,
+        rider_id -> Int4,
+        status -> RiderShiftStatus,
+        started_at -> Nullable<Timestamptz>,
+        checked_in_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Emergency contact table schema
+diesel::table! {
+    emergency_contact (local_user_id) {
+        local_user_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 32]
+        phone -> Varchar,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Trip incident table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{DispatchJobKind, IncidentKind, IncidentStatus};
+
+    trip_incident (id) {
+        id -> Int4,
+        post_id -> Int4,
+        job_kind -> DispatchJobKind,
+        reporter_id -> Int4,
+        reported_by_rider -> Bool,
+        rider_id -> Nullable<Int4>,
+        kind -> IncidentKind,
+        status -> IncidentStatus,
+        notes -> Nullable<Text>,
+        photo_urls -> Array<Text>,
+        lat -> Nullable<Float8>,
+        lng -> Nullable<Float8>,
+        located_at -> Nullable<Timestamptz>,
+        tracking_link_id -> Nullable<Int4>,
+        contact_notified_at -> Nullable<Timestamptz>,
+        resolved_by -> Nullable<Int4>,
+        resolution_note -> Nullable<Text>,
+        resolved_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Rider fleet table schema
+diesel::table! {
+    rider_fleet (id) {
+        id -> Int4,
+        owner_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 32]
+        join_code -> Varchar,
+        route_earnings -> Bool,
+        fleet_share_percent -> Float8,
+        is_verified -> Bool,
+        verified_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+// Fleet document table schema
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::schema::sql_types::{FleetDocumentKind, FleetDocumentStatus};
+
+    fleet_document (id) {
+        id -> Int4,
+        fleet_id -> Int4,
+        kind -> FleetDocumentKind,
+        file_url -> Text,
+        expires_at -> Nullable<Timestamptz>,
+        status -> FleetDocumentStatus,
+        reviewed_by -> Nullable<Int4>,
+        review_note -> Nullable<Text>,
+        reviewed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
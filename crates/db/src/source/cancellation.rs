@@ -0,0 +1,110 @@
+#[cfg(feature = "full")]
+use crate::schema::{cancellation_policy, trip_cancellation};
+use crate::{
+  enums::{CancellationFeeKind, CancellationParty, DispatchJobKind, PostKind},
+  newtypes::{CancellationPolicyId, PostId, PricingConfigId, RiderId, TripCancellationId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// What an employer pays the rider for cancelling a trip after a rider was
+/// assigned. Applies to a post kind, or to one pricing config of it; the
+/// more specific policy wins.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = cancellation_policy))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct CancellationPolicy {
+  pub id: CancellationPolicyId,
+  pub post_kind: PostKind,
+  pub pricing_config_id: Option<PricingConfigId>,
+  /// Cancelling within this many minutes of assignment is free
+  pub free_minutes: i32,
+  pub fee_kind: CancellationFeeKind,
+  pub flat_fee_coin: i32,
+  /// Only used by distance-based fees
+  pub fee_per_km_coin: i32,
+  pub max_fee_coin: Option<i32>,
+  pub is_active: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = cancellation_policy))]
+pub struct CancellationPolicyInsertForm {
+  pub post_kind: PostKind,
+  pub free_minutes: i32,
+  pub fee_kind: CancellationFeeKind,
+  pub flat_fee_coin: i32,
+  #[new(default)]
+  pub pricing_config_id: Option<PricingConfigId>,
+  #[new(default)]
+  pub fee_per_km_coin: Option<i32>,
+  #[new(default)]
+  pub max_fee_coin: Option<i32>,
+  #[new(default)]
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = cancellation_policy))]
+pub struct CancellationPolicyUpdateForm {
+  pub free_minutes: Option<i32>,
+  pub fee_kind: Option<CancellationFeeKind>,
+  pub flat_fee_coin: Option<i32>,
+  pub fee_per_km_coin: Option<i32>,
+  pub max_fee_coin: Option<Option<i32>>,
+  pub is_active: Option<bool>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// A cancelled trip that already had a rider: who cancelled, how far the
+/// rider had come, and the fee they were paid.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = trip_cancellation))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct TripCancellation {
+  pub id: TripCancellationId,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub rider_id: RiderId,
+  pub cancelled_by: CancellationParty,
+  pub policy_id: Option<CancellationPolicyId>,
+  pub minutes_since_assigned: i32,
+  /// Distance the rider drove between assignment and cancellation
+  pub distance_km: f64,
+  pub fee_coin: i32,
+  pub reason: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = trip_cancellation))]
+pub struct TripCancellationInsertForm {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub rider_id: RiderId,
+  pub cancelled_by: CancellationParty,
+  pub minutes_since_assigned: i32,
+  pub distance_km: f64,
+  #[new(default)]
+  pub policy_id: Option<CancellationPolicyId>,
+  #[new(default)]
+  pub fee_coin: i32,
+  #[new(default)]
+  pub reason: Option<String>,
+}
@@ -0,0 +1,127 @@
+#[cfg(feature = "full")]
+use crate::schema::cargo_tariff;
+use crate::{
+  enums::VehicleType,
+  newtypes::{CargoTariffId, PricingConfigId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serde_with::skip_serializing_none;
+
+/// What a delivery's cargo adds to the fare of its pricing config: a fee per
+/// weight bracket and size class, surcharges for fragile packages and
+/// signatures, and a multiplier for the vehicle the package needs.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = cargo_tariff))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct CargoTariff {
+  pub id: CargoTariffId,
+  pub pricing_config_id: PricingConfigId,
+  /// [`WeightBracket`]s, lightest first
+  #[cfg_attr(feature = "ts-rs", ts(type = "Array<WeightBracket>"))]
+  pub weight_brackets: JsonValue,
+  /// [`SizeClass`]es
+  #[cfg_attr(feature = "ts-rs", ts(type = "Array<SizeClass>"))]
+  pub size_classes: JsonValue,
+  pub fragile_surcharge_coin: i32,
+  pub signature_surcharge_coin: i32,
+  pub motorcycle_multiplier: f64,
+  pub bicycle_multiplier: f64,
+  pub car_multiplier: f64,
+  pub is_active: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = cargo_tariff))]
+pub struct CargoTariffInsertForm {
+  pub pricing_config_id: PricingConfigId,
+  pub weight_brackets: JsonValue,
+  pub size_classes: JsonValue,
+  #[new(default)]
+  pub fragile_surcharge_coin: Option<i32>,
+  #[new(default)]
+  pub signature_surcharge_coin: Option<i32>,
+  #[new(default)]
+  pub motorcycle_multiplier: Option<f64>,
+  #[new(default)]
+  pub bicycle_multiplier: Option<f64>,
+  #[new(default)]
+  pub car_multiplier: Option<f64>,
+  #[new(default)]
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = cargo_tariff))]
+pub struct CargoTariffUpdateForm {
+  pub weight_brackets: Option<JsonValue>,
+  pub size_classes: Option<JsonValue>,
+  pub fragile_surcharge_coin: Option<i32>,
+  pub signature_surcharge_coin: Option<i32>,
+  pub motorcycle_multiplier: Option<f64>,
+  pub bicycle_multiplier: Option<f64>,
+  pub car_multiplier: Option<f64>,
+  pub is_active: Option<bool>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Fee for packages up to a weight. The last bracket may leave the weight
+/// open; without one, heavier packages are refused.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct WeightBracket {
+  pub up_to_kg: Option<f64>,
+  pub fee_coin: i32,
+}
+
+/// Fee for a package size, matched case-insensitively against
+/// `package_size`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct SizeClass {
+  pub name: String,
+  pub fee_coin: i32,
+}
+
+/// The cargo of a delivery as far as its price is concerned.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct CargoSpec {
+  pub weight_kg: Option<f64>,
+  pub size: Option<String>,
+  pub fragile: bool,
+  pub requires_signature: bool,
+  pub vehicle: Option<VehicleType>,
+}
+
+/// Cargo charges under a tariff, before the vehicle multiplier.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct CargoCharges {
+  pub weight_fee_coin: i32,
+  pub size_fee_coin: i32,
+  pub fragile_surcharge_coin: i32,
+  pub signature_surcharge_coin: i32,
+  pub vehicle_multiplier: f64,
+}
+
+impl CargoCharges {
+  pub fn surcharges_coin(&self) -> i32 {
+    self.weight_fee_coin
+      + self.size_fee_coin
+      + self.fragile_surcharge_coin
+      + self.signature_surcharge_coin
+  }
+}
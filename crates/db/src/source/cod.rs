@@ -0,0 +1,100 @@
+#[cfg(feature = "full")]
+use crate::schema::{cod_collection, cod_settlement};
+use crate::{
+  enums::CodSettlementMethod,
+  newtypes::{CodCollectionId, CodSettlementId, LocalUserId, PersonId, PostId, RiderId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Cash a rider collected on a cash-on-delivery job.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = cod_collection))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct CodCollection {
+  pub id: CodCollectionId,
+  pub post_id: PostId,
+  pub rider_id: RiderId,
+  /// The employer the cash belongs to
+  pub employer_id: LocalUserId,
+  /// Amount as entered on the delivery, in cash
+  pub cod_amount: f64,
+  /// Amount converted to coins; this is what the rider owes
+  pub amount_coin: i32,
+  /// Part of `amount_coin` already remitted to the employer
+  pub settled_coin: i32,
+  pub collected_at: DateTime<Utc>,
+  pub settled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = cod_collection))]
+pub struct CodCollectionInsertForm {
+  pub post_id: PostId,
+  pub rider_id: RiderId,
+  pub employer_id: LocalUserId,
+  pub cod_amount: f64,
+  pub amount_coin: i32,
+}
+
+/// A payment against a rider's outstanding COD cash.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = cod_settlement))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct CodSettlement {
+  pub id: CodSettlementId,
+  pub rider_id: RiderId,
+  pub method: CodSettlementMethod,
+  pub amount_coin: i32,
+  /// Admin who recorded a cash handover
+  pub recorded_by_person_id: Option<PersonId>,
+  pub note: Option<String>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = cod_settlement))]
+pub struct CodSettlementInsertForm {
+  pub rider_id: RiderId,
+  pub method: CodSettlementMethod,
+  pub amount_coin: i32,
+  #[new(default)]
+  pub recorded_by_person_id: Option<PersonId>,
+  #[new(default)]
+  pub note: Option<String>,
+}
+
+/// Totals for an employer's COD remittance report.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct CodRemittanceSummary {
+  pub collected_coin: i64,
+  pub remitted_coin: i64,
+  pub outstanding_coin: i64,
+}
+
+/// A rider holding unsettled COD cash, for the admin overview.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderCodOutstanding {
+  pub rider_id: RiderId,
+  pub outstanding_coin: i64,
+  pub open_collections: i64,
+}
@@ -25,4 +25,6 @@ pub struct SearchCombined {
   pub proposal_id: Option<ProposalId>,
   pub category_id: Option<CategoryId>,
   pub person_id: Option<PersonId>,
+  /// Whether the post has a running paid boost
+  pub boosted: bool,
 }
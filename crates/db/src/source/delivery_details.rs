@@ -83,6 +83,16 @@ pub struct DeliveryDetails {
   pub rider_wallet_transaction_id: Option<i32>,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
+  /// The rider must photograph the handed-over parcel
+  pub requires_photo: bool,
+  /// The rider must enter the receiver's one-time code
+  pub requires_otp: bool,
+  /// Booked pickup time; `None` for an immediate delivery
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
+  /// When the rider who reserved a scheduled delivery confirmed it
+  pub rider_confirmed_at: Option<DateTime<Utc>>,
+  /// When the rider holding the reservation was reminded
+  pub reminder_sent_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, derive_new::new, Serialize, Deserialize)]
@@ -113,6 +123,10 @@ pub struct DeliveryDetailsInsertForm {
   pub fragile: Option<bool>,
   #[new(default)]
   pub requires_signature: Option<bool>,
+  #[new(default)]
+  pub requires_photo: Option<bool>,
+  #[new(default)]
+  pub requires_otp: Option<bool>,
 
   // Constraints
   #[new(default)]
@@ -121,6 +135,8 @@ pub struct DeliveryDetailsInsertForm {
   pub latest_pickup_at: Option<DateTime<Utc>>,
   #[new(default)]
   pub latest_dropoff_at: Option<DateTime<Utc>>,
+  #[new(default)]
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
 
   // Contacts
   #[new(default)]
@@ -193,11 +209,14 @@ pub struct DeliveryDetailsUpdateForm {
   pub package_size: Option<Option<String>>,
   pub fragile: Option<bool>,
   pub requires_signature: Option<bool>,
+  pub requires_photo: Option<bool>,
+  pub requires_otp: Option<bool>,
 
   // Constraints
   pub vehicle_required: Option<Option<VehicleType>>,
   pub latest_pickup_at: Option<Option<DateTime<Utc>>>,
   pub latest_dropoff_at: Option<Option<DateTime<Utc>>>,
+  pub scheduled_pickup_at: Option<Option<DateTime<Utc>>>,
 
   // Contacts
   pub sender_name: Option<Option<String>>,
@@ -224,6 +243,8 @@ pub struct DeliveryDetailsUpdateForm {
   pub assigned_at: Option<Option<DateTime<Utc>>>,
   pub assigned_by_person_id: Option<Option<PersonId>>,
   pub linked_proposal_id: Option<Option<ProposalId>>,
+  pub rider_confirmed_at: Option<Option<DateTime<Utc>>>,
+  pub reminder_sent_at: Option<Option<DateTime<Utc>>>,
 
   // Metadata
   pub updated_at: Option<DateTime<Utc>>,
@@ -252,11 +273,15 @@ pub struct DeliveryDetailsPayload {
   pub package_size: Option<String>,
   pub fragile: Option<bool>,
   pub requires_signature: Option<bool>,
+  pub requires_photo: Option<bool>,
+  pub requires_otp: Option<bool>,
 
   // Constraints
   pub vehicle_required: Option<VehicleType>,
   pub latest_pickup_at: Option<DateTime<Utc>>,
   pub latest_dropoff_at: Option<DateTime<Utc>>,
+  /// Book the pickup for a later time; only honoured when the post is created
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
 
   // Contacts
   pub sender_name: Option<String>,
@@ -286,6 +311,8 @@ impl DeliveryDetailsPayload {
       package_size: self.package_size.map(Some),
       fragile: self.fragile,
       requires_signature: self.requires_signature,
+      requires_photo: self.requires_photo,
+      requires_otp: self.requires_otp,
       vehicle_required: self.vehicle_required.map(Some),
       latest_pickup_at: self.latest_pickup_at.map(Some),
       latest_dropoff_at: self.latest_dropoff_at.map(Some),
@@ -302,6 +329,10 @@ impl DeliveryDetailsPayload {
       assigned_at: None,
       assigned_by_person_id: None,
       linked_proposal_id: None,
+      // Scheduling is fixed once the post is created:
+      scheduled_pickup_at: None,
+      rider_confirmed_at: None,
+      reminder_sent_at: None,
       // Payment tracking fields are not editable via post update:
       delivery_fee: None,
       employer_confirmed_at: None,
@@ -336,12 +367,15 @@ pub struct DeliveryDetailsPublic {
   pub package_size: Option<String>,
   pub fragile: bool,
   pub requires_signature: bool,
+  pub requires_photo: bool,
+  pub requires_otp: bool,
   #[cfg_attr(feature = "ts-rs", ts(type = "string"))]
   pub vehicle_required: Option<VehicleType>,
 
   // Timing (safe to show)
   pub latest_pickup_at: Option<DateTime<Utc>>,
   pub latest_dropoff_at: Option<DateTime<Utc>>,
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
 
   // Payment - show COD status but NOT amount (that's between parties)
   pub cash_on_delivery: bool,
@@ -381,9 +415,13 @@ pub struct DeliveryDetailsPrivate {
   pub package_size: Option<String>,
   pub fragile: bool,
   pub requires_signature: bool,
+  pub requires_photo: bool,
+  pub requires_otp: bool,
   pub vehicle_required: Option<VehicleType>,
   pub latest_pickup_at: Option<DateTime<Utc>>,
   pub latest_dropoff_at: Option<DateTime<Utc>>,
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
+  pub rider_confirmed_at: Option<DateTime<Utc>>,
 
   // Sensitive contact info - ONLY for authorized parties
   pub sender_name: Option<String>,
@@ -467,9 +505,12 @@ impl DeliveryDetails {
       package_size: self.package_size.clone(),
       fragile: self.fragile,
       requires_signature: self.requires_signature,
+      requires_photo: self.requires_photo,
+      requires_otp: self.requires_otp,
       vehicle_required: self.vehicle_required,
       latest_pickup_at: self.latest_pickup_at,
       latest_dropoff_at: self.latest_dropoff_at,
+      scheduled_pickup_at: self.scheduled_pickup_at,
       cash_on_delivery: self.cash_on_delivery,
       delivery_fee: self.delivery_fee,
       employer_confirmed_at: self.employer_confirmed_at,
@@ -497,9 +538,13 @@ impl DeliveryDetails {
       package_size: self.package_size.clone(),
       fragile: self.fragile,
       requires_signature: self.requires_signature,
+      requires_photo: self.requires_photo,
+      requires_otp: self.requires_otp,
       vehicle_required: self.vehicle_required,
       latest_pickup_at: self.latest_pickup_at,
       latest_dropoff_at: self.latest_dropoff_at,
+      scheduled_pickup_at: self.scheduled_pickup_at,
+      rider_confirmed_at: self.rider_confirmed_at,
       sender_name: self.sender_name.clone(),
       sender_phone: self.sender_phone.clone(),
       receiver_name: self.receiver_name.clone(),
@@ -0,0 +1,62 @@
+use crate::newtypes::{DbUrl, DeliveryProofId, PostId, RiderId};
+#[cfg(feature = "full")]
+use crate::schema::delivery_proof;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// Evidence captured when a delivery was handed over.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = delivery_proof))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryProof {
+  pub id: DeliveryProofId,
+  pub post_id: PostId,
+  pub rider_id: RiderId,
+  pub photo_url: Option<DbUrl>,
+  pub signature_url: Option<DbUrl>,
+  /// Code the receiver gives the rider; never sent to the rider
+  #[serde(skip)]
+  pub otp_code: Option<String>,
+  pub otp_attempts: i32,
+  pub otp_verified_at: Option<DateTime<Utc>>,
+  /// Rider location when the delivery was marked Delivered
+  pub delivered_lat: Option<f64>,
+  pub delivered_lng: Option<f64>,
+  pub submitted_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = delivery_proof))]
+pub struct DeliveryProofInsertForm {
+  pub post_id: PostId,
+  pub rider_id: RiderId,
+  #[new(default)]
+  pub photo_url: Option<DbUrl>,
+  #[new(default)]
+  pub signature_url: Option<DbUrl>,
+  #[new(default)]
+  pub otp_code: Option<String>,
+  #[new(default)]
+  pub delivered_lat: Option<f64>,
+  #[new(default)]
+  pub delivered_lng: Option<f64>,
+  #[new(default)]
+  pub submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Evidence submitted with the `Delivered` status.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeliveryEvidence {
+  pub photo_url: Option<DbUrl>,
+  pub signature_url: Option<DbUrl>,
+  pub otp: Option<String>,
+}
@@ -0,0 +1,83 @@
+#[cfg(feature = "full")]
+use crate::schema::delivery_stop;
+use crate::{
+  enums::DeliveryStopStatus,
+  newtypes::{Coin, DbUrl, DeliveryStopId, PostId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// One drop-off of a multi-stop delivery. Stops are visited in `stop_order`.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = delivery_stop))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryStop {
+  pub id: DeliveryStopId,
+  pub post_id: PostId,
+  /// 1 for the first drop-off after the pickup
+  pub stop_order: i32,
+  pub address: String,
+  pub lat: f64,
+  pub lng: f64,
+  pub contact_name: Option<String>,
+  pub contact_phone: Option<String>,
+  /// Cash to collect at this stop
+  pub cod_amount: Option<f64>,
+  pub status: DeliveryStopStatus,
+  pub skip_reason: Option<String>,
+  /// Route distance from the previous stop, or from the pickup
+  pub leg_distance_km: f64,
+  /// Price of the leg ending at this stop
+  pub leg_fee: Coin,
+  pub photo_url: Option<DbUrl>,
+  pub signature_url: Option<DbUrl>,
+  /// Rider location when the stop was completed or skipped
+  pub completed_lat: Option<f64>,
+  pub completed_lng: Option<f64>,
+  pub arrived_at: Option<DateTime<Utc>>,
+  pub completed_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = delivery_stop))]
+pub struct DeliveryStopInsertForm {
+  pub post_id: PostId,
+  pub stop_order: i32,
+  pub address: String,
+  pub lat: f64,
+  pub lng: f64,
+  #[new(default)]
+  pub contact_name: Option<String>,
+  #[new(default)]
+  pub contact_phone: Option<String>,
+  #[new(default)]
+  pub cod_amount: Option<f64>,
+  #[new(default)]
+  pub leg_distance_km: Option<f64>,
+  #[new(default)]
+  pub leg_fee: Option<Coin>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = delivery_stop))]
+pub struct DeliveryStopUpdateForm {
+  pub status: Option<DeliveryStopStatus>,
+  pub skip_reason: Option<Option<String>>,
+  pub photo_url: Option<Option<DbUrl>>,
+  pub signature_url: Option<Option<DbUrl>>,
+  pub completed_lat: Option<Option<f64>>,
+  pub completed_lng: Option<Option<f64>>,
+  pub arrived_at: Option<Option<DateTime<Utc>>>,
+  pub completed_at: Option<Option<DateTime<Utc>>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
@@ -0,0 +1,160 @@
+#[cfg(feature = "full")]
+use crate::schema::{dispatch_offer, dispatch_request};
+use crate::{
+  enums::{DispatchJobKind, DispatchOfferStatus, DispatchStatus, VehicleType},
+  newtypes::{DispatchOfferId, DispatchRequestId, LocalUserId, PostId, RiderId, ServiceAreaId},
+};
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// An automatic search for a rider on a delivery or ride post.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = dispatch_request))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchRequest {
+  pub id: DispatchRequestId,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  /// Only riders with this vehicle are offered the job
+  pub vehicle_type: Option<VehicleType>,
+  pub pickup_lat: f64,
+  pub pickup_lng: f64,
+  pub status: DispatchStatus,
+  /// Number of waves sent so far
+  pub wave: i32,
+  pub accepted_rider_id: Option<RiderId>,
+  /// When the current wave times out
+  pub next_wave_at: DateTime<Utc>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+  /// Only riders inside this service area are offered the job
+  pub service_area_id: Option<ServiceAreaId>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = dispatch_request))]
+pub struct DispatchRequestInsertForm {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub pickup_lat: f64,
+  pub pickup_lng: f64,
+  #[new(default)]
+  pub vehicle_type: Option<VehicleType>,
+  #[new(default)]
+  pub service_area_id: Option<ServiceAreaId>,
+  /// When the first wave goes out; now if not set
+  #[new(default)]
+  pub next_wave_at: Option<DateTime<Utc>>,
+}
+
+/// A job offer sent to one rider.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = dispatch_offer))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchOffer {
+  pub id: DispatchOfferId,
+  pub dispatch_request_id: DispatchRequestId,
+  pub rider_id: RiderId,
+  pub wave: i32,
+  /// Straight-line distance from the rider's last location to the pickup
+  pub distance_km: f64,
+  pub score: f64,
+  pub status: DispatchOfferStatus,
+  pub offered_at: DateTime<Utc>,
+  pub expires_at: DateTime<Utc>,
+  pub responded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = dispatch_offer))]
+pub struct DispatchOfferInsertForm {
+  pub dispatch_request_id: DispatchRequestId,
+  pub rider_id: RiderId,
+  pub wave: i32,
+  pub distance_km: f64,
+  pub score: f64,
+  pub expires_at: DateTime<Utc>,
+}
+
+/// An offer together with the job it is for, as shown to the rider.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchOfferView {
+  pub offer: DispatchOffer,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub pickup_lat: f64,
+  pub pickup_lng: f64,
+}
+
+/// A rider eligible for an offer, before ranking.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DispatchCandidate {
+  pub rider_id: RiderId,
+  pub local_user_id: LocalUserId,
+  /// Rider's last known location
+  pub lat: f64,
+  pub lng: f64,
+  /// Straight-line distance to the pickup, used for the search radius
+  pub distance_km: f64,
+  /// Road distance to the pickup when a router provided one, used for
+  /// ranking instead of the straight line
+  pub road_distance_km: Option<f64>,
+  pub rating: f64,
+  /// Share of answered offers the rider accepted, 1.0 with no history
+  pub acceptance_rate: f64,
+}
+
+/// Road distances from rider locations to a pickup, used to rank dispatch
+/// candidates. Entries left `None` keep the straight-line distance.
+pub trait DispatchDistances: Send + Sync {
+  fn to_pickup<'a>(
+    &'a self,
+    riders: &'a [(f64, f64)],
+    pickup: (f64, f64),
+  ) -> BoxFuture<'a, Vec<Option<f64>>>;
+}
+
+/// Rank by straight-line distance only.
+pub struct StraightLineDistances;
+
+impl DispatchDistances for StraightLineDistances {
+  fn to_pickup<'a>(
+    &'a self,
+    riders: &'a [(f64, f64)],
+    _pickup: (f64, f64),
+  ) -> BoxFuture<'a, Vec<Option<f64>>> {
+    Box::pin(std::future::ready(vec![None; riders.len()]))
+  }
+}
+
+/// Which riders have a live connection right now. Only they are offered the
+/// job, so an offer is never spent on a rider who will not see it in time.
+pub trait DispatchPresence: Send + Sync {
+  fn online<'a>(&'a self, users: &'a [LocalUserId]) -> BoxFuture<'a, Vec<bool>>;
+}
+
+/// Treat every candidate as connected.
+pub struct AnyPresence;
+
+impl DispatchPresence for AnyPresence {
+  fn online<'a>(&'a self, users: &'a [LocalUserId]) -> BoxFuture<'a, Vec<bool>> {
+    Box::pin(std::future::ready(vec![true; users.len()]))
+  }
+}
@@ -0,0 +1,71 @@
+#[cfg(feature = "full")]
+use crate::schema::{employer_rating, employer_reliability};
+use crate::{
+  enums::{DispatchJobKind, EmployerRatingTag},
+  newtypes::{EmployerRatingId, PersonId, PostId, RiderId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A rider's rating of the employer or passenger of a finished delivery or
+/// ride. One per rider and trip; rating again replaces it.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = employer_rating))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct EmployerRating {
+  pub id: EmployerRatingId,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub rider_id: RiderId,
+  pub employer_id: PersonId,
+  pub rating: i16,
+  pub tags: Vec<EmployerRatingTag>,
+  pub comment: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = employer_rating))]
+pub struct EmployerRatingInsertForm {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub rider_id: RiderId,
+  pub employer_id: PersonId,
+  pub rating: i16,
+  pub tags: Vec<EmployerRatingTag>,
+  #[new(default)]
+  pub comment: Option<String>,
+}
+
+/// Aggregate of the ratings riders gave an employer. `score` runs from 0 to
+/// 100 and starts near the top for employers with few ratings.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Identifiable, Insertable, AsChangeset)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = employer_reliability))]
+#[cfg_attr(feature = "full", diesel(primary_key(employer_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct EmployerReliability {
+  pub employer_id: PersonId,
+  pub rating_count: i32,
+  pub average_rating: f64,
+  pub no_show_count: i32,
+  pub wrong_address_count: i32,
+  pub rude_count: i32,
+  pub great_count: i32,
+  pub score: f64,
+  pub updated_at: DateTime<Utc>,
+}
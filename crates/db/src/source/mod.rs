@@ -1,13 +1,16 @@
 pub mod actor_language;
 pub mod bank;
 pub mod billing;
+pub mod cancellation;
 pub mod captcha_answer;
+pub mod cargo_tariff;
 pub mod category;
 pub mod category_report;
 pub mod chat_message;
 pub mod chat_participant;
 pub mod chat_room;
 pub mod chat_unread;
+pub mod cod;
 pub mod coin;
 pub mod combined;
 pub mod currency;
@@ -15,8 +18,13 @@ pub mod currency_rate_history;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
 pub mod delivery_details;
+pub mod delivery_proof;
 pub mod delivery_rider_rating;
+pub mod delivery_stop;
+pub mod dispatch;
 pub mod email_verification;
+pub mod emergency_contact;
+pub mod employer_rating;
 pub mod images;
 pub mod instance;
 pub mod job_budget_plan;
@@ -37,22 +45,33 @@ pub mod person;
 pub mod person_post_mention;
 pub mod person_proposal_mention;
 pub mod post;
+pub mod post_boost;
 pub mod post_report;
 pub mod post_tag;
 pub mod pricing_config;
+pub mod pricing_rule;
 pub mod proposal;
 pub mod proposal_reply;
 pub mod proposal_report;
+pub mod referral;
 pub mod registration_application;
+pub mod ride_meter_flag;
 pub mod ride_session;
 pub mod rider;
+pub mod rider_earning;
+pub mod rider_fleet;
+pub mod rider_shift;
 pub mod secret;
+pub mod service_area;
 pub mod site;
 pub mod tag;
 pub mod tagline;
 pub mod top_up_request;
+pub mod tracking_link;
+pub mod trip_incident;
 pub mod trip_location_current;
 pub mod trip_location_history;
+pub mod trip_status_history;
 pub mod user_bank_account;
 pub mod user_review;
 pub mod wallet;
@@ -89,6 +89,9 @@ pub struct Post {
   pub is_english_required: bool,
   pub post_kind: PostKind,
   pub pending: bool,
+  /// Whether the post has a running paid boost, which ranks it first in hot and active listings
+  #[serde(skip)]
+  pub boosted: bool,
 }
 
 // TODO: FromBytes, ToBytes are only needed to develop wasm plugin, could be behind feature flag
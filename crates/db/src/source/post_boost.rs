@@ -0,0 +1,99 @@
+#[cfg(feature = "full")]
+use crate::schema::{post_boost, post_boost_package};
+use crate::{
+  enums::{PostBoostKind, PostBoostStatus},
+  newtypes::{LocalUserId, PostBoostId, PostBoostPackageId, PostId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A boost package employers can buy for their job posts.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_boost_package))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct PostBoostPackage {
+  pub id: PostBoostPackageId,
+  pub name: String,
+  pub kind: PostBoostKind,
+  pub duration_days: i32,
+  pub price_coin: i32,
+  pub is_active: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_boost_package))]
+pub struct PostBoostPackageInsertForm {
+  pub name: String,
+  pub kind: PostBoostKind,
+  pub duration_days: i32,
+  pub price_coin: i32,
+  #[new(default)]
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_boost_package))]
+pub struct PostBoostPackageUpdateForm {
+  pub name: Option<String>,
+  pub duration_days: Option<i32>,
+  pub price_coin: Option<i32>,
+  pub is_active: Option<bool>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// A boost bought for a post. Price and window are copied from the package
+/// at purchase time.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_boost))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct PostBoost {
+  pub id: PostBoostId,
+  pub post_id: PostId,
+  pub package_id: PostBoostPackageId,
+  pub local_user_id: LocalUserId,
+  pub kind: PostBoostKind,
+  pub price_coin: i32,
+  pub status: PostBoostStatus,
+  pub starts_at: DateTime<Utc>,
+  pub ends_at: DateTime<Utc>,
+  pub refunded_coin: Option<i32>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_boost))]
+pub struct PostBoostInsertForm {
+  pub post_id: PostId,
+  pub package_id: PostBoostPackageId,
+  pub local_user_id: LocalUserId,
+  pub kind: PostBoostKind,
+  pub price_coin: i32,
+  pub starts_at: DateTime<Utc>,
+  pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_boost))]
+pub struct PostBoostUpdateForm {
+  pub status: Option<PostBoostStatus>,
+  pub refunded_coin: Option<Option<i32>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
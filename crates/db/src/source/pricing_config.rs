@@ -63,3 +63,11 @@ pub struct PricingConfigUpdateForm {
   pub is_active: Option<bool>,
   pub updated_at: Option<Option<DateTime<Utc>>>,
 }
+
+/// Metered charges for a trip before any pricing rule multiplier.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MeteredFare {
+  pub time_charge_coin: i32,
+  pub distance_charge_coin: i32,
+  pub subtotal_coin: i32,
+}
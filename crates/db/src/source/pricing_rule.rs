@@ -0,0 +1,107 @@
+#[cfg(feature = "full")]
+use crate::schema::pricing_rule;
+use crate::{
+  enums::PricingRuleKind,
+  newtypes::{PricingConfigId, PricingRuleId, ServiceAreaId},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A multiplier layered on a pricing config.
+///
+/// Criteria left empty match everything, so a `TimeWindow` rule without
+/// `days_of_week` applies every day and a rule without a zone applies
+/// everywhere.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = pricing_rule))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct PricingRule {
+  pub id: PricingRuleId,
+  pub pricing_config_id: PricingConfigId,
+  pub name: String,
+  pub kind: PricingRuleKind,
+  pub multiplier: f64,
+  /// Local minute of the day the window opens, 0-1439
+  pub start_minute: Option<i32>,
+  /// Local minute of the day the window closes; before `start_minute` means
+  /// the window runs past midnight
+  pub end_minute: Option<i32>,
+  /// ISO weekdays, 1 = Monday to 7 = Sunday
+  pub days_of_week: Option<Vec<i32>>,
+  pub holiday_date: Option<NaiveDate>,
+  pub zone_lat: Option<f64>,
+  pub zone_lng: Option<f64>,
+  pub zone_radius_km: Option<f64>,
+  /// Surge applies once open requests per online rider reach this ratio
+  pub demand_ratio_threshold: Option<f64>,
+  pub is_active: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+  /// Limits the rule to pickups inside this service area
+  pub service_area_id: Option<ServiceAreaId>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = pricing_rule))]
+pub struct PricingRuleInsertForm {
+  pub pricing_config_id: PricingConfigId,
+  pub name: String,
+  pub kind: PricingRuleKind,
+  pub multiplier: f64,
+  #[new(default)]
+  pub start_minute: Option<i32>,
+  #[new(default)]
+  pub end_minute: Option<i32>,
+  #[new(default)]
+  pub days_of_week: Option<Vec<i32>>,
+  #[new(default)]
+  pub holiday_date: Option<NaiveDate>,
+  #[new(default)]
+  pub zone_lat: Option<f64>,
+  #[new(default)]
+  pub zone_lng: Option<f64>,
+  #[new(default)]
+  pub zone_radius_km: Option<f64>,
+  #[new(default)]
+  pub demand_ratio_threshold: Option<f64>,
+  #[new(default)]
+  pub is_active: Option<bool>,
+  #[new(default)]
+  pub service_area_id: Option<ServiceAreaId>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = pricing_rule))]
+pub struct PricingRuleUpdateForm {
+  pub name: Option<String>,
+  pub multiplier: Option<f64>,
+  pub start_minute: Option<Option<i32>>,
+  pub end_minute: Option<Option<i32>>,
+  pub days_of_week: Option<Option<Vec<i32>>>,
+  pub holiday_date: Option<Option<NaiveDate>>,
+  pub zone_lat: Option<Option<f64>>,
+  pub zone_lng: Option<Option<f64>>,
+  pub zone_radius_km: Option<Option<f64>>,
+  pub demand_ratio_threshold: Option<Option<f64>>,
+  pub is_active: Option<bool>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+  pub service_area_id: Option<Option<ServiceAreaId>>,
+}
+
+/// The multiplier in force for a ride and the rules that produced it.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct PriceMultiplier {
+  pub multiplier: f64,
+  pub applied_rules: Vec<PricingRuleId>,
+}
@@ -0,0 +1,101 @@
+#[cfg(feature = "full")]
+use crate::schema::{referral, referral_code};
+use crate::{
+  enums::ReferralStatus,
+  newtypes::{LocalUserId, ReferralId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A user's personal invite code.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = referral_code))]
+#[cfg_attr(feature = "full", diesel(primary_key(local_user_id)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ReferralCode {
+  pub local_user_id: LocalUserId,
+  pub code: String,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = referral_code))]
+pub struct ReferralCodeInsertForm {
+  pub local_user_id: LocalUserId,
+  pub code: String,
+}
+
+/// A signup attributed to a referrer's invite code.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = referral))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct Referral {
+  pub id: ReferralId,
+  pub referrer_id: LocalUserId,
+  pub referred_id: LocalUserId,
+  pub code: String,
+  pub status: ReferralStatus,
+  #[serde(skip)]
+  pub signup_ip: Option<String>,
+  #[serde(skip)]
+  pub device_id: Option<String>,
+  pub reward_coin: Option<i32>,
+  /// What qualified the referral, e.g. `billing` or `delivery`
+  pub reference_type: Option<String>,
+  pub reference_id: Option<i32>,
+  pub rejected_reason: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub rewarded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = referral))]
+pub struct ReferralInsertForm {
+  pub referrer_id: LocalUserId,
+  pub referred_id: LocalUserId,
+  pub code: String,
+  #[new(default)]
+  pub status: Option<ReferralStatus>,
+  #[new(default)]
+  pub signup_ip: Option<String>,
+  #[new(default)]
+  pub device_id: Option<String>,
+  #[new(default)]
+  pub rejected_reason: Option<String>,
+}
+
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = referral))]
+pub struct ReferralUpdateForm {
+  pub status: Option<ReferralStatus>,
+  pub reward_coin: Option<Option<i32>>,
+  pub reference_type: Option<Option<String>>,
+  pub reference_id: Option<Option<i32>>,
+  pub rejected_reason: Option<Option<String>>,
+  pub rewarded_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Aggregated referral counters shown on the referrer's dashboard.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ReferralSummary {
+  pub pending_count: i64,
+  pub rewarded_count: i64,
+  pub rejected_count: i64,
+  pub earned_coin: i64,
+}
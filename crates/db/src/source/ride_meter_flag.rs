@@ -0,0 +1,64 @@
+#[cfg(feature = "full")]
+use crate::schema::ride_meter_flag;
+use crate::{
+  enums::MeterCheckStage,
+  newtypes::{PersonId, RideMeterFlagId, RideSessionId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A ride whose reported meter diverged from the GPS history.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = ride_meter_flag))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RideMeterFlag {
+  pub id: RideMeterFlagId,
+  pub ride_session_id: RideSessionId,
+  pub stage: MeterCheckStage,
+  /// What the rider app reported, or the rider's last meter at completion
+  pub reported_distance_km: f64,
+  /// Distance recomputed from the filtered GPS track
+  pub server_distance_km: f64,
+  pub reported_minutes: i32,
+  pub server_minutes: i32,
+  pub points_used: i32,
+  /// GPS fixes discarded as inaccurate or as teleports
+  pub points_dropped: i32,
+  /// Whether the meter update was refused
+  pub rejected: bool,
+  pub created_at: DateTime<Utc>,
+  pub reviewed_at: Option<DateTime<Utc>>,
+  pub reviewed_by_person_id: Option<PersonId>,
+  pub resolution_note: Option<String>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = ride_meter_flag))]
+pub struct RideMeterFlagInsertForm {
+  pub ride_session_id: RideSessionId,
+  pub stage: MeterCheckStage,
+  pub reported_distance_km: f64,
+  pub server_distance_km: f64,
+  pub reported_minutes: i32,
+  pub server_minutes: i32,
+  pub points_used: i32,
+  pub points_dropped: i32,
+  #[new(default)]
+  pub rejected: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = ride_meter_flag))]
+pub struct RideMeterFlagUpdateForm {
+  pub reviewed_at: Option<Option<DateTime<Utc>>>,
+  pub reviewed_by_person_id: Option<Option<PersonId>>,
+  pub resolution_note: Option<Option<String>>,
+}
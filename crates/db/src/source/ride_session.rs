@@ -71,6 +71,15 @@ pub struct RideSession {
 
   pub created_at: DateTime<Utc>,
   pub updated_at: Option<DateTime<Utc>>,
+
+  /// Pricing rule multiplier locked in when the ride was requested
+  pub price_multiplier: Option<f64>,
+  /// Most the meter may charge, from an accepted fare quote
+  pub fare_cap_coin: Option<i32>,
+  /// Booked pickup time; `None` for an immediate ride
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
+  /// When the rider holding the reservation was reminded
+  pub reminder_sent_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, derive_new::new)]
@@ -98,6 +107,7 @@ pub struct RideSessionInsertForm {
   pub status: Option<TripStatus>,
   pub requested_at: Option<DateTime<Utc>>,
   pub current_price_coin: Option<i32>,
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -130,4 +140,7 @@ pub struct RideSessionUpdateForm {
   pub distance_charge_applied_coin: Option<Option<i32>>,
   pub cancellation_reason: Option<Option<String>>,
   pub updated_at: Option<Option<DateTime<Utc>>>,
+  pub price_multiplier: Option<Option<f64>>,
+  pub fare_cap_coin: Option<Option<i32>>,
+  pub reminder_sent_at: Option<Option<DateTime<Utc>>>,
 }
@@ -1,6 +1,6 @@
 use crate::{
   enums::{RiderVerificationStatus, VehicleType},
-  newtypes::{LocalUserId, PersonId, RiderId},
+  newtypes::{LocalUserId, PersonId, RiderFleetId, RiderId},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -43,8 +43,6 @@ pub struct Rider {
   pub rating: f64,
   pub completed_jobs: i32,
   pub total_jobs: i32,
-  pub total_earnings: f64,
-  pub pending_earnings: f64,
 
   /// Availability
   pub is_online: bool,
@@ -54,6 +52,20 @@ pub struct Rider {
   pub joined_at: Option<DateTime<Utc>>,
   pub last_active_at: Option<DateTime<Utc>>,
   pub verified_at: Option<DateTime<Utc>>,
+
+  /// Document expiry
+  /// Smallest reminder, in days before expiry, already sent for this license
+  pub license_reminder_days: Option<i32>,
+  /// Set when the rider was suspended for an expired license
+  pub documents_expired_at: Option<DateTime<Utc>>,
+
+  /// Cancellation penalties
+  /// Until then the rider may not accept jobs, after repeated cancellations
+  pub accept_banned_until: Option<DateTime<Utc>>,
+
+  /// Fleet
+  /// The courier company the rider drives for
+  pub fleet_id: Option<RiderFleetId>,
 }
 
 #[derive(Debug, Clone, derive_new::new)]
@@ -91,10 +103,6 @@ pub struct RiderInsertForm {
   pub completed_jobs: Option<i32>,
   #[new(default)]
   pub total_jobs: Option<i32>,
-  #[new(default)]
-  pub total_earnings: Option<f64>,
-  #[new(default)]
-  pub pending_earnings: Option<f64>,
 
   /// Availability
   #[new(default)]
@@ -130,8 +138,6 @@ pub struct RiderUpdateForm {
   pub rating: Option<f64>,
   pub completed_jobs: Option<i32>,
   pub total_jobs: Option<i32>,
-  pub total_earnings: Option<f64>,
-  pub pending_earnings: Option<f64>,
 
   /// Availability
   pub is_online: Option<bool>,
@@ -141,4 +147,14 @@ pub struct RiderUpdateForm {
   pub joined_at: Option<Option<DateTime<Utc>>>,
   pub last_active_at: Option<Option<DateTime<Utc>>>,
   pub verified_at: Option<Option<DateTime<Utc>>>,
+
+  /// Document expiry
+  pub license_reminder_days: Option<Option<i32>>,
+  pub documents_expired_at: Option<Option<DateTime<Utc>>>,
+
+  /// Cancellation penalties
+  pub accept_banned_until: Option<Option<DateTime<Utc>>>,
+
+  /// Fleet
+  pub fleet_id: Option<Option<RiderFleetId>>,
 }
@@ -0,0 +1,83 @@
+use crate::newtypes::{Coin, PostId, RiderEarningHoldId, RiderId};
+#[cfg(feature = "full")]
+use crate::schema::rider_earning_hold;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A delivery payout kept pending in the rider's wallet until it clears.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_earning_hold))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderEarningHold {
+  pub id: RiderEarningHoldId,
+  pub rider_id: RiderId,
+  pub post_id: PostId,
+  pub amount_coin: i32,
+  /// When the coins become withdrawable
+  pub clears_at: DateTime<Utc>,
+  pub released_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_earning_hold))]
+pub struct RiderEarningHoldInsertForm {
+  pub rider_id: RiderId,
+  pub post_id: PostId,
+  pub amount_coin: i32,
+  pub clears_at: DateTime<Utc>,
+}
+
+/// Rider earnings derived from delivery payouts in the wallet ledger.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderEarningsSummary {
+  /// All delivery payouts ever released to the rider
+  pub total_earned_coin: i64,
+  /// Payouts still inside their clearing period
+  pub pending_coin: i64,
+  /// Wallet balance the rider can withdraw now
+  pub withdrawable_coin: Coin,
+  pub paid_deliveries: i64,
+}
+
+/// Bucket size for the earnings breakdown.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum RiderEarningsPeriod {
+  #[default]
+  Day,
+  Week,
+}
+
+impl RiderEarningsPeriod {
+  /// The `date_trunc` field name for this period.
+  pub fn trunc_field(self) -> &'static str {
+    match self {
+      RiderEarningsPeriod::Day => "day",
+      RiderEarningsPeriod::Week => "week",
+    }
+  }
+}
+
+/// Delivery payouts released within one day or week.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderEarningsBucket {
+  /// Start of the day or week (weeks start on Monday, UTC)
+  pub period_start: DateTime<Utc>,
+  pub earned_coin: i64,
+  pub deliveries: i64,
+}
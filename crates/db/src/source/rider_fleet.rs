@@ -0,0 +1,118 @@
+#[cfg(feature = "full")]
+use crate::schema::{fleet_document, rider_fleet};
+use crate::{
+  enums::{DispatchJobKind, FleetDocumentKind, FleetDocumentStatus, TripStatus},
+  newtypes::{DbUrl, FleetDocumentId, LocalUserId, PersonId, PostId, RiderFleetId, RiderId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A courier company managing several riders. With `route_earnings` on and
+/// the fleet verified, `fleet_share_percent` of its riders' delivery payouts
+/// goes to the owner's wallet.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_fleet))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderFleet {
+  pub id: RiderFleetId,
+  pub owner_id: LocalUserId,
+  pub name: String,
+  /// Riders join the fleet with this code
+  pub join_code: String,
+  pub route_earnings: bool,
+  pub fleet_share_percent: f64,
+  /// Every document of the fleet has been approved by an admin
+  pub is_verified: bool,
+  pub verified_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_fleet))]
+pub struct RiderFleetInsertForm {
+  pub owner_id: LocalUserId,
+  pub name: String,
+  pub join_code: String,
+  #[new(default)]
+  pub route_earnings: Option<bool>,
+  #[new(default)]
+  pub fleet_share_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_fleet))]
+pub struct RiderFleetUpdateForm {
+  pub name: Option<String>,
+  pub join_code: Option<String>,
+  pub route_earnings: Option<bool>,
+  pub fleet_share_percent: Option<f64>,
+  pub is_verified: Option<bool>,
+  pub verified_at: Option<Option<DateTime<Utc>>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// A company document submitted by a fleet owner for admins to review.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = fleet_document))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct FleetDocument {
+  pub id: FleetDocumentId,
+  pub fleet_id: RiderFleetId,
+  pub kind: FleetDocumentKind,
+  pub file_url: DbUrl,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub status: FleetDocumentStatus,
+  pub reviewed_by: Option<PersonId>,
+  pub review_note: Option<String>,
+  pub reviewed_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = fleet_document))]
+pub struct FleetDocumentInsertForm {
+  pub fleet_id: RiderFleetId,
+  pub kind: FleetDocumentKind,
+  pub file_url: DbUrl,
+  #[new(default)]
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = fleet_document))]
+pub struct FleetDocumentUpdateForm {
+  pub status: Option<FleetDocumentStatus>,
+  pub reviewed_by: Option<Option<PersonId>>,
+  pub review_note: Option<Option<String>>,
+  pub reviewed_at: Option<Option<DateTime<Utc>>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// A delivery or ride a fleet rider is on right now.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct FleetRiderJob {
+  pub rider_id: RiderId,
+  pub job_kind: DispatchJobKind,
+  pub post_id: PostId,
+  pub status: TripStatus,
+}
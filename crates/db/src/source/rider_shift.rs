@@ -0,0 +1,122 @@
+#[cfg(feature = "full")]
+use crate::schema::{rider_shift, rider_shift_slot};
+use crate::{
+  enums::RiderShiftStatus,
+  newtypes::{RiderId, RiderShiftId, RiderShiftSlotId, ServiceAreaId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A window in a service area that up to `capacity` riders can claim.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_shift_slot))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderShiftSlot {
+  pub id: RiderShiftSlotId,
+  pub service_area_id: ServiceAreaId,
+  pub starts_at: DateTime<Utc>,
+  pub ends_at: DateTime<Utc>,
+  pub capacity: i32,
+  pub note: Option<String>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_shift_slot))]
+pub struct RiderShiftSlotInsertForm {
+  pub service_area_id: ServiceAreaId,
+  pub starts_at: DateTime<Utc>,
+  pub ends_at: DateTime<Utc>,
+  pub capacity: i32,
+  #[new(default)]
+  pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_shift_slot))]
+pub struct RiderShiftSlotUpdateForm {
+  pub starts_at: Option<DateTime<Utc>>,
+  pub ends_at: Option<DateTime<Utc>>,
+  pub capacity: Option<i32>,
+  pub note: Option<Option<String>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// A rider's claim on a shift slot.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_shift))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderShift {
+  pub id: RiderShiftId,
+  pub slot_id: RiderShiftSlotId,
+  pub rider_id: RiderId,
+  pub status: RiderShiftStatus,
+  /// When the rider was put online for the shift
+  pub started_at: Option<DateTime<Utc>>,
+  /// First sign of life from the rider after the shift started
+  pub checked_in_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = rider_shift))]
+pub struct RiderShiftInsertForm {
+  pub slot_id: RiderShiftSlotId,
+  pub rider_id: RiderId,
+}
+
+/// A slot with how many riders hold a claim on it.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftSlotAvailability {
+  pub slot: RiderShiftSlot,
+  pub claimed: i32,
+}
+
+/// A rider's shift with the slot it is for.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct RiderShiftView {
+  pub shift: RiderShift,
+  pub slot: RiderShiftSlot,
+}
+
+/// Planned and actual rider coverage of one service area for one hour.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftCoverageHour {
+  pub service_area_id: ServiceAreaId,
+  /// Start of the hour
+  pub hour: DateTime<Utc>,
+  /// Riders the slots overlapping this hour ask for
+  pub capacity: i32,
+  /// Riders holding a claim on those slots
+  pub claimed: i32,
+  /// Claims whose rider showed up
+  pub checked_in: i32,
+  pub no_shows: i32,
+  /// Riders short of the plan: unclaimed places plus no-shows
+  pub gap: i32,
+}
@@ -0,0 +1,84 @@
+#[cfg(feature = "full")]
+use crate::schema::service_area;
+use crate::{
+  enums::VehicleType,
+  newtypes::{PricingConfigId, ServiceAreaId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serde_with::skip_serializing_none;
+
+/// An area we operate rides and deliveries in.
+///
+/// Once any active area exists, pickups and dropoffs outside all of them
+/// are rejected.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = service_area))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceArea {
+  pub id: ServiceAreaId,
+  pub name: String,
+  /// GeoJSON `Polygon` or `MultiPolygon` geometry
+  #[cfg_attr(feature = "ts-rs", ts(type = "any"))]
+  pub geometry: JsonValue,
+  /// Bounding box of the geometry, for cheap prefiltering
+  pub min_lat: f64,
+  pub max_lat: f64,
+  pub min_lng: f64,
+  pub max_lng: f64,
+  /// Pricing used for trips starting here instead of the currency default
+  pub pricing_config_id: Option<PricingConfigId>,
+  /// Vehicles allowed to operate here; all when empty
+  pub allowed_vehicle_types: Option<Vec<VehicleType>>,
+  pub is_active: bool,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = service_area))]
+pub struct ServiceAreaInsertForm {
+  pub name: String,
+  pub geometry: JsonValue,
+  pub min_lat: f64,
+  pub max_lat: f64,
+  pub min_lng: f64,
+  pub max_lng: f64,
+  pub pricing_config_id: Option<PricingConfigId>,
+  pub allowed_vehicle_types: Option<Vec<VehicleType>>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = service_area))]
+pub struct ServiceAreaUpdateForm {
+  pub name: Option<String>,
+  pub geometry: Option<JsonValue>,
+  pub min_lat: Option<f64>,
+  pub max_lat: Option<f64>,
+  pub min_lng: Option<f64>,
+  pub max_lng: Option<f64>,
+  pub pricing_config_id: Option<Option<PricingConfigId>>,
+  pub allowed_vehicle_types: Option<Option<Vec<VehicleType>>>,
+  pub is_active: Option<bool>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Polygons of a GeoJSON geometry as rings of `(lat, lng)`, with their
+/// bounding box.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ServiceAreaShape {
+  pub polygons: Vec<Vec<Vec<(f64, f64)>>>,
+  pub min_lat: f64,
+  pub max_lat: f64,
+  pub min_lng: f64,
+  pub max_lng: f64,
+}
@@ -0,0 +1,40 @@
+#[cfg(feature = "full")]
+use crate::schema::tracking_link;
+use crate::{
+  enums::DispatchJobKind,
+  newtypes::{PostId, TrackingLinkId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A public share link for following a delivery or ride without an account.
+/// It stops working at `expires_at`, or once the trip is delivered or
+/// cancelled (a database trigger then sets `revoked_at`).
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = tracking_link))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct TrackingLink {
+  pub id: TrackingLinkId,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub token: String,
+  pub expires_at: DateTime<Utc>,
+  pub revoked_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = tracking_link))]
+pub struct TrackingLinkInsertForm {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub token: String,
+  pub expires_at: DateTime<Utc>,
+}
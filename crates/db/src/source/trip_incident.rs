@@ -0,0 +1,81 @@
+#[cfg(feature = "full")]
+use crate::schema::trip_incident;
+use crate::{
+  enums::{DispatchJobKind, IncidentKind, IncidentStatus},
+  newtypes::{DbUrl, PersonId, PostId, RiderId, TrackingLinkId, TripIncidentId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// An incident raised by the rider or the employer during a delivery or
+/// ride, with the rider's location when it was raised.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = trip_incident))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct TripIncident {
+  pub id: TripIncidentId,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub reporter_id: PersonId,
+  pub reported_by_rider: bool,
+  pub rider_id: Option<RiderId>,
+  pub kind: IncidentKind,
+  pub status: IncidentStatus,
+  pub notes: Option<String>,
+  pub photo_urls: Vec<DbUrl>,
+  /// The rider's last known location when the incident was raised
+  pub lat: Option<f64>,
+  pub lng: Option<f64>,
+  /// When that location was recorded
+  pub located_at: Option<DateTime<Utc>>,
+  /// The live tracking link shared with the reporter's emergency contact
+  pub tracking_link_id: Option<TrackingLinkId>,
+  pub contact_notified_at: Option<DateTime<Utc>>,
+  pub resolved_by: Option<PersonId>,
+  pub resolution_note: Option<String>,
+  pub resolved_at: Option<DateTime<Utc>>,
+  pub created_at: DateTime<Utc>,
+  pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, derive_new::new)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = trip_incident))]
+pub struct TripIncidentInsertForm {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub reporter_id: PersonId,
+  pub reported_by_rider: bool,
+  pub kind: IncidentKind,
+  #[new(default)]
+  pub rider_id: Option<RiderId>,
+  #[new(default)]
+  pub notes: Option<String>,
+  #[new(default)]
+  pub photo_urls: Vec<DbUrl>,
+  #[new(default)]
+  pub lat: Option<f64>,
+  #[new(default)]
+  pub lng: Option<f64>,
+  #[new(default)]
+  pub located_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = trip_incident))]
+pub struct TripIncidentUpdateForm {
+  pub status: Option<IncidentStatus>,
+  pub tracking_link_id: Option<Option<TrackingLinkId>>,
+  pub contact_notified_at: Option<Option<DateTime<Utc>>>,
+  pub resolved_by: Option<Option<PersonId>>,
+  pub resolution_note: Option<Option<String>>,
+  pub resolved_at: Option<Option<DateTime<Utc>>>,
+  pub updated_at: Option<Option<DateTime<Utc>>>,
+}
@@ -0,0 +1,28 @@
+#[cfg(feature = "full")]
+use crate::schema::trip_status_history;
+use crate::{
+  enums::TripStatus,
+  newtypes::{PostId, TripStatusHistoryId},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+/// A status a delivery or ride moved into. Written by a database trigger
+/// whenever `delivery_details.status` or `ride_session.status` changes.
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = trip_status_history))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct TripStatusHistory {
+  pub id: TripStatusHistoryId,
+  pub post_id: PostId,
+  pub status: TripStatus,
+  /// Cancellation reason, for a change to `Cancelled`
+  pub reason: Option<String>,
+  pub changed_at: DateTime<Utc>,
+}
@@ -88,6 +88,7 @@ pub struct WalletTransaction {
   pub description: String,
   pub counter_user_id: Option<LocalUserId>,
   pub idempotency_key: String,
+  pub created_at: DateTime<Utc>,
 }
 #[derive(Clone)]
 #[cfg_attr(feature = "full", derive(Insertable))]
@@ -1,5 +1,10 @@
 use crate::{CurrencyRateHistoryView, CurrencyView, PricingConfigView};
-use app_108jobs_db::newtypes::{CurrencyId, PricingConfigId};
+use app_108jobs_db::{
+  enums::PricingRuleKind,
+  newtypes::{CurrencyId, PricingConfigId, PricingRuleId, ServiceAreaId},
+  source::pricing_rule::PricingRule,
+};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
@@ -214,6 +219,95 @@ pub struct PricingConfigListResponse {
   pub pricing_configs: Vec<PricingConfigView>,
 }
 
+// ============================================================================
+// Pricing Rule Admin API Types
+// ============================================================================
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// Add a time, weekday, holiday or surge multiplier to a pricing config (admin only).
+pub struct CreatePricingRuleRequest {
+  pub pricing_config_id: PricingConfigId,
+  pub name: String,
+  pub kind: PricingRuleKind,
+  pub multiplier: f64,
+  /// Local minute of the day, 0-1439
+  pub start_minute: Option<i32>,
+  pub end_minute: Option<i32>,
+  /// ISO weekdays, 1 = Monday to 7 = Sunday
+  pub days_of_week: Option<Vec<i32>>,
+  pub holiday_date: Option<NaiveDate>,
+  pub zone_lat: Option<f64>,
+  pub zone_lng: Option<f64>,
+  pub zone_radius_km: Option<f64>,
+  /// Limit the rule to pickups inside this service area
+  pub service_area_id: Option<ServiceAreaId>,
+  pub demand_ratio_threshold: Option<f64>,
+  pub is_active: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// Update a pricing rule (admin only). The kind cannot be changed.
+pub struct UpdatePricingRuleRequest {
+  pub rule_id: PricingRuleId,
+  pub name: Option<String>,
+  pub multiplier: Option<f64>,
+  pub start_minute: Option<i32>,
+  pub end_minute: Option<i32>,
+  pub days_of_week: Option<Vec<i32>>,
+  pub holiday_date: Option<NaiveDate>,
+  pub zone_lat: Option<f64>,
+  pub zone_lng: Option<f64>,
+  pub zone_radius_km: Option<f64>,
+  /// Limit the rule to pickups inside this service area
+  pub service_area_id: Option<ServiceAreaId>,
+  pub demand_ratio_threshold: Option<f64>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// List the rules of a pricing config.
+pub struct ListPricingRules {
+  pub pricing_config_id: PricingConfigId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// Delete a pricing rule.
+pub struct DeletePricingRule {
+  pub rule_id: PricingRuleId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// Response for single pricing rule.
+pub struct PricingRuleResponse {
+  pub pricing_rule: PricingRule,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// Response for pricing rule list.
+pub struct PricingRuleListResponse {
+  pub pricing_rules: Vec<PricingRule>,
+}
+
 // ============================================================================
 // Currency Rate History API Types
 // ============================================================================
@@ -10,7 +10,11 @@ use crate::api::{
   UpdatePricingConfigRequest,
 };
 use app_108jobs_core::error::{FastJobError, FastJobErrorType, FastJobResult};
-use app_108jobs_db::newtypes::{CurrencyId, PricingConfigId};
+use app_108jobs_db::{
+  enums::PricingRuleKind,
+  newtypes::{CurrencyId, PricingConfigId},
+  source::pricing_rule::PricingRuleInsertForm,
+};
 
 /// Validates that currency code is not empty and is uppercase
 pub fn validate_currency_code(code: &str) -> FastJobResult<()> {
@@ -119,6 +123,67 @@ pub fn validate_minimum_charge_minutes(minutes: i32) -> FastJobResult<()> {
   Ok(())
 }
 
+fn invalid_rule(message: &str) -> FastJobError {
+  FastJobErrorType::InvalidField(message.to_string()).into()
+}
+
+/// Validates a pricing rule, including the criteria its kind requires
+pub fn validate_pricing_rule(rule: &PricingRuleInsertForm) -> FastJobResult<()> {
+  if rule.name.trim().is_empty() {
+    return Err(invalid_rule("pricing rule name cannot be empty"));
+  }
+  if !rule.multiplier.is_finite() || rule.multiplier <= 0.0 {
+    return Err(invalid_rule("multiplier must be positive"));
+  }
+  match (rule.start_minute, rule.end_minute) {
+    (None, None) => {}
+    (Some(start), Some(end)) if (0..1440).contains(&start) && (0..=1440).contains(&end) => {}
+    _ => {
+      return Err(invalid_rule(
+        "start and end minute must both be set within 0-1440",
+      ))
+    }
+  }
+  if let Some(ref days) = rule.days_of_week {
+    if days.is_empty() || days.iter().any(|d| !(1..=7).contains(d)) {
+      return Err(invalid_rule(
+        "days of week must be 1 (Monday) to 7 (Sunday)",
+      ));
+    }
+  }
+  match (rule.zone_lat, rule.zone_lng, rule.zone_radius_km) {
+    (None, None, None) => {}
+    (Some(lat), Some(lng), Some(radius_km))
+      if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) && radius_km > 0.0 => {}
+    _ => {
+      return Err(invalid_rule(
+        "zone needs a valid centre and a positive radius",
+      ))
+    }
+  }
+
+  let criteria_present = match rule.kind {
+    PricingRuleKind::TimeWindow => rule.start_minute.is_some(),
+    PricingRuleKind::DayOfWeek => rule.days_of_week.is_some(),
+    PricingRuleKind::Holiday => rule.holiday_date.is_some(),
+    PricingRuleKind::Surge => rule.demand_ratio_threshold.is_some_and(|t| t > 0.0),
+  };
+  if !criteria_present {
+    return Err(invalid_rule(match rule.kind {
+      PricingRuleKind::TimeWindow => "time window rule needs start and end minute",
+      PricingRuleKind::DayOfWeek => "day of week rule needs days of week",
+      PricingRuleKind::Holiday => "holiday rule needs a holiday date",
+      PricingRuleKind::Surge => "surge rule needs a positive demand ratio threshold",
+    }));
+  }
+  if rule.kind != PricingRuleKind::Surge && rule.demand_ratio_threshold.is_some() {
+    return Err(invalid_rule(
+      "only surge rules take a demand ratio threshold",
+    ));
+  }
+  Ok(())
+}
+
 // ============================================================================
 // Validated Request Types
 // ============================================================================
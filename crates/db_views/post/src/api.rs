@@ -5,13 +5,27 @@ use app_108jobs_db::{
     JobType,
     ListingType,
     PaymentMethod,
+    PostBoostKind,
     PostKind,
     PostNotifications,
     PostSortType,
     TripStatus,
   },
-  newtypes::{CategoryId, Coin, DbUrl, LanguageId, PaginationCursor, PostId, ProposalId, TagId},
-  source::delivery_details::DeliveryDetailsPayload,
+  newtypes::{
+    CategoryId,
+    Coin,
+    DbUrl,
+    LanguageId,
+    PaginationCursor,
+    PostBoostPackageId,
+    PostId,
+    ProposalId,
+    TagId,
+  },
+  source::{
+    delivery_details::DeliveryDetailsPayload,
+    post_boost::{PostBoost, PostBoostPackage},
+  },
   PostFeatureType,
 };
 use app_108jobs_db_views_category::CategoryView;
@@ -105,6 +119,8 @@ pub struct RideSessionCreatePayload {
   pub passenger_name: Option<String>,
   pub passenger_phone: Option<String>,
   pub payment_method: PaymentMethod,
+  /// Book the ride for a later pickup time
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -471,3 +487,82 @@ pub struct SavePost {
 pub struct MarkManyPostsAsRead {
   pub post_ids: Vec<PostId>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Buy a boost package for one of your posts, paid from your wallet.
+#[serde(rename_all = "camelCase")]
+pub struct PurchasePostBoost {
+  pub post_id: PostId,
+  pub package_id: PostBoostPackageId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// List the boosts bought for a post.
+#[serde(rename_all = "camelCase")]
+pub struct ListPostBoosts {
+  pub post_id: PostId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct PostBoostResponse {
+  pub post_boost: PostBoost,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ListPostBoostsResponse {
+  pub post_boosts: Vec<PostBoost>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct ListPostBoostPackagesResponse {
+  pub packages: Vec<PostBoostPackage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Create a boost package (admin only).
+#[serde(rename_all = "camelCase")]
+pub struct CreatePostBoostPackage {
+  pub name: String,
+  pub kind: PostBoostKind,
+  pub duration_days: i32,
+  pub price_coin: i32,
+  pub is_active: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+/// Update a boost package (admin only). Running boosts keep the price and
+/// duration they were bought with.
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePostBoostPackage {
+  pub package_id: PostBoostPackageId,
+  pub name: Option<String>,
+  pub duration_days: Option<i32>,
+  pub price_coin: Option<i32>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+pub struct PostBoostPackageResponse {
+  pub package: PostBoostPackage,
+}
@@ -578,6 +578,11 @@ impl PostQuery<'_> {
       };
     }
 
+    // boosted posts next, for hot and active
+    if sort == Hot || sort == Active {
+      pq = pq.then_order_by(key::boosted);
+    }
+
     // then use the main sort
     pq = match sort {
       Active => pq.then_order_by(key::hot_rank_active),
@@ -658,6 +663,7 @@ mod tests {
   use app_108jobs_db::{
     impls::actor_language::UNDETERMINED_ID,
     newtypes::LanguageId,
+    schema::post,
     source::{
       actor_language::LocalUserLanguage,
       category::{Category, CategoryInsertForm},
@@ -682,10 +688,12 @@ mod tests {
     },
     test_data::TestData,
     traits::{Bannable, Blockable, Crud, Hideable, Likeable, Readable},
-    utils::{build_db_pool, uplete, ActualDbPool, DbPool},
+    utils::{build_db_pool, get_conn, uplete, ActualDbPool, DbPool},
   };
   use app_108jobs_db_views_local_user::LocalUserView;
   use chrono::Utc;
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
   use std::time::Duration;
@@ -1843,4 +1851,34 @@ mod tests {
 
     Ok(())
   }
+
+  #[test_context(Data)]
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_boosted_first(data: &mut Data) -> FastJobResult<()> {
+    let pool = &data.pool();
+    let pool = &mut pool.into();
+
+    // The oldest post would otherwise come last
+    diesel::update(post::table.find(data.post.id))
+      .set(post::boosted.eq(true))
+      .execute(&mut get_conn(pool).await?)
+      .await?;
+
+    for sort in [PostSortType::Hot, PostSortType::Active] {
+      let post_listing = PostQuery {
+        sort: Some(sort),
+        category_id: Some(data.category.id),
+        ..data.default_post_query()
+      }
+      .list(&data.site, pool)
+      .await?;
+
+      assert_eq!(3, post_listing.len());
+      assert_eq!(POST, post_listing[0].post.name);
+      assert!(post_listing[0].post.boosted);
+    }
+
+    Ok(())
+  }
 }
@@ -75,6 +75,10 @@ pub struct Register {
   /// An answer is mandatory if require application is enabled on the server
   pub answer: Option<String>,
   pub accepted_application: Option<bool>,
+  /// Invite code of the user who referred this signup.
+  pub referral_code: Option<String>,
+  /// Client-generated device identifier, used for referral anti-abuse checks.
+  pub device_id: Option<String>,
 }
 
 #[skip_serializing_none]
@@ -93,6 +97,8 @@ pub struct RegisterRequest {
   pub honeypot: Option<String>,
   pub answer: Option<String>,
   pub accepted_application: Option<bool>,
+  pub referral_code: Option<String>,
+  pub device_id: Option<String>,
 }
 
 #[skip_serializing_none]
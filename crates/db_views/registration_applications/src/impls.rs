@@ -169,6 +169,8 @@ impl TryFrom<RegisterRequest> for Register {
       honeypot: None,
       answer: form.answer.take(),
       accepted_application: Some(false),
+      referral_code: form.referral_code.take(),
+      device_id: form.device_id.take(),
     })
   }
 }
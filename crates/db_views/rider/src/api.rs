@@ -4,15 +4,64 @@ use crate::{
 };
 use app_108jobs_core::error::{FastJobError, FastJobResult};
 use app_108jobs_db::{
-  enums::{PaymentMethod, TripStatus, VehicleType},
+  enums::{
+    CancellationFeeKind,
+    DeliveryStopStatus,
+    DispatchJobKind,
+    EmployerRatingTag,
+    FleetDocumentKind,
+    FleetDocumentStatus,
+    IncidentKind,
+    IncidentStatus,
+    PaymentMethod,
+    PostKind,
+    TripStatus,
+    VehicleType,
+  },
   newtypes::{
+    Coin,
+    CurrencyId,
+    DbUrl,
+    DeliveryStopId,
+    DispatchOfferId,
     PaginationCursor,
     PersonId,
     PostId,
     PricingConfigId,
     ProposalId,
     RideSessionId,
+    RiderFleetId,
     RiderId,
+    ServiceAreaId,
+  },
+  source::{
+    cancellation::CancellationPolicy,
+    cargo_tariff::{CargoTariff, SizeClass, WeightBracket},
+    cod::{CodCollection, CodRemittanceSummary, CodSettlement, RiderCodOutstanding},
+    delivery_details::DeliveryDetailsPublic,
+    delivery_proof::DeliveryProof,
+    delivery_stop::DeliveryStop,
+    dispatch::{DispatchOffer, DispatchOfferView, DispatchRequest},
+    emergency_contact::EmergencyContact,
+    employer_rating::{EmployerRating, EmployerReliability},
+    ride_meter_flag::RideMeterFlag,
+    rider_earning::{
+      RiderEarningHold,
+      RiderEarningsBucket,
+      RiderEarningsPeriod,
+      RiderEarningsSummary,
+    },
+    rider_fleet::{FleetDocument, FleetRiderJob, RiderFleet},
+    rider_shift::{
+      RiderShift,
+      RiderShiftSlot,
+      RiderShiftView,
+      ShiftCoverageHour,
+      ShiftSlotAvailability,
+    },
+    service_area::ServiceArea,
+    trip_incident::TripIncident,
+    trip_status_history::TripStatusHistory,
   },
 };
 use chrono::{DateTime, Utc};
@@ -127,6 +176,14 @@ pub struct UpdateTripStatusRequest {
   pub status: TripStatus,
   /// Optional reason for status change (required for cancellation)
   pub reason: Option<String>,
+  /// Rider confirms the cash was collected (required when marking a COD delivery Delivered)
+  pub cod_collected: Option<bool>,
+  /// Photo of the handed-over parcel, uploaded through the image endpoint
+  pub photo_url: Option<DbUrl>,
+  /// Receiver signature image, uploaded through the image endpoint
+  pub signature_url: Option<DbUrl>,
+  /// One-time code the receiver gives the rider
+  pub otp: Option<String>,
 }
 
 /// Response after updating delivery status
@@ -137,6 +194,25 @@ pub struct TripStatusResponse {
   pub status: TripStatus,
   pub cancellation_reason: Option<String>,
   pub updated_at: DateTime<Utc>,
+  /// Live ETA to the next stop while a rider is on the way
+  pub eta: Option<TripEta>,
+}
+
+/// Estimate of when the rider reaches the next stop, from their last known
+/// location
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TripEta {
+  /// `pickup` until the parcel is collected, then `stop` for each drop-off of
+  /// a multi-stop delivery, or `dropoff`
+  pub target: &'static str,
+  pub distance_km: f64,
+  pub duration_minutes: f64,
+  pub arrive_at: DateTime<Utc>,
+  /// Straight-line estimate rather than a road route
+  pub estimated: bool,
+  /// When the rider location used for the estimate was recorded
+  pub location_updated_at: DateTime<Utc>,
 }
 
 /// Event published to Redis for WebSocket clients
@@ -304,6 +380,8 @@ pub struct CreateRideSessionRequest {
   pub passenger_phone: Option<String>,
   /// Payment method: cash or coin
   pub payment_method: PaymentMethod,
+  /// Token from a fare quote; caps the fare at the top of the quoted range
+  pub quote_token: Option<String>,
 }
 
 /// Response after creating a ride session
@@ -352,6 +430,10 @@ pub struct PricingBreakdown {
   pub base_fare_coin: i32,
   pub time_charge_coin: i32,
   pub distance_charge_coin: i32,
+  /// Pricing rule multiplier locked onto the ride
+  pub price_multiplier: f64,
+  /// What pricing rules and the quote cap added to (or took off) the metered fare
+  pub surcharge_coin: i32,
   pub total_coin: i32,
   /// Display-formatted price in local currency
   pub formatted_price: String,
@@ -455,6 +537,8 @@ pub struct CancelRideSessionResponse {
   pub session_id: RideSessionId,
   pub status: TripStatus,
   pub cancellation_reason: String,
+  /// Paid to the rider when the employer cancels late
+  pub cancellation_fee_coin: i32,
   pub cancelled_at: DateTime<Utc>,
 }
 
@@ -504,6 +588,8 @@ pub struct PricingConfigSnapshot {
   pub time_charge_per_minute_coin: i32,
   pub minimum_charge_minutes: i32,
   pub distance_charge_per_km_coin: i32,
+  /// Multiply the metered fare by this
+  pub price_multiplier: f64,
   pub currency_code: String,
   pub currency_symbol: String,
 }
@@ -526,3 +612,960 @@ pub struct RiderLocationResponse {
   pub accuracy_m: Option<f64>,
   pub updated_at: DateTime<Utc>,
 }
+
+// ============================================================================
+// Cash-on-delivery Reconciliation API Types
+// ============================================================================
+
+/// Rider's outstanding COD cash and recent settlements
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiderCodBalanceResponse {
+  pub outstanding_coin: i64,
+  pub cash_limit_coin: i64,
+  /// Whether the rider is currently blocked from new COD deliveries
+  pub over_limit: bool,
+  pub open_collections: Vec<CodCollection>,
+  pub recent_settlements: Vec<CodSettlement>,
+}
+
+/// Request body for a rider paying COD cash back from their wallet
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleCodRequest {
+  pub amount_coin: i32,
+}
+
+/// Request body for an admin recording cash handed over by a rider
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRecordCodHandoverRequest {
+  pub rider_id: RiderId,
+  pub amount_coin: i32,
+  pub note: Option<String>,
+}
+
+/// Response after a COD settlement
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CodSettlementResponse {
+  pub settlement: CodSettlement,
+  pub outstanding_coin: i64,
+}
+
+/// Riders holding unsettled COD cash
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRiderCodOutstandingResponse {
+  pub riders: Vec<RiderCodOutstanding>,
+}
+
+/// Query for the employer's COD remittance report
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCodRemittanceQuery {
+  pub limit: Option<i64>,
+}
+
+/// Cash collected on the employer's deliveries and how much has reached their wallet
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CodRemittanceReportResponse {
+  pub summary: CodRemittanceSummary,
+  pub collections: Vec<CodCollection>,
+}
+
+// ============================================================================
+// Rider Earnings API Types
+// ============================================================================
+
+/// Query for the rider's earnings breakdown
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRiderEarningsQuery {
+  /// Bucket size, `Day` by default
+  pub period: Option<RiderEarningsPeriod>,
+  /// Number of most recent buckets to return
+  pub limit: Option<i64>,
+}
+
+/// Rider earnings derived from the wallet ledger
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiderEarningsResponse {
+  pub summary: RiderEarningsSummary,
+  pub clearing_period_hours: i64,
+  pub period: RiderEarningsPeriod,
+  pub breakdown: Vec<RiderEarningsBucket>,
+  /// Payouts still inside their clearing period, soonest first
+  pub pending_holds: Vec<RiderEarningHold>,
+}
+
+// ============================================================================
+// Automatic Dispatch API Types
+// ============================================================================
+
+/// Request body for auto-dispatching a delivery. The contacts are stored on
+/// the delivery and used when a rider accepts.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StartDeliveryDispatchRequest {
+  pub sender_name: String,
+  pub sender_phone: String,
+  pub receiver_name: String,
+  pub receiver_phone: String,
+}
+
+/// Request body for auto-dispatching a ride
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StartRideDispatchRequest {
+  /// Only offer the ride to riders with this vehicle
+  pub vehicle_type: Option<VehicleType>,
+}
+
+/// A dispatch and the offers sent so far
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchResponse {
+  pub dispatch: DispatchRequest,
+  pub offers: Vec<DispatchOffer>,
+}
+
+/// Job offers the current rider can still accept
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDispatchOffersResponse {
+  pub offers: Vec<DispatchOfferView>,
+}
+
+/// The job the rider was assigned by accepting an offer
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptDispatchOfferResponse {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+}
+
+/// Pushed to the riders still holding an offer once another rider took the
+/// job or the search was cancelled
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchOfferTakenEvent {
+  pub offer_id: DispatchOfferId,
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+}
+
+// ============================================================================
+// Fare Quote API Types
+// ============================================================================
+
+/// Request for a fare estimate before booking
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FareQuoteRequest {
+  pub pickup_lat: f64,
+  pub pickup_lng: f64,
+  pub dropoff_lat: f64,
+  pub dropoff_lng: f64,
+  pub vehicle_type: Option<VehicleType>,
+  /// Cargo only
+  pub package_weight_kg: Option<f64>,
+  /// Cargo only, e.g. "small", "medium" or "large"
+  pub package_size: Option<String>,
+  /// Cargo only
+  pub fragile: Option<bool>,
+  /// Cargo only
+  pub requires_signature: Option<bool>,
+  /// Defaults to the site's default currency
+  pub currency_id: Option<CurrencyId>,
+}
+
+/// A fare estimate and the token that pins it
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FareQuoteResponse {
+  pub pricing_config_id: PricingConfigId,
+  pub vehicle_type: Option<VehicleType>,
+  pub distance_km: f64,
+  pub duration_minutes: i32,
+  pub price_multiplier: f64,
+  pub estimated_price_coin: i32,
+  pub min_price_coin: i32,
+  pub max_price_coin: i32,
+  /// Deliveries under a cargo tariff only: the lowest fee a delivery post
+  /// may offer
+  pub minimum_fee_coin: Option<i32>,
+  pub formatted_min_price: String,
+  pub formatted_max_price: String,
+  pub currency_code: String,
+  /// Pass as `quoteToken` when creating the ride
+  pub quote_token: String,
+  pub expires_at: DateTime<Utc>,
+  /// Encoded polyline of the route the estimate is based on
+  pub route_polyline: String,
+  /// Straight-line estimate because no road route was available
+  pub route_estimated: bool,
+}
+
+// ============================================================================
+// Meter Verification API Types
+// ============================================================================
+
+/// Query for rides flagged for meter review
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRideMeterFlagsQuery {
+  pub limit: Option<i64>,
+}
+
+/// Unreviewed meter flags, oldest first
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRideMeterFlagsResponse {
+  pub flags: Vec<RideMeterFlag>,
+}
+
+/// Request body for closing a meter flag
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewRideMeterFlagRequest {
+  pub resolution_note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RideMeterFlagResponse {
+  pub flag: RideMeterFlag,
+}
+
+// ============================================================================
+// Service Area API Types
+// ============================================================================
+
+/// Request body for importing service areas (admin only). `geojson` is a
+/// `Polygon` or `MultiPolygon` geometry, a `Feature`, or a
+/// `FeatureCollection` that becomes one area per feature.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportServiceAreasRequest {
+  pub geojson: serde_json::Value,
+  /// Name for features without a `name` property
+  pub name: Option<String>,
+  pub pricing_config_id: Option<PricingConfigId>,
+  /// All vehicle types when empty or missing
+  pub allowed_vehicle_types: Option<Vec<VehicleType>>,
+}
+
+/// Request body for changing a service area (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateServiceAreaRequest {
+  pub name: Option<String>,
+  /// A `Polygon` or `MultiPolygon` geometry replacing the current one
+  pub geometry: Option<serde_json::Value>,
+  pub pricing_config_id: Option<PricingConfigId>,
+  pub allowed_vehicle_types: Option<Vec<VehicleType>>,
+  pub is_active: Option<bool>,
+}
+
+/// Query for listing service areas
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListServiceAreasQuery {
+  pub active_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAreaResponse {
+  pub service_area: ServiceArea,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListServiceAreasResponse {
+  pub service_areas: Vec<ServiceArea>,
+}
+
+// ============================================================================
+// Proof of Delivery API Types
+// ============================================================================
+
+/// Proof of delivery and what the delivery requires. The receiver code is
+/// only included for the employer, until the delivery is marked Delivered.
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryProofResponse {
+  pub post_id: PostId,
+  pub requires_photo: bool,
+  pub requires_signature: bool,
+  pub requires_otp: bool,
+  pub proof: Option<DeliveryProof>,
+  pub otp: Option<String>,
+}
+
+// ============================================================================
+// Multi-stop Delivery API Types
+// ============================================================================
+
+/// One drop-off, in the order the rider should visit it
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryStopInput {
+  pub address: String,
+  pub lat: f64,
+  pub lng: f64,
+  pub contact_name: Option<String>,
+  pub contact_phone: Option<String>,
+  /// Cash to collect at this stop
+  pub cod_amount: Option<f64>,
+}
+
+/// Replace the stops of a delivery before a rider is assigned. An empty list
+/// makes it a single drop-off delivery again.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDeliveryStopsRequest {
+  pub stops: Vec<DeliveryStopInput>,
+}
+
+/// Stops in order with the price of each leg
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryStopsResponse {
+  pub post_id: PostId,
+  pub stops: Vec<DeliveryStop>,
+  /// Sum of the leg fees
+  pub total_fee: Coin,
+  /// The stop the rider should go to next
+  pub next_stop_id: Option<DeliveryStopId>,
+}
+
+/// Move the current stop forward. `Completed` needs the proof the delivery
+/// asks for, and `codCollected` when the stop has cash to collect; `Skipped`
+/// needs a reason.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDeliveryStopRequest {
+  pub status: DeliveryStopStatus,
+  pub reason: Option<String>,
+  pub cod_collected: Option<bool>,
+  pub photo_url: Option<DbUrl>,
+  pub signature_url: Option<DbUrl>,
+}
+
+/// The updated stop and the delivery status, which is `Delivered` once no
+/// stop is left
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDeliveryStopResponse {
+  pub stop: DeliveryStop,
+  pub delivery_status: TripStatus,
+}
+
+// ============================================================================
+// Delivery Batching API Types
+// ============================================================================
+
+/// Deliveries to plan one run over: ones assigned to the rider and open ones
+/// they could take. Without a start location the run starts at whichever
+/// stop suits the plan best.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanDeliveryBatchRequest {
+  pub post_ids: Vec<PostId>,
+  pub start_lat: Option<f64>,
+  pub start_lng: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStopKind {
+  Pickup,
+  Dropoff,
+}
+
+/// One pickup or drop-off of the run, in visiting order
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedBatchStop {
+  pub post_id: PostId,
+  pub kind: BatchStopKind,
+  /// The drop-off of a multi-stop delivery
+  pub stop_id: Option<DeliveryStopId>,
+  pub address: String,
+  pub lat: f64,
+  pub lng: f64,
+  /// From the previous stop, or the start
+  pub leg_distance_km: f64,
+  pub leg_duration_minutes: f64,
+  pub eta: DateTime<Utc>,
+  /// `latestPickupAt` or `latestDropoffAt` of the delivery
+  pub deadline: Option<DateTime<Utc>>,
+  /// How long after the deadline the rider gets there
+  pub late_minutes: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryBatchPlanResponse {
+  pub stops: Vec<PlannedBatchStop>,
+  pub total_distance_km: f64,
+  /// Travel plus the time spent at each stop
+  pub total_duration_minutes: f64,
+  pub finish_at: DateTime<Utc>,
+  /// Every stop is reached by its deadline
+  pub on_time: bool,
+  /// Straight-line estimates because no road routes were available
+  pub estimated: bool,
+}
+
+// ============================================================================
+// Scheduled Trip API Types
+// ============================================================================
+
+/// Query params for listing scheduled jobs that riders can reserve
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListScheduledJobs {
+  /// Max number of results
+  pub limit: Option<i64>,
+}
+
+/// Scheduled rides nobody has reserved yet, soonest pickup first
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListScheduledRidesResponse {
+  pub rides: Vec<RidePublic>,
+}
+
+/// Scheduled deliveries nobody has reserved yet, soonest pickup first
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListScheduledDeliveriesResponse {
+  pub deliveries: Vec<DeliveryDetailsPublic>,
+}
+
+/// Event published to Redis on `schedule:rider:{riderId}` to remind a rider
+/// of a reservation, or to tell them it was cancelled
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTripEvent {
+  pub kind: &'static str,
+  pub job_kind: DispatchJobKind,
+  pub post_id: PostId,
+  pub session_id: Option<RideSessionId>,
+  pub scheduled_pickup_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Trip Track Export API Types
+// ============================================================================
+
+/// Output format of a trip track export
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TripTrackFormat {
+  /// A GeoJSON FeatureCollection with the timed track and status markers
+  #[default]
+  Geojson,
+  /// A GPX 1.1 document with the track and status waypoints
+  Gpx,
+  /// Only the simplified route, for drawing it on a map
+  Polyline,
+}
+
+/// Query params for exporting a trip's recorded track
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TripTrackQuery {
+  pub format: Option<TripTrackFormat>,
+}
+
+/// The simplified route of a trip
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TripRouteResponse {
+  pub post_id: PostId,
+  /// Douglas–Peucker simplified route in the encoded polyline format
+  pub polyline: String,
+  /// Number of recorded fixes before simplification
+  pub points_recorded: usize,
+  pub distance_km: f64,
+  pub status_changes: Vec<TripStatusHistory>,
+}
+
+// ============================================================================
+// Tracking Link API Types
+// ============================================================================
+
+/// A public link the employer shares with the receiver or passenger
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackingLinkResponse {
+  pub token: String,
+  /// Page to open to follow the trip
+  pub url: String,
+  pub expires_at: DateTime<Utc>,
+}
+
+/// The rider's last reported position, as cached on `trip:current:{postId}`
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PublicTrackingLocation {
+  pub lat: f64,
+  pub lng: f64,
+  pub heading: Option<f64>,
+  pub ts: DateTime<Utc>,
+}
+
+/// What anyone holding a tracking link can see about a trip
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicTrackingView {
+  pub job_kind: DispatchJobKind,
+  pub status: TripStatus,
+  pub rider_first_name: Option<String>,
+  pub vehicle_type: Option<VehicleType>,
+  pub vehicle_plate: Option<String>,
+  pub location: Option<PublicTrackingLocation>,
+  /// Minutes until the rider reaches the pickup, or the dropoff once the
+  /// trip is under way
+  pub eta_minutes: Option<f64>,
+  pub expires_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Employer Rating API Types
+// ============================================================================
+
+/// Request body for a rider rating the employer or passenger of a trip
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RateEmployerRequest {
+  /// The delivery or ride post
+  pub post_id: PostId,
+  /// Rating from 1 to 5
+  pub rating: i16,
+  #[serde(default)]
+  pub tags: Vec<EmployerRatingTag>,
+  pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RateEmployerResponse {
+  pub rating: EmployerRating,
+}
+
+/// Query for listing flagged employers (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFlaggedEmployersQuery {
+  pub limit: Option<i64>,
+}
+
+/// Employers riders rated poorly, lowest score first
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFlaggedEmployersResponse {
+  pub employers: Vec<EmployerReliability>,
+}
+
+// ============================================================================
+// Cancellation API Types
+// ============================================================================
+
+/// Request body for an employer cancelling their delivery
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelDeliveryRequest {
+  pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelDeliveryResponse {
+  pub post_id: PostId,
+  pub status: TripStatus,
+  pub cancellation_reason: String,
+  /// Paid to the rider out of the escrowed delivery fee; the rest is refunded
+  pub cancellation_fee_coin: i32,
+  pub cancelled_at: DateTime<Utc>,
+}
+
+/// Request body for creating a cancellation policy (admin only). Without a
+/// pricing config the policy is the default for its post kind.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCancellationPolicyRequest {
+  pub post_kind: PostKind,
+  pub pricing_config_id: Option<PricingConfigId>,
+  pub free_minutes: i32,
+  pub fee_kind: CancellationFeeKind,
+  pub flat_fee_coin: i32,
+  pub fee_per_km_coin: Option<i32>,
+  pub max_fee_coin: Option<i32>,
+}
+
+/// Request body for changing a cancellation policy (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCancellationPolicyRequest {
+  pub free_minutes: Option<i32>,
+  pub fee_kind: Option<CancellationFeeKind>,
+  pub flat_fee_coin: Option<i32>,
+  pub fee_per_km_coin: Option<i32>,
+  pub max_fee_coin: Option<i32>,
+  /// Remove the fee cap
+  pub clear_max_fee: Option<bool>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancellationPolicyResponse {
+  pub policy: CancellationPolicy,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCancellationPoliciesResponse {
+  pub policies: Vec<CancellationPolicy>,
+}
+
+/// Request body for setting up the cargo tariff of a pricing config (admin
+/// only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCargoTariffRequest {
+  pub pricing_config_id: PricingConfigId,
+  /// Lightest first; only the last bracket may leave `upToKg` open
+  pub weight_brackets: Vec<WeightBracket>,
+  pub size_classes: Vec<SizeClass>,
+  pub fragile_surcharge_coin: Option<i32>,
+  pub signature_surcharge_coin: Option<i32>,
+  pub motorcycle_multiplier: Option<f64>,
+  pub bicycle_multiplier: Option<f64>,
+  pub car_multiplier: Option<f64>,
+}
+
+/// Request body for changing a cargo tariff (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCargoTariffRequest {
+  pub weight_brackets: Option<Vec<WeightBracket>>,
+  pub size_classes: Option<Vec<SizeClass>>,
+  pub fragile_surcharge_coin: Option<i32>,
+  pub signature_surcharge_coin: Option<i32>,
+  pub motorcycle_multiplier: Option<f64>,
+  pub bicycle_multiplier: Option<f64>,
+  pub car_multiplier: Option<f64>,
+  pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CargoTariffResponse {
+  pub tariff: CargoTariff,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCargoTariffsResponse {
+  pub tariffs: Vec<CargoTariff>,
+}
+
+// ============================================================================
+// Rider Shift API Types
+// ============================================================================
+
+/// Query for shift slots or coverage in a time window. Defaults to the next
+/// seven days in every area.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftWindowQuery {
+  pub service_area_id: Option<ServiceAreaId>,
+  pub from: Option<DateTime<Utc>>,
+  pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListShiftSlotsResponse {
+  pub slots: Vec<ShiftSlotAvailability>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RiderShiftResponse {
+  pub shift: RiderShift,
+}
+
+/// The rider's current and upcoming shifts
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRiderShiftsResponse {
+  pub shifts: Vec<RiderShiftView>,
+  /// No-shows counting towards a claim ban
+  pub recent_no_shows: i64,
+}
+
+/// Request body for opening a shift slot (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateShiftSlotRequest {
+  pub service_area_id: ServiceAreaId,
+  pub starts_at: DateTime<Utc>,
+  pub ends_at: DateTime<Utc>,
+  pub capacity: i32,
+  pub note: Option<String>,
+}
+
+/// Request body for changing a shift slot (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateShiftSlotRequest {
+  pub starts_at: Option<DateTime<Utc>>,
+  pub ends_at: Option<DateTime<Utc>>,
+  pub capacity: Option<i32>,
+  pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftSlotResponse {
+  pub slot: RiderShiftSlot,
+}
+
+/// Hourly coverage per service area, in area then hour order
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftCoverageResponse {
+  pub hours: Vec<ShiftCoverageHour>,
+}
+
+// ============================================================================
+// Incident API Types
+// ============================================================================
+
+/// Request body for raising an incident during an active delivery or ride
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportIncidentRequest {
+  /// Defaults to `Sos`
+  pub kind: Option<IncidentKind>,
+  pub notes: Option<String>,
+  /// Images uploaded by the reporter beforehand
+  pub photo_urls: Option<Vec<DbUrl>>,
+  /// Share a live tracking link with the reporter's emergency contact
+  pub share_with_emergency_contact: Option<bool>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentResponse {
+  pub incident: TripIncident,
+  /// The link shared with the emergency contact, for the app to pass on by
+  /// phone as well
+  pub tracking_url: Option<String>,
+}
+
+/// Query for listing incidents (admin only). Without a status, the ones not
+/// resolved yet.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListIncidentsQuery {
+  pub status: Option<IncidentStatus>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListIncidentsResponse {
+  pub incidents: Vec<TripIncident>,
+}
+
+/// Request body for acknowledging or resolving an incident (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateIncidentRequest {
+  pub status: IncidentStatus,
+  pub resolution_note: Option<String>,
+}
+
+/// Request body for setting the user's emergency contact
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveEmergencyContactRequest {
+  pub name: String,
+  pub phone: String,
+  pub email: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EmergencyContactResponse {
+  pub contact: Option<EmergencyContact>,
+}
+
+// ============================================================================
+// Fleet API Types
+// ============================================================================
+
+/// Request body for setting up a fleet; the caller becomes its owner
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFleetRequest {
+  pub name: String,
+  /// Pay `fleetSharePercent` of rider payouts to the owner once verified
+  pub route_earnings: Option<bool>,
+  pub fleet_share_percent: Option<f64>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFleetRequest {
+  pub name: Option<String>,
+  pub route_earnings: Option<bool>,
+  pub fleet_share_percent: Option<f64>,
+  /// Issue a new join code; the old one stops working
+  pub regenerate_join_code: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetResponse {
+  pub fleet: RiderFleet,
+}
+
+/// A fleet rider with their live status, rating and current jobs
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetRiderStatus {
+  pub rider: RiderView,
+  pub active_jobs: Vec<FleetRiderJob>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFleetRidersResponse {
+  pub riders: Vec<FleetRiderStatus>,
+}
+
+/// Request body for a rider joining a fleet
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinFleetRequest {
+  pub join_code: String,
+}
+
+/// The fleet a rider drives for, as the rider sees it
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetMembership {
+  pub fleet_id: RiderFleetId,
+  pub name: String,
+  pub is_verified: bool,
+  /// Whether the fleet takes its share of payouts right now
+  pub route_earnings: bool,
+  pub fleet_share_percent: f64,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetMembershipResponse {
+  pub fleet: Option<FleetMembership>,
+}
+
+/// Request body for submitting a fleet document for review
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitFleetDocumentRequest {
+  pub kind: FleetDocumentKind,
+  /// Image uploaded by the owner beforehand
+  pub file_url: DbUrl,
+  pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetDocumentResponse {
+  pub document: FleetDocument,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFleetDocumentsResponse {
+  pub documents: Vec<FleetDocument>,
+}
+
+/// Query for the fleet document review queue (admin only). Without a
+/// status, the documents waiting for review.
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListFleetDocumentsQuery {
+  pub status: Option<FleetDocumentStatus>,
+  pub limit: Option<i64>,
+}
+
+/// Request body for approving or rejecting a fleet document (admin only)
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewFleetDocumentRequest {
+  pub approve: bool,
+  pub note: Option<String>,
+}
+
+/// The reviewed document and its fleet, whose verification follows from
+/// the review
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewFleetDocumentResponse {
+  pub document: FleetDocument,
+  pub fleet: RiderFleet,
+}
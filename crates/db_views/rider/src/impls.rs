@@ -1,7 +1,7 @@
 use crate::RiderView;
 use app_108jobs_core::error::{FastJobErrorExt, FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
-  newtypes::{DecodedCursor, LocalUserId, PaginationCursor, RiderId},
+  newtypes::{DecodedCursor, LocalUserId, PaginationCursor, RiderFleetId, RiderId},
   schema::{person, rider},
   source::rider::{rider_keys as key, Rider},
   traits::PaginationCursorBuilder,
@@ -115,6 +115,25 @@ impl RiderView {
       .await
       .with_fastjob_type(FastJobErrorType::NotFound)
   }
+
+  /// Riders of a fleet, longest-serving first
+  pub async fn list_for_fleet(
+    pool: &mut DbPool<'_>,
+    fleet_id: RiderFleetId,
+  ) -> FastJobResult<Vec<Self>> {
+    let conn = &mut get_conn(pool).await?;
+
+    let query = Self::joins()
+      .filter(rider::fleet_id.eq(fleet_id))
+      .order_by((rider::joined_at.asc(), rider::id.asc()))
+      .select(Self::as_select());
+
+    Commented::new(query)
+      .text("RiderView::list_for_fleet")
+      .load::<Self>(conn)
+      .await
+      .with_fastjob_type(FastJobErrorType::DatabaseError)
+  }
 }
 
 // ============================================================================
@@ -28,6 +28,7 @@ pub struct RidePublic {
   pub status: TripStatus,
   pub rider_id: Option<RiderId>,
   pub rider_assigned_at: Option<DateTime<Utc>>,
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
 }
 
 #[skip_serializing_none]
@@ -55,6 +56,7 @@ pub struct RidePrivate {
   pub payment_status: String,
   pub status: TripStatus,
   pub requested_at: DateTime<Utc>,
+  pub scheduled_pickup_at: Option<DateTime<Utc>>,
   pub rider_assigned_at: Option<DateTime<Utc>>,
   pub rider_confirmed_at: Option<DateTime<Utc>>,
   pub arrived_at_pickup_at: Option<DateTime<Utc>>,
@@ -110,6 +112,7 @@ pub fn project_ride_session(
       payment_status: session.payment_status.clone(),
       status: session.status,
       requested_at: session.requested_at,
+      scheduled_pickup_at: session.scheduled_pickup_at,
       rider_assigned_at: session.rider_assigned_at,
       rider_confirmed_at: session.rider_confirmed_at,
       arrived_at_pickup_at: session.arrived_at_pickup_at,
@@ -135,6 +138,7 @@ pub fn project_ride_session(
       status: session.status,
       rider_id: session.rider_id,
       rider_assigned_at: session.rider_assigned_at,
+      scheduled_pickup_at: session.scheduled_pickup_at,
     })
   }
 }
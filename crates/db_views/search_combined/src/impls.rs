@@ -345,6 +345,11 @@ impl SearchCombinedQuery {
       self.page_back,
     );
 
+    // Boosted posts come first, except when browsing oldest first
+    if sort != Old {
+      paginated_query = paginated_query.then_order_by(key::boosted);
+    }
+
     paginated_query = match sort {
       New | Old => paginated_query.then_order_by(key::published_at),
       Top => paginated_query.then_order_by(key::score),
@@ -79,6 +79,9 @@ pub struct AuthenticateWithOauth {
   /// An answer is mandatory if require application is enabled on the server
   pub answer: Option<String>,
   pub pkce_code_verifier: Option<String>,
+  /// Invite code of the referring user, only used when this creates a new account
+  pub referral_code: Option<String>,
+  pub device_id: Option<String>,
 }
 #[skip_serializing_none]
 #[derive(Debug, Validate, Serialize, Deserialize, Clone)]
@@ -92,6 +95,8 @@ pub struct AuthenticateWithOauthRequest {
   pub name: Option<String>,
   pub email: Option<String>,
   pub answer: Option<String>,
+  pub referral_code: Option<String>,
+  pub device_id: Option<String>,
 }
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -107,6 +112,8 @@ pub struct RegisterWithOauthRequest {
   pub self_promotion: Option<bool>,
   pub answer: Option<String>,
   pub pkce_code_verifier: Option<String>,
+  pub referral_code: Option<String>,
+  pub device_id: Option<String>,
 }
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -128,6 +135,8 @@ impl TryFrom<AuthenticateWithOauthRequest> for AuthenticateWithOauth {
       name: value.name,
       answer: value.answer,
       pkce_code_verifier: None,
+      referral_code: value.referral_code,
+      device_id: value.device_id,
     })
   }
 }
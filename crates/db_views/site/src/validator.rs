@@ -56,6 +56,8 @@ impl TryFrom<AuthenticateWithOauthRequest> for ValidAuthenticateWithOauth {
       name: value.name,
       answer: value.answer,
       pkce_code_verifier: None,
+      referral_code: value.referral_code,
+      device_id: value.device_id,
     }))
   }
 }
@@ -10,6 +10,7 @@ use app_108jobs_db::{
     WalletId,
     WithdrawRequestId,
   },
+  source::referral::{Referral, ReferralSummary},
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -187,3 +188,29 @@ pub struct RejectWithdrawalRequest {
   pub withdrawal_id: WithdrawRequestId,
   pub reason: String,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// Query for the referral dashboard.
+pub struct GetReferralDashboardQuery {
+  /// Limit the referral list (default 20)
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(optional_fields, export))]
+#[serde(rename_all = "camelCase")]
+/// The caller's invite code with pending and earned referral rewards.
+pub struct ReferralDashboardResponse {
+  pub code: String,
+  /// Coins paid per qualifying referral
+  pub reward_per_referral: Coin,
+  pub summary: ReferralSummary,
+  /// Coins still to be paid if every pending referral completes a paid job
+  pub pending_reward_coin: i64,
+  pub referrals: Vec<Referral>,
+}
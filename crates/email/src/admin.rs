@@ -51,3 +51,27 @@ pub async fn send_new_report_email_to_admins(
   }
   Ok(())
 }
+
+/// Alert all admins to an incident raised during a trip
+pub async fn send_new_incident_email_to_admins(
+  reporter_username: &str,
+  kind: &str,
+  location: &str,
+  pool: &mut DbPool<'_>,
+  settings: &Settings,
+) -> FastJobResult<()> {
+  // Collect the admins with emails
+  let admins = LocalUserView::list_admins_with_emails(pool).await?;
+
+  let incidents_link = &format!("{}/admin/incidents", settings.get_protocol_and_hostname());
+
+  for admin in &admins {
+    if let Some(email) = &admin.local_user.email {
+      let lang = user_language(admin);
+      let subject = lang.new_incident_subject(&settings.hostname, kind, reporter_username);
+      let body = lang.new_incident_body(incidents_link, location);
+      send_email(&subject, email, &admin.person.name, &body, settings).await?;
+    }
+  }
+  Ok(())
+}
@@ -103,6 +103,27 @@ pub async fn send_private_message_email(
   .await;
 }
 
+/// Send the live tracking link of a trip to the emergency contact of the
+/// user who raised an incident during it.
+pub async fn send_emergency_contact_email(
+  reporter: &LocalUserView,
+  contact_name: &str,
+  contact_email: &str,
+  tracking_link: &str,
+  settings: &Settings,
+) -> FastJobResult<()> {
+  let lang = user_language(reporter);
+  let username = &reporter.person.name;
+  send_email(
+    &lang.emergency_contact_subject(&settings.hostname, username),
+    contact_email,
+    contact_name,
+    &lang.emergency_contact_body(contact_name, &settings.hostname, tracking_link, username),
+    settings,
+  )
+  .await
+}
+
 async fn send_email_to_user(
   local_user_view: &LocalUserView,
   subject: &str,
@@ -87,3 +87,73 @@ pub async fn send_rider_application_denied_email(
   send_email(&subject, &email, &user.person.name, &body, settings).await?;
   Ok(())
 }
+
+/// Remind a rider that their license expires in `days` days, on
+/// `expiry_date`.
+pub async fn send_rider_license_expiring_email(
+  user: &LocalUserView,
+  days: i32,
+  expiry_date: &str,
+  settings: &Settings,
+) -> FastJobResult<()> {
+  let lang = user_language(user);
+  let subject = lang.rider_license_expiring_subject(days);
+  let email = user_email(user)?;
+
+  let footer = (
+    lang.contact_us(),
+    lang.copyright(),
+    lang.privacy_policy(),
+    lang.regards(),
+    lang.sent_to(&*email),
+    lang.team(),
+    lang.terms_of_service(),
+  );
+
+  let title = lang.rider_license_expiring_title();
+  let message =
+    lang.rider_license_expiring_message(days, expiry_date, &settings.hostname, &user.person.name);
+  let next_steps = lang.rider_license_expiring_next_steps();
+
+  let body = lang.rider_license_email(
+    footer.0, footer.1, message, next_steps, footer.2, footer.3, footer.4, footer.5, footer.6,
+    title,
+  );
+
+  send_email(&subject, &email, &user.person.name, &body, settings).await?;
+  Ok(())
+}
+
+/// Tell a rider they were suspended because their license expired.
+pub async fn send_rider_license_expired_email(
+  user: &LocalUserView,
+  expiry_date: &str,
+  settings: &Settings,
+) -> FastJobResult<()> {
+  let lang = user_language(user);
+  let subject = lang.rider_license_expired_subject(&user.person.name);
+  let email = user_email(user)?;
+
+  let footer = (
+    lang.contact_us(),
+    lang.copyright(),
+    lang.privacy_policy(),
+    lang.regards(),
+    lang.sent_to(&*email),
+    lang.team(),
+    lang.terms_of_service(),
+  );
+
+  let title = lang.rider_license_expired_title();
+  let message =
+    lang.rider_license_expired_message(expiry_date, &settings.hostname, &user.person.name);
+  let next_steps = lang.rider_license_expired_next_steps();
+
+  let body = lang.rider_license_email(
+    footer.0, footer.1, message, next_steps, footer.2, footer.3, footer.4, footer.5, footer.6,
+    title,
+  );
+
+  send_email(&subject, &email, &user.person.name, &body, settings).await?;
+  Ok(())
+}
@@ -23,7 +23,7 @@ use app_108jobs_core::{
 };
 use app_108jobs_db::{
   enums::RegistrationMode,
-  newtypes::{LanguageId, OAuthProviderId},
+  newtypes::{LanguageId, LocalUserId, OAuthProviderId},
   source::{
     captcha_answer::{CaptchaAnswer, CheckCaptchaAnswer},
     local_site::LocalSite,
@@ -31,6 +31,7 @@ use app_108jobs_db::{
     oauth_account::{OAuthAccount, OAuthAccountInsertForm},
     oauth_provider::OAuthProvider,
     person::{Person, PersonInsertForm},
+    referral::Referral,
     registration_application::{RegistrationApplication, RegistrationApplicationInsertForm},
     wallet::WalletModel,
   },
@@ -57,6 +58,7 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use std::sync::LazyLock;
+use tracing::warn;
 
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -135,6 +137,7 @@ pub async fn register(
     .unwrap_or(site_view.site.content_warning.is_some());
 
   let language_tags = get_language_tags(&req);
+  let signup_ip = get_client_ip(&req);
 
   // Wrap the insert person, insert local user, and create registration,
   // in a transaction, so that if any fail, the rows aren't created.
@@ -167,6 +170,16 @@ pub async fn register(
         )
         .await?;
 
+        attach_referral(
+          conn,
+          &tx_context,
+          local_user.id,
+          tx_data.referral_code.as_deref(),
+          signup_ip,
+          tx_data.device_id.clone(),
+        )
+        .await?;
+
         if site_view.local_site.site_setup && require_registration_terms {
           if let Some(answer) = tx_data.answer.clone() {
             // Create the registration application
@@ -253,6 +266,7 @@ pub async fn register_with_oauth(
     .unwrap_or(site_view.site.content_warning.is_some());
 
   let language_tags = get_language_tags(&req);
+  let signup_ip = get_client_ip(&req);
 
   if data.oauth_provider_id == OAuthProviderId(0) || data.code.is_empty() || data.code.len() > 300 {
     return Err(FastJobErrorType::OauthAuthorizationInvalid)?;
@@ -366,6 +380,16 @@ pub async fn register_with_oauth(
 
           OAuthAccount::create(&mut conn.into(), &oauth_account_form).await?;
 
+          attach_referral(
+            conn,
+            &tx_context,
+            local_user.id,
+            data.referral_code.as_deref(),
+            signup_ip,
+            data.device_id.clone(),
+          )
+          .await?;
+
           // prevent sign in until application is accepted
           if local_site.site_setup
             && require_registration_application
@@ -435,6 +459,7 @@ pub async fn authenticate_with_oauth(
     .unwrap_or(site_view.site.content_warning.is_some());
 
   let language_tags = get_language_tags(&req);
+  let signup_ip = get_client_ip(&req);
 
   // validate inputs
   if data.oauth_provider_id == OAuthProviderId(0) || data.code.is_empty() || data.code.len() > 300 {
@@ -609,6 +634,16 @@ pub async fn authenticate_with_oauth(
 
             OAuthAccount::create(&mut conn.into(), &oauth_account_form).await?;
 
+            attach_referral(
+              conn,
+              &tx_context,
+              local_user.id,
+              data.referral_code.as_deref(),
+              signup_ip,
+              data.device_id.clone(),
+            )
+            .await?;
+
             // prevent sign in until application is accepted
             if local_site.site_setup
               && require_registration_application
@@ -696,6 +731,54 @@ async fn create_person(
   Ok(inserted_person)
 }
 
+fn get_client_ip(req: &HttpRequest) -> Option<String> {
+  req
+    .connection_info()
+    .realip_remote_addr()
+    .map(ToString::to_string)
+}
+
+/// Attribute a new account to the owner of `referral_code`, if one was given.
+/// An unknown or self-referral code is skipped with a warning so it never
+/// blocks the signup. The reward itself is paid later, when the account
+/// completes its first paid workflow or delivery.
+async fn attach_referral(
+  conn: &mut AsyncPgConnection,
+  context: &FastJobContext,
+  local_user_id: LocalUserId,
+  referral_code: Option<&str>,
+  signup_ip: Option<String>,
+  device_id: Option<String>,
+) -> FastJobResult<()> {
+  let config = &context.settings().referral;
+  let Some(code) = referral_code.filter(|c| !c.trim().is_empty()) else {
+    return Ok(());
+  };
+  if !config.enabled {
+    return Ok(());
+  }
+  match Referral::create_on_signup(
+    conn,
+    local_user_id,
+    code,
+    signup_ip,
+    device_id,
+    config.max_referrals_per_ip,
+  )
+  .await
+  {
+    Err(e) if e.error_type == FastJobErrorType::InvalidReferralCode => {
+      warn!(
+        "Ignoring referral code {code} for user {}: {e}",
+        local_user_id.0
+      );
+      Ok(())
+    }
+    Err(e) => Err(e),
+    Ok(_) => Ok(()),
+  }
+}
+
 fn get_language_tags(req: &HttpRequest) -> Vec<String> {
   req
     .headers()
@@ -1,4 +1,4 @@
-use super::convert_published_time;
+use super::{check_delivery_fee_floor, convert_published_time};
 use actix_web::web::{Data, Json};
 use app_108jobs_api_utils::{
   build_response::{build_post_response, send_local_notifs},
@@ -24,9 +24,11 @@ use app_108jobs_db::{
   enums::{PostKind, TripStatus},
   impls::actor_language::{validate_post_language, UNDETERMINED_ID},
   source::{
+    cargo_tariff::CargoSpec,
     delivery_details::{DeliveryDetails, DeliveryDetailsInsertForm},
     post::{Post, PostActions, PostInsertForm, PostLikeForm, PostReadForm},
     ride_session::RideSessionInsertForm,
+    service_area::ServiceArea,
   },
   traits::{Crud, Likeable, Readable},
   utils::diesel_url_create,
@@ -34,6 +36,7 @@ use app_108jobs_db::{
 use app_108jobs_db_views_category::CategoryView;
 use app_108jobs_db_views_local_user::LocalUserView;
 use app_108jobs_db_views_post::api::{CreatePost, CreatePostRequest, PostResponse};
+use chrono::{Duration, Utc};
 
 pub async fn create_post(
   data: Json<CreatePostRequest>,
@@ -127,6 +130,77 @@ pub async fn create_post(
     ..PostInsertForm::new(data.name.trim().to_string(), local_user_view.person.id)
   };
 
+  // Deliveries and rides must start and end inside a service area
+  let trip = match data.post_kind {
+    PostKind::Delivery => data.delivery_details.as_ref().map(|dd| {
+      (
+        dd.pickup_lat.zip(dd.pickup_lng),
+        dd.dropoff_lat.zip(dd.dropoff_lng),
+        dd.vehicle_required,
+      )
+    }),
+    PostKind::RideTaxi => data.ride_payload.as_ref().map(|rp| {
+      (
+        rp.pickup_lat.zip(rp.pickup_lng),
+        rp.dropoff_lat.zip(rp.dropoff_lng),
+        None,
+      )
+    }),
+    _ => None,
+  };
+  let area = match trip {
+    Some((Some(pickup), dropoff, vehicle_type)) => {
+      ServiceArea::validate_trip(&mut context.pool(), pickup, dropoff, vehicle_type).await?
+    }
+    _ => None,
+  };
+
+  // Scheduled pickups must leave time to find a rider and not be too far out
+  let scheduled_pickup_at = match data.post_kind {
+    PostKind::Delivery => data
+      .delivery_details
+      .as_ref()
+      .and_then(|dd| dd.scheduled_pickup_at),
+    PostKind::RideTaxi => data
+      .ride_payload
+      .as_ref()
+      .and_then(|rp| rp.scheduled_pickup_at),
+    _ => None,
+  };
+  if let Some(at) = scheduled_pickup_at {
+    let cfg = &context.settings().scheduling;
+    let now = Utc::now();
+    if at < now + Duration::minutes(cfg.min_lead_minutes)
+      || at > now + Duration::days(cfg.max_days_ahead)
+    {
+      return Err(FastJobErrorType::InvalidScheduledPickupTime.into());
+    }
+  }
+
+  // The employer's delivery fee may not undercut the cargo tariff
+  if let Some(dd) = data
+    .delivery_details
+    .as_ref()
+    .filter(|_| data.post_kind == PostKind::Delivery)
+  {
+    let cargo = CargoSpec {
+      weight_kg: dd.package_weight_kg,
+      size: dd.package_size.clone(),
+      fragile: dd.fragile.unwrap_or(false),
+      requires_signature: dd.requires_signature.unwrap_or(false),
+      vehicle: dd.vehicle_required,
+    };
+    check_delivery_fee_floor(
+      &context,
+      area.as_ref(),
+      data.budget,
+      dd.pickup_lat.zip(dd.pickup_lng),
+      dd.dropoff_lat.zip(dd.dropoff_lng),
+      &cargo,
+    )
+    .await?;
+  }
+
   let inserted_post = Post::create(&mut context.pool(), &post_form).await?;
 
   // Persist logistics child based on post_kind
@@ -162,9 +236,12 @@ pub async fn create_post(
       package_size: dd.package_size.clone(),
       fragile: dd.fragile,
       requires_signature: dd.requires_signature,
+      requires_photo: dd.requires_photo,
+      requires_otp: dd.requires_otp,
       vehicle_required: dd.vehicle_required,
       latest_pickup_at: dd.latest_pickup_at,
       latest_dropoff_at: dd.latest_dropoff_at,
+      scheduled_pickup_at: dd.scheduled_pickup_at,
       sender_name: dd.sender_name.clone(),
       sender_phone: dd.sender_phone.clone(),
       receiver_name: dd.receiver_name.clone(),
@@ -207,8 +284,9 @@ pub async fn create_post(
       payment_method: rp.payment_method,
       payment_status: Some("Pending".to_string()),
       status: Some(TripStatus::Pending),
-      requested_at: Some(chrono::Utc::now()),
+      requested_at: Some(Utc::now()),
       current_price_coin: Some(0),
+      scheduled_pickup_at: rp.scheduled_pickup_at,
     };
 
     app_108jobs_db::source::ride_session::RideSession::create(&mut context.pool(), &session_form)
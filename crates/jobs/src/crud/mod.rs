@@ -5,8 +5,18 @@ pub mod remove;
 pub mod update;
 
 use app_108jobs_api_utils::context::FastJobContext;
-use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
-use app_108jobs_db::source::post::Post;
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  utils::geo::haversine_km,
+};
+use app_108jobs_db::{
+  newtypes::Coin,
+  source::{
+    cargo_tariff::{CargoSpec, CargoTariff},
+    post::Post,
+    service_area::ServiceArea,
+  },
+};
 use app_108jobs_db_views_local_user::LocalUserView;
 use chrono::{DateTime, TimeZone, Utc};
 
@@ -36,3 +46,27 @@ pub(crate) async fn convert_published_time(
     Ok(None)
   }
 }
+
+/// Reject a delivery fee that undercuts the cargo tariff for the trip. The
+/// straight line is never longer than the road, so the floor errs low.
+pub(crate) async fn check_delivery_fee_floor(
+  context: &FastJobContext,
+  area: Option<&ServiceArea>,
+  budget: Coin,
+  pickup: Option<(f64, f64)>,
+  dropoff: Option<(f64, f64)>,
+  cargo: &CargoSpec,
+) -> FastJobResult<()> {
+  let Some((config, tariff)) = CargoTariff::find_for_area(&mut context.pool(), area).await? else {
+    return Ok(());
+  };
+  let distance_km = match (pickup, dropoff) {
+    (Some(from), Some(to)) => haversine_km(from.0, from.1, to.0, to.1),
+    _ => 0.0,
+  };
+  let minimum_fee_coin = tariff.minimum_fee(&config, distance_km, cargo)?;
+  if budget < minimum_fee_coin {
+    return Err(FastJobErrorType::DeliveryFeeBelowMinimum(minimum_fee_coin).into());
+  }
+  Ok(())
+}
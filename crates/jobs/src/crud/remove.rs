@@ -11,6 +11,7 @@ use app_108jobs_db::{
     local_user::LocalUser,
     mod_log::moderator::{ModRemovePost, ModRemovePostForm},
     post::{Post, PostUpdateForm},
+    post_boost::PostBoost,
     post_report::PostReport,
   },
   traits::{Crud, Reportable},
@@ -49,18 +50,26 @@ pub async fn remove_post(
     }
   }
 
-  // Update the post
+  // Update the post. Paid boosts on a post taken down by moderation are
+  // refunded for the unused time, together with the removal.
   let post_id = data.post_id;
   let removed = data.removed;
-  Post::update(
-    &mut context.pool(),
-    post_id,
-    &PostUpdateForm {
-      removed: Some(removed),
-      ..Default::default()
-    },
-  )
-  .await?;
+  if removed && !orig_post.removed {
+    let coin_id = context.get_coin_id().await?;
+    let platform_wallet_id = context.get_platform_wallet_id().await?;
+    PostBoost::remove_post_and_refund(&mut context.pool(), post_id, coin_id, platform_wallet_id)
+      .await?;
+  } else {
+    Post::update(
+      &mut context.pool(),
+      post_id,
+      &PostUpdateForm {
+        removed: Some(removed),
+        ..Default::default()
+      },
+    )
+    .await?;
+  }
 
   PostReport::resolve_all_for_object(&mut context.pool(), post_id, local_user_view.person.id)
     .await?;
@@ -1,4 +1,4 @@
-use super::convert_published_time;
+use super::{check_delivery_fee_floor, convert_published_time};
 use actix_web::web::{Data, Json};
 use app_108jobs_api_utils::{
   build_response::{build_post_response, send_local_notifs},
@@ -24,8 +24,10 @@ use app_108jobs_db::{
   enums::PostKind,
   impls::actor_language::{validate_post_language, UNDETERMINED_ID},
   source::{
+    cargo_tariff::CargoSpec,
     delivery_details::DeliveryDetails,
     post::{Post, PostUpdateForm},
+    service_area::ServiceArea,
   },
   traits::Crud,
   utils::{diesel_string_update, diesel_url_update},
@@ -156,13 +158,63 @@ pub async fn update_post(
   };
 
   let post_id = data.post_id;
+
+  // A new fee or cargo may not undercut the cargo tariff, checked against
+  // the delivery as it will be after the edit
+  let current_delivery = if orig_post.post.post_kind == PostKind::Delivery
+    && (data.budget.is_some() || data.delivery_details.is_some())
+  {
+    let current = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+    let edit = data.delivery_details.as_ref();
+    let pickup = edit
+      .and_then(|dd| dd.pickup_lat.zip(dd.pickup_lng))
+      .or(current.pickup_lat.zip(current.pickup_lng));
+    let dropoff = edit
+      .and_then(|dd| dd.dropoff_lat.zip(dd.dropoff_lng))
+      .or(current.dropoff_lat.zip(current.dropoff_lng));
+    let cargo = CargoSpec {
+      weight_kg: edit
+        .and_then(|dd| dd.package_weight_kg)
+        .or(current.package_weight_kg),
+      size: edit
+        .and_then(|dd| dd.package_size.clone())
+        .or(current.package_size.clone()),
+      fragile: edit.and_then(|dd| dd.fragile).unwrap_or(current.fragile),
+      requires_signature: edit
+        .and_then(|dd| dd.requires_signature)
+        .unwrap_or(current.requires_signature),
+      vehicle: edit
+        .and_then(|dd| dd.vehicle_required)
+        .or(current.vehicle_required),
+    };
+    let area = match pickup {
+      Some((lat, lng)) => ServiceArea::find_for_point(&mut context.pool(), lat, lng).await?,
+      None => None,
+    };
+    check_delivery_fee_floor(
+      &context,
+      area.as_ref(),
+      data.budget.unwrap_or(orig_post.post.budget),
+      pickup,
+      dropoff,
+      &cargo,
+    )
+    .await?;
+    Some(current)
+  } else {
+    None
+  };
+
   let updated_post = Post::update(&mut context.pool(), post_id, &post_form).await?;
 
   // Handle delivery details updates for delivery posts
   if orig_post.post.post_kind == PostKind::Delivery {
     if let Some(delivery_payload) = data.delivery_details {
       // Get the current delivery details
-      let current_delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+      let current_delivery = match current_delivery {
+        Some(current) => current,
+        None => DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?,
+      };
 
       // Convert payload to update form
       let delivery_update_form = delivery_payload.to_update_form();
@@ -0,0 +1,80 @@
+use actix_web::web::{Data, Json, Query};
+use app_108jobs_api_utils::{context::FastJobContext, utils::is_admin};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::PostBoostKind,
+  source::{
+    post::Post,
+    post_boost::{PostBoost, PostBoostPackage},
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_post::api::{
+  ListPostBoostPackagesResponse,
+  ListPostBoosts,
+  ListPostBoostsResponse,
+  PostBoostResponse,
+  PurchasePostBoost,
+};
+
+pub async fn list_post_boost_packages(
+  context: Data<FastJobContext>,
+  _local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListPostBoostPackagesResponse>> {
+  let packages = PostBoostPackage::list(&mut context.pool(), true).await?;
+  Ok(Json(ListPostBoostPackagesResponse { packages }))
+}
+
+pub async fn purchase_post_boost(
+  data: Json<PurchasePostBoost>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<PostBoostResponse>> {
+  let post = Post::read(&mut context.pool(), data.post_id).await?;
+  if post.creator_id != local_user_view.person.id {
+    return Err(FastJobErrorType::NoPostEditAllowed.into());
+  }
+  if post.deleted || post.removed {
+    return Err(FastJobErrorType::PostBoostNotAllowed.into());
+  }
+
+  let package = PostBoostPackage::read(&mut context.pool(), data.package_id)
+    .await
+    .map_err(|_| FastJobErrorType::PostBoostPackageNotFound)?;
+  if !package.is_active {
+    return Err(FastJobErrorType::PostBoostPackageNotFound.into());
+  }
+  // Category pins need a category to be pinned in
+  if package.kind == PostBoostKind::FeaturedCategory && post.category_id.is_none() {
+    return Err(FastJobErrorType::PostBoostNotAllowed.into());
+  }
+
+  let coin_id = context.get_coin_id().await?;
+  let platform_wallet_id = context.get_platform_wallet_id().await?;
+  let post_boost = PostBoost::purchase(
+    &mut context.pool(),
+    post.id,
+    &package,
+    local_user_view.local_user.id,
+    coin_id,
+    platform_wallet_id,
+  )
+  .await?;
+
+  Ok(Json(PostBoostResponse { post_boost }))
+}
+
+pub async fn list_post_boosts(
+  data: Query<ListPostBoosts>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListPostBoostsResponse>> {
+  let post = Post::read(&mut context.pool(), data.post_id).await?;
+  if post.creator_id != local_user_view.person.id {
+    is_admin(&local_user_view)?;
+  }
+
+  let post_boosts = PostBoost::list_for_post(&mut context.pool(), post.id).await?;
+  Ok(Json(ListPostBoostsResponse { post_boosts }))
+}
@@ -1,3 +1,4 @@
+pub mod boost;
 pub mod feature;
 pub mod get_link_metadata;
 pub mod hide;
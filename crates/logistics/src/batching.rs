@@ -0,0 +1,508 @@
+//! Batching deliveries for one rider.
+//!
+//! A rider holding several deliveries, or weighing up open ones, gets the
+//! order to visit their pickups and drop-offs in. Every drop-off comes after
+//! its pickup, and the drop-offs of a multi-stop delivery keep their order. A
+//! nearest-neighbour run over the routing provider's travel times is improved
+//! with 2-opt moves. Lateness against `latest_pickup_at` and
+//! `latest_dropoff_at` counts before travel time, so a longer run that makes
+//! every deadline beats a shorter one that misses some.
+
+use crate::routing::{routing_provider, RoutingProvider, TravelMatrix};
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::TripStatus,
+  newtypes::{DeliveryStopId, PostId, RiderId},
+  source::{delivery_details::DeliveryDetails, delivery_stop::DeliveryStop},
+};
+use app_108jobs_db_views_rider::api::{
+  BatchStopKind,
+  DeliveryBatchPlanResponse,
+  PlanDeliveryBatchRequest,
+  PlannedBatchStop,
+};
+use chrono::{DateTime, Duration, Utc};
+
+/// Passes of 2-opt before settling for the run found so far
+const MAX_TWO_OPT_PASSES: usize = 50;
+/// Smaller differences in minutes are rounding noise
+const EPSILON: f64 = 1e-6;
+
+/// Plan the run over the given deliveries for the rider.
+pub async fn plan_delivery_batch(
+  context: &FastJobContext,
+  rider_id: RiderId,
+  data: &PlanDeliveryBatchRequest,
+) -> FastJobResult<DeliveryBatchPlanResponse> {
+  let cfg = &context.settings().batching;
+  let mut post_ids: Vec<PostId> = Vec::with_capacity(data.post_ids.len());
+  for post_id in &data.post_ids {
+    if !post_ids.contains(post_id) {
+      post_ids.push(*post_id);
+    }
+  }
+  if post_ids.is_empty() {
+    return Err(FastJobErrorType::InvalidField("postIds must not be empty".to_string()).into());
+  }
+  if post_ids.len() > cfg.max_deliveries {
+    return Err(FastJobErrorType::DeliveryBatchTooLarge.into());
+  }
+  let start = match (data.start_lat, data.start_lng) {
+    (Some(lat), Some(lng)) => {
+      if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+        return Err(FastJobErrorType::InvalidField("invalid coordinates".to_string()).into());
+      }
+      Some((lat, lng))
+    }
+    (None, None) => None,
+    _ => {
+      return Err(
+        FastJobErrorType::InvalidField("startLat and startLng go together".to_string()).into(),
+      )
+    }
+  };
+
+  let mut stops = Vec::new();
+  for post_id in post_ids {
+    let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+    let drop_offs = DeliveryStop::list_for_post(&mut context.pool(), post_id).await?;
+    push_stops(&mut stops, &delivery, drop_offs, rider_id)?;
+  }
+
+  let points: Vec<(f64, f64)> = start
+    .into_iter()
+    .chain(stops.iter().map(|s| (s.lat, s.lng)))
+    .collect();
+  let matrix = routing_provider(context).matrix(&points).await?;
+  let planner = Planner {
+    stops: &stops,
+    legs: Legs {
+      matrix: &matrix,
+      has_start: start.is_some(),
+    },
+    now: Utc::now(),
+    service_minutes: cfg.stop_service_minutes.max(0.0),
+  };
+  let order = planner.plan();
+  Ok(planner.response(&order))
+}
+
+/// A pickup or drop-off to visit
+#[derive(Debug, Clone)]
+struct BatchStop {
+  post_id: PostId,
+  kind: BatchStopKind,
+  stop_id: Option<DeliveryStopId>,
+  address: String,
+  lat: f64,
+  lng: f64,
+  deadline: Option<DateTime<Utc>>,
+  /// The stop that has to be visited first, by index
+  after: Option<usize>,
+}
+
+/// Add the stops the rider still has to make for a delivery. Open deliveries
+/// and ones assigned to the rider can be batched; the pickup is left out once
+/// the parcel is collected, and so are finished drop-offs.
+fn push_stops(
+  stops: &mut Vec<BatchStop>,
+  delivery: &DeliveryDetails,
+  drop_offs: Vec<DeliveryStop>,
+  rider_id: RiderId,
+) -> FastJobResult<()> {
+  let mine = delivery.assigned_rider_id == Some(rider_id);
+  let needs_pickup = match delivery.status {
+    TripStatus::Pending if delivery.assigned_rider_id.is_none() => true,
+    TripStatus::Assigned | TripStatus::RiderConfirmed | TripStatus::EnRouteToPickup if mine => true,
+    TripStatus::PickedUp | TripStatus::EnRouteToDropoff if mine => false,
+    _ => return Err(FastJobErrorType::DeliveryNotBatchable.into()),
+  };
+  let post_id = delivery.post_id;
+  let located = |lat: Option<f64>, lng: Option<f64>| {
+    lat.zip(lng).ok_or_else(|| {
+      FastJobErrorType::InvalidField(format!("delivery {} has no coordinates", post_id.0))
+    })
+  };
+
+  let mut previous = None;
+  if needs_pickup {
+    let (lat, lng) = located(delivery.pickup_lat, delivery.pickup_lng)?;
+    previous = Some(stops.len());
+    stops.push(BatchStop {
+      post_id,
+      kind: BatchStopKind::Pickup,
+      stop_id: None,
+      address: delivery.pickup_address.clone(),
+      lat,
+      lng,
+      deadline: delivery.latest_pickup_at,
+      after: None,
+    });
+  }
+
+  if drop_offs.is_empty() {
+    let (lat, lng) = located(delivery.dropoff_lat, delivery.dropoff_lng)?;
+    stops.push(BatchStop {
+      post_id,
+      kind: BatchStopKind::Dropoff,
+      stop_id: None,
+      address: delivery.dropoff_address.clone(),
+      lat,
+      lng,
+      deadline: delivery.latest_dropoff_at,
+      after: previous,
+    });
+    return Ok(());
+  }
+  for stop in drop_offs.into_iter().filter(|s| !s.is_finished()) {
+    let index = stops.len();
+    stops.push(BatchStop {
+      post_id,
+      kind: BatchStopKind::Dropoff,
+      stop_id: Some(stop.id),
+      address: stop.address,
+      lat: stop.lat,
+      lng: stop.lng,
+      deadline: delivery.latest_dropoff_at,
+      after: previous,
+    });
+    previous = Some(index);
+  }
+  Ok(())
+}
+
+/// Travel between the stops, with the start location in front when there is
+/// one.
+struct Legs<'a> {
+  matrix: &'a TravelMatrix,
+  has_start: bool,
+}
+
+impl Legs<'_> {
+  /// Kilometres and minutes from `from`, or the start, to `to`. Without a
+  /// start location the first leg is free.
+  fn between(&self, from: Option<usize>, to: usize) -> (f64, f64) {
+    let offset = usize::from(self.has_start);
+    let from = match from {
+      Some(from) => from + offset,
+      None if self.has_start => 0,
+      None => return (0.0, 0.0),
+    };
+    let to = to + offset;
+    (
+      self.matrix.distances_km[from][to],
+      self.matrix.durations_minutes[from][to],
+    )
+  }
+}
+
+/// How a run fares: minutes late summed over the stops, then its length in
+/// minutes
+#[derive(Debug, Clone, Copy)]
+struct RunCost {
+  late_minutes: f64,
+  minutes: f64,
+}
+
+impl RunCost {
+  fn better_than(&self, other: &Self) -> bool {
+    if (self.late_minutes - other.late_minutes).abs() > EPSILON {
+      self.late_minutes < other.late_minutes
+    } else {
+      self.minutes < other.minutes - EPSILON
+    }
+  }
+}
+
+struct Planner<'a> {
+  stops: &'a [BatchStop],
+  legs: Legs<'a>,
+  now: DateTime<Utc>,
+  service_minutes: f64,
+}
+
+impl Planner<'_> {
+  /// The visiting order, as indices into the stops.
+  fn plan(&self) -> Vec<usize> {
+    let mut order = self.nearest_neighbour();
+    self.two_opt(&mut order);
+    order
+  }
+
+  /// Always go to the closest stop that may be visited next, the one due
+  /// first on a tie.
+  fn nearest_neighbour(&self) -> Vec<usize> {
+    let mut visited = vec![false; self.stops.len()];
+    let mut order = Vec::with_capacity(self.stops.len());
+    let mut current = None;
+    while order.len() < self.stops.len() {
+      let Some(next) = (0..self.stops.len())
+        .filter(|&i| !visited[i] && self.stops[i].after.map_or(true, |a| visited[a]))
+        .min_by(|&a, &b| {
+          let (_, to_a) = self.legs.between(current, a);
+          let (_, to_b) = self.legs.between(current, b);
+          to_a
+            .total_cmp(&to_b)
+            .then_with(|| due(&self.stops[a]).cmp(&due(&self.stops[b])))
+        })
+      else {
+        break;
+      };
+      visited[next] = true;
+      order.push(next);
+      current = Some(next);
+    }
+    order
+  }
+
+  /// Reverse stretches of the run while that makes it better and keeps every
+  /// drop-off after its pickup.
+  fn two_opt(&self, order: &mut [usize]) {
+    let mut best = self.cost(order);
+    for _ in 0..MAX_TWO_OPT_PASSES {
+      let mut improved = false;
+      for i in 0..order.len() {
+        for j in i + 1..order.len() {
+          let mut candidate = order.to_vec();
+          candidate[i..=j].reverse();
+          if !self.respects_precedence(&candidate) {
+            continue;
+          }
+          let cost = self.cost(&candidate);
+          if cost.better_than(&best) {
+            order.copy_from_slice(&candidate);
+            best = cost;
+            improved = true;
+          }
+        }
+      }
+      if !improved {
+        break;
+      }
+    }
+  }
+
+  fn respects_precedence(&self, order: &[usize]) -> bool {
+    let mut position = vec![0; self.stops.len()];
+    for (pos, &i) in order.iter().enumerate() {
+      position[i] = pos;
+    }
+    self
+      .stops
+      .iter()
+      .enumerate()
+      .all(|(i, stop)| stop.after.map_or(true, |a| position[a] < position[i]))
+  }
+
+  /// Minutes from now until the rider reaches each stop of `order`.
+  fn arrivals(&self, order: &[usize]) -> Vec<f64> {
+    let mut clock = 0.0;
+    let mut previous = None;
+    order
+      .iter()
+      .map(|&i| {
+        clock += self.legs.between(previous, i).1;
+        previous = Some(i);
+        let arrival = clock;
+        clock += self.service_minutes;
+        arrival
+      })
+      .collect()
+  }
+
+  fn late_minutes(&self, stop: &BatchStop, arrival: f64) -> f64 {
+    stop.deadline.map_or(0.0, |deadline| {
+      let due_in = (deadline - self.now).num_milliseconds() as f64 / 60_000.0;
+      (arrival - due_in).max(0.0)
+    })
+  }
+
+  fn cost(&self, order: &[usize]) -> RunCost {
+    let arrivals = self.arrivals(order);
+    RunCost {
+      late_minutes: order
+        .iter()
+        .zip(&arrivals)
+        .map(|(&i, &arrival)| self.late_minutes(&self.stops[i], arrival))
+        .sum(),
+      minutes: arrivals.last().map_or(0.0, |a| a + self.service_minutes),
+    }
+  }
+
+  fn response(&self, order: &[usize]) -> DeliveryBatchPlanResponse {
+    let arrivals = self.arrivals(order);
+    let mut previous = None;
+    let mut total_distance_km = 0.0;
+    let stops: Vec<PlannedBatchStop> = order
+      .iter()
+      .zip(&arrivals)
+      .map(|(&i, &arrival)| {
+        let stop = &self.stops[i];
+        let (leg_distance_km, leg_duration_minutes) = self.legs.between(previous, i);
+        previous = Some(i);
+        total_distance_km += leg_distance_km;
+        PlannedBatchStop {
+          post_id: stop.post_id,
+          kind: stop.kind,
+          stop_id: stop.stop_id,
+          address: stop.address.clone(),
+          lat: stop.lat,
+          lng: stop.lng,
+          leg_distance_km,
+          leg_duration_minutes,
+          eta: self.at(arrival),
+          deadline: stop.deadline,
+          late_minutes: self.late_minutes(stop, arrival),
+        }
+      })
+      .collect();
+
+    let total_duration_minutes = self.cost(order).minutes;
+    DeliveryBatchPlanResponse {
+      on_time: stops.iter().all(|s| s.late_minutes <= EPSILON),
+      stops,
+      total_distance_km,
+      total_duration_minutes,
+      finish_at: self.at(total_duration_minutes),
+      estimated: self.legs.matrix.estimated,
+    }
+  }
+
+  fn at(&self, minutes: f64) -> DateTime<Utc> {
+    self.now + Duration::seconds((minutes * 60.0).round() as i64)
+  }
+}
+
+/// Stops without a deadline go last.
+fn due(stop: &BatchStop) -> DateTime<Utc> {
+  stop.deadline.unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn stop(post_id: i32, kind: BatchStopKind, after: Option<usize>) -> BatchStop {
+    BatchStop {
+      post_id: PostId(post_id),
+      kind,
+      stop_id: None,
+      address: String::new(),
+      lat: 0.0,
+      lng: 0.0,
+      deadline: None,
+      after,
+    }
+  }
+
+  /// Stops on a line, `positions` km from the start, at a km a minute.
+  fn line_matrix(positions: &[f64]) -> TravelMatrix {
+    let points: Vec<f64> = std::iter::once(0.0)
+      .chain(positions.iter().copied())
+      .collect();
+    let distances_km: Vec<Vec<f64>> = points
+      .iter()
+      .map(|a| points.iter().map(|b| (a - b).abs()).collect())
+      .collect();
+    TravelMatrix {
+      durations_minutes: distances_km.clone(),
+      distances_km,
+      estimated: true,
+    }
+  }
+
+  fn planner<'a>(stops: &'a [BatchStop], matrix: &'a TravelMatrix) -> Planner<'a> {
+    Planner {
+      stops,
+      legs: Legs {
+        matrix,
+        has_start: true,
+      },
+      now: Utc::now(),
+      service_minutes: 0.0,
+    }
+  }
+
+  #[test]
+  fn drop_off_waits_for_its_pickup() {
+    // The drop-off is right by the start, the pickup far out
+    let stops = [
+      stop(1, BatchStopKind::Pickup, None),
+      stop(1, BatchStopKind::Dropoff, Some(0)),
+    ];
+    let matrix = line_matrix(&[10.0, 1.0]);
+    let order = planner(&stops, &matrix).plan();
+    assert_eq!(order, vec![0, 1]);
+  }
+
+  #[test]
+  fn two_opt_untangles_a_greedy_run() {
+    // Greedy zigzags 1 → -2 → -6 → 4.5; sweeping one side and then the
+    // other is shorter
+    let stops = [
+      stop(1, BatchStopKind::Dropoff, None),
+      stop(2, BatchStopKind::Dropoff, None),
+      stop(3, BatchStopKind::Dropoff, None),
+      stop(4, BatchStopKind::Dropoff, None),
+    ];
+    let matrix = line_matrix(&[1.0, -2.0, 4.5, -6.0]);
+    let planner = planner(&stops, &matrix);
+
+    let greedy = planner.nearest_neighbour();
+    assert_eq!(greedy, vec![0, 1, 3, 2]);
+    let order = planner.plan();
+    assert!(planner.respects_precedence(&order));
+    assert!((planner.cost(&greedy).minutes - 18.5).abs() < EPSILON);
+    assert!((planner.cost(&order).minutes - 15.0).abs() < EPSILON);
+  }
+
+  #[test]
+  fn deadlines_come_before_distance() {
+    // The far stop is missed when the near one is visited first
+    let now = Utc::now();
+    let mut stops = [
+      stop(1, BatchStopKind::Dropoff, None),
+      stop(2, BatchStopKind::Dropoff, None),
+    ];
+    stops[1].deadline = Some(now + Duration::minutes(11));
+    let matrix = line_matrix(&[1.0, -10.0]);
+    let planner = Planner {
+      now,
+      ..planner(&stops, &matrix)
+    };
+
+    assert_eq!(planner.nearest_neighbour(), vec![0, 1]);
+    let order = planner.plan();
+    assert_eq!(order, vec![1, 0]);
+    let plan = planner.response(&order);
+    assert!(plan.on_time);
+    assert!((plan.total_distance_km - 21.0).abs() < EPSILON);
+  }
+
+  #[test]
+  fn first_leg_is_free_without_start() {
+    let stops = [
+      stop(1, BatchStopKind::Pickup, None),
+      stop(1, BatchStopKind::Dropoff, Some(0)),
+    ];
+    let matrix = TravelMatrix {
+      distances_km: vec![vec![0.0, 4.0], vec![4.0, 0.0]],
+      durations_minutes: vec![vec![0.0, 8.0], vec![8.0, 0.0]],
+      estimated: false,
+    };
+    let planner = Planner {
+      legs: Legs {
+        matrix: &matrix,
+        has_start: false,
+      },
+      service_minutes: 3.0,
+      ..planner(&stops, &matrix)
+    };
+
+    let plan = planner.response(&planner.plan());
+    assert_eq!(plan.stops[0].leg_distance_km, 0.0);
+    assert!((plan.total_duration_minutes - 14.0).abs() < EPSILON);
+    assert!(!plan.estimated);
+  }
+}
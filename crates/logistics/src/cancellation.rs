@@ -0,0 +1,187 @@
+//! Cancellation fees and penalties for trips that already had a rider.
+//!
+//! When the employer cancels, the rider is paid the fee set by the trip's
+//! cancellation policy. When the rider cancels, the employer is refunded in
+//! full and the cancellation counts against the rider: after
+//! `rider_offences_before_ban` of them within `rider_offence_window_hours`,
+//! the rider may not accept jobs for `rider_ban_minutes`, longer with every
+//! further cancellation.
+
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::{error::FastJobResult, settings::structs::CancellationConfig};
+use app_108jobs_db::{
+  enums::{CancellationParty, DispatchJobKind, PostKind},
+  newtypes::{CancellationPolicyId, PostId, PricingConfigId, RiderId},
+  source::{
+    cancellation::{CancellationPolicy, TripCancellation, TripCancellationInsertForm},
+    delivery_details::DeliveryDetails,
+    rider::{Rider, RiderUpdateForm},
+    trip_location_history::TripLocationHistory,
+  },
+  traits::Crud,
+};
+use chrono::{DateTime, Duration, Utc};
+use tracing::{info, warn};
+
+/// What cancelling a trip now would cost the employer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CancellationCharge {
+  pub policy_id: Option<CancellationPolicyId>,
+  pub minutes_since_assigned: i32,
+  /// Distance the rider drove since they were assigned
+  pub distance_km: f64,
+  pub fee_coin: i32,
+}
+
+/// A trip being cancelled after a rider was assigned.
+pub struct CancelledTrip {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub post_kind: PostKind,
+  pub pricing_config_id: Option<PricingConfigId>,
+  pub rider_id: RiderId,
+  pub assigned_at: Option<DateTime<Utc>>,
+}
+
+impl CancelledTrip {
+  /// A delivery with an assigned rider. Deliveries have no pricing config.
+  pub fn for_delivery(delivery: &DeliveryDetails) -> Option<Self> {
+    Some(Self {
+      post_id: delivery.post_id,
+      job_kind: DispatchJobKind::Delivery,
+      post_kind: PostKind::Delivery,
+      pricing_config_id: None,
+      rider_id: delivery.assigned_rider_id?,
+      assigned_at: delivery.assigned_at,
+    })
+  }
+
+  /// The fee owed to the rider under the trip's policy. Free without a
+  /// policy or an assignment time.
+  pub async fn charge(
+    &self,
+    context: &FastJobContext,
+    now: DateTime<Utc>,
+  ) -> FastJobResult<CancellationCharge> {
+    let Some(assigned_at) = self.assigned_at else {
+      return Ok(CancellationCharge {
+        policy_id: None,
+        minutes_since_assigned: 0,
+        distance_km: 0.0,
+        fee_coin: 0,
+      });
+    };
+    let minutes = (now - assigned_at).num_minutes().max(0);
+    let track = TripLocationHistory::track_distance_between(
+      &mut context.pool(),
+      self.post_id,
+      assigned_at,
+      now,
+      &context.settings().meter_verification,
+    )
+    .await?;
+    let policy =
+      CancellationPolicy::find_for(&mut context.pool(), self.post_kind, self.pricing_config_id)
+        .await?;
+
+    Ok(CancellationCharge {
+      policy_id: policy.as_ref().map(|p| p.id),
+      minutes_since_assigned: i32::try_from(minutes).unwrap_or(i32::MAX),
+      distance_km: track.distance_km,
+      fee_coin: policy.map_or(0, |p| p.fee(minutes, track.distance_km)),
+    })
+  }
+
+  /// Record the cancellation with the fee actually paid. Rider cancellations
+  /// may get the rider banned from accepting jobs. The trip is already
+  /// cancelled, so failures are logged rather than returned.
+  pub async fn record(
+    &self,
+    context: &FastJobContext,
+    cancelled_by: CancellationParty,
+    charge: &CancellationCharge,
+    reason: Option<String>,
+  ) {
+    let form = TripCancellationInsertForm {
+      policy_id: charge.policy_id,
+      fee_coin: charge.fee_coin,
+      reason,
+      ..TripCancellationInsertForm::new(
+        self.post_id,
+        self.job_kind,
+        self.rider_id,
+        cancelled_by,
+        charge.minutes_since_assigned,
+        charge.distance_km,
+      )
+    };
+    if let Err(e) = TripCancellation::create(&mut context.pool(), &form).await {
+      warn!(?e, post_id = %self.post_id, "Failed to record trip cancellation");
+      return;
+    }
+    if cancelled_by == CancellationParty::Rider {
+      if let Err(e) = penalize_rider(context, self.rider_id).await {
+        warn!(?e, rider_id = %self.rider_id, "Failed to apply rider cancellation penalty");
+      }
+    }
+  }
+}
+
+/// Ban the rider from accepting jobs if they cancelled too often lately.
+async fn penalize_rider(context: &FastJobContext, rider_id: RiderId) -> FastJobResult<()> {
+  let cfg = &context.settings().cancellation;
+  let now = Utc::now();
+  let since = now - Duration::hours(cfg.rider_offence_window_hours);
+  let offences =
+    TripCancellation::count_rider_cancellations_since(&mut context.pool(), rider_id, since).await?;
+  let Some(ban) = ban_length(cfg, offences) else {
+    return Ok(());
+  };
+
+  let form = RiderUpdateForm {
+    accept_banned_until: Some(Some(now + ban)),
+    ..Default::default()
+  };
+  Rider::update(&mut context.pool(), rider_id, &form).await?;
+  info!(
+    "Rider {} banned from accepting jobs for {} minutes after {} cancellations",
+    rider_id,
+    ban.num_minutes(),
+    offences
+  );
+  Ok(())
+}
+
+/// How long a rider with this many recent cancellations is banned for, if at
+/// all.
+fn ban_length(cfg: &CancellationConfig, offences: i64) -> Option<Duration> {
+  if cfg.rider_offences_before_ban <= 0 || offences < cfg.rider_offences_before_ban {
+    return None;
+  }
+  let multiple = offences - cfg.rider_offences_before_ban + 1;
+  Some(Duration::minutes(
+    cfg.rider_ban_minutes.saturating_mul(multiple),
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bans_start_at_the_threshold_and_grow() {
+    let cfg = CancellationConfig::default();
+    assert_eq!(ban_length(&cfg, 2), None);
+    assert_eq!(ban_length(&cfg, 3), Some(Duration::minutes(60)));
+    assert_eq!(ban_length(&cfg, 5), Some(Duration::minutes(180)));
+  }
+
+  #[test]
+  fn a_zero_threshold_disables_bans() {
+    let cfg = CancellationConfig {
+      rider_offences_before_ban: 0,
+      ..Default::default()
+    };
+    assert_eq!(ban_length(&cfg, 10), None);
+  }
+}
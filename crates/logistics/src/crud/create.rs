@@ -35,8 +35,6 @@ pub async fn create_rider(
     rating: Some(0.0),
     completed_jobs: Some(0),
     total_jobs: Some(0),
-    total_earnings: Some(0.0),
-    pending_earnings: Some(0.0),
 
     is_online: Some(false),
     accepting_jobs: Some(false),
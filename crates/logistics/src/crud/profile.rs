@@ -2,7 +2,10 @@ use actix_web::web::{Data, Json};
 use app_108jobs_api_utils::context::FastJobContext;
 use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
-  source::rider::{Rider, RiderUpdateForm},
+  source::{
+    rider::{Rider, RiderUpdateForm},
+    rider_shift::RiderShift,
+  },
   traits::Crud,
 };
 use app_108jobs_db_views_local_user::LocalUserView;
@@ -23,14 +26,26 @@ async fn current_rider(
     .ok_or(FastJobErrorType::NotFound.into())
 }
 
+/// Like `current_rider`, but also finds a profile suspended for expired
+/// documents.
+async fn current_rider_with_inactive(
+  context: &FastJobContext,
+  local_user_view: &LocalUserView,
+) -> FastJobResult<Rider> {
+  Rider::get_by_person_id_with_inactive(&mut context.pool(), local_user_view.person.id)
+    .await?
+    .ok_or(FastJobErrorType::NotFound.into())
+}
+
 /// PUT /riders/profile
 /// Update the authenticated rider's own editable profile fields.
+/// A rider suspended for an expired license submits new documents here.
 pub async fn update_rider(
   data: Json<UpdateRiderRequest>,
   context: Data<FastJobContext>,
   local_user_view: LocalUserView,
 ) -> FastJobResult<Json<Rider>> {
-  let rider = current_rider(&context, &local_user_view).await?;
+  let rider = current_rider_with_inactive(&context, &local_user_view).await?;
 
   validate_license_expiry(data.license_expiry_date)?;
 
@@ -40,6 +55,8 @@ pub async fn update_rider(
     vehicle_plate_number: data.vehicle_plate_number.clone().map(Some),
     license_number: data.license_number.clone().map(Some),
     license_expiry_date: data.license_expiry_date.map(Some),
+    // A new license gets its own expiry reminders
+    license_reminder_days: data.license_expiry_date.map(|_| None),
     ..Default::default()
   };
 
@@ -56,45 +73,67 @@ pub async fn set_online(
 ) -> FastJobResult<Json<SuccessResponse>> {
   let rider = current_rider(&context, &local_user_view).await?;
 
+  let now = Utc::now();
   let form = RiderUpdateForm {
     is_online: Some(data.is_online),
-    last_active_at: Some(Some(Utc::now())),
+    last_active_at: Some(Some(now)),
     ..Default::default()
   };
   Rider::update(&mut context.pool(), rider.id, &form).await?;
+  if data.is_online {
+    RiderShift::check_in(&mut context.pool(), rider.id, now).await?;
+  }
   Ok(Json(SuccessResponse::default()))
 }
 
 /// PATCH /riders/status/accepting
-/// Toggle whether the rider is currently accepting jobs.
+/// Toggle whether the rider is currently accepting jobs. Riders with an
+/// expired license cannot start accepting until new documents are approved,
+/// nor can riders banned for cancelling too often until the ban ends.
 pub async fn set_accepting(
   data: Json<SetAcceptingRequest>,
   context: Data<FastJobContext>,
   local_user_view: LocalUserView,
 ) -> FastJobResult<Json<SuccessResponse>> {
-  let rider = current_rider(&context, &local_user_view).await?;
+  let rider = current_rider_with_inactive(&context, &local_user_view).await?;
+  if data.accepting_jobs && rider.documents_expired(Utc::now()) {
+    return Err(FastJobErrorType::RiderDocumentsExpired.into());
+  }
+  if data.accepting_jobs && rider.accept_banned(Utc::now()) {
+    return Err(FastJobErrorType::RiderAcceptBanned.into());
+  }
+  if !rider.is_active {
+    return Err(FastJobErrorType::NotFound.into());
+  }
 
+  let now = Utc::now();
   let form = RiderUpdateForm {
     accepting_jobs: Some(data.accepting_jobs),
-    last_active_at: Some(Some(Utc::now())),
+    last_active_at: Some(Some(now)),
     ..Default::default()
   };
   Rider::update(&mut context.pool(), rider.id, &form).await?;
+  if data.accepting_jobs {
+    RiderShift::check_in(&mut context.pool(), rider.id, now).await?;
+  }
   Ok(Json(SuccessResponse::default()))
 }
 
 /// POST /riders/heartbeat
-/// Liveness ping — refreshes the rider's last-active timestamp.
+/// Liveness ping — refreshes the rider's last-active timestamp and checks the
+/// rider in to a shift that has started.
 pub async fn heartbeat(
   context: Data<FastJobContext>,
   local_user_view: LocalUserView,
 ) -> FastJobResult<Json<SuccessResponse>> {
   let rider = current_rider(&context, &local_user_view).await?;
 
+  let now = Utc::now();
   let form = RiderUpdateForm {
-    last_active_at: Some(Some(Utc::now())),
+    last_active_at: Some(Some(now)),
     ..Default::default()
   };
   Rider::update(&mut context.pool(), rider.id, &form).await?;
+  RiderShift::check_in(&mut context.pool(), rider.id, now).await?;
   Ok(Json(SuccessResponse::default()))
 }
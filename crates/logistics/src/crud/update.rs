@@ -1,6 +1,6 @@
 use actix_web::web::{Data, Json};
 use app_108jobs_api_utils::{context::FastJobContext, utils::is_admin};
-use app_108jobs_core::error::FastJobResult;
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
   enums::RiderVerificationStatus,
   source::rider::{Rider, RiderUpdateForm},
@@ -33,10 +33,16 @@ pub async fn admin_verify_rider(
   let rider = Rider::read(&mut context.pool(), rider_id).await?;
 
   let update_form = if approve {
+    // Approving a rider suspended for an expired license needs a valid one
+    if rider.license_expiry_date.is_some_and(|e| e <= Utc::now()) {
+      return Err(FastJobErrorType::RiderLicenseExpired.into());
+    }
     RiderUpdateForm {
       is_verified: Some(true),
       verification_status: Some(RiderVerificationStatus::Verified),
       verified_at: Some(Some(Utc::now())),
+      is_active: rider.documents_expired_at.map(|_| true),
+      documents_expired_at: Some(None),
       ..Default::default()
     }
   } else {
@@ -0,0 +1,97 @@
+//! Daily checks of rider license expiry.
+//!
+//! Riders are emailed 30, 7 and 1 days before their license expires. Once it
+//! has expired they are taken off the road and sent back to pending
+//! verification until an admin approves new documents.
+
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db::{
+  impls::rider::LICENSE_REMINDER_DAYS,
+  source::rider::{Rider, RiderUpdateForm},
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_email::rider::{
+  send_rider_license_expired_email,
+  send_rider_license_expiring_email,
+};
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+/// Email riders whose license expiry crossed a reminder threshold. Returns
+/// how many reminders went out.
+pub async fn send_license_expiry_reminders(context: &FastJobContext) -> FastJobResult<usize> {
+  let now = Utc::now();
+  let furthest = LICENSE_REMINDER_DAYS
+    .iter()
+    .copied()
+    .max()
+    .unwrap_or_default();
+  let riders =
+    Rider::list_license_expiring(&mut context.pool(), now + Duration::days(furthest.into()))
+      .await?;
+  let mut sent = 0;
+
+  for rider in riders {
+    let (Some(days), Some(expiry)) = (rider.license_reminder_due(now), rider.license_expiry_date)
+    else {
+      continue;
+    };
+    let user = LocalUserView::read(&mut context.pool(), rider.user_id).await?;
+    let expiry_date = expiry.format("%Y-%m-%d").to_string();
+    if let Err(e) =
+      send_rider_license_expiring_email(&user, days, &expiry_date, context.settings()).await
+    {
+      warn!(
+        ?e,
+        rider_id = rider.id.0,
+        "Failed to send license expiry reminder"
+      );
+      continue;
+    }
+    let form = RiderUpdateForm {
+      license_reminder_days: Some(Some(days)),
+      ..Default::default()
+    };
+    Rider::update(&mut context.pool(), rider.id, &form).await?;
+    sent += 1;
+  }
+
+  if sent > 0 {
+    info!("Sent {} license expiry reminder(s)", sent);
+  }
+  Ok(sent)
+}
+
+/// Suspend riders whose license has expired. Returns how many were suspended.
+pub async fn suspend_riders_with_expired_licenses(
+  context: &FastJobContext,
+) -> FastJobResult<usize> {
+  let riders = Rider::list_license_expired(&mut context.pool()).await?;
+  let mut suspended = 0;
+
+  for rider in riders {
+    Rider::suspend_for_expired_documents(&mut context.pool(), rider.id).await?;
+    suspended += 1;
+
+    let Some(expiry) = rider.license_expiry_date else {
+      continue;
+    };
+    let user = LocalUserView::read(&mut context.pool(), rider.user_id).await?;
+    let expiry_date = expiry.format("%Y-%m-%d").to_string();
+    if let Err(e) = send_rider_license_expired_email(&user, &expiry_date, context.settings()).await
+    {
+      warn!(
+        ?e,
+        rider_id = rider.id.0,
+        "Failed to send license expired email"
+      );
+    }
+  }
+
+  if suspended > 0 {
+    info!("Suspended {} rider(s) with an expired license", suspended);
+  }
+  Ok(suspended)
+}
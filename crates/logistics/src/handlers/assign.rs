@@ -1,3 +1,4 @@
+use crate::offers::cancel_dispatch_for_post;
 use actix_web::web::{Data, Json, Path};
 use app_108jobs_api_utils::{
   context::FastJobContext,
@@ -8,11 +9,11 @@ use app_108jobs_api_utils::{
     verify_proposal_on_post,
   },
 };
-use app_108jobs_core::error::FastJobResult;
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
   enums::TripStatus,
   newtypes::PostId,
-  source::delivery_details::DeliveryDetails,
+  source::{cod::CodCollection, delivery_details::DeliveryDetails},
   utils::get_conn,
 };
 use app_108jobs_db_views_local_user::LocalUserView;
@@ -34,6 +35,9 @@ use diesel_async::scoped_futures::ScopedFutureExt;
 ///
 /// Requires sender and receiver contact information to be provided.
 ///
+/// Cash-on-delivery jobs cannot go to a rider already holding more unsettled
+/// COD cash than the configured limit.
+///
 /// All database operations are performed in a single transaction to ensure atomicity.
 pub async fn assign_delivery_from_proposal(
   path: Path<PostId>,
@@ -50,6 +54,7 @@ pub async fn assign_delivery_from_proposal(
   let receiver_phone = form.receiver_phone.clone();
   let employer_person_id = local_user_view.person.id;
   let employer_local_user_id = local_user_view.local_user.id;
+  let cod_cash_limit = context.settings().cod.rider_cash_limit_coin;
 
   // Get connection and run all database operations in a transaction
   let mut pool = context.pool();
@@ -73,6 +78,16 @@ pub async fn assign_delivery_from_proposal(
         // Get the rider from the person_id
         let rider = get_active_rider_by_person(&mut pool, rider_person_id).await?;
         let rider_id = rider.id;
+        if rider.accept_banned(Utc::now()) {
+          return Err(FastJobErrorType::RiderAcceptBanned.into());
+        }
+
+        let current = DeliveryDetails::get_by_post_id(&mut pool, post_id).await?;
+        if current.cash_on_delivery
+          && CodCollection::outstanding_for_rider(&mut pool, rider_id).await? >= cod_cash_limit
+        {
+          return Err(FastJobErrorType::CodCashLimitExceeded.into());
+        }
 
         // Perform the assignment with escrow hold and sender/receiver information
         let delivery = DeliveryDetails::assign_from_comment_with_escrow(
@@ -99,6 +114,11 @@ pub async fn assign_delivery_from_proposal(
     })
     .await?;
 
+  // Stop any automatic dispatch still offering this job
+  if let Err(e) = cancel_dispatch_for_post(&context, post_id).await {
+    tracing::warn!(?e, post_id = %post_id, "Failed to cancel dispatch after manual assignment");
+  }
+
   // Publish event to Redis for WebSocket clients (outside transaction)
   let event = DeliveryAssignmentEvent {
     kind: "delivery_assigned",
@@ -0,0 +1,20 @@
+use crate::batching::plan_delivery_batch;
+use actix_web::web::{Data, Json};
+use app_108jobs_api_utils::{context::FastJobContext, utils::get_active_rider_by_person};
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{DeliveryBatchPlanResponse, PlanDeliveryBatchRequest};
+
+/// POST /api/v4/deliveries/batch-plan
+///
+/// Plan one run over several deliveries the rider holds or could take: the
+/// order to visit pickups and drop-offs in, with an ETA for each stop.
+pub async fn plan_batch(
+  data: Json<PlanDeliveryBatchRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryBatchPlanResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let plan = plan_delivery_batch(&context, rider.id, &data).await?;
+  Ok(Json(plan))
+}
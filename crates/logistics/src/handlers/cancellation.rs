@@ -0,0 +1,233 @@
+use crate::{
+  cancellation::{CancellationCharge, CancelledTrip},
+  handlers::status::publish_delivery_status,
+  offers::cancel_dispatch_for_post,
+};
+use actix_web::web::{Data, Json, Path};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{is_admin, verify_post_creator},
+};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::{CancellationParty, TripStatus},
+  newtypes::{CancellationPolicyId, Coin, PostId},
+  source::{
+    cancellation::{
+      CancellationPolicy,
+      CancellationPolicyInsertForm,
+      CancellationPolicyUpdateForm,
+    },
+    delivery_details::DeliveryDetails,
+    pricing_config::PricingConfig,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  CancelDeliveryRequest,
+  CancelDeliveryResponse,
+  CancellationPolicyResponse,
+  CreateCancellationPolicyRequest,
+  ListCancellationPoliciesResponse,
+  UpdateCancellationPolicyRequest,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::Utc;
+
+/// POST /api/v4/deliveries/{postId}/cancel
+///
+/// The employer cancels their delivery. Once a rider is assigned and the
+/// free period of the delivery cancellation policy is over, the rider is
+/// paid the policy's fee out of the escrowed delivery fee and the rest is
+/// refunded.
+pub async fn cancel_delivery(
+  path: Path<PostId>,
+  data: Json<CancelDeliveryRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CancelDeliveryResponse>> {
+  let post_id = path.into_inner();
+  let reason = data.reason.trim().to_string();
+  if reason.is_empty() {
+    return Err(FastJobErrorType::ReasonIsRequiredWhenCancelling.into());
+  }
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if !delivery.can_transition_to(TripStatus::Cancelled) {
+    return Err(
+      FastJobErrorType::InvalidField(format!(
+        "Cannot transition from {:?} to {:?}",
+        delivery.status,
+        TripStatus::Cancelled
+      ))
+      .into(),
+    );
+  }
+
+  let cancelled_at = Utc::now();
+  let (updated, fee_coin) = match CancelledTrip::for_delivery(&delivery) {
+    Some(trip) => {
+      let charge = trip.charge(&context, cancelled_at).await?;
+      let (updated, fee_paid) = DeliveryDetails::cancel_with_rider_fee(
+        &mut context.pool(),
+        post_id,
+        Some(reason.clone()),
+        Coin(charge.fee_coin),
+        context.get_coin_id().await?,
+        context.get_platform_wallet_id().await?,
+      )
+      .await?;
+      // The fee is capped at what was held in escrow
+      let charge = CancellationCharge {
+        fee_coin: fee_paid.0,
+        ..charge
+      };
+      trip
+        .record(
+          &context,
+          CancellationParty::Employer,
+          &charge,
+          Some(reason.clone()),
+        )
+        .await;
+      (updated, charge.fee_coin)
+    }
+    None => {
+      let updated = DeliveryDetails::update_status(
+        &mut context.pool(),
+        post_id,
+        TripStatus::Cancelled,
+        Some(reason.clone()),
+      )
+      .await?;
+      if let Err(e) = cancel_dispatch_for_post(&context, post_id).await {
+        tracing::warn!(?e, post_id = %post_id, "Failed to cancel dispatch for cancelled delivery");
+      }
+      (updated, 0)
+    }
+  };
+
+  publish_delivery_status(
+    &context,
+    post_id,
+    TripStatus::Cancelled,
+    updated.updated_at,
+    Some(reason.clone()),
+  )
+  .await;
+
+  Ok(Json(CancelDeliveryResponse {
+    post_id,
+    status: TripStatus::Cancelled,
+    cancellation_reason: reason,
+    cancellation_fee_coin: fee_coin,
+    cancelled_at,
+  }))
+}
+
+/// GET /api/v4/admin/cancellation-policies
+pub async fn admin_list_cancellation_policies(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListCancellationPoliciesResponse>> {
+  is_admin(&local_user_view)?;
+
+  let policies = CancellationPolicy::list(&mut context.pool()).await?;
+  Ok(Json(ListCancellationPoliciesResponse { policies }))
+}
+
+/// POST /api/v4/admin/cancellation-policies
+///
+/// A policy for a pricing config takes precedence over the default policy
+/// of its post kind.
+pub async fn admin_create_cancellation_policy(
+  data: Json<CreateCancellationPolicyRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CancellationPolicyResponse>> {
+  is_admin(&local_user_view)?;
+  check_non_negative(&[
+    Some(data.free_minutes),
+    Some(data.flat_fee_coin),
+    data.fee_per_km_coin,
+    data.max_fee_coin,
+  ])?;
+  if let Some(config_id) = data.pricing_config_id {
+    PricingConfig::read(&mut context.pool(), config_id).await?;
+  }
+
+  let form = CancellationPolicyInsertForm {
+    pricing_config_id: data.pricing_config_id,
+    fee_per_km_coin: data.fee_per_km_coin,
+    max_fee_coin: data.max_fee_coin,
+    ..CancellationPolicyInsertForm::new(
+      data.post_kind,
+      data.free_minutes,
+      data.fee_kind,
+      data.flat_fee_coin,
+    )
+  };
+  let policy = CancellationPolicy::create(&mut context.pool(), &form).await?;
+  Ok(Json(CancellationPolicyResponse { policy }))
+}
+
+/// PUT /api/v4/admin/cancellation-policies/{policyId}
+pub async fn admin_update_cancellation_policy(
+  path: Path<CancellationPolicyId>,
+  data: Json<UpdateCancellationPolicyRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CancellationPolicyResponse>> {
+  is_admin(&local_user_view)?;
+  check_non_negative(&[
+    data.free_minutes,
+    data.flat_fee_coin,
+    data.fee_per_km_coin,
+    data.max_fee_coin,
+  ])?;
+
+  let max_fee_coin = if data.clear_max_fee == Some(true) {
+    Some(None)
+  } else {
+    data.max_fee_coin.map(Some)
+  };
+  let form = CancellationPolicyUpdateForm {
+    free_minutes: data.free_minutes,
+    fee_kind: data.fee_kind,
+    flat_fee_coin: data.flat_fee_coin,
+    fee_per_km_coin: data.fee_per_km_coin,
+    max_fee_coin,
+    is_active: data.is_active,
+    updated_at: Some(Some(Utc::now())),
+  };
+  let policy = CancellationPolicy::update(&mut context.pool(), path.into_inner(), &form).await?;
+  Ok(Json(CancellationPolicyResponse { policy }))
+}
+
+/// DELETE /api/v4/admin/cancellation-policies/{policyId}
+///
+/// Recorded cancellations keep their fee but lose the link to the policy.
+pub async fn admin_delete_cancellation_policy(
+  path: Path<CancellationPolicyId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let deleted = CancellationPolicy::delete(&mut context.pool(), path.into_inner()).await?;
+  if deleted == 0 {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  Ok(Json(SuccessResponse { success: true }))
+}
+
+fn check_non_negative(values: &[Option<i32>]) -> FastJobResult<()> {
+  if values.iter().flatten().any(|v| *v < 0) {
+    return Err(
+      FastJobErrorType::InvalidField("minutes and fees must not be negative".to_string()).into(),
+    );
+  }
+  Ok(())
+}
@@ -0,0 +1,140 @@
+use actix_web::web::{Data, Json, Path};
+use app_108jobs_api_utils::{context::FastJobContext, utils::is_admin};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  newtypes::CargoTariffId,
+  source::{
+    cargo_tariff::{CargoTariff, CargoTariffInsertForm, CargoTariffUpdateForm},
+    pricing_config::PricingConfig,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  CargoTariffResponse,
+  CreateCargoTariffRequest,
+  ListCargoTariffsResponse,
+  UpdateCargoTariffRequest,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::Utc;
+use serde_json::json;
+
+/// GET /api/v4/admin/cargo-tariffs
+pub async fn admin_list_cargo_tariffs(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListCargoTariffsResponse>> {
+  is_admin(&local_user_view)?;
+
+  let tariffs = CargoTariff::list(&mut context.pool()).await?;
+  Ok(Json(ListCargoTariffsResponse { tariffs }))
+}
+
+/// POST /api/v4/admin/cargo-tariffs
+///
+/// A pricing config has at most one tariff. Without one, delivery fees are
+/// not checked against a floor.
+pub async fn admin_create_cargo_tariff(
+  data: Json<CreateCargoTariffRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CargoTariffResponse>> {
+  is_admin(&local_user_view)?;
+  check_surcharges(&[data.fragile_surcharge_coin, data.signature_surcharge_coin])?;
+  check_multipliers(&[
+    data.motorcycle_multiplier,
+    data.bicycle_multiplier,
+    data.car_multiplier,
+  ])?;
+  let weight_brackets = json!(data.weight_brackets);
+  let size_classes = json!(data.size_classes);
+  CargoTariff::parse_rates(&weight_brackets, &size_classes)?;
+  PricingConfig::read(&mut context.pool(), data.pricing_config_id).await?;
+
+  let form = CargoTariffInsertForm {
+    fragile_surcharge_coin: data.fragile_surcharge_coin,
+    signature_surcharge_coin: data.signature_surcharge_coin,
+    motorcycle_multiplier: data.motorcycle_multiplier,
+    bicycle_multiplier: data.bicycle_multiplier,
+    car_multiplier: data.car_multiplier,
+    ..CargoTariffInsertForm::new(data.pricing_config_id, weight_brackets, size_classes)
+  };
+  let tariff = CargoTariff::create(&mut context.pool(), &form).await?;
+  Ok(Json(CargoTariffResponse { tariff }))
+}
+
+/// PUT /api/v4/admin/cargo-tariffs/{tariffId}
+pub async fn admin_update_cargo_tariff(
+  path: Path<CargoTariffId>,
+  data: Json<UpdateCargoTariffRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CargoTariffResponse>> {
+  is_admin(&local_user_view)?;
+  check_surcharges(&[data.fragile_surcharge_coin, data.signature_surcharge_coin])?;
+  check_multipliers(&[
+    data.motorcycle_multiplier,
+    data.bicycle_multiplier,
+    data.car_multiplier,
+  ])?;
+  let tariff_id = path.into_inner();
+
+  // Brackets and classes are checked together as they will be stored
+  let weight_brackets = data.weight_brackets.as_ref().map(|b| json!(b));
+  let size_classes = data.size_classes.as_ref().map(|c| json!(c));
+  if weight_brackets.is_some() || size_classes.is_some() {
+    let current = CargoTariff::read(&mut context.pool(), tariff_id).await?;
+    CargoTariff::parse_rates(
+      weight_brackets.as_ref().unwrap_or(&current.weight_brackets),
+      size_classes.as_ref().unwrap_or(&current.size_classes),
+    )?;
+  }
+
+  let form = CargoTariffUpdateForm {
+    weight_brackets,
+    size_classes,
+    fragile_surcharge_coin: data.fragile_surcharge_coin,
+    signature_surcharge_coin: data.signature_surcharge_coin,
+    motorcycle_multiplier: data.motorcycle_multiplier,
+    bicycle_multiplier: data.bicycle_multiplier,
+    car_multiplier: data.car_multiplier,
+    is_active: data.is_active,
+    updated_at: Some(Some(Utc::now())),
+  };
+  let tariff = CargoTariff::update(&mut context.pool(), tariff_id, &form).await?;
+  Ok(Json(CargoTariffResponse { tariff }))
+}
+
+/// DELETE /api/v4/admin/cargo-tariffs/{tariffId}
+pub async fn admin_delete_cargo_tariff(
+  path: Path<CargoTariffId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let deleted = CargoTariff::delete(&mut context.pool(), path.into_inner()).await?;
+  if deleted == 0 {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  Ok(Json(SuccessResponse { success: true }))
+}
+
+fn check_surcharges(values: &[Option<i32>]) -> FastJobResult<()> {
+  if values.iter().flatten().any(|v| *v < 0) {
+    return Err(
+      FastJobErrorType::InvalidField("surcharges must not be negative".to_string()).into(),
+    );
+  }
+  Ok(())
+}
+
+fn check_multipliers(values: &[Option<f64>]) -> FastJobResult<()> {
+  if values.iter().flatten().any(|v| !v.is_finite() || *v <= 0.0) {
+    return Err(
+      FastJobErrorType::InvalidField("vehicle multipliers must be positive".to_string()).into(),
+    );
+  }
+  Ok(())
+}
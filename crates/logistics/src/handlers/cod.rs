@@ -0,0 +1,167 @@
+use actix_web::web::{Data, Json, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, get_active_rider_by_person, is_admin},
+};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::CodSettlementMethod,
+  newtypes::PersonId,
+  source::{
+    cod::{CodCollection, CodSettlement},
+    rider::Rider,
+  },
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  AdminRecordCodHandoverRequest,
+  CodRemittanceReportResponse,
+  CodSettlementResponse,
+  GetCodRemittanceQuery,
+  ListRiderCodOutstandingResponse,
+  RiderCodBalanceResponse,
+  SettleCodRequest,
+};
+
+/// Number of recent settlements shown alongside the rider's COD balance.
+const RECENT_SETTLEMENTS: i64 = 20;
+
+/// GET /api/v4/deliveries/cod/balance
+///
+/// The current rider's unsettled COD cash and recent settlements.
+pub async fn get_rider_cod_balance(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RiderCodBalanceResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+
+  let outstanding_coin =
+    CodCollection::outstanding_for_rider(&mut context.pool(), rider.id).await?;
+  let open_collections = CodCollection::list_open_for_rider(&mut context.pool(), rider.id).await?;
+  let recent_settlements =
+    CodSettlement::list_for_rider(&mut context.pool(), rider.id, RECENT_SETTLEMENTS).await?;
+  let cash_limit_coin = context.settings().cod.rider_cash_limit_coin;
+
+  Ok(Json(RiderCodBalanceResponse {
+    outstanding_coin,
+    cash_limit_coin,
+    over_limit: outstanding_coin >= cash_limit_coin,
+    open_collections,
+    recent_settlements,
+  }))
+}
+
+/// POST /api/v4/deliveries/cod/settle
+///
+/// The current rider pays collected cash back from their wallet balance.
+/// Riders short on coins can top up their wallet first.
+pub async fn settle_rider_cod(
+  data: Json<SettleCodRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CodSettlementResponse>> {
+  if data.amount_coin <= 0 {
+    return Err(FastJobErrorType::NegativeAmount.into());
+  }
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+
+  settle(
+    &context,
+    &rider,
+    CodSettlementMethod::WalletPayment,
+    data.amount_coin,
+    None,
+    None,
+  )
+  .await
+}
+
+/// POST /api/v4/admin/riders/cod/handover
+///
+/// An admin records cash physically handed over by a rider. The employer is
+/// paid from the platform wallet.
+pub async fn admin_record_cod_handover(
+  data: Json<AdminRecordCodHandoverRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CodSettlementResponse>> {
+  is_admin(&local_user_view)?;
+  if data.amount_coin <= 0 {
+    return Err(FastJobErrorType::NegativeAmount.into());
+  }
+  let rider = Rider::read(&mut context.pool(), data.rider_id).await?;
+
+  settle(
+    &context,
+    &rider,
+    CodSettlementMethod::CashHandover,
+    data.amount_coin,
+    Some(local_user_view.person.id),
+    data.note.clone(),
+  )
+  .await
+}
+
+/// GET /api/v4/admin/riders/cod/outstanding
+pub async fn admin_list_rider_cod_outstanding(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListRiderCodOutstandingResponse>> {
+  is_admin(&local_user_view)?;
+
+  let riders = CodCollection::list_outstanding_riders(&mut context.pool()).await?;
+  Ok(Json(ListRiderCodOutstandingResponse { riders }))
+}
+
+/// GET /api/v4/deliveries/cod/remittance
+///
+/// COD remittance report for the current employer: cash collected on their
+/// deliveries and how much of it has been paid into their wallet.
+pub async fn get_cod_remittance_report(
+  query: Query<GetCodRemittanceQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<CodRemittanceReportResponse>> {
+  let limit = check_fetch_limit(query.limit)?;
+  let employer_id = local_user_view.local_user.id;
+
+  let summary = CodCollection::remittance_summary(&mut context.pool(), employer_id).await?;
+  let collections =
+    CodCollection::list_for_employer(&mut context.pool(), employer_id, limit).await?;
+
+  Ok(Json(CodRemittanceReportResponse {
+    summary,
+    collections,
+  }))
+}
+
+async fn settle(
+  context: &FastJobContext,
+  rider: &Rider,
+  method: CodSettlementMethod,
+  amount_coin: i32,
+  recorded_by: Option<PersonId>,
+  note: Option<String>,
+) -> FastJobResult<Json<CodSettlementResponse>> {
+  let coin_id = context.get_coin_id().await?;
+  let platform_wallet_id = context.get_platform_wallet_id().await?;
+
+  let settlement = CodSettlement::settle(
+    &mut context.pool(),
+    rider,
+    method,
+    amount_coin,
+    recorded_by,
+    note,
+    coin_id,
+    platform_wallet_id,
+  )
+  .await?;
+  let outstanding_coin =
+    CodCollection::outstanding_for_rider(&mut context.pool(), rider.id).await?;
+
+  Ok(Json(CodSettlementResponse {
+    settlement,
+    outstanding_coin,
+  }))
+}
@@ -1,10 +1,14 @@
 use actix_web::web::{Data, Json, Path};
-use app_108jobs_api_utils::{context::FastJobContext, utils::verify_post_creator};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{reward_referrals, verify_post_creator},
+};
 use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
   enums::TripStatus,
   newtypes::PostId,
-  source::delivery_details::DeliveryDetails,
+  source::{delivery_details::DeliveryDetails, rider::Rider},
+  traits::Crud,
 };
 use app_108jobs_db_views_local_user::LocalUserView;
 use app_108jobs_db_views_rider::api::TripStatusEvent;
@@ -21,6 +25,10 @@ use app_108jobs_db_views_site::api::SuccessResponse;
 /// 1. Verify the caller is the employer
 /// 2. Release the escrowed funds to the rider's wallet
 /// 3. Update the employer_confirmed_at timestamp
+/// 4. Pay the fleet's share of the payout to the fleet owner, if the rider drives for one
+/// 5. Settle the rider's outstanding COD cash out of the payout, if enabled
+/// 6. Keep the rest of the payout pending until the clearing period ends
+/// 7. Pay referral rewards if this is the employer's or rider's first paid job
 pub async fn confirm_delivery_completion(
   path: Path<PostId>,
   context: Data<FastJobContext>,
@@ -44,13 +52,15 @@ pub async fn confirm_delivery_completion(
   let coin_id = context.get_coin_id().await?;
   let platform_wallet_id = context.get_platform_wallet_id().await?;
 
-  // Confirm completion and release payment
+  // Confirm completion and release payment, less the fleet share and COD
+  // cash, and hold it until it clears
   let updated_delivery = DeliveryDetails::confirm_completion_and_release_payment(
     &mut context.pool(),
     post_id,
     employer_person_id,
     coin_id,
     platform_wallet_id,
+    context.settings(),
   )
   .await?;
 
@@ -75,5 +85,16 @@ pub async fn confirm_delivery_completion(
     }
   }
 
+  let rider = match updated_delivery.assigned_rider_id {
+    Some(rider_id) => Rider::read(&mut context.pool(), rider_id).await.ok(),
+    None => None,
+  };
+
+  let mut participants = vec![local_user_view.local_user.id];
+  if let Some(rider) = &rider {
+    participants.push(rider.user_id);
+  }
+  reward_referrals(&context, &participants, "delivery", post_id.0).await;
+
   Ok(Json(SuccessResponse::default()))
 }
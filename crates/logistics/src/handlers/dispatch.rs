@@ -0,0 +1,258 @@
+use crate::{
+  offers::{accept_for_rider, push_dispatch_offers, push_offers_taken, rider_presence},
+  routing::routing_provider,
+};
+use actix_web::web::{Data, Json, Path};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{get_active_rider_by_person, verify_post_creator},
+};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::{DispatchJobKind, TripStatus},
+  newtypes::{DispatchOfferId, PersonId, PostId, RideSessionId},
+  source::{
+    delivery_details::{DeliveryDetails, DeliveryDetailsUpdateForm},
+    dispatch::{DispatchOffer, DispatchRequest, DispatchRequestInsertForm},
+    employer_rating::EmployerReliability,
+    ride_session::RideSession,
+    service_area::ServiceArea,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  AcceptDispatchOfferResponse,
+  DispatchResponse,
+  ListDispatchOffersResponse,
+  StartDeliveryDispatchRequest,
+  StartRideDispatchRequest,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::{DateTime, Duration, Utc};
+
+/// POST /api/v4/deliveries/{postId}/dispatch
+///
+/// Offer a pending delivery to the nearest available riders instead of
+/// picking a proposal by hand. Offers go out in waves until one rider
+/// accepts; the escrow is held at that point.
+pub async fn start_delivery_dispatch(
+  path: Path<PostId>,
+  form: Json<StartDeliveryDispatchRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DispatchResponse>> {
+  let post_id = path.into_inner();
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if delivery.status != TripStatus::Pending || delivery.assigned_rider_id.is_some() {
+    return Err(FastJobErrorType::DispatchJobNoLongerOpen.into());
+  }
+  check_dispatch_window(&context, delivery.scheduled_pickup_at)?;
+  let (Some(pickup_lat), Some(pickup_lng)) = (delivery.pickup_lat, delivery.pickup_lng) else {
+    return Err(FastJobErrorType::DispatchPickupLocationRequired.into());
+  };
+
+  if form.sender_name.trim().is_empty() {
+    return Err(FastJobErrorType::SenderNameIsRequired.into());
+  }
+  if form.sender_phone.trim().is_empty() {
+    return Err(FastJobErrorType::SenderPhoneIsRequired.into());
+  }
+  if form.receiver_name.trim().is_empty() {
+    return Err(FastJobErrorType::ReceiverNameIsRequired.into());
+  }
+  if form.receiver_phone.trim().is_empty() {
+    return Err(FastJobErrorType::ReceiverPhoneIsRequired.into());
+  }
+
+  // Store the contacts now so the accepting rider sees them straight away
+  let contacts = DeliveryDetailsUpdateForm {
+    sender_name: Some(Some(form.sender_name.clone())),
+    sender_phone: Some(Some(form.sender_phone.clone())),
+    receiver_name: Some(Some(form.receiver_name.clone())),
+    receiver_phone: Some(Some(form.receiver_phone.clone())),
+    updated_at: Some(Utc::now()),
+    ..Default::default()
+  };
+  DeliveryDetails::update(&mut context.pool(), delivery.id, &contacts).await?;
+
+  let mut insert_form =
+    DispatchRequestInsertForm::new(post_id, DispatchJobKind::Delivery, pickup_lat, pickup_lng);
+  insert_form.vehicle_type = delivery.vehicle_required;
+  insert_form.service_area_id =
+    ServiceArea::find_for_point(&mut context.pool(), pickup_lat, pickup_lng)
+      .await?
+      .map(|area| area.id);
+  insert_form.next_wave_at = first_wave_at(&context, local_user_view.person.id).await?;
+  let (dispatch, offers) = DispatchRequest::start(
+    &mut context.pool(),
+    &insert_form,
+    &context.settings().dispatch,
+    &routing_provider(&context),
+    &rider_presence(&context),
+  )
+  .await?;
+
+  push_dispatch_offers(&context, &offers).await;
+
+  Ok(Json(DispatchResponse { dispatch, offers }))
+}
+
+/// POST /api/v4/rides/{sessionId}/dispatch
+///
+/// Offer a pending ride to the nearest available riders.
+pub async fn start_ride_dispatch(
+  path: Path<RideSessionId>,
+  form: Json<StartRideDispatchRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DispatchResponse>> {
+  let session = RideSession::read(&mut context.pool(), path.into_inner()).await?;
+  if session.employer_id != local_user_view.local_user.id {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  if session.status != TripStatus::Pending || session.rider_id.is_some() {
+    return Err(FastJobErrorType::DispatchJobNoLongerOpen.into());
+  }
+  check_dispatch_window(&context, session.scheduled_pickup_at)?;
+  let (Some(pickup_lat), Some(pickup_lng)) = (session.pickup_lat, session.pickup_lng) else {
+    return Err(FastJobErrorType::DispatchPickupLocationRequired.into());
+  };
+
+  let mut insert_form = DispatchRequestInsertForm::new(
+    session.post_id,
+    DispatchJobKind::Ride,
+    pickup_lat,
+    pickup_lng,
+  );
+  insert_form.vehicle_type = form.vehicle_type;
+  insert_form.service_area_id =
+    ServiceArea::find_for_point(&mut context.pool(), pickup_lat, pickup_lng)
+      .await?
+      .map(|area| area.id);
+  insert_form.next_wave_at = first_wave_at(&context, local_user_view.person.id).await?;
+  let (dispatch, offers) = DispatchRequest::start(
+    &mut context.pool(),
+    &insert_form,
+    &context.settings().dispatch,
+    &routing_provider(&context),
+    &rider_presence(&context),
+  )
+  .await?;
+
+  push_dispatch_offers(&context, &offers).await;
+
+  Ok(Json(DispatchResponse { dispatch, offers }))
+}
+
+/// With deprioritising turned on, jobs from employers riders flagged as
+/// unreliable hold back their first wave so riders see other jobs first.
+async fn first_wave_at(
+  context: &FastJobContext,
+  employer_id: PersonId,
+) -> FastJobResult<Option<DateTime<Utc>>> {
+  let config = &context.settings().employer_reliability;
+  if !config.deprioritize_in_dispatch {
+    return Ok(None);
+  }
+  let flagged = EmployerReliability::read_for_employer(&mut context.pool(), employer_id)
+    .await?
+    .is_some_and(|r| r.is_flagged(config.flag_below_score, config.min_ratings));
+  Ok(flagged.then(|| Utc::now() + Duration::seconds(config.dispatch_delay_seconds)))
+}
+
+/// Scheduled jobs may only be dispatched once their pickup is inside the
+/// pre-dispatch window; until then riders reserve them instead.
+fn check_dispatch_window(
+  context: &FastJobContext,
+  scheduled_pickup_at: Option<DateTime<Utc>>,
+) -> FastJobResult<()> {
+  let pre_dispatch = Duration::minutes(context.settings().scheduling.pre_dispatch_minutes);
+  if scheduled_pickup_at.is_some_and(|at| at > Utc::now() + pre_dispatch) {
+    return Err(FastJobErrorType::ScheduledJobNotOpenYet.into());
+  }
+  Ok(())
+}
+
+/// GET /api/v4/dispatch/{postId}
+///
+/// The employer's view of a dispatch and every offer sent so far.
+pub async fn get_dispatch_status(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DispatchResponse>> {
+  let post_id = path.into_inner();
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let dispatch = DispatchRequest::get_by_post(&mut context.pool(), post_id)
+    .await?
+    .ok_or(FastJobErrorType::DispatchNotFound)?;
+  let offers = DispatchOffer::list_for_request(&mut context.pool(), dispatch.id).await?;
+
+  Ok(Json(DispatchResponse { dispatch, offers }))
+}
+
+/// POST /api/v4/dispatch/{postId}/cancel
+///
+/// Stop searching for a rider. The job stays open for proposals.
+pub async fn cancel_dispatch(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DispatchResponse>> {
+  let post_id = path.into_inner();
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let dispatch = DispatchRequest::cancel_for_post(&mut context.pool(), post_id)
+    .await?
+    .ok_or(FastJobErrorType::DispatchNotFound)?;
+  push_offers_taken(&context, dispatch.id).await;
+  let offers = DispatchOffer::list_for_request(&mut context.pool(), dispatch.id).await?;
+
+  Ok(Json(DispatchResponse { dispatch, offers }))
+}
+
+/// GET /api/v4/riders/offers
+///
+/// Job offers the current rider can still accept.
+pub async fn list_my_dispatch_offers(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListDispatchOffersResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let offers = DispatchOffer::list_open_for_rider(&mut context.pool(), rider.id).await?;
+
+  Ok(Json(ListDispatchOffersResponse { offers }))
+}
+
+/// POST /api/v4/riders/offers/{offerId}/accept
+///
+/// Accept a job offer. Only the first rider to accept is assigned; later
+/// accepts fail with `DispatchOfferNoLongerAvailable`.
+pub async fn accept_dispatch_offer(
+  path: Path<DispatchOfferId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<AcceptDispatchOfferResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let response = accept_for_rider(&context, &rider, path.into_inner()).await?;
+
+  Ok(Json(response))
+}
+
+/// POST /api/v4/riders/offers/{offerId}/decline
+///
+/// Decline a job offer. The job moves on to the next wave of riders.
+pub async fn decline_dispatch_offer(
+  path: Path<DispatchOfferId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  DispatchRequest::decline_offer(&mut context.pool(), path.into_inner(), rider.id).await?;
+
+  Ok(Json(SuccessResponse { success: true }))
+}
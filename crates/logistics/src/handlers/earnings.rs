@@ -0,0 +1,41 @@
+use actix_web::web::{Data, Json, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, get_active_rider_by_person},
+};
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db::source::{
+  rider_earning::{RiderEarningHold, RiderEarningsBucket, RiderEarningsSummary},
+  wallet::WalletModel,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{GetRiderEarningsQuery, RiderEarningsResponse};
+
+/// GET /api/v4/riders/earnings
+///
+/// The current rider's earnings: totals, coins still clearing, and a
+/// breakdown of delivery payouts by day or week.
+pub async fn get_rider_earnings(
+  query: Query<GetRiderEarningsQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RiderEarningsResponse>> {
+  let limit = check_fetch_limit(query.limit)?;
+  let period = query.period.unwrap_or_default();
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+
+  let summary = RiderEarningsSummary::for_rider(&mut context.pool(), &rider).await?;
+  let wallet = WalletModel::get_by_user(&mut context.pool(), rider.user_id).await?;
+  let breakdown =
+    RiderEarningsBucket::list_for_wallet(&mut context.pool(), wallet.id, period, limit).await?;
+  let pending_holds =
+    RiderEarningHold::list_pending_for_rider(&mut context.pool(), rider.id).await?;
+
+  Ok(Json(RiderEarningsResponse {
+    summary,
+    clearing_period_hours: context.settings().rider_earnings.clearing_period_hours,
+    period,
+    breakdown,
+    pending_holds,
+  }))
+}
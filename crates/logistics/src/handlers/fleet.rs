@@ -0,0 +1,361 @@
+use actix_web::web::{Data, Json, Path, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, get_active_rider_by_person, is_admin},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  settings::structs::FleetConfig,
+};
+use app_108jobs_db::{
+  enums::FleetDocumentStatus,
+  newtypes::{FleetDocumentId, LocalUserId, RiderFleetId, RiderId},
+  source::{
+    images::LocalImage,
+    rider::{Rider, RiderUpdateForm},
+    rider_fleet::{
+      FleetDocument,
+      FleetDocumentInsertForm,
+      FleetDocumentUpdateForm,
+      RiderFleet,
+      RiderFleetInsertForm,
+      RiderFleetUpdateForm,
+    },
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::{
+  api::{
+    CreateFleetRequest,
+    FleetDocumentResponse,
+    FleetMembership,
+    FleetMembershipResponse,
+    FleetResponse,
+    FleetRiderStatus,
+    JoinFleetRequest,
+    ListFleetDocumentsQuery,
+    ListFleetDocumentsResponse,
+    ListFleetRidersResponse,
+    ReviewFleetDocumentRequest,
+    ReviewFleetDocumentResponse,
+    SubmitFleetDocumentRequest,
+    UpdateFleetRequest,
+  },
+  RiderView,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::Utc;
+use std::collections::HashMap;
+
+/// POST /api/v4/fleets
+///
+/// Set up a fleet owned by the caller. Earnings are only routed to it once
+/// its documents have been approved.
+pub async fn create_fleet(
+  data: Json<CreateFleetRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FleetResponse>> {
+  let owner_id = local_user_view.local_user.id;
+  let cfg = &context.settings().fleets;
+  let name = check_fleet_name(&data.name)?;
+  if let Some(share) = data.fleet_share_percent {
+    check_fleet_share(share, cfg)?;
+  }
+  if RiderFleet::read_for_owner(&mut context.pool(), owner_id)
+    .await?
+    .is_some()
+  {
+    return Err(FastJobErrorType::FleetAlreadyExists.into());
+  }
+
+  let form = RiderFleetInsertForm {
+    route_earnings: data.route_earnings,
+    fleet_share_percent: data.fleet_share_percent,
+    ..RiderFleetInsertForm::new(owner_id, name, RiderFleet::generate_join_code())
+  };
+  let fleet = RiderFleet::create(&mut context.pool(), &form).await?;
+  Ok(Json(FleetResponse { fleet }))
+}
+
+/// GET /api/v4/fleets/mine
+pub async fn get_my_fleet(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FleetResponse>> {
+  let fleet = read_owned_fleet(&context, local_user_view.local_user.id).await?;
+  Ok(Json(FleetResponse { fleet }))
+}
+
+/// PUT /api/v4/fleets/mine
+///
+/// Rename the fleet, change how earnings are routed or issue a new join code.
+pub async fn update_my_fleet(
+  data: Json<UpdateFleetRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FleetResponse>> {
+  let fleet = read_owned_fleet(&context, local_user_view.local_user.id).await?;
+  let name = data.name.as_deref().map(check_fleet_name).transpose()?;
+  if let Some(share) = data.fleet_share_percent {
+    check_fleet_share(share, &context.settings().fleets)?;
+  }
+
+  let form = RiderFleetUpdateForm {
+    name,
+    join_code: (data.regenerate_join_code == Some(true)).then(RiderFleet::generate_join_code),
+    route_earnings: data.route_earnings,
+    fleet_share_percent: data.fleet_share_percent,
+    updated_at: Some(Some(Utc::now())),
+    ..Default::default()
+  };
+  let fleet = RiderFleet::update(&mut context.pool(), fleet.id, &form).await?;
+  Ok(Json(FleetResponse { fleet }))
+}
+
+/// GET /api/v4/fleets/mine/riders
+///
+/// The fleet's riders with their live status, rating and current jobs.
+pub async fn list_fleet_riders(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListFleetRidersResponse>> {
+  let fleet = read_owned_fleet(&context, local_user_view.local_user.id).await?;
+
+  let riders = RiderView::list_for_fleet(&mut context.pool(), fleet.id).await?;
+  let rider_ids: Vec<RiderId> = riders.iter().map(|r| r.rider.id).collect();
+  let mut jobs: HashMap<RiderId, Vec<_>> = HashMap::new();
+  for job in RiderFleet::active_jobs(&mut context.pool(), &rider_ids).await? {
+    jobs.entry(job.rider_id).or_default().push(job);
+  }
+
+  let riders = riders
+    .into_iter()
+    .map(|rider| FleetRiderStatus {
+      active_jobs: jobs.remove(&rider.rider.id).unwrap_or_default(),
+      rider,
+    })
+    .collect();
+  Ok(Json(ListFleetRidersResponse { riders }))
+}
+
+/// DELETE /api/v4/fleets/mine/riders/{riderId}
+pub async fn remove_fleet_rider(
+  path: Path<RiderId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  let fleet = read_owned_fleet(&context, local_user_view.local_user.id).await?;
+  let rider = Rider::read(&mut context.pool(), path.into_inner()).await?;
+  if rider.fleet_id != Some(fleet.id) {
+    return Err(FastJobErrorType::RiderNotInFleet.into());
+  }
+
+  set_rider_fleet(&context, &rider, None).await?;
+  Ok(Json(SuccessResponse::default()))
+}
+
+/// GET /api/v4/fleets/mine/documents
+pub async fn list_fleet_documents(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListFleetDocumentsResponse>> {
+  let fleet = read_owned_fleet(&context, local_user_view.local_user.id).await?;
+  let documents = FleetDocument::list_for_fleet(&mut context.pool(), fleet.id).await?;
+  Ok(Json(ListFleetDocumentsResponse { documents }))
+}
+
+/// POST /api/v4/fleets/mine/documents
+///
+/// Submit a company document for review. The fleet stays unverified until
+/// every document it has submitted is approved.
+pub async fn submit_fleet_document(
+  data: Json<SubmitFleetDocumentRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FleetDocumentResponse>> {
+  let fleet = read_owned_fleet(&context, local_user_view.local_user.id).await?;
+  let max_documents = context.settings().fleets.max_documents;
+  let submitted = FleetDocument::list_for_fleet(&mut context.pool(), fleet.id).await?;
+  if submitted.len() >= max_documents {
+    return Err(
+      FastJobErrorType::InvalidField(format!(
+        "a fleet can submit at most {max_documents} documents"
+      ))
+      .into(),
+    );
+  }
+  let alias = data
+    .file_url
+    .as_str()
+    .split('/')
+    .next_back()
+    .unwrap_or_default();
+  LocalImage::validate_by_alias_and_user(&mut context.pool(), alias, local_user_view.person.id)
+    .await
+    .map_err(|_| {
+      FastJobErrorType::InvalidField("fleet documents must be uploaded by the owner".to_string())
+    })?;
+
+  let form = FleetDocumentInsertForm {
+    expires_at: data.expires_at,
+    ..FleetDocumentInsertForm::new(fleet.id, data.kind, data.file_url.clone())
+  };
+  let document = FleetDocument::create(&mut context.pool(), &form).await?;
+  RiderFleet::refresh_verification(&mut context.pool(), fleet.id).await?;
+  Ok(Json(FleetDocumentResponse { document }))
+}
+
+/// GET /api/v4/riders/fleet
+pub async fn get_my_fleet_membership(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FleetMembershipResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let fleet = match rider.fleet_id {
+    Some(fleet_id) => Some(RiderFleet::read(&mut context.pool(), fleet_id).await?),
+    None => None,
+  };
+
+  let max_share_percent = context.settings().fleets.max_share_percent;
+  let fleet = fleet.map(|fleet| FleetMembership {
+    fleet_id: fleet.id,
+    route_earnings: fleet.route_earnings && fleet.is_verified && fleet.owner_id != rider.user_id,
+    fleet_share_percent: fleet.fleet_share_percent.min(max_share_percent),
+    is_verified: fleet.is_verified,
+    name: fleet.name,
+  });
+  Ok(Json(FleetMembershipResponse { fleet }))
+}
+
+/// POST /api/v4/riders/fleet/join
+///
+/// Join the fleet that handed out the code. A rider drives for one fleet at
+/// a time.
+pub async fn join_fleet(
+  data: Json<JoinFleetRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  if rider.fleet_id.is_some() {
+    return Err(FastJobErrorType::RiderAlreadyInFleet.into());
+  }
+  let fleet = RiderFleet::read_by_join_code(&mut context.pool(), &data.join_code).await?;
+
+  set_rider_fleet(&context, &rider, Some(fleet.id)).await?;
+  Ok(Json(SuccessResponse::default()))
+}
+
+/// POST /api/v4/riders/fleet/leave
+pub async fn leave_fleet(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  if rider.fleet_id.is_none() {
+    return Err(FastJobErrorType::RiderNotInFleet.into());
+  }
+
+  set_rider_fleet(&context, &rider, None).await?;
+  Ok(Json(SuccessResponse::default()))
+}
+
+/// GET /api/v4/admin/fleet-documents
+pub async fn admin_list_fleet_documents(
+  query: Query<ListFleetDocumentsQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListFleetDocumentsResponse>> {
+  is_admin(&local_user_view)?;
+  let limit = check_fetch_limit(query.limit)?;
+
+  let documents = FleetDocument::list(&mut context.pool(), query.status, limit).await?;
+  Ok(Json(ListFleetDocumentsResponse { documents }))
+}
+
+/// PUT /api/v4/admin/fleet-documents/{documentId}
+///
+/// Approve or reject a fleet document. The fleet is verified once all its
+/// documents are approved, and loses verification when one is rejected.
+pub async fn admin_review_fleet_document(
+  path: Path<FleetDocumentId>,
+  data: Json<ReviewFleetDocumentRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ReviewFleetDocumentResponse>> {
+  is_admin(&local_user_view)?;
+  let document_id = path.into_inner();
+  FleetDocument::read(&mut context.pool(), document_id).await?;
+
+  let now = Utc::now();
+  let status = if data.approve {
+    FleetDocumentStatus::Approved
+  } else {
+    FleetDocumentStatus::Rejected
+  };
+  let form = FleetDocumentUpdateForm {
+    status: Some(status),
+    reviewed_by: Some(Some(local_user_view.person.id)),
+    review_note: Some(
+      data
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string),
+    ),
+    reviewed_at: Some(Some(now)),
+    updated_at: Some(Some(now)),
+  };
+  let document = FleetDocument::update(&mut context.pool(), document_id, &form).await?;
+  let fleet = RiderFleet::refresh_verification(&mut context.pool(), document.fleet_id).await?;
+  Ok(Json(ReviewFleetDocumentResponse { document, fleet }))
+}
+
+/// The fleet owned by the caller.
+async fn read_owned_fleet(
+  context: &FastJobContext,
+  owner_id: LocalUserId,
+) -> FastJobResult<RiderFleet> {
+  RiderFleet::read_for_owner(&mut context.pool(), owner_id)
+    .await?
+    .ok_or(FastJobErrorType::NotFound.into())
+}
+
+async fn set_rider_fleet(
+  context: &FastJobContext,
+  rider: &Rider,
+  fleet_id: Option<RiderFleetId>,
+) -> FastJobResult<()> {
+  let form = RiderUpdateForm {
+    fleet_id: Some(fleet_id),
+    ..Default::default()
+  };
+  Rider::update(&mut context.pool(), rider.id, &form).await?;
+  Ok(())
+}
+
+fn check_fleet_name(name: &str) -> FastJobResult<String> {
+  let name = name.trim();
+  if name.is_empty() || name.chars().count() > 100 {
+    return Err(
+      FastJobErrorType::InvalidField("name must be 1 to 100 characters".to_string()).into(),
+    );
+  }
+  Ok(name.to_string())
+}
+
+fn check_fleet_share(share: f64, cfg: &FleetConfig) -> FastJobResult<()> {
+  if !(0.0..=cfg.max_share_percent).contains(&share) {
+    return Err(
+      FastJobErrorType::InvalidField(format!(
+        "fleetSharePercent must be between 0 and {}",
+        cfg.max_share_percent
+      ))
+      .into(),
+    );
+  }
+  Ok(())
+}
@@ -0,0 +1,196 @@
+use crate::incidents::{raise_incident, IncidentTrip};
+use actix_web::web::{Data, Json, Path, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, is_admin, verify_post_creator},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  utils::validation::{is_valid_email, is_valid_phone},
+};
+use app_108jobs_db::{
+  enums::{DispatchJobKind, IncidentStatus},
+  newtypes::{PostId, RideSessionId, TripIncidentId},
+  source::{
+    delivery_details::DeliveryDetails,
+    emergency_contact::{EmergencyContact, EmergencyContactForm},
+    ride_session::RideSession,
+    rider::Rider,
+    trip_incident::{TripIncident, TripIncidentUpdateForm},
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  EmergencyContactResponse,
+  IncidentResponse,
+  ListIncidentsQuery,
+  ListIncidentsResponse,
+  ReportIncidentRequest,
+  SaveEmergencyContactRequest,
+  UpdateIncidentRequest,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::Utc;
+
+/// POST /api/v4/deliveries/{postId}/incidents
+///
+/// Raise an incident on an active delivery, as its rider or employer.
+/// Admins are alerted right away.
+pub async fn report_delivery_incident(
+  path: Path<PostId>,
+  data: Json<ReportIncidentRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<IncidentResponse>> {
+  let post_id = path.into_inner();
+  let person_id = local_user_view.person.id;
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  // A suspended rider can still call for help on the trip they are on
+  let rider = Rider::get_by_person_id_with_inactive(&mut context.pool(), person_id).await?;
+  let is_rider = rider.is_some_and(|r| delivery.assigned_rider_id == Some(r.id));
+  if !is_rider {
+    verify_post_creator(&mut context.pool(), post_id, person_id).await?;
+  }
+
+  let trip = IncidentTrip {
+    post_id,
+    job_kind: DispatchJobKind::Delivery,
+    status: delivery.status,
+    rider_id: delivery.assigned_rider_id,
+  };
+  let response = raise_incident(&context, &local_user_view, trip, is_rider, &data).await?;
+  Ok(Json(response))
+}
+
+/// POST /api/v4/rides/{sessionId}/incidents
+///
+/// Raise an incident on an active ride, as its rider or passenger. Admins
+/// are alerted right away.
+pub async fn report_ride_incident(
+  path: Path<RideSessionId>,
+  data: Json<ReportIncidentRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<IncidentResponse>> {
+  let session = RideSession::read(&mut context.pool(), path.into_inner()).await?;
+  let rider =
+    Rider::get_by_person_id_with_inactive(&mut context.pool(), local_user_view.person.id).await?;
+  let is_rider = rider.is_some_and(|r| session.rider_id == Some(r.id));
+  if !is_rider && session.employer_id != local_user_view.local_user.id {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+
+  let trip = IncidentTrip {
+    post_id: session.post_id,
+    job_kind: DispatchJobKind::Ride,
+    status: session.status,
+    rider_id: session.rider_id,
+  };
+  let response = raise_incident(&context, &local_user_view, trip, is_rider, &data).await?;
+  Ok(Json(response))
+}
+
+/// GET /api/v4/account/emergency-contact
+pub async fn get_emergency_contact(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<EmergencyContactResponse>> {
+  let contact =
+    EmergencyContact::read_for_user(&mut context.pool(), local_user_view.local_user.id).await?;
+  Ok(Json(EmergencyContactResponse { contact }))
+}
+
+/// PUT /api/v4/account/emergency-contact
+///
+/// Set who gets a live tracking link when the user raises an incident.
+pub async fn save_emergency_contact(
+  data: Json<SaveEmergencyContactRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<EmergencyContactResponse>> {
+  let name = data.name.trim();
+  if name.is_empty() || name.chars().count() > 100 {
+    return Err(
+      FastJobErrorType::InvalidField("name must be 1 to 100 characters".to_string()).into(),
+    );
+  }
+  let phone = data.phone.trim();
+  if !is_valid_phone(phone) || phone.len() > 32 {
+    return Err(FastJobErrorType::InvalidField("phone is not valid".to_string()).into());
+  }
+  let email = data
+    .email
+    .as_deref()
+    .map(str::trim)
+    .filter(|e| !e.is_empty());
+  if let Some(email) = email {
+    if !is_valid_email(email) || email.len() > 255 {
+      return Err(FastJobErrorType::InvalidEmailAddress(email.to_string()).into());
+    }
+  }
+
+  let form = EmergencyContactForm::new(
+    local_user_view.local_user.id,
+    name.to_string(),
+    phone.to_string(),
+    email.map(str::to_string),
+  );
+  let contact = EmergencyContact::upsert(&mut context.pool(), &form).await?;
+  Ok(Json(EmergencyContactResponse {
+    contact: Some(contact),
+  }))
+}
+
+/// DELETE /api/v4/account/emergency-contact
+pub async fn delete_emergency_contact(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  EmergencyContact::delete_for_user(&mut context.pool(), local_user_view.local_user.id).await?;
+  Ok(Json(SuccessResponse { success: true }))
+}
+
+/// GET /api/v4/admin/incidents
+pub async fn admin_list_incidents(
+  query: Query<ListIncidentsQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListIncidentsResponse>> {
+  is_admin(&local_user_view)?;
+  let limit = check_fetch_limit(query.limit)?;
+
+  let incidents = TripIncident::list(&mut context.pool(), query.status, limit).await?;
+  Ok(Json(ListIncidentsResponse { incidents }))
+}
+
+/// PUT /api/v4/admin/incidents/{incidentId}
+///
+/// Acknowledge or resolve an incident. Resolving records who resolved it.
+pub async fn admin_update_incident(
+  path: Path<TripIncidentId>,
+  data: Json<UpdateIncidentRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<IncidentResponse>> {
+  is_admin(&local_user_view)?;
+  let incident_id = path.into_inner();
+  TripIncident::read(&mut context.pool(), incident_id).await?;
+
+  let now = Utc::now();
+  let resolved = data.status == IncidentStatus::Resolved;
+  let form = TripIncidentUpdateForm {
+    status: Some(data.status),
+    resolved_by: Some(resolved.then_some(local_user_view.person.id)),
+    resolution_note: data.resolution_note.clone().map(Some),
+    resolved_at: Some(resolved.then_some(now)),
+    updated_at: Some(Some(now)),
+    ..Default::default()
+  };
+  let incident = TripIncident::update(&mut context.pool(), incident_id, &form).await?;
+  Ok(Json(IncidentResponse {
+    incident,
+    tracking_url: None,
+  }))
+}
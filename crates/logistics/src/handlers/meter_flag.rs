@@ -0,0 +1,55 @@
+use actix_web::web::{Data, Json, Path, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, is_admin},
+};
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db::{newtypes::RideMeterFlagId, source::ride_meter_flag::RideMeterFlag};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  ListRideMeterFlagsQuery,
+  ListRideMeterFlagsResponse,
+  ReviewRideMeterFlagRequest,
+  RideMeterFlagResponse,
+};
+
+/// GET /api/v4/admin/rides/meter-flags
+///
+/// Rides whose reported meter diverged from the GPS history and that nobody
+/// has reviewed yet.
+pub async fn admin_list_ride_meter_flags(
+  query: Query<ListRideMeterFlagsQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListRideMeterFlagsResponse>> {
+  is_admin(&local_user_view)?;
+  let limit = check_fetch_limit(query.limit)?;
+
+  let flags = RideMeterFlag::list_unreviewed(&mut context.pool(), limit).await?;
+  Ok(Json(ListRideMeterFlagsResponse { flags }))
+}
+
+/// POST /api/v4/admin/rides/meter-flags/{flagId}/review
+pub async fn admin_review_ride_meter_flag(
+  path: Path<RideMeterFlagId>,
+  data: Json<ReviewRideMeterFlagRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RideMeterFlagResponse>> {
+  is_admin(&local_user_view)?;
+  let note = data
+    .resolution_note
+    .as_deref()
+    .map(str::trim)
+    .filter(|n| !n.is_empty())
+    .map(str::to_string);
+
+  let flag = RideMeterFlag::mark_reviewed(
+    &mut context.pool(),
+    path.into_inner(),
+    local_user_view.person.id,
+    note,
+  )
+  .await?;
+  Ok(Json(RideMeterFlagResponse { flag }))
+}
@@ -1,7 +1,24 @@
 pub mod assign;
+pub mod batch;
+pub mod cancellation;
+pub mod cargo_tariff;
+pub mod cod;
 pub mod confirm;
+pub mod dispatch;
+pub mod earnings;
+pub mod fleet;
+pub mod incident;
 pub mod list;
 pub mod location;
+pub mod meter_flag;
+pub mod proof;
+pub mod quote;
 pub mod rate;
 pub mod ride;
+pub mod schedule;
+pub mod service_area;
+pub mod shift;
 pub mod status;
+pub mod stop;
+pub mod track;
+pub mod tracking;
@@ -0,0 +1,91 @@
+use actix_web::web::{Data, Json, Path};
+use app_108jobs_api_utils::{context::FastJobContext, utils::verify_post_creator};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::TripStatus,
+  newtypes::PostId,
+  source::{delivery_details::DeliveryDetails, delivery_proof::DeliveryProof},
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::DeliveryProofResponse;
+
+/// GET /api/v4/deliveries/{postId}/proof
+///
+/// Proof of delivery, so the employer can check it before confirming
+/// completion. The employer also gets the receiver's one-time code to pass
+/// on while the delivery is under way. Readable by the employer, the
+/// assigned rider and admins.
+pub async fn get_delivery_proof(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryProofResponse>> {
+  let post_id = path.into_inner();
+  let person_id = local_user_view.person.id;
+
+  let is_admin = local_user_view.local_user.admin;
+  let is_employer = verify_post_creator(&mut context.pool(), post_id, person_id)
+    .await
+    .is_ok();
+  if !is_admin && !is_employer {
+    DeliveryDetails::validate_rider_identity(&mut context.pool(), person_id, post_id).await?;
+  }
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  let proof = DeliveryProof::get_by_post(&mut context.pool(), post_id).await?;
+  Ok(Json(proof_response(
+    &delivery,
+    proof,
+    is_employer || is_admin,
+  )))
+}
+
+/// POST /api/v4/deliveries/{postId}/proof/otp
+///
+/// Issue a new receiver code, for example after the rider used up their
+/// attempts. Only the employer can do this, while the parcel is on its way.
+pub async fn reissue_delivery_otp(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryProofResponse>> {
+  let post_id = path.into_inner();
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if !delivery.requires_otp {
+    return Err(FastJobErrorType::InvalidField("delivery does not use a code".to_string()).into());
+  }
+  if !matches!(
+    delivery.status,
+    TripStatus::PickedUp | TripStatus::EnRouteToDropoff
+  ) {
+    return Err(FastJobErrorType::DeliveryIsNotActive.into());
+  }
+  let rider_id = delivery
+    .assigned_rider_id
+    .ok_or(FastJobErrorType::NoRiderAssigned)?;
+
+  let proof = DeliveryProof::issue_otp(&mut context.pool(), post_id, rider_id).await?;
+  Ok(Json(proof_response(&delivery, Some(proof), true)))
+}
+
+fn proof_response(
+  delivery: &DeliveryDetails,
+  proof: Option<DeliveryProof>,
+  show_otp: bool,
+) -> DeliveryProofResponse {
+  let otp = proof
+    .as_ref()
+    .filter(|_| show_otp && delivery.status != TripStatus::Delivered)
+    .and_then(|p| p.otp_code.clone());
+
+  DeliveryProofResponse {
+    post_id: delivery.post_id,
+    requires_photo: delivery.requires_photo,
+    requires_signature: delivery.requires_signature,
+    requires_otp: delivery.requires_otp,
+    proof,
+    otp,
+  }
+}
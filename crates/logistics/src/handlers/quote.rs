@@ -0,0 +1,211 @@
+use crate::routing::{routing_provider, RoutingProvider};
+use actix_web::web::{Data, Json};
+use app_108jobs_api_utils::{context::FastJobContext, fare_quote::FareQuoteClaims};
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  settings::structs::FareQuoteConfig,
+  utils::geo::haversine_km,
+};
+use app_108jobs_db::{
+  newtypes::CurrencyId,
+  source::{
+    cargo_tariff::{CargoSpec, CargoTariff},
+    currency::Currency,
+    pricing_config::PricingConfig,
+    pricing_rule::PriceMultiplier,
+    service_area::ServiceArea,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{FareQuoteRequest, FareQuoteResponse};
+use chrono::{DateTime, Duration, Utc};
+
+/// POST /api/v4/rides/quote
+///
+/// Estimate the fare for a trip before it is booked. Distance and duration
+/// come from the routing provider; the pricing config of the pickup's service
+/// area, or else the active one for the currency, is applied with its
+/// pricing rules. The returned token caps the fare at the top of the range
+/// when passed to `create_ride_session`.
+pub async fn quote_fare(
+  data: Json<FareQuoteRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FareQuoteResponse>> {
+  quote(data.into_inner(), &context, &local_user_view, false).await
+}
+
+/// POST /api/v4/deliveries/quote
+///
+/// Like a ride quote, but when the pricing config has a cargo tariff the
+/// cargo is priced by it and the response carries the lowest delivery fee a
+/// delivery post may offer.
+pub async fn quote_delivery(
+  data: Json<FareQuoteRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<FareQuoteResponse>> {
+  quote(data.into_inner(), &context, &local_user_view, true).await
+}
+
+async fn quote(
+  data: FareQuoteRequest,
+  context: &FastJobContext,
+  local_user_view: &LocalUserView,
+  delivery: bool,
+) -> FastJobResult<Json<FareQuoteResponse>> {
+  let pickup = (data.pickup_lat, data.pickup_lng);
+  let dropoff = (data.dropoff_lat, data.dropoff_lng);
+  for (lat, lng) in [pickup, dropoff] {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+      return Err(FastJobErrorType::InvalidField("invalid coordinates".to_string()).into());
+    }
+  }
+  if data
+    .package_weight_kg
+    .is_some_and(|kg| !kg.is_finite() || kg < 0.0)
+  {
+    return Err(
+      FastJobErrorType::InvalidField("package_weight_kg must be >= 0".to_string()).into(),
+    );
+  }
+
+  let area = ServiceArea::validate_trip(
+    &mut context.pool(),
+    pickup,
+    Some(dropoff),
+    data.vehicle_type,
+  )
+  .await?;
+
+  let (currency, pricing_config) = trip_pricing(context, area, data.currency_id).await?;
+
+  let settings = context.settings();
+  let cfg = &settings.fare_quote;
+  let now = Utc::now();
+  let multiplier = PriceMultiplier::for_ride(
+    &mut context.pool(),
+    pricing_config.id,
+    now,
+    Some(pickup),
+    &settings.surge_pricing,
+  )
+  .await?
+  .multiplier;
+
+  let route = routing_provider(context).route(pickup, dropoff).await?;
+  let distance_km = route.distance_km;
+  let duration_minutes = route.duration_minutes.ceil() as i32;
+  let subtotal = pricing_config
+    .metered_fare(duration_minutes, distance_km)
+    .subtotal_coin;
+
+  let tariff = if delivery {
+    CargoTariff::find_for_config(&mut context.pool(), pricing_config.id).await?
+  } else {
+    None
+  };
+  let (estimated_price_coin, minimum_fee_coin) = match tariff {
+    Some(tariff) => {
+      let cargo = CargoSpec {
+        weight_kg: data.package_weight_kg,
+        size: data.package_size.clone(),
+        fragile: data.fragile.unwrap_or(false),
+        requires_signature: data.requires_signature.unwrap_or(false),
+        vehicle: data.vehicle_type,
+      };
+      let charges = tariff.charges(&cargo)?;
+      let estimated = ((subtotal as f64 * multiplier + f64::from(charges.surcharges_coin()))
+        * charges.vehicle_multiplier)
+        .round() as i32;
+      // The same floor delivery posts are checked against
+      let straight_km = haversine_km(pickup.0, pickup.1, dropoff.0, dropoff.1);
+      let minimum = tariff.minimum_fee(&pricing_config, straight_km, &cargo)?;
+      (estimated.max(minimum), Some(minimum))
+    }
+    None => {
+      let cargo = cargo_factor(cfg, data.package_weight_kg, data.package_size.as_deref());
+      ((subtotal as f64 * multiplier * cargo).round() as i32, None)
+    }
+  };
+  let min_price_coin = (estimated_price_coin as f64 * (1.0 - cfg.range_spread)).floor() as i32;
+  let max_price_coin = (estimated_price_coin as f64 * (1.0 + cfg.range_spread)).ceil() as i32;
+
+  let claims = FareQuoteClaims::new(
+    local_user_view.local_user.id,
+    pricing_config.id,
+    pickup,
+    dropoff,
+    multiplier,
+    max_price_coin,
+    cfg.token_ttl_minutes,
+  );
+  let quote_token = claims.sign(context)?;
+  let expires_at = DateTime::<Utc>::from_timestamp(claims.exp, 0)
+    .unwrap_or_else(|| now + Duration::minutes(cfg.token_ttl_minutes));
+
+  Ok(Json(FareQuoteResponse {
+    pricing_config_id: pricing_config.id,
+    vehicle_type: data.vehicle_type,
+    distance_km,
+    duration_minutes,
+    price_multiplier: multiplier,
+    estimated_price_coin,
+    min_price_coin,
+    max_price_coin,
+    minimum_fee_coin,
+    formatted_min_price: currency.format_coins(min_price_coin),
+    formatted_max_price: currency.format_coins(max_price_coin),
+    currency_code: currency.code,
+    quote_token,
+    expires_at,
+    route_polyline: route.polyline,
+    route_estimated: route.estimated,
+  }))
+}
+
+/// The pricing config of the pickup's service area, unless another currency
+/// was asked for, or else the active config for the currency.
+pub(crate) async fn trip_pricing(
+  context: &FastJobContext,
+  area: Option<ServiceArea>,
+  currency_id: Option<CurrencyId>,
+) -> FastJobResult<(Currency, PricingConfig)> {
+  let area_config = match area.and_then(|a| a.pricing_config_id) {
+    Some(config_id) => Some(PricingConfig::read(&mut context.pool(), config_id).await?),
+    None => None,
+  }
+  .filter(|c| c.is_active && currency_id.map_or(true, |id| id == c.currency_id));
+  if let Some(config) = area_config {
+    let currency = Currency::read(&mut context.pool(), config.currency_id).await?;
+    return Ok((currency, config));
+  }
+
+  let currency = match currency_id {
+    Some(currency_id) => Currency::read(&mut context.pool(), currency_id).await?,
+    None => Currency::get_default(&mut context.pool())
+      .await?
+      .ok_or(FastJobErrorType::NotFound)?,
+  };
+  let config = PricingConfig::get_active_for_currency(&mut context.pool(), currency.id)
+    .await?
+    .ok_or(FastJobErrorType::NotFound)?;
+  Ok((currency, config))
+}
+
+/// Weight and size surcharges for cargo, as a multiplier, for pricing
+/// configs without a cargo tariff.
+fn cargo_factor(cfg: &FareQuoteConfig, weight_kg: Option<f64>, size: Option<&str>) -> f64 {
+  let mut factor = 1.0;
+  if let Some(kg) = weight_kg {
+    if cfg.cargo_weight_step_kg > 0.0 && kg > cfg.cargo_weight_step_kg {
+      let extra_steps = ((kg - cfg.cargo_weight_step_kg) / cfg.cargo_weight_step_kg).ceil();
+      factor += extra_steps * cfg.cargo_weight_step_rate;
+    }
+  }
+  if size.is_some_and(|s| s.trim().eq_ignore_ascii_case("large")) {
+    factor += cfg.large_package_rate;
+  }
+  factor
+}
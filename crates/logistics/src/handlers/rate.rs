@@ -1,13 +1,18 @@
-use actix_web::web::{Data, Json};
-use app_108jobs_api_utils::{context::FastJobContext, utils::verify_post_creator};
+use actix_web::web::{Data, Json, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, get_active_rider_by_person, is_admin, verify_post_creator},
+};
 use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
-  enums::{PostKind, TripStatus},
+  enums::{DispatchJobKind, PostKind, TripStatus},
   newtypes::RiderId,
   source::{
     delivery_details::DeliveryDetails,
     delivery_rider_rating::DeliveryRiderRating,
+    employer_rating::{EmployerRating, EmployerRatingInsertForm, EmployerReliability},
     post::Post,
+    ride_session::RideSession,
     rider::Rider,
   },
   traits::Crud,
@@ -15,6 +20,10 @@ use app_108jobs_db::{
 use app_108jobs_db_views_local_user::LocalUserView;
 use app_108jobs_db_views_rider::api::{
   DeliveryRiderRatingData,
+  ListFlaggedEmployersQuery,
+  ListFlaggedEmployersResponse,
+  RateEmployerRequest,
+  RateEmployerResponse,
   RateRiderRequest,
   RateRiderResponse,
 };
@@ -149,3 +158,92 @@ pub async fn get_rider_ratings(
     },
   ))
 }
+
+/// POST /api/v4/riders/rate-employer
+///
+/// Rate the employer or passenger of a finished delivery or ride, with
+/// optional tags such as no-show or wrong address. Only the rider who did
+/// the trip can rate it; rating again replaces the earlier rating.
+pub async fn rate_employer(
+  context: Data<FastJobContext>,
+  form: Json<RateEmployerRequest>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RateEmployerResponse>> {
+  if !(1..=5).contains(&form.rating) {
+    return Err(FastJobErrorType::RatingMustBeBetween1And5.into());
+  }
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+
+  let post = Post::read(&mut context.pool(), form.post_id).await?;
+  let (job_kind, status, rider_id) = match post.post_kind {
+    PostKind::Delivery => {
+      let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post.id).await?;
+      (
+        DispatchJobKind::Delivery,
+        delivery.status,
+        delivery.assigned_rider_id,
+      )
+    }
+    PostKind::RideTaxi => {
+      let session = RideSession::get_by_post(&mut context.pool(), post.id)
+        .await?
+        .ok_or(FastJobErrorType::NotFound)?;
+      (DispatchJobKind::Ride, session.status, session.rider_id)
+    }
+    PostKind::Normal => return Err(FastJobErrorType::NotFound.into()),
+  };
+  if rider_id != Some(rider.id) {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  // A no-show usually ends in a cancellation, so those can be rated too
+  if !matches!(status, TripStatus::Delivered | TripStatus::Cancelled) {
+    return Err(FastJobErrorType::EmployerRatingTripNotFinished.into());
+  }
+
+  let mut tags = Vec::with_capacity(form.tags.len());
+  for tag in &form.tags {
+    if !tags.contains(tag) {
+      tags.push(*tag);
+    }
+  }
+  let mut insert_form = EmployerRatingInsertForm::new(
+    post.id,
+    job_kind,
+    rider.id,
+    post.creator_id,
+    form.rating,
+    tags,
+  );
+  insert_form.comment = form
+    .comment
+    .as_deref()
+    .map(str::trim)
+    .filter(|c| !c.is_empty())
+    .map(str::to_string);
+
+  let (rating, _) = EmployerRating::upsert(&mut context.pool(), &insert_form).await?;
+  Ok(Json(RateEmployerResponse { rating }))
+}
+
+/// GET /api/v4/admin/employers/flagged
+///
+/// Employers whose reliability score from rider ratings fell below the
+/// configured threshold.
+pub async fn admin_list_flagged_employers(
+  query: Query<ListFlaggedEmployersQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListFlaggedEmployersResponse>> {
+  is_admin(&local_user_view)?;
+  let limit = check_fetch_limit(query.limit)?;
+  let config = &context.settings().employer_reliability;
+
+  let employers = EmployerReliability::list_flagged(
+    &mut context.pool(),
+    config.flag_below_score,
+    config.min_ratings,
+    limit,
+  )
+  .await?;
+  Ok(Json(ListFlaggedEmployersResponse { employers }))
+}
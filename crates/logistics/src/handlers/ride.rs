@@ -1,17 +1,26 @@
+use crate::{cancellation::CancelledTrip, offers::cancel_dispatch_for_post};
 use actix_web::web::{Data, Json, Path, Query};
 use app_108jobs_api_utils::{
   context::FastJobContext,
+  fare_quote::FareQuoteClaims,
   utils::{check_fetch_limit, get_active_rider_by_person},
 };
-use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  utils::geo::TrackDistance,
+};
 use app_108jobs_db::{
-  enums::TripStatus,
-  newtypes::RideSessionId,
+  enums::{CancellationParty, DispatchJobKind, MeterCheckStage, PostKind, TripStatus},
+  newtypes::{Coin, RideSessionId},
   source::{
     currency::Currency,
-    pricing_config::PricingConfig,
+    pricing_config::{MeteredFare, PricingConfig},
+    pricing_rule::PriceMultiplier,
+    ride_meter_flag::{RideMeterFlag, RideMeterFlagInsertForm},
     ride_session::{RideSession, RideSessionUpdateForm},
     rider::{Rider, RiderUpdateForm},
+    service_area::ServiceArea,
+    trip_location_history::TripLocationHistory,
   },
   traits::Crud,
 };
@@ -39,7 +48,7 @@ use app_108jobs_db_views_rider::{
   },
   ride_session_view::{project_ride_session, RideViewer},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 /// POST /api/v4/rides/create
 ///
@@ -52,6 +61,7 @@ use chrono::Utc;
 /// - Update passenger contact info
 /// - Update pickup note
 /// - Set payment method
+/// - Pin a fare quote from /rides/quote, capping the fare
 ///
 /// If rider_person_id is provided, the ride will be assigned to that rider directly.
 /// Validation: One rider can only have one active ride.
@@ -73,12 +83,44 @@ pub async fn create_ride_session(
     return Err(FastJobErrorType::NotFound.into());
   }
 
+  // A fare quote pins the pricing config and multiplier and caps the fare
+  let quote = match &form.quote_token {
+    Some(token) => {
+      let claims = FareQuoteClaims::verify(token, employer_id, &context)?;
+      let pickup = existing_session.pickup_lat.zip(existing_session.pickup_lng);
+      let dropoff = existing_session
+        .dropoff_lat
+        .zip(existing_session.dropoff_lng);
+      let tolerance_km = context.settings().fare_quote.location_tolerance_km;
+      if !claims.matches_route(pickup, dropoff, tolerance_km) {
+        return Err(FastJobErrorType::FareQuoteMismatch.into());
+      }
+      Some(claims)
+    }
+    None => None,
+  };
+
+  // Refuse rides outside coverage; the pickup's area may have its own pricing
+  let area = match existing_session.pickup_lat.zip(existing_session.pickup_lng) {
+    Some(pickup) => {
+      let dropoff = existing_session
+        .dropoff_lat
+        .zip(existing_session.dropoff_lng);
+      ServiceArea::validate_trip(&mut context.pool(), pickup, dropoff, None).await?
+    }
+    None => None,
+  };
+
   // Get the pricing config
-  let pricing_config = if let Some(config_id) = form.pricing_config_id {
+  let pricing_config = if let Some(quote) = &quote {
+    PricingConfig::read(&mut context.pool(), quote.pricing_config_id).await?
+  } else if let Some(config_id) = form.pricing_config_id {
     PricingConfig::read(&mut context.pool(), config_id).await?
   } else if let Some(existing_config_id) = existing_session.pricing_config_id {
     // Use existing pricing config if already set
     PricingConfig::read(&mut context.pool(), existing_config_id).await?
+  } else if let Some(area_config_id) = area.as_ref().and_then(|a| a.pricing_config_id) {
+    PricingConfig::read(&mut context.pool(), area_config_id).await?
   } else {
     // Get default currency and its active pricing config
     let currency = Currency::get_default(&mut context.pool())
@@ -89,7 +131,26 @@ pub async fn create_ride_session(
       .ok_or(FastJobErrorType::NotFound)?
   };
 
-  let base_fare = pricing_config.base_fare_coin;
+  // Lock the pricing rule multiplier the first time the ride is priced, so
+  // the meter keeps charging the rate in force when it was requested
+  let price_multiplier = match (&quote, existing_session.price_multiplier) {
+    (Some(quote), _) => quote.price_multiplier,
+    (None, Some(multiplier)) => multiplier,
+    (None, None) => {
+      let pickup = existing_session.pickup_lat.zip(existing_session.pickup_lng);
+      PriceMultiplier::for_ride(
+        &mut context.pool(),
+        pricing_config.id,
+        Utc::now(),
+        pickup,
+        &context.settings().surge_pricing,
+      )
+      .await?
+      .multiplier
+    }
+  };
+
+  let base_fare = (pricing_config.base_fare_coin as f64 * price_multiplier).round() as i32;
 
   // Lookup rider if rider_person_id is provided
   let (rider_id, new_status, rider_assigned_at, rider_to_update) =
@@ -105,12 +166,27 @@ pub async fn create_ride_session(
           None,
         )
       } else {
+        if area
+          .as_ref()
+          .is_some_and(|a| !a.allows_vehicle(Some(rider.vehicle_type)))
+        {
+          return Err(FastJobErrorType::VehicleNotAllowedInServiceArea.into());
+        }
+        if rider.accept_banned(Utc::now()) {
+          return Err(FastJobErrorType::RiderAcceptBanned.into());
+        }
         // Check if rider is accepting jobs (not busy)
         if !rider.accepting_jobs {
           return Err(FastJobErrorType::RiderAlreadyHasActiveRide.into());
         }
         // Check if rider already has an active ride session
-        if RideSession::has_active_session(&mut context.pool(), rider.id).await? {
+        if RideSession::has_active_session(
+          &mut context.pool(),
+          rider.id,
+          &context.settings().scheduling,
+        )
+        .await?
+        {
           return Err(FastJobErrorType::RiderAlreadyHasActiveRide.into());
         }
         (
@@ -147,6 +223,8 @@ pub async fn create_ride_session(
     status: Some(new_status),
     rider_assigned_at: Some(rider_assigned_at),
     current_price_coin: Some(current_price_coin),
+    price_multiplier: Some(Some(price_multiplier)),
+    fare_cap_coin: quote.as_ref().map(|q| Some(q.max_price_coin)),
     updated_at: Some(Some(Utc::now())),
     ..Default::default()
   };
@@ -164,6 +242,10 @@ pub async fn create_ride_session(
 
   // Publish event if rider was assigned
   if rider_assigned_at.is_some() {
+    // Stop any automatic dispatch still offering this ride
+    if let Err(e) = cancel_dispatch_for_post(&context, post_id).await {
+      tracing::warn!(?e, post_id = %post_id, "Failed to cancel dispatch after manual assignment");
+    }
     let event = RideStatusEvent {
       kind: "ride_assigned",
       session_id: session.id,
@@ -295,19 +377,45 @@ pub async fn update_ride_meter(
     .ok_or(FastJobErrorType::NotFound)?;
   let pricing_config = PricingConfig::read(&mut context.pool(), pricing_config_id).await?;
 
-  // Calculate pricing using f64 to avoid integer-division truncation.
-  // Billing interval: charge for each started block of minimum_charge_minutes.
-  let min_block = pricing_config.minimum_charge_minutes as f64;
-  let intervals = if min_block > 0.0 {
-    (elapsed_minutes as f64 / min_block).ceil().max(1.0)
-  } else {
-    1.0
-  };
-  let time_charge_coin =
-    (intervals * pricing_config.time_charge_per_minute_coin as f64).round() as i32;
-  let distance_charge_coin =
-    (distance_km * pricing_config.distance_charge_per_km_coin as f64).round() as i32;
-  let total_coin = pricing_config.base_fare_coin + time_charge_coin + distance_charge_coin;
+  // Compare the reading with the distance and time recorded server-side
+  let cfg = &context.settings().meter_verification;
+  let now = Utc::now();
+  if let Some((track, server_minutes)) = server_meter(&context, &session, now).await? {
+    let diverges = RideMeterFlag::diverges(
+      distance_km,
+      track.distance_km,
+      elapsed_minutes,
+      server_minutes,
+      cfg,
+    );
+    if track.points_used >= 2 && diverges {
+      let mut flag = RideMeterFlagInsertForm::new(
+        session_id,
+        MeterCheckStage::Meter,
+        distance_km,
+        track.distance_km,
+        elapsed_minutes,
+        server_minutes,
+        track.points_used as i32,
+        track.points_dropped as i32,
+      );
+      flag.rejected = Some(cfg.reject_divergent);
+      RideMeterFlag::create(&mut context.pool(), &flag).await?;
+      if cfg.reject_divergent {
+        return Err(FastJobErrorType::MeterReadingRejected.into());
+      }
+    }
+  }
+
+  let (
+    MeteredFare {
+      time_charge_coin,
+      distance_charge_coin,
+      subtotal_coin,
+    },
+    total_coin,
+  ) = ride_fare(&session, &pricing_config, elapsed_minutes, distance_km);
+  let price_multiplier = session.price_multiplier.unwrap_or(1.0);
 
   // Update session
   let update_form = RideSessionUpdateForm {
@@ -327,6 +435,8 @@ pub async fn update_ride_meter(
     base_fare_coin: pricing_config.base_fare_coin,
     time_charge_coin,
     distance_charge_coin,
+    price_multiplier,
+    surcharge_coin: total_coin - subtotal_coin,
     total_coin,
     formatted_price,
     currency_code: currency.code,
@@ -340,7 +450,7 @@ pub async fn update_ride_meter(
     current_price_coin: total_coin,
     elapsed_minutes,
     distance_km,
-    updated_at: updated.updated_at.unwrap_or_else(Utc::now),
+    updated_at: updated.updated_at.unwrap_or(now),
   };
 
   publish_meter_event(&context, &event, session_id).await;
@@ -400,6 +510,7 @@ pub async fn get_ride_pricing_config(
       time_charge_per_minute_coin: pricing_config.time_charge_per_minute_coin,
       minimum_charge_minutes: pricing_config.minimum_charge_minutes,
       distance_charge_per_km_coin: pricing_config.distance_charge_per_km_coin,
+      price_multiplier: session.price_multiplier.unwrap_or(1.0),
       currency_code: currency.code,
       currency_symbol: currency.symbol,
     },
@@ -448,7 +559,12 @@ pub async fn list_available_rides(
   let limit = check_fetch_limit(query.limit)?;
 
   // Get available rides (Pending, no rider assigned)
-  let sessions = RideSession::list_available_for_rider(&mut context.pool(), Some(limit)).await?;
+  let sessions = RideSession::list_available_for_rider(
+    &mut context.pool(),
+    Some(limit),
+    &context.settings().scheduling,
+  )
+  .await?;
 
   // Project to public views (limited info for available rides)
   let rides = sessions
@@ -470,6 +586,11 @@ pub async fn list_available_rides(
 /// Cancel a ride session (employer or rider can cancel).
 /// Only the assigned rider or employer who created the session can cancel.
 /// Reason for cancellation is required.
+///
+/// An employer cancelling after the free period of the ride's cancellation
+/// policy pays the rider the policy's fee from their wallet; if the wallet
+/// cannot cover it the ride stays as it was. A rider cancelling counts
+/// towards a ban on accepting jobs.
 pub async fn cancel_ride_session(
   path: Path<RideSessionId>,
   form: Json<CancelRideSessionRequest>,
@@ -505,16 +626,47 @@ pub async fn cancel_ride_session(
   }
 
   let cancelled_at = Utc::now();
-
-  // Update session status to Cancelled
-  let update_form = RideSessionUpdateForm {
-    status: Some(TripStatus::Cancelled),
-    cancellation_reason: Some(Some(form.reason.clone())),
-    updated_at: Some(Some(cancelled_at)),
-    ..Default::default()
+  let cancelled_trip = session.rider_id.map(|rider_id| CancelledTrip {
+    post_id: session.post_id,
+    job_kind: DispatchJobKind::Ride,
+    post_kind: PostKind::RideTaxi,
+    pricing_config_id: session.pricing_config_id,
+    rider_id,
+    assigned_at: session.rider_assigned_at,
+  });
+  let charge = match &cancelled_trip {
+    Some(trip) => {
+      let mut charge = trip.charge(&context, cancelled_at).await?;
+      // Riders pay no fee; the cancellation counts against them instead
+      if !is_employer {
+        charge.fee_coin = 0;
+      }
+      Some(charge)
+    }
+    None => None,
   };
 
-  RideSession::update(&mut context.pool(), session_id, &update_form).await?;
+  // Cancel and charge the fee together, so a fee the employer cannot pay
+  // fails the cancellation instead of letting it through for free
+  RideSession::cancel_with_rider_fee(
+    &mut context.pool(),
+    session_id,
+    form.reason.clone(),
+    Coin(charge.as_ref().map_or(0, |c| c.fee_coin)),
+    cancelled_at,
+  )
+  .await?;
+
+  if let (Some(trip), Some(charge)) = (&cancelled_trip, charge.as_ref()) {
+    let cancelled_by = if is_employer {
+      CancellationParty::Employer
+    } else {
+      CancellationParty::Rider
+    };
+    trip
+      .record(&context, cancelled_by, charge, Some(form.reason.clone()))
+      .await;
+  }
 
   // Mark rider as available (accepting jobs) if they were assigned
   if let Some(rider_id) = session.rider_id {
@@ -540,6 +692,7 @@ pub async fn cancel_ride_session(
     session_id,
     status: TripStatus::Cancelled,
     cancellation_reason: form.reason.clone(),
+    cancellation_fee_coin: charge.map_or(0, |c| c.fee_coin),
     cancelled_at,
   }))
 }
@@ -612,7 +765,7 @@ pub async fn update_ride_status(
   let now = Utc::now();
 
   // Build update form with appropriate timestamp based on new status
-  let update_form = RideSessionUpdateForm {
+  let mut update_form = RideSessionUpdateForm {
     status: Some(new_status),
     arrived_at_pickup_at: if new_status == TripStatus::EnRouteToPickup {
       Some(Some(now))
@@ -633,6 +786,10 @@ pub async fn update_ride_status(
     ..Default::default()
   };
 
+  if new_status == TripStatus::Delivered {
+    complete_ride_fare(&context, &session, now, &mut update_form).await?;
+  }
+
   let _updated = RideSession::update(&mut context.pool(), session_id, &update_form).await?;
 
   // Mark rider as available (accepting jobs) when ride is delivered
@@ -665,8 +822,111 @@ pub async fn update_ride_status(
   }))
 }
 
+/// The fare for a reading: the metered subtotal times the multiplier locked
+/// in when the ride was requested, capped at the accepted fare quote.
+fn ride_fare(
+  session: &RideSession,
+  pricing_config: &PricingConfig,
+  elapsed_minutes: i32,
+  distance_km: f64,
+) -> (MeteredFare, i32) {
+  let fare = pricing_config.metered_fare(elapsed_minutes, distance_km);
+  let price_multiplier = session.price_multiplier.unwrap_or(1.0);
+  let mut total_coin = (fare.subtotal_coin as f64 * price_multiplier).round() as i32;
+  if let Some(cap) = session.fare_cap_coin {
+    total_coin = total_coin.min(cap);
+  }
+  (fare, total_coin)
+}
+
+/// Distance from the recorded GPS track and minutes elapsed since pickup,
+/// or `None` before the ride has started.
+async fn server_meter(
+  context: &FastJobContext,
+  session: &RideSession,
+  now: DateTime<Utc>,
+) -> FastJobResult<Option<(TrackDistance, i32)>> {
+  let Some(started_at) = session.ride_started_at else {
+    return Ok(None);
+  };
+  let track = TripLocationHistory::track_distance_between(
+    &mut context.pool(),
+    session.post_id,
+    started_at,
+    now,
+    &context.settings().meter_verification,
+  )
+  .await?;
+  let minutes = (now - started_at).num_minutes().max(0) as i32;
+
+  Ok(Some((track, minutes)))
+}
+
+/// Compute the final fare from server-side data when a ride is delivered.
+/// The rider's last meter reading is only used when there is no usable GPS
+/// track, and is flagged for review if it disagrees with the server.
+async fn complete_ride_fare(
+  context: &FastJobContext,
+  session: &RideSession,
+  now: DateTime<Utc>,
+  update_form: &mut RideSessionUpdateForm,
+) -> FastJobResult<()> {
+  let Some(pricing_config_id) = session.pricing_config_id else {
+    return Ok(());
+  };
+  let Some((track, server_minutes)) = server_meter(context, session, now).await? else {
+    return Ok(());
+  };
+  let pricing_config = PricingConfig::read(&mut context.pool(), pricing_config_id).await?;
+
+  let reported_km = session.total_distance_km;
+  let reported_minutes = session.total_duration_minutes;
+  let has_track = track.points_used >= 2;
+  let distance_km = if has_track {
+    track.distance_km
+  } else {
+    reported_km.unwrap_or(0.0)
+  };
+
+  if let (Some(reported_km), Some(reported_minutes)) = (reported_km, reported_minutes) {
+    let cfg = &context.settings().meter_verification;
+    if has_track
+      && RideMeterFlag::diverges(
+        reported_km,
+        distance_km,
+        reported_minutes,
+        server_minutes,
+        cfg,
+      )
+    {
+      let flag = RideMeterFlagInsertForm::new(
+        session.id,
+        MeterCheckStage::Completion,
+        reported_km,
+        distance_km,
+        reported_minutes,
+        server_minutes,
+        track.points_used as i32,
+        track.points_dropped as i32,
+      );
+      RideMeterFlag::create(&mut context.pool(), &flag).await?;
+    }
+  }
+
+  let (fare, total_coin) = ride_fare(session, &pricing_config, server_minutes, distance_km);
+  update_form.current_price_coin = Some(total_coin);
+  update_form.final_price_coin = Some(Some(total_coin));
+  update_form.total_distance_km = Some(Some(distance_km));
+  update_form.total_duration_minutes = Some(Some(server_minutes));
+  update_form.base_fare_applied_coin = Some(Some(pricing_config.base_fare_coin));
+  update_form.time_charge_applied_coin = Some(Some(fare.time_charge_coin));
+  update_form.distance_charge_applied_coin = Some(Some(fare.distance_charge_coin));
+
+  Ok(())
+}
+
 /// Helper function to publish ride status events to Redis
-async fn publish_ride_event(
+pub(crate) async fn publish_ride_event(
   context: &FastJobContext,
   event: &RideStatusEvent,
   session_id: RideSessionId,
@@ -0,0 +1,181 @@
+use crate::{
+  handlers::{ride::publish_ride_event, status::publish_delivery_status},
+  offers::cancel_dispatch_for_post,
+};
+use actix_web::web::{Data, Json, Path, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{check_fetch_limit, get_active_rider_by_person},
+};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::TripStatus,
+  newtypes::{PostId, RideSessionId},
+  source::{
+    cod::CodCollection,
+    delivery_details::{DeliveryDetails, DeliveryDetailsPrivate},
+    ride_session::RideSession,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::{
+  api::{
+    ListScheduledDeliveriesResponse,
+    ListScheduledJobs,
+    ListScheduledRidesResponse,
+    RideSessionResponse,
+    RideStatusEvent,
+  },
+  ride_session_view::{project_ride_session, RideSessionView, RideViewer},
+};
+use chrono::Utc;
+
+/// GET /api/v4/rides/scheduled
+///
+/// Scheduled rides no rider has reserved yet, soonest pickup first.
+pub async fn list_scheduled_rides(
+  query: Query<ListScheduledJobs>,
+  context: Data<FastJobContext>,
+  _local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListScheduledRidesResponse>> {
+  let limit = check_fetch_limit(query.limit)?;
+  let sessions = RideSession::list_scheduled_open(&mut context.pool(), Some(limit)).await?;
+
+  let rides = sessions
+    .iter()
+    .map(|session| project_ride_session(session, RideViewer::Public, Default::default(), false))
+    .filter_map(|view| match view {
+      RideSessionView::Public(public) => Some(public),
+      _ => None,
+    })
+    .collect();
+
+  Ok(Json(ListScheduledRidesResponse { rides }))
+}
+
+/// POST /api/v4/rides/{sessionId}/reserve
+///
+/// Reserve a scheduled ride. The rider then confirms it through
+/// `/rides/{sessionId}/confirm` before the deadline, or it is cancelled.
+pub async fn reserve_scheduled_ride(
+  path: Path<RideSessionId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RideSessionResponse>> {
+  let session_id = path.into_inner();
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  if rider.accept_banned(Utc::now()) {
+    return Err(FastJobErrorType::RiderAcceptBanned.into());
+  }
+
+  let session = RideSession::read(&mut context.pool(), session_id).await?;
+  if session.scheduled_pickup_at.is_none() {
+    return Err(FastJobErrorType::NotAScheduledJob.into());
+  }
+  let session = RideSession::reserve(&mut context.pool(), session_id, rider.id).await?;
+
+  // Stop any automatic dispatch still offering this ride
+  if let Err(e) = cancel_dispatch_for_post(&context, session.post_id).await {
+    tracing::warn!(?e, post_id = %session.post_id, "Failed to cancel dispatch after reservation");
+  }
+  let event = RideStatusEvent {
+    kind: "ride_assigned",
+    session_id: session.id,
+    post_id: session.post_id,
+    status: session.status,
+    updated_at: session.updated_at.unwrap_or_else(Utc::now),
+  };
+  publish_ride_event(&context, &event, session.id).await;
+
+  Ok(Json(RideSessionResponse {
+    id: session.id,
+    post_id: session.post_id,
+    rider_id: session.rider_id,
+    status: session.status,
+    current_price_coin: session.current_price_coin,
+    payment_method: session.payment_method,
+    payment_status: session.payment_status,
+    created_at: session.created_at,
+  }))
+}
+
+/// GET /api/v4/deliveries/scheduled
+///
+/// Scheduled deliveries no rider has reserved yet, soonest pickup first.
+pub async fn list_scheduled_deliveries(
+  query: Query<ListScheduledJobs>,
+  context: Data<FastJobContext>,
+  _local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListScheduledDeliveriesResponse>> {
+  let limit = check_fetch_limit(query.limit)?;
+  let deliveries = DeliveryDetails::list_scheduled_open(&mut context.pool(), Some(limit))
+    .await?
+    .iter()
+    .map(DeliveryDetails::to_public)
+    .collect();
+
+  Ok(Json(ListScheduledDeliveriesResponse { deliveries }))
+}
+
+/// POST /api/v4/deliveries/{postId}/reserve
+///
+/// Reserve a scheduled delivery. The delivery fee is held in escrow now and
+/// refunded if the rider does not confirm before the deadline.
+pub async fn reserve_scheduled_delivery(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryDetailsPrivate>> {
+  let post_id = path.into_inner();
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  if rider.accept_banned(Utc::now()) {
+    return Err(FastJobErrorType::RiderAcceptBanned.into());
+  }
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if delivery.scheduled_pickup_at.is_none() {
+    return Err(FastJobErrorType::NotAScheduledJob.into());
+  }
+  if delivery.status != TripStatus::Pending || delivery.assigned_rider_id.is_some() {
+    return Err(FastJobErrorType::ScheduledJobAlreadyReserved.into());
+  }
+  if delivery.cash_on_delivery
+    && CodCollection::outstanding_for_rider(&mut context.pool(), rider.id).await?
+      >= context.settings().cod.rider_cash_limit_coin
+  {
+    return Err(FastJobErrorType::CodCashLimitExceeded.into());
+  }
+
+  let delivery = DeliveryDetails::reserve_scheduled(&mut context.pool(), post_id, rider.id).await?;
+  if let Err(e) = cancel_dispatch_for_post(&context, post_id).await {
+    tracing::warn!(?e, post_id = %post_id, "Failed to cancel dispatch after reservation");
+  }
+  publish_delivery_status(
+    &context,
+    post_id,
+    delivery.status,
+    delivery.updated_at,
+    None,
+  )
+  .await;
+
+  Ok(Json(delivery.to_private()))
+}
+
+/// POST /api/v4/deliveries/{postId}/reserve/confirm
+///
+/// The reserving rider confirms they will do the scheduled delivery.
+pub async fn confirm_scheduled_delivery(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryDetailsPrivate>> {
+  let post_id = path.into_inner();
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+
+  let delivery =
+    DeliveryDetails::confirm_reservation(&mut context.pool(), post_id, rider.id).await?;
+
+  Ok(Json(delivery.to_private()))
+}
@@ -0,0 +1,124 @@
+use actix_web::web::{Data, Json, Path, Query};
+use app_108jobs_api_utils::{context::FastJobContext, utils::is_admin};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  newtypes::{PricingConfigId, ServiceAreaId},
+  source::{
+    pricing_config::PricingConfig,
+    service_area::{ServiceArea, ServiceAreaInsertForm, ServiceAreaUpdateForm},
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  ImportServiceAreasRequest,
+  ListServiceAreasQuery,
+  ListServiceAreasResponse,
+  ServiceAreaResponse,
+  UpdateServiceAreaRequest,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::Utc;
+
+/// POST /api/v4/admin/service-areas/import
+///
+/// Create service areas from GeoJSON. Once any area is active, rides and
+/// deliveries must start and end inside one.
+pub async fn admin_import_service_areas(
+  data: Json<ImportServiceAreasRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListServiceAreasResponse>> {
+  is_admin(&local_user_view)?;
+  if let Some(config_id) = data.pricing_config_id {
+    check_pricing_config(&context, config_id).await?;
+  }
+
+  let default_name = data
+    .name
+    .as_deref()
+    .map(str::trim)
+    .filter(|n| !n.is_empty())
+    .unwrap_or("Service area");
+  let forms = ServiceAreaInsertForm::from_geojson(&data.geojson, default_name)?;
+
+  let mut service_areas = Vec::with_capacity(forms.len());
+  for mut form in forms {
+    form.pricing_config_id = data.pricing_config_id;
+    form.allowed_vehicle_types = data.allowed_vehicle_types.clone();
+    service_areas.push(ServiceArea::create(&mut context.pool(), &form).await?);
+  }
+
+  Ok(Json(ListServiceAreasResponse { service_areas }))
+}
+
+/// GET /api/v4/admin/service-areas
+pub async fn admin_list_service_areas(
+  query: Query<ListServiceAreasQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListServiceAreasResponse>> {
+  is_admin(&local_user_view)?;
+
+  let service_areas =
+    ServiceArea::list(&mut context.pool(), query.active_only.unwrap_or(false)).await?;
+  Ok(Json(ListServiceAreasResponse { service_areas }))
+}
+
+/// PUT /api/v4/admin/service-areas/{areaId}
+pub async fn admin_update_service_area(
+  path: Path<ServiceAreaId>,
+  data: Json<UpdateServiceAreaRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ServiceAreaResponse>> {
+  is_admin(&local_user_view)?;
+  if let Some(config_id) = data.pricing_config_id {
+    check_pricing_config(&context, config_id).await?;
+  }
+
+  let mut form = ServiceAreaUpdateForm {
+    name: data
+      .name
+      .as_deref()
+      .map(str::trim)
+      .filter(|n| !n.is_empty())
+      .map(str::to_string),
+    pricing_config_id: data.pricing_config_id.map(Some),
+    allowed_vehicle_types: data.allowed_vehicle_types.clone().map(Some),
+    is_active: data.is_active,
+    updated_at: Some(Some(Utc::now())),
+    ..Default::default()
+  };
+  if let Some(geometry) = &data.geometry {
+    form.set_geometry(geometry.clone())?;
+  }
+
+  let service_area = ServiceArea::update(&mut context.pool(), path.into_inner(), &form).await?;
+  Ok(Json(ServiceAreaResponse { service_area }))
+}
+
+/// DELETE /api/v4/admin/service-areas/{areaId}
+///
+/// Pricing rules limited to the area are deleted with it.
+pub async fn admin_delete_service_area(
+  path: Path<ServiceAreaId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let deleted = ServiceArea::delete(&mut context.pool(), path.into_inner()).await?;
+  if deleted == 0 {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  Ok(Json(SuccessResponse { success: true }))
+}
+
+async fn check_pricing_config(
+  context: &FastJobContext,
+  config_id: PricingConfigId,
+) -> FastJobResult<()> {
+  PricingConfig::read(&mut context.pool(), config_id).await?;
+  Ok(())
+}
@@ -0,0 +1,251 @@
+use crate::shifts::claim_shift;
+use actix_web::web::{Data, Json, Path, Query};
+use app_108jobs_api_utils::{
+  context::FastJobContext,
+  utils::{get_active_rider_by_person, is_admin},
+};
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  settings::structs::ShiftConfig,
+};
+use app_108jobs_db::{
+  impls::rider_shift::shift_coverage,
+  newtypes::{RiderShiftId, RiderShiftSlotId},
+  source::{
+    rider_shift::{RiderShift, RiderShiftSlot, RiderShiftSlotInsertForm, RiderShiftSlotUpdateForm},
+    service_area::ServiceArea,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  CreateShiftSlotRequest,
+  ListRiderShiftsResponse,
+  ListShiftSlotsResponse,
+  RiderShiftResponse,
+  ShiftCoverageResponse,
+  ShiftSlotResponse,
+  ShiftWindowQuery,
+  UpdateShiftSlotRequest,
+};
+use app_108jobs_db_views_site::api::SuccessResponse;
+use chrono::{DateTime, Duration, Utc};
+
+/// GET /api/v4/riders/shifts/slots
+///
+/// Slots that have not started yet, with how many places are taken.
+pub async fn list_shift_slots(
+  query: Query<ShiftWindowQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListShiftSlotsResponse>> {
+  get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let (from, to) = shift_window(&query, &context.settings().shifts)?;
+
+  let now = Utc::now();
+  let slots =
+    RiderShiftSlot::list_with_claims(&mut context.pool(), query.service_area_id, from, to)
+      .await?
+      .into_iter()
+      .filter(|s| s.slot.starts_at > now)
+      .collect();
+  Ok(Json(ListShiftSlotsResponse { slots }))
+}
+
+/// POST /api/v4/riders/shifts/slots/{slotId}/claim
+///
+/// Claim a place in a slot. The rider is put online when it starts.
+pub async fn claim_shift_slot(
+  path: Path<RiderShiftSlotId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RiderShiftResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let shift = claim_shift(&context, &rider, path.into_inner()).await?;
+  Ok(Json(RiderShiftResponse { shift }))
+}
+
+/// POST /api/v4/riders/shifts/{shiftId}/release
+///
+/// Give a claimed place back, up to `release_cutoff_minutes` before the
+/// shift starts.
+pub async fn release_shift(
+  path: Path<RiderShiftId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<RiderShiftResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let shift = RiderShift::release(
+    &mut context.pool(),
+    path.into_inner(),
+    rider.id,
+    context.settings().shifts.release_cutoff_minutes,
+  )
+  .await?;
+  Ok(Json(RiderShiftResponse { shift }))
+}
+
+/// GET /api/v4/riders/shifts
+pub async fn list_my_shifts(
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListRiderShiftsResponse>> {
+  let rider = get_active_rider_by_person(&mut context.pool(), local_user_view.person.id).await?;
+  let cfg = &context.settings().shifts;
+  let now = Utc::now();
+
+  let shifts = RiderShift::list_for_rider(&mut context.pool(), rider.id, now).await?;
+  let since = now - Duration::days(cfg.no_show_window_days);
+  let recent_no_shows =
+    RiderShift::count_no_shows_since(&mut context.pool(), rider.id, since).await?;
+  Ok(Json(ListRiderShiftsResponse {
+    shifts,
+    recent_no_shows,
+  }))
+}
+
+/// GET /api/v4/admin/shift-slots
+pub async fn admin_list_shift_slots(
+  query: Query<ShiftWindowQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ListShiftSlotsResponse>> {
+  is_admin(&local_user_view)?;
+  let (from, to) = shift_window(&query, &context.settings().shifts)?;
+
+  let slots =
+    RiderShiftSlot::list_with_claims(&mut context.pool(), query.service_area_id, from, to).await?;
+  Ok(Json(ListShiftSlotsResponse { slots }))
+}
+
+/// POST /api/v4/admin/shift-slots
+pub async fn admin_create_shift_slot(
+  data: Json<CreateShiftSlotRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ShiftSlotResponse>> {
+  is_admin(&local_user_view)?;
+  check_slot(data.starts_at, data.ends_at, data.capacity)?;
+  ServiceArea::read(&mut context.pool(), data.service_area_id).await?;
+
+  let form = RiderShiftSlotInsertForm {
+    note: data.note.clone(),
+    ..RiderShiftSlotInsertForm::new(
+      data.service_area_id,
+      data.starts_at,
+      data.ends_at,
+      data.capacity,
+    )
+  };
+  let slot = RiderShiftSlot::create(&mut context.pool(), &form).await?;
+  Ok(Json(ShiftSlotResponse { slot }))
+}
+
+/// PUT /api/v4/admin/shift-slots/{slotId}
+///
+/// Lowering the capacity keeps the claims already made.
+pub async fn admin_update_shift_slot(
+  path: Path<RiderShiftSlotId>,
+  data: Json<UpdateShiftSlotRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ShiftSlotResponse>> {
+  is_admin(&local_user_view)?;
+  let slot_id = path.into_inner();
+  let current = RiderShiftSlot::read(&mut context.pool(), slot_id).await?;
+  check_slot(
+    data.starts_at.unwrap_or(current.starts_at),
+    data.ends_at.unwrap_or(current.ends_at),
+    data.capacity.unwrap_or(current.capacity),
+  )?;
+
+  let form = RiderShiftSlotUpdateForm {
+    starts_at: data.starts_at,
+    ends_at: data.ends_at,
+    capacity: data.capacity,
+    note: data.note.clone().map(Some),
+    updated_at: Some(Some(Utc::now())),
+  };
+  let slot = RiderShiftSlot::update(&mut context.pool(), slot_id, &form).await?;
+  Ok(Json(ShiftSlotResponse { slot }))
+}
+
+/// DELETE /api/v4/admin/shift-slots/{slotId}
+///
+/// Claims on the slot are deleted with it.
+pub async fn admin_delete_shift_slot(
+  path: Path<RiderShiftSlotId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let deleted = RiderShiftSlot::delete(&mut context.pool(), path.into_inner()).await?;
+  if deleted == 0 {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  Ok(Json(SuccessResponse { success: true }))
+}
+
+/// GET /api/v4/admin/shift-coverage
+///
+/// Planned and actual rider coverage by service area and hour. Without an
+/// area every active area is reported, including hours no slot covers.
+pub async fn admin_get_shift_coverage(
+  query: Query<ShiftWindowQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ShiftCoverageResponse>> {
+  is_admin(&local_user_view)?;
+  let (from, to) = shift_window(&query, &context.settings().shifts)?;
+
+  let area_ids = match query.service_area_id {
+    Some(area_id) => vec![ServiceArea::read(&mut context.pool(), area_id).await?.id],
+    None => ServiceArea::list(&mut context.pool(), true)
+      .await?
+      .into_iter()
+      .map(|a| a.id)
+      .collect(),
+  };
+  let slots =
+    RiderShiftSlot::list_overlapping(&mut context.pool(), query.service_area_id, from, to).await?;
+  let slot_ids: Vec<RiderShiftSlotId> = slots.iter().map(|s| s.id).collect();
+  let shifts = RiderShift::list_for_slots(&mut context.pool(), &slot_ids).await?;
+
+  let hours = shift_coverage(&area_ids, &slots, &shifts, from, to);
+  Ok(Json(ShiftCoverageResponse { hours }))
+}
+
+/// The queried window, the next seven days by default, at most
+/// `max_report_days` long.
+fn shift_window(
+  query: &ShiftWindowQuery,
+  cfg: &ShiftConfig,
+) -> FastJobResult<(DateTime<Utc>, DateTime<Utc>)> {
+  let from = query.from.unwrap_or_else(Utc::now);
+  let to = query.to.unwrap_or(from + Duration::days(7));
+  if to <= from || to - from > Duration::days(cfg.max_report_days) {
+    return Err(
+      FastJobErrorType::InvalidField(format!(
+        "window must end after it starts and span at most {} days",
+        cfg.max_report_days
+      ))
+      .into(),
+    );
+  }
+  Ok((from, to))
+}
+
+fn check_slot(
+  starts_at: DateTime<Utc>,
+  ends_at: DateTime<Utc>,
+  capacity: i32,
+) -> FastJobResult<()> {
+  if ends_at <= starts_at {
+    return Err(FastJobErrorType::InvalidField("slot must end after it starts".to_string()).into());
+  }
+  if capacity <= 0 {
+    return Err(FastJobErrorType::InvalidField("capacity must be positive".to_string()).into());
+  }
+  Ok(())
+}
@@ -1,23 +1,41 @@
+use crate::{
+  cancellation::{CancellationCharge, CancelledTrip},
+  routing::{routing_provider, RoutingProvider},
+};
 use actix_web::web::{Data, Json, Path};
-use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_api_utils::{context::FastJobContext, utils::verify_post_creator};
 use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
-  enums::TripStatus,
-  newtypes::PostId,
-  source::delivery_details::DeliveryDetails,
+  enums::{CancellationParty, TripStatus},
+  newtypes::{PersonId, PostId},
+  source::{
+    cod::CodCollectionInsertForm,
+    delivery_details::DeliveryDetails,
+    delivery_proof::{DeliveryEvidence, DeliveryProof, DeliveryProofInsertForm},
+    delivery_stop::DeliveryStop,
+    images::LocalImage,
+    trip_location_current::TripLocationCurrent,
+  },
+  traits::Crud,
 };
 use app_108jobs_db_views_local_user::LocalUserView;
 use app_108jobs_db_views_rider::api::{
+  TripEta,
   TripStatusEvent,
   TripStatusResponse,
   UpdateTripStatusRequest,
 };
+use chrono::{DateTime, Duration, Utc};
 
 /// PUT /api/v4/deliveries/{postId}/status
 ///
 /// Updates the status of a delivery. The authenticated user must be either:
 /// - The assigned rider for this delivery, or
-/// - An employer
+/// - An admin
+///
+/// A rider or admin cancelling refunds the employer in full. Employers cancel
+/// through `cancel_delivery`, which applies the cancellation policy, so an
+/// admin may not cancel their own delivery here.
 ///
 /// Valid status transitions:
 /// - Pending → Assigned
@@ -26,6 +44,16 @@ use app_108jobs_db_views_rider::api::{
 /// - PickedUp → EnRouteToDropoff
 /// - EnRouteToDropoff → Delivered
 /// - (Any active state) → Cancelled
+///
+/// Marking a cash-on-delivery job Delivered requires `codCollected: true` and
+/// records the cash in the rider's COD ledger.
+///
+/// Marking a delivery Delivered requires the proof its flags ask for: a
+/// photo, the receiver's signature and the receiver's one-time code, which is
+/// issued when the parcel is picked up. Admins may skip the proof.
+///
+/// A multi-stop delivery is marked Delivered when its last stop is done; see
+/// `update_delivery_stop`.
 pub async fn update_delivery_status(
   path: Path<PostId>,
   data: Json<UpdateTripStatusRequest>,
@@ -57,6 +85,19 @@ pub async fn update_delivery_status(
   if !is_admin && !is_rider {
     return Err(FastJobErrorType::NotAnActiveRider.into());
   }
+  if new_status == TripStatus::Cancelled
+    && !is_rider
+    && verify_post_creator(&mut context.pool(), post_id, person_id)
+      .await
+      .is_ok()
+  {
+    return Err(
+      FastJobErrorType::InvalidField(
+        "Employers cancel a delivery through its cancel endpoint".to_string(),
+      )
+      .into(),
+    );
+  }
 
   // Get current delivery to check if status is actually changing
   let current_delivery = {
@@ -67,38 +108,265 @@ pub async fn update_delivery_status(
   // Check if status is actually changing
   if current_delivery.status == new_status {
     // Idempotent - return current state without error
+    let eta = delivery_eta(&context, &current_delivery).await;
     return Ok(Json(TripStatusResponse {
       post_id,
       status: new_status,
       cancellation_reason: current_delivery.cancellation_reason,
       updated_at: current_delivery.updated_at,
+      eta,
     }));
   }
 
-  // Update status — for assigned cancellations, also refund held escrow.
+  // Multi-stop deliveries finish through their stops; only admins may close
+  // one with stops left open.
+  let stops = if new_status == TripStatus::Delivered {
+    DeliveryStop::list_for_post(&mut context.pool(), post_id).await?
+  } else {
+    Vec::new()
+  };
+  if !is_admin && DeliveryStop::next_open(&stops).is_some() {
+    return Err(FastJobErrorType::DeliveryStopsNotFinished.into());
+  }
+
+  if new_status == TripStatus::Delivered
+    && current_delivery.cash_on_delivery
+    && data.cod_collected != Some(true)
+  {
+    return Err(FastJobErrorType::CodCollectionNotConfirmed.into());
+  }
+
+  let proof_form = if new_status == TripStatus::Delivered && stops.is_empty() {
+    delivery_proof_form(&context, &current_delivery, &data, person_id, is_admin).await?
+  } else {
+    None
+  };
+
+  let cod_form = if new_status == TripStatus::Delivered && current_delivery.cash_on_delivery {
+    let cod_amount = current_delivery.cod_amount.unwrap_or(0.0);
+    cod_collection_form(&context, &current_delivery, cod_amount).await?
+  } else {
+    None
+  };
+
+  // Update status — for assigned cancellations, also refund held escrow, and
+  // store the proof and the collected cash along with Delivered.
   let updated_delivery = {
     let mut pool = context.pool();
     if new_status == TripStatus::Cancelled && current_delivery.assigned_rider_id.is_some() {
       DeliveryDetails::cancel_and_refund_escrow(&mut pool, post_id, data.reason.clone()).await?
+    } else if new_status == TripStatus::Delivered {
+      DeliveryDetails::mark_delivered(&mut pool, post_id, proof_form.as_ref(), cod_form.as_ref())
+        .await?
     } else {
       DeliveryDetails::update_status(&mut pool, post_id, new_status, data.reason.clone()).await?
     }
   };
 
+  if new_status == TripStatus::Cancelled {
+    record_delivery_cancellation(&context, &current_delivery, is_rider, data.reason.clone())
+      .await?;
+  }
+  if matches!(
+    new_status,
+    TripStatus::PickedUp | TripStatus::EnRouteToDropoff
+  ) {
+    ensure_delivery_otp(&context, &updated_delivery).await?;
+  }
+
+  let eta = delivery_eta(&context, &updated_delivery).await;
   let response = TripStatusResponse {
     post_id,
     status: new_status,
     cancellation_reason: updated_delivery.cancellation_reason,
     updated_at: updated_delivery.updated_at,
+    eta,
+  };
+
+  publish_delivery_status(
+    &context,
+    post_id,
+    new_status,
+    updated_delivery.updated_at,
+    data.reason.clone(),
+  )
+  .await;
+
+  Ok(Json(response))
+}
+
+/// GET /api/v4/deliveries/{postId}/status
+///
+/// Current status of a delivery with a live ETA to the next stop. Readable
+/// by the employer who posted it, the assigned rider and admins.
+pub async fn get_delivery_status(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<TripStatusResponse>> {
+  let post_id = path.into_inner();
+  let person_id = local_user_view.person.id;
+
+  if !local_user_view.local_user.admin
+    && verify_post_creator(&mut context.pool(), post_id, person_id)
+      .await
+      .is_err()
+  {
+    DeliveryDetails::validate_rider_identity(&mut context.pool(), person_id, post_id).await?;
+  }
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  let eta = delivery_eta(&context, &delivery).await;
+
+  Ok(Json(TripStatusResponse {
+    post_id,
+    status: delivery.status,
+    cancellation_reason: delivery.cancellation_reason,
+    updated_at: delivery.updated_at,
+    eta,
+  }))
+}
+
+/// ETA from the rider's last known location to the pickup, or to the next
+/// stop or the dropoff once the parcel is collected. `None` when no rider is
+/// on the way or their location is unknown; an ETA is never worth failing a
+/// request.
+async fn delivery_eta(context: &FastJobContext, delivery: &DeliveryDetails) -> Option<TripEta> {
+  let (target, lat, lng) = match delivery.status {
+    TripStatus::Assigned | TripStatus::RiderConfirmed | TripStatus::EnRouteToPickup => {
+      ("pickup", delivery.pickup_lat?, delivery.pickup_lng?)
+    }
+    TripStatus::PickedUp | TripStatus::EnRouteToDropoff => {
+      let stops = DeliveryStop::list_for_post(&mut context.pool(), delivery.post_id)
+        .await
+        .ok()?;
+      match DeliveryStop::next_open(&stops) {
+        Some(stop) => ("stop", stop.lat, stop.lng),
+        None => ("dropoff", delivery.dropoff_lat?, delivery.dropoff_lng?),
+      }
+    }
+    _ => return None,
   };
+  let location = TripLocationCurrent::read(&mut context.pool(), delivery.post_id)
+    .await
+    .ok()?;
+
+  let route = routing_provider(context)
+    .route((location.lat, location.lng), (lat, lng))
+    .await
+    .ok()?;
+  let travel = Duration::seconds((route.duration_minutes * 60.0).round() as i64);
 
-  // Publish status change event to Redis for WebSocket listeners
+  Some(TripEta {
+    target,
+    distance_km: route.distance_km,
+    duration_minutes: route.duration_minutes,
+    arrive_at: Utc::now() + travel,
+    estimated: route.estimated,
+    location_updated_at: location.updated_at,
+  })
+}
+
+/// Check the evidence sent with `Delivered` and build the proof to store.
+/// Images must have been uploaded by the caller.
+async fn delivery_proof_form(
+  context: &FastJobContext,
+  delivery: &DeliveryDetails,
+  data: &UpdateTripStatusRequest,
+  person_id: PersonId,
+  is_admin: bool,
+) -> FastJobResult<Option<DeliveryProofInsertForm>> {
+  let evidence = DeliveryEvidence {
+    photo_url: data.photo_url.clone(),
+    signature_url: data.signature_url.clone(),
+    otp: data.otp.clone(),
+  };
+  if !is_admin {
+    delivery.check_delivery_evidence(&evidence)?;
+  }
+
+  check_proof_images(context, &evidence, person_id).await?;
+
+  if delivery.requires_otp && !is_admin {
+    let otp = evidence.otp.as_deref().unwrap_or_default();
+    DeliveryProof::verify_otp(&mut context.pool(), delivery.post_id, otp).await?;
+  }
+
+  let Some(rider_id) = delivery.assigned_rider_id else {
+    return Ok(None);
+  };
+  let location = TripLocationCurrent::read(&mut context.pool(), delivery.post_id)
+    .await
+    .ok();
+
+  Ok(Some(DeliveryProofInsertForm {
+    photo_url: evidence.photo_url,
+    signature_url: evidence.signature_url,
+    delivered_lat: location.as_ref().map(|l| l.lat),
+    delivered_lng: location.as_ref().map(|l| l.lng),
+    submitted_at: Some(Utc::now()),
+    ..DeliveryProofInsertForm::new(delivery.post_id, rider_id)
+  }))
+}
+
+/// Proof images must have been uploaded by the caller.
+pub(crate) async fn check_proof_images(
+  context: &FastJobContext,
+  evidence: &DeliveryEvidence,
+  person_id: PersonId,
+) -> FastJobResult<()> {
+  for url in [&evidence.photo_url, &evidence.signature_url]
+    .into_iter()
+    .flatten()
+  {
+    let alias = url.as_str().split('/').next_back().unwrap_or_default();
+    LocalImage::validate_by_alias_and_user(&mut context.pool(), alias, person_id)
+      .await
+      .map_err(|_| {
+        FastJobErrorType::InvalidField("proof images must be uploaded by the rider".to_string())
+      })?;
+  }
+  Ok(())
+}
+
+/// Record a rider or admin cancelling an assigned delivery. The employer is
+/// refunded in full; a rider's cancellation counts towards an accept ban.
+async fn record_delivery_cancellation(
+  context: &FastJobContext,
+  delivery: &DeliveryDetails,
+  is_rider: bool,
+  reason: Option<String>,
+) -> FastJobResult<()> {
+  let Some(trip) = CancelledTrip::for_delivery(delivery) else {
+    return Ok(());
+  };
+  let charge = CancellationCharge {
+    fee_coin: 0,
+    ..trip.charge(context, Utc::now()).await?
+  };
+  let cancelled_by = if is_rider {
+    CancellationParty::Rider
+  } else {
+    CancellationParty::Admin
+  };
+  trip.record(context, cancelled_by, &charge, reason).await;
+  Ok(())
+}
+
+/// Publish a delivery status change to Redis for WebSocket listeners.
+pub(crate) async fn publish_delivery_status(
+  context: &FastJobContext,
+  post_id: PostId,
+  status: TripStatus,
+  updated_at: DateTime<Utc>,
+  reason: Option<String>,
+) {
   let event = TripStatusEvent {
     kind: "delivery_status_update",
     post_id,
-    status: new_status,
-    updated_at: updated_delivery.updated_at,
-    reason: data.reason.clone(),
+    status,
+    updated_at,
+    reason,
   };
 
   if let Ok(json) = serde_json::to_string(&event) {
@@ -112,6 +380,49 @@ pub async fn update_delivery_status(
       );
     }
   }
+}
 
-  Ok(Json(response))
+/// Issue the receiver code once the parcel is on its way, unless one exists.
+async fn ensure_delivery_otp(
+  context: &FastJobContext,
+  delivery: &DeliveryDetails,
+) -> FastJobResult<()> {
+  let Some(rider_id) = delivery.assigned_rider_id.filter(|_| delivery.requires_otp) else {
+    return Ok(());
+  };
+  let existing = DeliveryProof::get_by_post(&mut context.pool(), delivery.post_id).await?;
+  if existing.is_some_and(|p| p.otp_code.is_some()) {
+    return Ok(());
+  }
+  DeliveryProof::issue_otp(&mut context.pool(), delivery.post_id, rider_id).await?;
+  Ok(())
+}
+
+/// The entry for the rider's COD ledger of the cash collected on a delivery,
+/// or `None` when the amount is worth no coins.
+pub(crate) async fn cod_collection_form(
+  context: &FastJobContext,
+  delivery: &DeliveryDetails,
+  cod_amount: f64,
+) -> FastJobResult<Option<CodCollectionInsertForm>> {
+  let rider_id = delivery
+    .assigned_rider_id
+    .ok_or(FastJobErrorType::NoRiderAssigned)?;
+  let employer_person_id = delivery
+    .assigned_by_person_id
+    .ok_or(FastJobErrorType::NotFound)?;
+  let employer = LocalUserView::read_person(&mut context.pool(), employer_person_id).await?;
+
+  let amount_coin = (cod_amount * context.settings().cod.coins_per_cash_unit).round() as i32;
+  if amount_coin <= 0 {
+    return Ok(None);
+  }
+
+  Ok(Some(CodCollectionInsertForm::new(
+    delivery.post_id,
+    rider_id,
+    employer.local_user.id,
+    cod_amount,
+    amount_coin,
+  )))
 }
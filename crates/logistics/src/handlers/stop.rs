@@ -0,0 +1,289 @@
+use crate::{
+  handlers::{
+    quote::trip_pricing,
+    status::{check_proof_images, cod_collection_form, publish_delivery_status},
+  },
+  routing::{routing_provider, RoutingProvider},
+};
+use actix_web::web::{Data, Json, Path};
+use app_108jobs_api_utils::{context::FastJobContext, utils::verify_post_creator};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::{DeliveryStopStatus, TripStatus},
+  newtypes::{Coin, DeliveryStopId, PostId},
+  source::{
+    delivery_details::DeliveryDetails,
+    delivery_proof::DeliveryEvidence,
+    delivery_stop::{DeliveryStop, DeliveryStopInsertForm, DeliveryStopUpdateForm},
+    service_area::ServiceArea,
+    trip_location_current::TripLocationCurrent,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  DeliveryStopsResponse,
+  SetDeliveryStopsRequest,
+  UpdateDeliveryStopRequest,
+  UpdateDeliveryStopResponse,
+};
+use chrono::Utc;
+
+/// PUT /api/v4/deliveries/{postId}/stops
+///
+/// Set the drop-offs of a delivery, in visiting order, before a rider is
+/// assigned. Each leg is routed from the previous stop, or the pickup, and
+/// priced with the pricing config of the pickup's service area; the post
+/// budget becomes the total. The delivery's dropoff becomes the last stop and
+/// its cash on delivery the sum over the stops.
+pub async fn set_delivery_stops(
+  path: Path<PostId>,
+  data: Json<SetDeliveryStopsRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryStopsResponse>> {
+  let post_id = path.into_inner();
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if data.stops.is_empty() {
+    let stops = DeliveryStop::replace_for_post(&mut context.pool(), post_id, Vec::new()).await?;
+    return Ok(Json(stops_response(post_id, stops)));
+  }
+  if delivery.requires_otp {
+    return Err(
+      FastJobErrorType::InvalidField(
+        "the receiver code is not available for multi-stop deliveries".to_string(),
+      )
+      .into(),
+    );
+  }
+  let pickup = match (delivery.pickup_lat, delivery.pickup_lng) {
+    (Some(lat), Some(lng)) => (lat, lng),
+    _ => {
+      return Err(
+        FastJobErrorType::InvalidField("multi-stop deliveries need pickup coordinates".to_string())
+          .into(),
+      )
+    }
+  };
+
+  let mut area = None;
+  for stop in &data.stops {
+    let valid_coordinates =
+      (-90.0..=90.0).contains(&stop.lat) && (-180.0..=180.0).contains(&stop.lng);
+    if stop.address.trim().is_empty()
+      || !valid_coordinates
+      || stop.cod_amount.is_some_and(|a| !a.is_finite() || a < 0.0)
+    {
+      return Err(FastJobErrorType::InvalidDeliveryStops.into());
+    }
+    area = ServiceArea::validate_trip(
+      &mut context.pool(),
+      pickup,
+      Some((stop.lat, stop.lng)),
+      delivery.vehicle_required,
+    )
+    .await?;
+  }
+  let (_, pricing_config) = trip_pricing(&context, area, None).await?;
+
+  let router = routing_provider(&context);
+  let mut legs = Vec::with_capacity(data.stops.len());
+  let mut from = pickup;
+  for stop in &data.stops {
+    let to = (stop.lat, stop.lng);
+    let route = router.route(from, to).await?;
+    legs.push((route.distance_km, route.duration_minutes.ceil() as i32));
+    from = to;
+  }
+  let fees = DeliveryStop::leg_fees(&pricing_config, &legs);
+
+  let forms = data
+    .stops
+    .iter()
+    .zip(legs.iter().zip(fees))
+    .enumerate()
+    .map(
+      |(i, (stop, (&(distance_km, _), fee)))| DeliveryStopInsertForm {
+        contact_name: stop.contact_name.clone(),
+        contact_phone: stop.contact_phone.clone(),
+        cod_amount: stop.cod_amount.filter(|a| *a > 0.0),
+        leg_distance_km: Some(distance_km),
+        leg_fee: Some(fee),
+        ..DeliveryStopInsertForm::new(
+          post_id,
+          i as i32 + 1,
+          stop.address.trim().to_string(),
+          stop.lat,
+          stop.lng,
+        )
+      },
+    )
+    .collect();
+
+  let stops = DeliveryStop::replace_for_post(&mut context.pool(), post_id, forms).await?;
+  Ok(Json(stops_response(post_id, stops)))
+}
+
+/// GET /api/v4/deliveries/{postId}/stops
+///
+/// Readable by the employer, the assigned rider and admins.
+pub async fn get_delivery_stops(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<DeliveryStopsResponse>> {
+  let post_id = path.into_inner();
+  let person_id = local_user_view.person.id;
+
+  if !local_user_view.local_user.admin
+    && verify_post_creator(&mut context.pool(), post_id, person_id)
+      .await
+      .is_err()
+  {
+    DeliveryDetails::validate_rider_identity(&mut context.pool(), person_id, post_id).await?;
+  }
+
+  let stops = DeliveryStop::list_for_post(&mut context.pool(), post_id).await?;
+  Ok(Json(stops_response(post_id, stops)))
+}
+
+/// PUT /api/v4/deliveries/{postId}/stops/{stopId}
+///
+/// The assigned rider works through the stops in order once the parcels are
+/// picked up: `Arrived`, then `Completed` with proof or `Skipped` with a
+/// reason. When no stop is left the delivery is marked Delivered and the
+/// cash collected at the completed stops goes to the rider's COD ledger.
+pub async fn update_delivery_stop(
+  path: Path<(PostId, DeliveryStopId)>,
+  data: Json<UpdateDeliveryStopRequest>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<UpdateDeliveryStopResponse>> {
+  let (post_id, stop_id) = path.into_inner();
+  let person_id = local_user_view.person.id;
+
+  let is_admin = local_user_view.local_user.admin;
+  if !is_admin {
+    DeliveryDetails::validate_rider_identity(&mut context.pool(), person_id, post_id).await?;
+  }
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if !matches!(
+    delivery.status,
+    TripStatus::PickedUp | TripStatus::EnRouteToDropoff
+  ) {
+    return Err(FastJobErrorType::DeliveryIsNotActive.into());
+  }
+  let stops = DeliveryStop::list_for_post(&mut context.pool(), post_id).await?;
+  let stop = stops
+    .iter()
+    .find(|s| s.id == stop_id)
+    .ok_or(FastJobErrorType::NotFound)?;
+
+  let now = Utc::now();
+  let mut form = DeliveryStopUpdateForm {
+    status: Some(data.status),
+    updated_at: Some(Some(now)),
+    ..Default::default()
+  };
+  match data.status {
+    DeliveryStopStatus::Pending => {
+      return Err(
+        FastJobErrorType::InvalidField("a stop cannot go back to Pending".to_string()).into(),
+      )
+    }
+    DeliveryStopStatus::Arrived => form.arrived_at = Some(Some(now)),
+    DeliveryStopStatus::Completed => {
+      let evidence = DeliveryEvidence {
+        photo_url: data.photo_url.clone(),
+        signature_url: data.signature_url.clone(),
+        otp: None,
+      };
+      if !is_admin {
+        DeliveryStop::check_evidence(&delivery, &evidence)?;
+      }
+      check_proof_images(&context, &evidence, person_id).await?;
+      if stop.cod_amount.is_some() && data.cod_collected != Some(true) {
+        return Err(FastJobErrorType::CodCollectionNotConfirmed.into());
+      }
+      form.photo_url = Some(evidence.photo_url);
+      form.signature_url = Some(evidence.signature_url);
+    }
+    DeliveryStopStatus::Skipped => {
+      let reason = data
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .ok_or(FastJobErrorType::DeliveryStopSkipReasonRequired)?;
+      form.skip_reason = Some(Some(reason.to_string()));
+    }
+  }
+  if data.status != DeliveryStopStatus::Arrived {
+    let location = TripLocationCurrent::read(&mut context.pool(), post_id)
+      .await
+      .ok();
+    form.completed_lat = Some(location.as_ref().map(|l| l.lat));
+    form.completed_lng = Some(location.as_ref().map(|l| l.lng));
+    form.completed_at = Some(Some(now));
+  }
+
+  // The cash on record if this update finishes the delivery: stops are done
+  // in order, so every other stop is finished by then
+  let finishes = data.status != DeliveryStopStatus::Arrived
+    && stops.iter().all(|s| s.id == stop_id || s.is_finished());
+  let cod_amount: f64 = stops
+    .iter()
+    .filter(|s| {
+      if s.id == stop_id {
+        data.status == DeliveryStopStatus::Completed
+      } else {
+        s.status == DeliveryStopStatus::Completed
+      }
+    })
+    .filter_map(|s| s.cod_amount)
+    .sum();
+  let cod_form = if finishes && cod_amount > 0.0 {
+    cod_collection_form(&context, &delivery, cod_amount).await?
+  } else {
+    None
+  };
+
+  let previous_status = delivery.status;
+  let (stop, delivery) = DeliveryStop::advance_delivery(
+    &mut context.pool(),
+    post_id,
+    stop_id,
+    &form,
+    cod_form.as_ref(),
+  )
+  .await?;
+  if delivery.status != previous_status {
+    publish_delivery_status(
+      &context,
+      post_id,
+      delivery.status,
+      delivery.updated_at,
+      None,
+    )
+    .await;
+  }
+
+  Ok(Json(UpdateDeliveryStopResponse {
+    stop,
+    delivery_status: delivery.status,
+  }))
+}
+
+fn stops_response(post_id: PostId, stops: Vec<DeliveryStop>) -> DeliveryStopsResponse {
+  let total_fee = Coin(stops.iter().map(|s| s.leg_fee.0).sum());
+  let next_stop_id = DeliveryStop::next_open(&stops).map(|s| s.id);
+  DeliveryStopsResponse {
+    post_id,
+    stops,
+    total_fee,
+    next_stop_id,
+  }
+}
@@ -0,0 +1,297 @@
+use actix_web::{
+  http::header::{ContentDisposition, DispositionParam, DispositionType},
+  web::{Data, Path, Query},
+  HttpResponse,
+};
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  utils::geo::{encode_polyline, simplify_polyline, track_distance, TrackPoint},
+};
+use app_108jobs_db::{
+  enums::TripStatus,
+  newtypes::{PostId, RideSessionId, RiderId},
+  source::{
+    delivery_details::DeliveryDetails,
+    post::Post,
+    ride_session::RideSession,
+    rider::Rider,
+    trip_location_history::TripLocationHistory,
+    trip_status_history::TripStatusHistory,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{TripRouteResponse, TripTrackFormat, TripTrackQuery};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value as JsonValue};
+use std::fmt::Write;
+
+/// Fixes closer than this to the simplified route are dropped from it.
+const SIMPLIFY_TOLERANCE_M: f64 = 10.0;
+
+/// A trip whose recorded track is being exported.
+struct Trip {
+  post_id: PostId,
+  status: TripStatus,
+  rider_id: Option<RiderId>,
+  created_at: DateTime<Utc>,
+  /// Whether the current user booked the trip
+  is_employer: bool,
+}
+
+/// GET /api/v4/deliveries/{postId}/track?format=geojson|gpx|polyline
+///
+/// Export a delivery's recorded track with a marker for every status change.
+/// Open to admins and the assigned rider, and to the employer once the
+/// delivery is finished.
+pub async fn export_delivery_track(
+  path: Path<PostId>,
+  query: Query<TripTrackQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<HttpResponse> {
+  let post_id = path.into_inner();
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  let post = Post::read(&mut context.pool(), post_id).await?;
+
+  let trip = Trip {
+    post_id,
+    status: delivery.status,
+    rider_id: delivery.assigned_rider_id,
+    created_at: delivery.created_at,
+    is_employer: post.creator_id == local_user_view.person.id,
+  };
+  export_track(
+    &context,
+    &local_user_view,
+    &trip,
+    query.format.unwrap_or_default(),
+  )
+  .await
+}
+
+/// GET /api/v4/rides/{sessionId}/track?format=geojson|gpx|polyline
+///
+/// Export a ride's recorded track with a marker for every status change.
+/// Open to admins and the rider, and to the passenger once the ride is
+/// finished.
+pub async fn export_ride_track(
+  path: Path<RideSessionId>,
+  query: Query<TripTrackQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<HttpResponse> {
+  let session = RideSession::read(&mut context.pool(), path.into_inner()).await?;
+
+  let trip = Trip {
+    post_id: session.post_id,
+    status: session.status,
+    rider_id: session.rider_id,
+    created_at: session.created_at,
+    is_employer: session.employer_id == local_user_view.local_user.id,
+  };
+  export_track(
+    &context,
+    &local_user_view,
+    &trip,
+    query.format.unwrap_or_default(),
+  )
+  .await
+}
+
+async fn export_track(
+  context: &FastJobContext,
+  local_user_view: &LocalUserView,
+  trip: &Trip,
+  format: TripTrackFormat,
+) -> FastJobResult<HttpResponse> {
+  check_track_access(context, local_user_view, trip).await?;
+
+  let points = TripLocationHistory::list_for_post_between(
+    &mut context.pool(),
+    trip.post_id,
+    trip.created_at,
+    Utc::now(),
+  )
+  .await?;
+  let status_changes = TripStatusHistory::list_for_post(&mut context.pool(), trip.post_id).await?;
+
+  let response = match format {
+    TripTrackFormat::Geojson => HttpResponse::Ok()
+      .insert_header(attachment(trip.post_id, "geojson"))
+      .content_type("application/geo+json")
+      .body(track_geojson(trip.post_id, &points, &status_changes).to_string()),
+    TripTrackFormat::Gpx => HttpResponse::Ok()
+      .insert_header(attachment(trip.post_id, "gpx"))
+      .content_type("application/gpx+xml")
+      .body(track_gpx(trip.post_id, &points, &status_changes)),
+    TripTrackFormat::Polyline => {
+      let track: Vec<TrackPoint> = points
+        .iter()
+        .map(TripLocationHistory::track_point)
+        .collect();
+      let config = &context.settings().meter_verification;
+      let distance = track_distance(&track, config.max_speed_kmh, config.max_accuracy_m);
+      let route: Vec<(f64, f64)> = points.iter().map(|p| (p.lat, p.lng)).collect();
+
+      HttpResponse::Ok().json(TripRouteResponse {
+        post_id: trip.post_id,
+        polyline: encode_polyline(&simplify_polyline(&route, SIMPLIFY_TOLERANCE_M)),
+        points_recorded: points.len(),
+        distance_km: distance.distance_km,
+        status_changes,
+      })
+    }
+  };
+  Ok(response)
+}
+
+/// Admins can always see a track, as can the rider assigned to the trip.
+/// The employer has to wait until the trip is delivered or cancelled.
+async fn check_track_access(
+  context: &FastJobContext,
+  local_user_view: &LocalUserView,
+  trip: &Trip,
+) -> FastJobResult<()> {
+  if local_user_view.local_user.admin {
+    return Ok(());
+  }
+  if trip.is_employer {
+    return if matches!(trip.status, TripStatus::Delivered | TripStatus::Cancelled) {
+      Ok(())
+    } else {
+      Err(FastJobErrorType::TripTrackNotAvailableYet.into())
+    };
+  }
+
+  let rider =
+    Rider::get_by_person_id_with_inactive(&mut context.pool(), local_user_view.person.id).await?;
+  match (rider, trip.rider_id) {
+    (Some(rider), Some(rider_id)) if rider.id == rider_id => Ok(()),
+    _ => Err(FastJobErrorType::NotFound.into()),
+  }
+}
+
+fn attachment(post_id: PostId, extension: &str) -> ContentDisposition {
+  ContentDisposition {
+    disposition: DispositionType::Attachment,
+    parameters: vec![DispositionParam::Filename(format!(
+      "trip-{}.{}",
+      post_id.0, extension
+    ))],
+  }
+}
+
+/// Where the rider was when the status changed: the last fix at or before
+/// the change, else the first fix after it.
+fn marker_point(
+  points: &[TripLocationHistory],
+  changed_at: DateTime<Utc>,
+) -> Option<&TripLocationHistory> {
+  points
+    .iter()
+    .rev()
+    .find(|p| p.recorded_at <= changed_at)
+    .or_else(|| points.first())
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+  at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// A LineString of the full track, with per-fix times in `coordTimes` for
+/// replay, followed by one Point per status change.
+fn track_geojson(
+  post_id: PostId,
+  points: &[TripLocationHistory],
+  status_changes: &[TripStatusHistory],
+) -> JsonValue {
+  let mut features = vec![json!({
+    "type": "Feature",
+    "geometry": {
+      "type": "LineString",
+      "coordinates": points.iter().map(|p| [p.lng, p.lat]).collect::<Vec<_>>(),
+    },
+    "properties": {
+      "postId": post_id,
+      "coordTimes": points.iter().map(|p| timestamp(p.recorded_at)).collect::<Vec<_>>(),
+      "speedsKmh": points.iter().map(|p| p.speed_kmh).collect::<Vec<_>>(),
+      "headings": points.iter().map(|p| p.heading).collect::<Vec<_>>(),
+    },
+  })];
+
+  for change in status_changes {
+    let geometry = marker_point(points, change.changed_at)
+      .map(|p| json!({ "type": "Point", "coordinates": [p.lng, p.lat] }));
+    features.push(json!({
+      "type": "Feature",
+      "geometry": geometry,
+      "properties": {
+        "status": change.status,
+        "reason": change.reason,
+        "changedAt": timestamp(change.changed_at),
+      },
+    }));
+  }
+
+  json!({ "type": "FeatureCollection", "features": features })
+}
+
+/// A GPX 1.1 document with a waypoint per status change and one track
+/// segment holding every fix.
+fn track_gpx(
+  post_id: PostId,
+  points: &[TripLocationHistory],
+  status_changes: &[TripStatusHistory],
+) -> String {
+  let mut gpx = String::from(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+     <gpx version=\"1.1\" creator=\"108jobs\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+  );
+
+  // Writing to a String cannot fail
+  for change in status_changes {
+    let Some(p) = marker_point(points, change.changed_at) else {
+      continue;
+    };
+    let _ = writeln!(
+      gpx,
+      "  <wpt lat=\"{}\" lon=\"{}\"><time>{}</time><name>{}</name>{}</wpt>",
+      p.lat,
+      p.lng,
+      timestamp(change.changed_at),
+      change.status,
+      change
+        .reason
+        .as_deref()
+        .map(|r| format!("<desc>{}</desc>", escape_xml(r)))
+        .unwrap_or_default(),
+    );
+  }
+
+  let _ = writeln!(
+    gpx,
+    "  <trk>\n    <name>Trip {}</name>\n    <trkseg>",
+    post_id.0
+  );
+  for p in points {
+    let _ = writeln!(
+      gpx,
+      "      <trkpt lat=\"{}\" lon=\"{}\"><time>{}</time></trkpt>",
+      p.lat,
+      p.lng,
+      timestamp(p.recorded_at),
+    );
+  }
+  gpx.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+  gpx
+}
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
@@ -0,0 +1,265 @@
+use crate::routing::{routing_provider, RoutingProvider};
+use actix_web::{
+  http::header::{CacheControl, CacheDirective},
+  rt::time::sleep,
+  web::{Bytes, Data, Json, Path},
+  HttpResponse,
+};
+use app_108jobs_api_utils::{context::FastJobContext, utils::verify_post_creator};
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  enums::{DispatchJobKind, TripStatus},
+  newtypes::{PostId, RideSessionId},
+  source::{
+    delivery_details::DeliveryDetails,
+    person::Person,
+    ride_session::RideSession,
+    rider::Rider,
+    tracking_link::TrackingLink,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{
+  PublicTrackingLocation,
+  PublicTrackingView,
+  TrackingLinkResponse,
+};
+use chrono::{Duration, Utc};
+use std::convert::Infallible;
+
+/// POST /api/v4/deliveries/{postId}/tracking-link
+///
+/// Create a public link the employer can send to the receiver. Any earlier
+/// link for the delivery stops working.
+pub async fn create_delivery_tracking_link(
+  path: Path<PostId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<TrackingLinkResponse>> {
+  let post_id = path.into_inner();
+  verify_post_creator(&mut context.pool(), post_id, local_user_view.person.id).await?;
+
+  let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), post_id).await?;
+  if is_finished(delivery.status) {
+    return Err(FastJobErrorType::TrackingLinkTripFinished.into());
+  }
+  create_link(&context, post_id, DispatchJobKind::Delivery).await
+}
+
+/// POST /api/v4/rides/{sessionId}/tracking-link
+///
+/// Create a public link the employer can send to the passenger. Any earlier
+/// link for the ride stops working.
+pub async fn create_ride_tracking_link(
+  path: Path<RideSessionId>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<TrackingLinkResponse>> {
+  let session = RideSession::read(&mut context.pool(), path.into_inner()).await?;
+  if session.employer_id != local_user_view.local_user.id {
+    return Err(FastJobErrorType::NotFound.into());
+  }
+  if is_finished(session.status) {
+    return Err(FastJobErrorType::TrackingLinkTripFinished.into());
+  }
+  create_link(&context, session.post_id, DispatchJobKind::Ride).await
+}
+
+/// GET /api/v4/track/{token}
+///
+/// Public, read-only view of a trip for whoever holds the link.
+pub async fn get_public_tracking(
+  path: Path<String>,
+  context: Data<FastJobContext>,
+) -> FastJobResult<Json<PublicTrackingView>> {
+  let link = TrackingLink::read_active(&mut context.pool(), &path).await?;
+
+  Ok(Json(tracking_view(&context, &link, None).await?))
+}
+
+/// GET /api/v4/track/{token}/events
+///
+/// Server-sent events with the public view of a trip, sent whenever the
+/// rider's location or the trip status changes. Once the link is revoked or
+/// expires a `closed` event is sent and the stream ends.
+pub async fn stream_public_tracking(
+  path: Path<String>,
+  context: Data<FastJobContext>,
+) -> FastJobResult<HttpResponse> {
+  let token = path.into_inner();
+  // Refuse a dead link up front rather than opening an empty stream
+  TrackingLink::read_active(&mut context.pool(), &token).await?;
+  let interval = std::time::Duration::from_secs(
+    context
+      .settings()
+      .tracking_link
+      .stream_interval_seconds
+      .max(1),
+  );
+
+  let state = TrackingStream {
+    context: context.into_inner(),
+    token,
+    last: None,
+    started: false,
+  };
+  let events = futures::stream::unfold(Some(state), move |state| async move {
+    let mut state = state?;
+    loop {
+      if state.started {
+        sleep(interval).await;
+      }
+      state.started = true;
+
+      let Ok(link) = TrackingLink::read_active(&mut state.context.pool(), &state.token).await
+      else {
+        let closed = Bytes::from_static(b"event: closed\ndata: {}\n\n");
+        return Some((Ok::<_, Infallible>(closed), None));
+      };
+      let view = match tracking_view(&state.context, &link, state.last.as_ref()).await {
+        Ok(view) => view,
+        Err(e) => {
+          tracing::warn!(?e, post_id = %link.post_id, "Failed to build public tracking view");
+          continue;
+        }
+      };
+      if state.last.as_ref() == Some(&view) {
+        continue;
+      }
+      let Ok(json) = serde_json::to_string(&view) else {
+        continue;
+      };
+      state.last = Some(view);
+      return Some((Ok(Bytes::from(format!("data: {json}\n\n"))), Some(state)));
+    }
+  });
+
+  Ok(
+    HttpResponse::Ok()
+      .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+      .content_type("text/event-stream")
+      .streaming(events),
+  )
+}
+
+struct TrackingStream {
+  context: std::sync::Arc<FastJobContext>,
+  token: String,
+  /// The view last sent, so unchanged views are not repeated
+  last: Option<PublicTrackingView>,
+  started: bool,
+}
+
+fn is_finished(status: TripStatus) -> bool {
+  matches!(status, TripStatus::Delivered | TripStatus::Cancelled)
+}
+
+async fn create_link(
+  context: &FastJobContext,
+  post_id: PostId,
+  job_kind: DispatchJobKind,
+) -> FastJobResult<Json<TrackingLinkResponse>> {
+  let expires_at = Utc::now() + Duration::hours(context.settings().tracking_link.ttl_hours);
+  let link =
+    TrackingLink::create_for_post(&mut context.pool(), post_id, job_kind, expires_at).await?;
+
+  Ok(Json(TrackingLinkResponse {
+    url: format!(
+      "{}/track/{}",
+      context.settings().get_protocol_and_hostname(),
+      link.token
+    ),
+    token: link.token,
+    expires_at: link.expires_at,
+  }))
+}
+
+/// Build the public view of a linked trip. The ETA is only recomputed when
+/// the location or status differs from `previous`.
+async fn tracking_view(
+  context: &FastJobContext,
+  link: &TrackingLink,
+  previous: Option<&PublicTrackingView>,
+) -> FastJobResult<PublicTrackingView> {
+  let (status, rider_id, pickup, dropoff) = match link.job_kind {
+    DispatchJobKind::Delivery => {
+      let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), link.post_id).await?;
+      (
+        delivery.status,
+        delivery.assigned_rider_id,
+        delivery.pickup_lat.zip(delivery.pickup_lng),
+        delivery.dropoff_lat.zip(delivery.dropoff_lng),
+      )
+    }
+    DispatchJobKind::Ride => {
+      let session = RideSession::get_by_post(&mut context.pool(), link.post_id)
+        .await?
+        .ok_or(FastJobErrorType::NotFound)?;
+      (
+        session.status,
+        session.rider_id,
+        session.pickup_lat.zip(session.pickup_lng),
+        session.dropoff_lat.zip(session.dropoff_lng),
+      )
+    }
+  };
+
+  let rider = match rider_id {
+    Some(rider_id) => Some(Rider::read(&mut context.pool(), rider_id).await?),
+    None => None,
+  };
+  let rider_first_name = match &rider {
+    Some(rider) => {
+      let person = Person::read(&mut context.pool(), rider.person_id).await?;
+      person
+        .display_name
+        .as_deref()
+        .unwrap_or(&person.name)
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+    }
+    None => None,
+  };
+
+  let location = match &rider {
+    Some(_) => context
+      .redis()
+      .clone()
+      .get_value::<PublicTrackingLocation>(&format!("trip:current:{}", link.post_id))
+      .await
+      .ok()
+      .flatten(),
+    None => None,
+  };
+  let target = match status {
+    TripStatus::Assigned | TripStatus::RiderConfirmed | TripStatus::EnRouteToPickup => pickup,
+    TripStatus::PickedUp | TripStatus::EnRouteToDropoff => dropoff,
+    _ => None,
+  };
+  let eta_minutes = match (&location, target) {
+    (Some(location), Some(target)) => {
+      match previous.filter(|p| p.status == status && p.location.as_ref() == Some(location)) {
+        Some(previous) => previous.eta_minutes,
+        None => routing_provider(context)
+          .route((location.lat, location.lng), target)
+          .await
+          .ok()
+          .map(|route| route.duration_minutes.round()),
+      }
+    }
+    _ => None,
+  };
+
+  Ok(PublicTrackingView {
+    job_kind: link.job_kind,
+    status,
+    rider_first_name,
+    vehicle_type: rider.as_ref().map(|r| r.vehicle_type),
+    vehicle_plate: rider.and_then(|r| r.vehicle_plate_number),
+    location,
+    eta_minutes,
+    expires_at: link.expires_at,
+  })
+}
@@ -0,0 +1,231 @@
+//! Incidents raised during a trip.
+//!
+//! The rider or the employer of an active delivery or ride can raise an
+//! incident, from a bare SOS to a report of an accident, harassment or a
+//! stolen package. It is recorded with the rider's last known location,
+//! pushed to every admin connected over the WebSocket and emailed to the
+//! admins. On request, a live tracking link is shared with the reporter's
+//! emergency contact.
+
+use actix_broker::{Broker, SystemBroker};
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  spawn_try_task,
+};
+use app_108jobs_db::{
+  enums::{DispatchJobKind, TripStatus},
+  newtypes::{DbUrl, PersonId, PostId, RiderId},
+  source::{
+    emergency_contact::EmergencyContact,
+    images::LocalImage,
+    tracking_link::TrackingLink,
+    trip_incident::{TripIncident, TripIncidentInsertForm, TripIncidentUpdateForm},
+    trip_location_current::TripLocationCurrent,
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_rider::api::{IncidentResponse, ReportIncidentRequest};
+use app_108jobs_email::{
+  admin::send_new_incident_email_to_admins,
+  notifications::send_emergency_contact_email,
+};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use tracing::warn;
+
+const MAX_INCIDENT_PHOTOS: usize = 10;
+
+/// A new incident for the WebSocket sessions of admins. It is issued on the
+/// system broker; the payload is the `TripIncident`.
+#[derive(actix::Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct IncidentAlert {
+  pub payload: Value,
+}
+
+/// The trip an incident is raised on.
+pub struct IncidentTrip {
+  pub post_id: PostId,
+  pub job_kind: DispatchJobKind,
+  pub status: TripStatus,
+  pub rider_id: Option<RiderId>,
+}
+
+impl IncidentTrip {
+  /// A rider is on the trip and it is not over yet.
+  fn is_active(&self) -> bool {
+    self.rider_id.is_some()
+      && !matches!(
+        self.status,
+        TripStatus::Pending | TripStatus::Delivered | TripStatus::Cancelled
+      )
+  }
+}
+
+/// Record an incident raised by the trip's rider or employer and alert the
+/// admins.
+pub async fn raise_incident(
+  context: &FastJobContext,
+  reporter: &LocalUserView,
+  trip: IncidentTrip,
+  reported_by_rider: bool,
+  data: &ReportIncidentRequest,
+) -> FastJobResult<IncidentResponse> {
+  if !trip.is_active() {
+    return Err(FastJobErrorType::IncidentTripNotActive.into());
+  }
+  let photo_urls = data.photo_urls.clone().unwrap_or_default();
+  check_incident_photos(context, &photo_urls, reporter.person.id).await?;
+
+  let location = TripLocationCurrent::read(&mut context.pool(), trip.post_id)
+    .await
+    .ok();
+  let form = TripIncidentInsertForm {
+    rider_id: trip.rider_id,
+    notes: data
+      .notes
+      .as_deref()
+      .map(str::trim)
+      .filter(|n| !n.is_empty())
+      .map(str::to_string),
+    photo_urls,
+    lat: location.as_ref().map(|l| l.lat),
+    lng: location.as_ref().map(|l| l.lng),
+    located_at: location.as_ref().map(|l| l.updated_at),
+    ..TripIncidentInsertForm::new(
+      trip.post_id,
+      trip.job_kind,
+      reporter.person.id,
+      reported_by_rider,
+      data.kind.unwrap_or_default(),
+    )
+  };
+  let mut incident = TripIncident::create(&mut context.pool(), &form).await?;
+  alert_admins(context, &incident, &reporter.person.name);
+
+  // The incident stands even when the link cannot be shared
+  let mut tracking_url = None;
+  if data.share_with_emergency_contact == Some(true) {
+    match share_with_contact(context, reporter, &incident).await {
+      Ok(Some((shared, url))) => {
+        incident = shared;
+        tracking_url = Some(url);
+      }
+      Ok(None) => {}
+      Err(e) => warn!(
+        ?e,
+        incident_id = incident.id.0,
+        "Failed to share incident with contact"
+      ),
+    }
+  }
+
+  Ok(IncidentResponse {
+    incident,
+    tracking_url,
+  })
+}
+
+/// Incident photos must have been uploaded by the reporter.
+async fn check_incident_photos(
+  context: &FastJobContext,
+  photo_urls: &[DbUrl],
+  person_id: PersonId,
+) -> FastJobResult<()> {
+  if photo_urls.len() > MAX_INCIDENT_PHOTOS {
+    return Err(
+      FastJobErrorType::InvalidField(format!(
+        "at most {MAX_INCIDENT_PHOTOS} photos can be attached"
+      ))
+      .into(),
+    );
+  }
+  for url in photo_urls {
+    let alias = url.as_str().split('/').next_back().unwrap_or_default();
+    LocalImage::validate_by_alias_and_user(&mut context.pool(), alias, person_id)
+      .await
+      .map_err(|_| {
+        FastJobErrorType::InvalidField(
+          "incident photos must be uploaded by the reporter".to_string(),
+        )
+      })?;
+  }
+  Ok(())
+}
+
+/// Push the incident to connected admins and email every admin.
+fn alert_admins(context: &FastJobContext, incident: &TripIncident, reporter_name: &str) {
+  match serde_json::to_value(incident) {
+    Ok(payload) => Broker::<SystemBroker>::issue_async(IncidentAlert { payload }),
+    Err(e) => warn!(?e, "Failed to serialize incident alert"),
+  }
+
+  let context = context.clone();
+  let reporter_name = reporter_name.to_string();
+  let kind = incident.kind.to_string();
+  let location = match incident.lat.zip(incident.lng) {
+    Some((lat, lng)) => format!("{lat:.5}, {lng:.5}"),
+    None => "unknown".to_string(),
+  };
+  spawn_try_task(async move {
+    send_new_incident_email_to_admins(
+      &reporter_name,
+      &kind,
+      &location,
+      &mut context.pool(),
+      context.settings(),
+    )
+    .await
+  });
+}
+
+/// Share a live tracking link for the trip with the reporter's emergency
+/// contact. The link is emailed when the contact has an address; the app
+/// gets it back to pass on by phone. Returns `None` without a contact.
+async fn share_with_contact(
+  context: &FastJobContext,
+  reporter: &LocalUserView,
+  incident: &TripIncident,
+) -> FastJobResult<Option<(TripIncident, String)>> {
+  let Some(contact) =
+    EmergencyContact::read_for_user(&mut context.pool(), reporter.local_user.id).await?
+  else {
+    return Ok(None);
+  };
+
+  let now = Utc::now();
+  let expires_at = now + Duration::hours(context.settings().tracking_link.ttl_hours);
+  let link = TrackingLink::create_additional(
+    &mut context.pool(),
+    incident.post_id,
+    incident.job_kind,
+    expires_at,
+  )
+  .await?;
+  let url = format!(
+    "{}/track/{}",
+    context.settings().get_protocol_and_hostname(),
+    link.token
+  );
+
+  let form = TripIncidentUpdateForm {
+    tracking_link_id: Some(Some(link.id)),
+    contact_notified_at: Some(Some(now)),
+    updated_at: Some(Some(now)),
+    ..Default::default()
+  };
+  let incident = TripIncident::update(&mut context.pool(), incident.id, &form).await?;
+
+  if let Some(email) = contact.email {
+    let context = context.clone();
+    let reporter = reporter.clone();
+    let name = contact.name;
+    let url = url.clone();
+    spawn_try_task(async move {
+      send_emergency_contact_email(&reporter, &name, &email, &url, context.settings()).await
+    });
+  }
+  Ok(Some((incident, url)))
+}
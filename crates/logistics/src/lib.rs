@@ -1,2 +1,11 @@
+pub mod batching;
+pub mod cancellation;
 pub mod crud;
+pub mod documents;
 pub mod handlers;
+pub mod incidents;
+pub mod offers;
+pub mod routing;
+pub mod scheduling;
+pub mod shifts;
+pub mod trip_history;
@@ -0,0 +1,275 @@
+//! Job offers pushed to riders over the WebSocket.
+//!
+//! With `require_presence`, a dispatch wave only goes to riders the presence
+//! manager counts as connected. Every new offer is pushed to the rider's
+//! offers topic, and riders still holding an offer are told once another
+//! rider took the job or the search stopped. Riders answer over the
+//! WebSocket or over HTTP; an accept ends in [`accept_for_rider`] either way.
+
+use crate::handlers::ride::publish_ride_event;
+use actix_broker::{Broker, SystemBroker};
+use app_108jobs_api_utils::{context::FastJobContext, utils::publish_dispatch_offers};
+use app_108jobs_core::{
+  error::{FastJobErrorType, FastJobResult},
+  redis::RedisClient,
+  utils::keys::presence_conn_count_key,
+};
+use app_108jobs_db::{
+  enums::{DispatchJobKind, DispatchOfferStatus, TripStatus},
+  newtypes::{DispatchOfferId, DispatchRequestId, LocalUserId, PostId},
+  source::{
+    cod::CodCollection,
+    delivery_details::DeliveryDetails,
+    dispatch::{DispatchOffer, DispatchPresence, DispatchRequest},
+    ride_session::RideSession,
+    rider::Rider,
+  },
+};
+use app_108jobs_db_views_rider::api::{
+  AcceptDispatchOfferResponse,
+  DeliveryAssignmentEvent,
+  DispatchOfferTakenEvent,
+  RideStatusEvent,
+};
+use chrono::Utc;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+/// What a [`RiderOfferPush`] tells the rider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiderOfferPushKind {
+  /// A new offer; the payload is its `DispatchOfferView`
+  Offer,
+  /// An offer that can no longer be accepted; the payload is a
+  /// `DispatchOfferTakenEvent`
+  Taken,
+}
+
+/// An offer event for the WebSocket sessions of one rider. It is issued on
+/// the system broker and each session forwards the ones for its own user.
+#[derive(actix::Message, Debug, Clone)]
+#[rtype(result = "()")]
+pub struct RiderOfferPush {
+  pub local_user_id: LocalUserId,
+  pub kind: RiderOfferPushKind,
+  pub payload: Value,
+}
+
+/// Riders the WebSocket presence manager counts as connected. Without
+/// `require_presence` every rider counts as connected.
+pub struct RiderPresence {
+  redis: Option<RedisClient>,
+}
+
+impl DispatchPresence for RiderPresence {
+  /// All riders are looked up in one MGET. A failed lookup counts them as
+  /// connected, so a Redis hiccup does not stall dispatch.
+  fn online<'a>(&'a self, users: &'a [LocalUserId]) -> BoxFuture<'a, Vec<bool>> {
+    Box::pin(async move {
+      let Some(redis) = &self.redis else {
+        return vec![true; users.len()];
+      };
+      let keys: Vec<String> = users
+        .iter()
+        .map(|user| presence_conn_count_key(user.0))
+        .collect();
+      match redis.clone().get_values::<i64>(&keys).await {
+        Ok(connections) => connections
+          .into_iter()
+          .map(|c| c.unwrap_or(0) > 0)
+          .collect(),
+        Err(e) => {
+          warn!(?e, riders = users.len(), "Failed to read rider presence");
+          vec![true; users.len()]
+        }
+      }
+    })
+  }
+}
+
+/// The presence check configured for this instance.
+pub fn rider_presence(context: &FastJobContext) -> RiderPresence {
+  RiderPresence {
+    redis: context
+      .settings()
+      .dispatch
+      .require_presence
+      .then(|| context.redis().clone()),
+  }
+}
+
+/// Tell riders about new offers, on Redis and over the WebSocket.
+pub async fn push_dispatch_offers(context: &FastJobContext, offers: &[DispatchOffer]) {
+  if offers.is_empty() {
+    return;
+  }
+  publish_dispatch_offers(context, offers).await;
+
+  let offer_ids: Vec<DispatchOfferId> = offers.iter().map(|o| o.id).collect();
+  match DispatchOffer::list_recipients(&mut context.pool(), &offer_ids).await {
+    Ok(recipients) => {
+      for (view, local_user_id) in recipients {
+        issue_push(local_user_id, RiderOfferPushKind::Offer, &view);
+      }
+    }
+    Err(e) => warn!(?e, "Failed to load dispatch offer recipients"),
+  }
+}
+
+/// Tell the riders whose offers were just superseded that they can no
+/// longer accept. Offers past their expiry are skipped; the rider's app
+/// already dropped them.
+pub async fn push_offers_taken(context: &FastJobContext, request_id: DispatchRequestId) {
+  let now = Utc::now();
+  let offer_ids: Vec<DispatchOfferId> =
+    match DispatchOffer::list_for_request(&mut context.pool(), request_id).await {
+      Ok(offers) => offers
+        .into_iter()
+        .filter(|o| o.status == DispatchOfferStatus::Superseded && o.expires_at > now)
+        .map(|o| o.id)
+        .collect(),
+      Err(e) => {
+        warn!(
+          ?e,
+          request_id = request_id.0,
+          "Failed to load superseded dispatch offers"
+        );
+        return;
+      }
+    };
+  if offer_ids.is_empty() {
+    return;
+  }
+
+  match DispatchOffer::list_recipients(&mut context.pool(), &offer_ids).await {
+    Ok(recipients) => {
+      for (view, local_user_id) in recipients {
+        let event = DispatchOfferTakenEvent {
+          offer_id: view.offer.id,
+          post_id: view.post_id,
+          job_kind: view.job_kind,
+        };
+        issue_push(local_user_id, RiderOfferPushKind::Taken, &event);
+      }
+    }
+    Err(e) => warn!(
+      ?e,
+      request_id = request_id.0,
+      "Failed to load dispatch offer recipients"
+    ),
+  }
+}
+
+/// Stop any search still offering the job, e.g. because a rider was
+/// assigned another way, and withdraw its open offers.
+pub async fn cancel_dispatch_for_post(
+  context: &FastJobContext,
+  post_id: PostId,
+) -> FastJobResult<Option<DispatchRequest>> {
+  let cancelled = DispatchRequest::cancel_for_post(&mut context.pool(), post_id).await?;
+  if let Some(dispatch) = &cancelled {
+    push_offers_taken(context, dispatch.id).await;
+  }
+  Ok(cancelled)
+}
+
+/// Accept a job offer for the rider. Only the first rider to accept is
+/// assigned; later accepts fail with `DispatchOfferNoLongerAvailable`.
+pub async fn accept_for_rider(
+  context: &FastJobContext,
+  rider: &Rider,
+  offer_id: DispatchOfferId,
+) -> FastJobResult<AcceptDispatchOfferResponse> {
+  if rider.accept_banned(Utc::now()) {
+    return Err(FastJobErrorType::RiderAcceptBanned.into());
+  }
+
+  let view = DispatchOffer::list_open_for_rider(&mut context.pool(), rider.id)
+    .await?
+    .into_iter()
+    .find(|v| v.offer.id == offer_id)
+    .ok_or(FastJobErrorType::DispatchOfferNoLongerAvailable)?;
+
+  // Same eligibility rules as a manual assignment
+  match view.job_kind {
+    DispatchJobKind::Delivery => {
+      let delivery = DeliveryDetails::get_by_post_id(&mut context.pool(), view.post_id).await?;
+      if delivery.cash_on_delivery
+        && CodCollection::outstanding_for_rider(&mut context.pool(), rider.id).await?
+          >= context.settings().cod.rider_cash_limit_coin
+      {
+        return Err(FastJobErrorType::CodCashLimitExceeded.into());
+      }
+    }
+    DispatchJobKind::Ride => {
+      if RideSession::has_active_session(
+        &mut context.pool(),
+        rider.id,
+        &context.settings().scheduling,
+      )
+      .await?
+      {
+        return Err(FastJobErrorType::RiderAlreadyHasActiveRide.into());
+      }
+    }
+  }
+
+  let dispatch = DispatchRequest::accept_offer(&mut context.pool(), offer_id, rider.id).await?;
+  let assigned_at = dispatch.updated_at.unwrap_or_else(Utc::now);
+
+  match dispatch.job_kind {
+    DispatchJobKind::Delivery => {
+      let event = DeliveryAssignmentEvent {
+        kind: "delivery_assigned",
+        post_id: dispatch.post_id,
+        rider_id: rider.id,
+        assigned_at,
+        status: TripStatus::Assigned,
+      };
+      if let Ok(json) = serde_json::to_string(&event) {
+        let channel = format!("delivery:{}", dispatch.post_id);
+        let mut redis = context.redis().clone();
+        if let Err(e) = redis.publish(&channel, &json).await {
+          warn!(
+            ?e,
+            post_id = %dispatch.post_id,
+            rider_id = %rider.id,
+            "Failed to publish delivery assignment event to Redis"
+          );
+        }
+      }
+    }
+    DispatchJobKind::Ride => {
+      let session = RideSession::get_by_post(&mut context.pool(), dispatch.post_id).await?;
+      if let Some(session) = session {
+        let event = RideStatusEvent {
+          kind: "ride_assigned",
+          session_id: session.id,
+          post_id: session.post_id,
+          status: session.status,
+          updated_at: assigned_at,
+        };
+        publish_ride_event(context, &event, session.id).await;
+      }
+    }
+  }
+  push_offers_taken(context, dispatch.id).await;
+
+  Ok(AcceptDispatchOfferResponse {
+    post_id: dispatch.post_id,
+    job_kind: dispatch.job_kind,
+  })
+}
+
+fn issue_push<T: Serialize>(local_user_id: LocalUserId, kind: RiderOfferPushKind, payload: &T) {
+  match serde_json::to_value(payload) {
+    Ok(payload) => Broker::<SystemBroker>::issue_async(RiderOfferPush {
+      local_user_id,
+      kind,
+      payload,
+    }),
+    Err(e) => warn!(?e, "Failed to serialize rider offer push"),
+  }
+}
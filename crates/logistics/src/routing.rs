@@ -0,0 +1,456 @@
+//! Route distance, duration and geometry between two points.
+//!
+//! `routing_provider` asks a self-hosted OSRM server when one is configured
+//! and falls back to the straight line scaled by a road factor when it is not
+//! or when the server fails to answer.
+
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::{
+  error::{FastJobErrorExt, FastJobErrorType, FastJobResult},
+  settings::structs::RoutingConfig,
+  utils::geo::{encode_polyline, haversine_km},
+};
+use app_108jobs_db::source::dispatch::DispatchDistances;
+use futures::future::BoxFuture;
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
+use url::Url;
+
+/// A route between two `(lat, lng)` points.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+  pub distance_km: f64,
+  pub duration_minutes: f64,
+  /// Encoded polyline of the route geometry, five decimal places
+  pub polyline: String,
+  /// Whether this is a straight-line estimate rather than a road route
+  pub estimated: bool,
+}
+
+/// Travel between every pair of points, indexed `[from][to]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TravelMatrix {
+  pub distances_km: Vec<Vec<f64>>,
+  pub durations_minutes: Vec<Vec<f64>>,
+  /// Whether these are straight-line estimates rather than road routes
+  pub estimated: bool,
+}
+
+pub trait RoutingProvider: Send + Sync {
+  fn route<'a>(&'a self, from: (f64, f64), to: (f64, f64)) -> BoxFuture<'a, FastJobResult<Route>>;
+
+  /// Road distances in kilometres from each origin to `to`, in order. An
+  /// entry is `None` if the provider found no route for it.
+  fn distances_to<'a>(
+    &'a self,
+    origins: &'a [(f64, f64)],
+    to: (f64, f64),
+  ) -> BoxFuture<'a, FastJobResult<Vec<Option<f64>>>>;
+
+  /// Distances and durations between every pair of `points`.
+  fn matrix<'a>(&'a self, points: &'a [(f64, f64)]) -> BoxFuture<'a, FastJobResult<TravelMatrix>>;
+}
+
+/// Straight-line distance times a road factor, at a fixed average speed.
+pub struct StraightLineRouter {
+  pub road_distance_factor: f64,
+  pub average_speed_kmh: f64,
+}
+
+impl StraightLineRouter {
+  fn distance_km(&self, from: (f64, f64), to: (f64, f64)) -> f64 {
+    haversine_km(from.0, from.1, to.0, to.1) * self.road_distance_factor
+  }
+
+  fn estimate_matrix(&self, points: &[(f64, f64)]) -> TravelMatrix {
+    let distances_km: Vec<Vec<f64>> = points
+      .iter()
+      .map(|&from| {
+        points
+          .iter()
+          .map(|&to| self.distance_km(from, to))
+          .collect()
+      })
+      .collect();
+    let speed = self.average_speed_kmh.max(1.0);
+    let durations_minutes = distances_km
+      .iter()
+      .map(|row| row.iter().map(|km| km / speed * 60.0).collect())
+      .collect();
+    TravelMatrix {
+      distances_km,
+      durations_minutes,
+      estimated: true,
+    }
+  }
+
+  fn estimate(&self, from: (f64, f64), to: (f64, f64)) -> Route {
+    let distance_km = self.distance_km(from, to);
+    Route {
+      distance_km,
+      duration_minutes: distance_km / self.average_speed_kmh.max(1.0) * 60.0,
+      polyline: encode_polyline(&[from, to]),
+      estimated: true,
+    }
+  }
+}
+
+impl RoutingProvider for StraightLineRouter {
+  fn route<'a>(&'a self, from: (f64, f64), to: (f64, f64)) -> BoxFuture<'a, FastJobResult<Route>> {
+    Box::pin(std::future::ready(Ok(self.estimate(from, to))))
+  }
+
+  fn distances_to<'a>(
+    &'a self,
+    origins: &'a [(f64, f64)],
+    to: (f64, f64),
+  ) -> BoxFuture<'a, FastJobResult<Vec<Option<f64>>>> {
+    let distances = origins
+      .iter()
+      .map(|&from| Some(self.distance_km(from, to)))
+      .collect();
+    Box::pin(std::future::ready(Ok(distances)))
+  }
+
+  fn matrix<'a>(&'a self, points: &'a [(f64, f64)]) -> BoxFuture<'a, FastJobResult<TravelMatrix>> {
+    Box::pin(std::future::ready(Ok(self.estimate_matrix(points))))
+  }
+}
+
+/// Client for the OSRM HTTP API (`/route/v1` and `/table/v1`).
+pub struct OsrmRouter {
+  client: ClientWithMiddleware,
+  base_url: Url,
+  profile: String,
+  timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct OsrmRouteResponse {
+  code: String,
+  #[serde(default)]
+  routes: Vec<OsrmRoute>,
+}
+
+#[derive(Deserialize)]
+struct OsrmRoute {
+  /// Metres
+  distance: f64,
+  /// Seconds
+  duration: f64,
+  geometry: String,
+}
+
+#[derive(Deserialize)]
+struct OsrmTableResponse {
+  code: String,
+  /// Metres
+  #[serde(default)]
+  distances: Vec<Vec<Option<f64>>>,
+  /// Seconds
+  #[serde(default)]
+  durations: Vec<Vec<Option<f64>>>,
+}
+
+impl OsrmRouter {
+  pub fn new(
+    client: ClientWithMiddleware,
+    base_url: Url,
+    profile: String,
+    timeout: Duration,
+  ) -> Self {
+    Self {
+      client,
+      base_url,
+      profile,
+      timeout,
+    }
+  }
+
+  /// `{base}/{service}/v1/{profile}/{lng,lat;...}`; OSRM wants longitude first.
+  fn url(&self, service: &str, points: &[(f64, f64)]) -> String {
+    let coordinates = points
+      .iter()
+      .map(|(lat, lng)| format!("{lng:.6},{lat:.6}"))
+      .collect::<Vec<_>>()
+      .join(";");
+    format!(
+      "{}/{service}/v1/{}/{coordinates}",
+      self.base_url.as_str().trim_end_matches('/'),
+      self.profile
+    )
+  }
+
+  async fn get<T: DeserializeOwned>(
+    &self,
+    url: String,
+    query: &[(&str, &str)],
+  ) -> FastJobResult<T> {
+    self
+      .client
+      .get(url)
+      .query(query)
+      .timeout(self.timeout)
+      .send()
+      .await
+      .with_fastjob_type(FastJobErrorType::RoutingFailed)?
+      .error_for_status()
+      .with_fastjob_type(FastJobErrorType::RoutingFailed)?
+      .json::<T>()
+      .await
+      .with_fastjob_type(FastJobErrorType::RoutingFailed)
+  }
+}
+
+impl RoutingProvider for OsrmRouter {
+  fn route<'a>(&'a self, from: (f64, f64), to: (f64, f64)) -> BoxFuture<'a, FastJobResult<Route>> {
+    Box::pin(async move {
+      let url = self.url("route", &[from, to]);
+      let response: OsrmRouteResponse = self
+        .get(url, &[("overview", "full"), ("geometries", "polyline")])
+        .await?;
+      if response.code != "Ok" {
+        return Err(FastJobErrorType::RoutingFailed.into());
+      }
+      let route = response
+        .routes
+        .into_iter()
+        .next()
+        .ok_or(FastJobErrorType::RoutingFailed)?;
+
+      Ok(Route {
+        distance_km: route.distance / 1000.0,
+        duration_minutes: route.duration / 60.0,
+        polyline: route.geometry,
+        estimated: false,
+      })
+    })
+  }
+
+  fn distances_to<'a>(
+    &'a self,
+    origins: &'a [(f64, f64)],
+    to: (f64, f64),
+  ) -> BoxFuture<'a, FastJobResult<Vec<Option<f64>>>> {
+    Box::pin(async move {
+      if origins.is_empty() {
+        return Ok(Vec::new());
+      }
+      let mut points = origins.to_vec();
+      points.push(to);
+      let sources = (0..origins.len())
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(";");
+      let destination = origins.len().to_string();
+
+      let url = self.url("table", &points);
+      let response: OsrmTableResponse = self
+        .get(
+          url,
+          &[
+            ("sources", sources.as_str()),
+            ("destinations", destination.as_str()),
+            ("annotations", "distance"),
+          ],
+        )
+        .await?;
+      if response.code != "Ok" || response.distances.len() != origins.len() {
+        return Err(FastJobErrorType::RoutingFailed.into());
+      }
+
+      Ok(
+        response
+          .distances
+          .into_iter()
+          .map(|row| row.first().copied().flatten().map(|m| m / 1000.0))
+          .collect(),
+      )
+    })
+  }
+
+  fn matrix<'a>(&'a self, points: &'a [(f64, f64)]) -> BoxFuture<'a, FastJobResult<TravelMatrix>> {
+    Box::pin(async move {
+      if points.is_empty() {
+        return Ok(TravelMatrix {
+          distances_km: Vec::new(),
+          durations_minutes: Vec::new(),
+          estimated: false,
+        });
+      }
+      let url = self.url("table", points);
+      let response: OsrmTableResponse = self
+        .get(url, &[("annotations", "distance,duration")])
+        .await?;
+      if response.code != "Ok" {
+        return Err(FastJobErrorType::RoutingFailed.into());
+      }
+
+      // Every pair must have a route
+      let complete = |rows: Vec<Vec<Option<f64>>>, scale: f64| -> Option<Vec<Vec<f64>>> {
+        if rows.len() != points.len() {
+          return None;
+        }
+        rows
+          .into_iter()
+          .map(|row| {
+            if row.len() != points.len() {
+              return None;
+            }
+            row.into_iter().map(|v| v.map(|v| v / scale)).collect()
+          })
+          .collect()
+      };
+      Ok(TravelMatrix {
+        distances_km: complete(response.distances, 1000.0)
+          .ok_or(FastJobErrorType::RoutingFailed)?,
+        durations_minutes: complete(response.durations, 60.0)
+          .ok_or(FastJobErrorType::RoutingFailed)?,
+        estimated: false,
+      })
+    })
+  }
+}
+
+/// OSRM when configured, with the straight line as a fallback. Never fails.
+pub struct FallbackRouter {
+  primary: Option<OsrmRouter>,
+  fallback: StraightLineRouter,
+}
+
+impl FallbackRouter {
+  pub fn new(config: &RoutingConfig, client: ClientWithMiddleware) -> Self {
+    let primary = config.osrm_url.clone().map(|url| {
+      OsrmRouter::new(
+        client,
+        url,
+        config.osrm_profile.clone(),
+        Duration::from_millis(config.request_timeout_ms),
+      )
+    });
+    Self {
+      primary,
+      fallback: StraightLineRouter {
+        road_distance_factor: config.road_distance_factor,
+        average_speed_kmh: config.average_speed_kmh,
+      },
+    }
+  }
+}
+
+impl RoutingProvider for FallbackRouter {
+  fn route<'a>(&'a self, from: (f64, f64), to: (f64, f64)) -> BoxFuture<'a, FastJobResult<Route>> {
+    Box::pin(async move {
+      if let Some(primary) = &self.primary {
+        match primary.route(from, to).await {
+          Ok(route) => return Ok(route),
+          Err(e) => warn!(?e, "OSRM route failed, using straight-line estimate"),
+        }
+      }
+      Ok(self.fallback.estimate(from, to))
+    })
+  }
+
+  fn distances_to<'a>(
+    &'a self,
+    origins: &'a [(f64, f64)],
+    to: (f64, f64),
+  ) -> BoxFuture<'a, FastJobResult<Vec<Option<f64>>>> {
+    Box::pin(async move {
+      if let Some(primary) = &self.primary {
+        match primary.distances_to(origins, to).await {
+          Ok(distances) => return Ok(distances),
+          Err(e) => warn!(?e, "OSRM table failed, using straight-line estimate"),
+        }
+      }
+      self.fallback.distances_to(origins, to).await
+    })
+  }
+
+  fn matrix<'a>(&'a self, points: &'a [(f64, f64)]) -> BoxFuture<'a, FastJobResult<TravelMatrix>> {
+    Box::pin(async move {
+      if let Some(primary) = &self.primary {
+        match primary.matrix(points).await {
+          Ok(matrix) => return Ok(matrix),
+          Err(e) => warn!(?e, "OSRM table failed, using straight-line estimate"),
+        }
+      }
+      Ok(self.fallback.estimate_matrix(points))
+    })
+  }
+}
+
+impl DispatchDistances for FallbackRouter {
+  /// Only real road distances are worth re-ranking by; a scaled straight
+  /// line orders riders exactly like the plain one.
+  fn to_pickup<'a>(
+    &'a self,
+    riders: &'a [(f64, f64)],
+    pickup: (f64, f64),
+  ) -> BoxFuture<'a, Vec<Option<f64>>> {
+    Box::pin(async move {
+      let Some(primary) = &self.primary else {
+        return vec![None; riders.len()];
+      };
+      primary
+        .distances_to(riders, pickup)
+        .await
+        .inspect_err(|e| warn!(?e, "OSRM table failed, ranking riders by straight line"))
+        .unwrap_or_else(|_| vec![None; riders.len()])
+    })
+  }
+}
+
+/// The router configured for this instance.
+pub fn routing_provider(context: &FastJobContext) -> FallbackRouter {
+  FallbackRouter::new(&context.settings().routing, context.client().clone())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn straight_line_scales_distance_and_duration() {
+    let router = StraightLineRouter {
+      road_distance_factor: 1.5,
+      average_speed_kmh: 30.0,
+    };
+    let from = (13.7563, 100.5018);
+    let to = (13.7367, 100.5231);
+    let route = router.estimate(from, to);
+
+    let straight = haversine_km(from.0, from.1, to.0, to.1);
+    assert!((route.distance_km - straight * 1.5).abs() < 1e-9);
+    assert!((route.duration_minutes - route.distance_km * 2.0).abs() < 1e-9);
+    assert_eq!(route.polyline, encode_polyline(&[from, to]));
+    assert!(route.estimated);
+  }
+
+  #[test]
+  fn straight_line_matrix_matches_single_estimates() {
+    let router = StraightLineRouter {
+      road_distance_factor: 1.3,
+      average_speed_kmh: 25.0,
+    };
+    let points = [
+      (13.7563, 100.5018),
+      (13.7367, 100.5231),
+      (13.7279, 100.5241),
+    ];
+    let matrix = router.estimate_matrix(&points);
+
+    for (i, &from) in points.iter().enumerate() {
+      for (j, &to) in points.iter().enumerate() {
+        let route = router.estimate(from, to);
+        assert!((matrix.distances_km[i][j] - route.distance_km).abs() < 1e-9);
+        assert!((matrix.durations_minutes[i][j] - route.duration_minutes).abs() < 1e-9);
+      }
+    }
+    assert_eq!(matrix.distances_km[1][1], 0.0);
+    assert!(matrix.estimated);
+  }
+}
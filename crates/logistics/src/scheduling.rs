@@ -0,0 +1,218 @@
+//! Background work for rides and deliveries booked for a later pickup.
+//!
+//! Riders holding a reservation are reminded `reminder_minutes` before
+//! pickup. Jobs that still have no confirmed rider `confirm_deadline_minutes`
+//! before pickup are cancelled; a reserved delivery gets its escrow refunded.
+
+use crate::{
+  handlers::{ride::publish_ride_event, status::publish_delivery_status},
+  offers::cancel_dispatch_for_post,
+};
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db::{
+  enums::DispatchJobKind,
+  newtypes::{PostId, RiderId},
+  source::{
+    delivery_details::{DeliveryDetails, DeliveryDetailsUpdateForm},
+    ride_session::{RideSession, RideSessionUpdateForm},
+  },
+  traits::Crud,
+};
+use app_108jobs_db_views_rider::api::{RideStatusEvent, ScheduledTripEvent};
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+const EXPIRED_REASON: &str = "No rider confirmed the scheduled pickup in time";
+
+/// Remind riders of reservations whose pickup is coming up. Returns how many
+/// reminders went out.
+pub async fn send_scheduled_trip_reminders(context: &FastJobContext) -> FastJobResult<usize> {
+  let cfg = &context.settings().scheduling;
+  let before = Utc::now() + Duration::minutes(cfg.reminder_minutes);
+  let mut sent = 0;
+
+  for session in RideSession::list_due_reminders(&mut context.pool(), before).await? {
+    let (Some(rider_id), Some(scheduled_pickup_at)) =
+      (session.rider_id, session.scheduled_pickup_at)
+    else {
+      continue;
+    };
+    let form = RideSessionUpdateForm {
+      reminder_sent_at: Some(Some(Utc::now())),
+      ..Default::default()
+    };
+    if let Err(e) = RideSession::update(&mut context.pool(), session.id, &form).await {
+      warn!(
+        ?e,
+        session_id = session.id.0,
+        "Failed to mark ride reminder sent"
+      );
+      continue;
+    }
+    let event = ScheduledTripEvent {
+      kind: "scheduled_trip_reminder",
+      job_kind: DispatchJobKind::Ride,
+      post_id: session.post_id,
+      session_id: Some(session.id),
+      scheduled_pickup_at,
+    };
+    publish_scheduled_trip_event(context, rider_id, &event).await;
+    sent += 1;
+  }
+
+  for delivery in DeliveryDetails::list_due_reminders(&mut context.pool(), before).await? {
+    let (Some(rider_id), Some(scheduled_pickup_at)) =
+      (delivery.assigned_rider_id, delivery.scheduled_pickup_at)
+    else {
+      continue;
+    };
+    let form = DeliveryDetailsUpdateForm {
+      reminder_sent_at: Some(Some(Utc::now())),
+      ..Default::default()
+    };
+    if let Err(e) = DeliveryDetails::update(&mut context.pool(), delivery.id, &form).await {
+      warn!(?e, post_id = %delivery.post_id, "Failed to mark delivery reminder sent");
+      continue;
+    }
+    let event = ScheduledTripEvent {
+      kind: "scheduled_trip_reminder",
+      job_kind: DispatchJobKind::Delivery,
+      post_id: delivery.post_id,
+      session_id: None,
+      scheduled_pickup_at,
+    };
+    publish_scheduled_trip_event(context, rider_id, &event).await;
+    sent += 1;
+  }
+
+  if sent > 0 {
+    info!("Sent {} scheduled trip reminder(s)", sent);
+  }
+  Ok(sent)
+}
+
+/// Cancel scheduled jobs that have no confirmed rider by the deadline. Any
+/// running dispatch stops, reserved riders may accept rides again and
+/// reserved deliveries are refunded to the employer. Jobs confirmed in the
+/// meantime are left alone. Returns how many jobs were cancelled.
+pub async fn expire_unconfirmed_scheduled_trips(context: &FastJobContext) -> FastJobResult<usize> {
+  let cfg = &context.settings().scheduling;
+  let before = Utc::now() + Duration::minutes(cfg.confirm_deadline_minutes);
+  let mut cancelled = 0;
+
+  for session in RideSession::list_unconfirmed_scheduled(&mut context.pool(), before).await? {
+    let updated = match RideSession::expire_unconfirmed(
+      &mut context.pool(),
+      session.id,
+      EXPIRED_REASON,
+    )
+    .await
+    {
+      Ok(Some(updated)) => updated,
+      // Confirmed or cancelled since it was listed
+      Ok(None) => continue,
+      Err(e) => {
+        warn!(
+          ?e,
+          session_id = session.id.0,
+          "Failed to expire scheduled ride"
+        );
+        continue;
+      }
+    };
+    stop_dispatch(context, updated.post_id).await;
+
+    let event = RideStatusEvent {
+      kind: "ride_cancelled",
+      session_id: updated.id,
+      post_id: updated.post_id,
+      status: updated.status,
+      updated_at: updated.updated_at.unwrap_or_else(Utc::now),
+    };
+    publish_ride_event(context, &event, updated.id).await;
+    if let (Some(rider_id), Some(scheduled_pickup_at)) =
+      (updated.rider_id, updated.scheduled_pickup_at)
+    {
+      let event = ScheduledTripEvent {
+        kind: "scheduled_trip_cancelled",
+        job_kind: DispatchJobKind::Ride,
+        post_id: updated.post_id,
+        session_id: Some(updated.id),
+        scheduled_pickup_at,
+      };
+      publish_scheduled_trip_event(context, rider_id, &event).await;
+    }
+    cancelled += 1;
+  }
+
+  for delivery in DeliveryDetails::list_unconfirmed_scheduled(&mut context.pool(), before).await? {
+    let updated = match DeliveryDetails::expire_unconfirmed(
+      &mut context.pool(),
+      delivery.post_id,
+      EXPIRED_REASON,
+    )
+    .await
+    {
+      Ok(Some(updated)) => updated,
+      // Confirmed or cancelled since it was listed
+      Ok(None) => continue,
+      Err(e) => {
+        warn!(?e, post_id = %delivery.post_id, "Failed to expire scheduled delivery");
+        continue;
+      }
+    };
+    stop_dispatch(context, updated.post_id).await;
+
+    publish_delivery_status(
+      context,
+      updated.post_id,
+      updated.status,
+      updated.updated_at,
+      Some(EXPIRED_REASON.to_string()),
+    )
+    .await;
+    if let (Some(rider_id), Some(scheduled_pickup_at)) =
+      (updated.assigned_rider_id, updated.scheduled_pickup_at)
+    {
+      let event = ScheduledTripEvent {
+        kind: "scheduled_trip_cancelled",
+        job_kind: DispatchJobKind::Delivery,
+        post_id: updated.post_id,
+        session_id: None,
+        scheduled_pickup_at,
+      };
+      publish_scheduled_trip_event(context, rider_id, &event).await;
+    }
+    cancelled += 1;
+  }
+
+  if cancelled > 0 {
+    info!("Cancelled {} unconfirmed scheduled trip(s)", cancelled);
+  }
+  Ok(cancelled)
+}
+
+async fn stop_dispatch(context: &FastJobContext, post_id: PostId) {
+  if let Err(e) = cancel_dispatch_for_post(context, post_id).await {
+    warn!(?e, post_id = %post_id, "Failed to cancel dispatch for expired scheduled trip");
+  }
+}
+
+async fn publish_scheduled_trip_event(
+  context: &FastJobContext,
+  rider_id: RiderId,
+  event: &ScheduledTripEvent,
+) {
+  if let Ok(json) = serde_json::to_string(event) {
+    let channel = format!("schedule:rider:{}", rider_id.0);
+    let mut redis = context.redis().clone();
+    if let Err(e) = redis.publish(&channel, &json).await {
+      warn!(
+        ?e,
+        rider_id = rider_id.0,
+        "Failed to publish scheduled trip event to Redis"
+      );
+    }
+  }
+}
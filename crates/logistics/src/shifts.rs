@@ -0,0 +1,101 @@
+//! Rider shifts.
+//!
+//! Ops open shift slots per service area; riders claim places in them up to
+//! the slot's capacity. When a claimed shift starts the rider is put online
+//! and accepting jobs. A rider who shows no sign of life within
+//! `no_show_grace_minutes` is a no-show and taken offline again; after
+//! `no_shows_before_claim_ban` no-shows within `no_show_window_days` they may
+//! not claim further shifts.
+
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
+use app_108jobs_db::{
+  newtypes::{RiderId, RiderShiftSlotId},
+  source::{
+    rider::{Rider, RiderUpdateForm},
+    rider_shift::RiderShift,
+  },
+  traits::Crud,
+};
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+/// Claim a place in a shift slot for the rider.
+pub async fn claim_shift(
+  context: &FastJobContext,
+  rider: &Rider,
+  slot_id: RiderShiftSlotId,
+) -> FastJobResult<RiderShift> {
+  let cfg = &context.settings().shifts;
+  if cfg.no_shows_before_claim_ban > 0 {
+    let since = Utc::now() - Duration::days(cfg.no_show_window_days);
+    let no_shows = RiderShift::count_no_shows_since(&mut context.pool(), rider.id, since).await?;
+    if no_shows >= cfg.no_shows_before_claim_ban {
+      return Err(FastJobErrorType::RiderShiftClaimBanned.into());
+    }
+  }
+  RiderShift::claim(&mut context.pool(), slot_id, rider.id).await
+}
+
+/// Put riders online whose claimed shift just started. Riders who may not
+/// take jobs right now, e.g. for expired documents, only go online. Returns
+/// how many shifts started.
+pub async fn start_due_shifts(context: &FastJobContext) -> FastJobResult<usize> {
+  let now = Utc::now();
+  let started = RiderShift::start_due(&mut context.pool(), now).await?;
+
+  for shift in &started {
+    let rider = match Rider::read(&mut context.pool(), shift.rider_id).await {
+      Ok(rider) => rider,
+      Err(e) => {
+        warn!(?e, rider_id = %shift.rider_id, "Failed to load rider for started shift");
+        continue;
+      }
+    };
+    let may_accept = rider.is_active && !rider.documents_expired(now) && !rider.accept_banned(now);
+    let form = RiderUpdateForm {
+      is_online: Some(true),
+      accepting_jobs: may_accept.then_some(true),
+      ..Default::default()
+    };
+    if let Err(e) = Rider::update(&mut context.pool(), rider.id, &form).await {
+      warn!(?e, rider_id = %rider.id, "Failed to put rider online for shift");
+    }
+  }
+
+  if !started.is_empty() {
+    info!("Started {} rider shift(s)", started.len());
+  }
+  Ok(started.len())
+}
+
+/// Mark riders who did not show up for their shift and take them offline,
+/// then close shifts that are over. Returns how many no-shows were marked.
+pub async fn settle_rider_shifts(context: &FastJobContext) -> FastJobResult<usize> {
+  let now = Utc::now();
+  let cfg = &context.settings().shifts;
+  let no_shows =
+    RiderShift::mark_no_shows(&mut context.pool(), now, cfg.no_show_grace_minutes).await?;
+
+  for shift in &no_shows {
+    if let Err(e) = take_offline(context, shift.rider_id).await {
+      warn!(?e, rider_id = %shift.rider_id, "Failed to take no-show rider offline");
+    }
+  }
+  if !no_shows.is_empty() {
+    info!("Marked {} rider shift no-show(s)", no_shows.len());
+  }
+
+  RiderShift::finish_ended(&mut context.pool(), now).await?;
+  Ok(no_shows.len())
+}
+
+async fn take_offline(context: &FastJobContext, rider_id: RiderId) -> FastJobResult<()> {
+  let form = RiderUpdateForm {
+    is_online: Some(false),
+    accepting_jobs: Some(false),
+    ..Default::default()
+  };
+  Rider::update(&mut context.pool(), rider_id, &form).await?;
+  Ok(())
+}
@@ -0,0 +1,76 @@
+//! Upkeep of recorded trip locations.
+//!
+//! `trip_location_history` is partitioned by month, with partitions created
+//! `partition_months_ahead` months in advance. Tracks of finished trips are
+//! thinned out `downsample_after_days` after the trip ends, keeping the fixes
+//! around each status change, and fixes older than `retention_days` are
+//! deleted.
+
+use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db::source::trip_location_history::TripLocationHistory;
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+/// Make sure the coming months have a partition. Returns how many were
+/// created.
+pub async fn create_trip_history_partitions(context: &FastJobContext) -> FastJobResult<i32> {
+  let cfg = &context.settings().trip_history;
+  let created =
+    TripLocationHistory::create_partitions(&mut context.pool(), cfg.partition_months_ahead).await?;
+  if created > 0 {
+    info!("Created {} trip location history partition(s)", created);
+  }
+  Ok(created)
+}
+
+/// Thin out the tracks of trips that finished long enough ago. Returns how
+/// many fixes were deleted.
+pub async fn downsample_finished_trip_tracks(context: &FastJobContext) -> FastJobResult<usize> {
+  let cfg = &context.settings().trip_history;
+  let now = Utc::now();
+  let post_ids = TripLocationHistory::list_downsample_candidates(
+    &mut context.pool(),
+    now - Duration::days(cfg.retention_days),
+    now - Duration::days(cfg.downsample_after_days),
+    cfg.downsample_batch_size,
+  )
+  .await?;
+  let mut deleted = 0;
+
+  for post_id in &post_ids {
+    match TripLocationHistory::downsample_for_post(
+      &mut context.pool(),
+      *post_id,
+      cfg.downsample_interval_seconds,
+    )
+    .await
+    {
+      Ok(rows) => deleted += rows,
+      Err(e) => warn!(?e, post_id = %post_id, "Failed to downsample trip track"),
+    }
+  }
+
+  if !post_ids.is_empty() {
+    info!(
+      "Downsampled {} trip track(s), deleting {} location(s)",
+      post_ids.len(),
+      deleted
+    );
+  }
+  Ok(deleted)
+}
+
+/// Delete fixes past the retention period. Returns how many were deleted.
+pub async fn purge_expired_trip_history(context: &FastJobContext) -> FastJobResult<i64> {
+  let cfg = &context.settings().trip_history;
+  let before = Utc::now() - Duration::days(cfg.retention_days);
+  let purged = TripLocationHistory::purge_before(&mut context.pool(), before).await?;
+  if purged > 0 {
+    info!(
+      "Purged {} trip location(s) recorded before {}",
+      purged, before
+    );
+  }
+  Ok(purged)
+}
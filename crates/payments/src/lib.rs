@@ -1,4 +1,5 @@
 pub mod bank_account;
 pub mod list_top_up_requests;
+pub mod referral;
 pub mod wallet;
 pub mod withdraw;
@@ -0,0 +1,35 @@
+use actix_web::web::{Data, Json, Query};
+use app_108jobs_api_utils::{context::FastJobContext, utils::check_fetch_limit};
+use app_108jobs_core::error::FastJobResult;
+use app_108jobs_db::{
+  newtypes::Coin,
+  source::referral::{Referral, ReferralCode},
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_db_views_wallet::api::{GetReferralDashboardQuery, ReferralDashboardResponse};
+
+/// GET /api/v4/account/referral
+///
+/// Returns the caller's invite code (creating it on first use) together with
+/// the referrals attributed to it and their pending and earned rewards.
+pub async fn get_referral_dashboard(
+  query: Query<GetReferralDashboardQuery>,
+  context: Data<FastJobContext>,
+  local_user_view: LocalUserView,
+) -> FastJobResult<Json<ReferralDashboardResponse>> {
+  let local_user_id = local_user_view.local_user.id;
+  let limit = check_fetch_limit(query.limit)?;
+  let reward = context.settings().referral.reward_coin;
+
+  let code = ReferralCode::get_or_create(&mut context.pool(), local_user_id).await?;
+  let summary = Referral::summary_for_referrer(&mut context.pool(), local_user_id).await?;
+  let referrals = Referral::list_for_referrer(&mut context.pool(), local_user_id, limit).await?;
+
+  Ok(Json(ReferralDashboardResponse {
+    code: code.code,
+    reward_per_referral: Coin(reward),
+    pending_reward_coin: summary.pending_count * i64::from(reward),
+    summary,
+    referrals,
+  }))
+}
@@ -5,10 +5,27 @@ use app_108jobs_core::{
   error::{FastJobErrorType, FastJobResult},
   settings::structs::PrometheusConfig,
 };
-use prometheus::{default_registry, Encoder, Gauge, Opts, TextEncoder};
-use std::sync::Arc;
+use prometheus::{default_registry, Encoder, Gauge, IntCounterVec, Opts, TextEncoder};
+use std::sync::{Arc, LazyLock};
 use tracing::error;
 
+/// Trip locations deleted by the maintenance jobs, labelled with the reason
+/// (`downsample` or `retention`)
+pub static TRIP_HISTORY_PRUNED_ROWS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+  let counter = IntCounterVec::new(
+    Opts::new(
+      "app_108jobs_trip_location_history_pruned_rows_total",
+      "Trip locations deleted by downsampling or the retention period",
+    ),
+    &["reason"],
+  )
+  .expect("valid metric options");
+  default_registry()
+    .register(Box::new(counter.clone()))
+    .expect("metric is registered once");
+  counter
+});
+
 /// Creates a middleware that populates http metrics for each path, method, and status code
 pub fn new_prometheus_metrics() -> FastJobResult<PrometheusMetrics> {
   Ok(
@@ -38,6 +55,8 @@ pub fn serve_prometheus(
     app_108jobs: app_108jobs_context,
     db_pool_metrics: create_db_pool_metrics()?,
   });
+  // Export the maintenance counters before their first increment
+  LazyLock::force(&TRIP_HISTORY_PRUNED_ROWS);
 
   // Bind synchronously so a port conflict surfaces as a clean startup error
   // rather than a silent background failure. Then drive the server on the
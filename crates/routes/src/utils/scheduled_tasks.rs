@@ -1,3 +1,4 @@
+use crate::utils::prometheus_metrics::TRIP_HISTORY_PRUNED_ROWS;
 use actix_web::web::Data;
 use app_108jobs_api_utils::context::FastJobContext;
 use app_108jobs_core::error::FastJobResult;
@@ -7,8 +8,21 @@ use app_108jobs_db::{
     captcha_answer,
     top_up_requests::{cs_ext_expiry_time, dsl::top_up_requests, id, status},
   },
+  source::{dispatch::DispatchRequest, post_boost::PostBoost, rider_earning::RiderEarningHold},
   utils::{get_conn, now, DbPool},
 };
+use app_108jobs_logistics::{
+  documents::{send_license_expiry_reminders, suspend_riders_with_expired_licenses},
+  offers::{push_dispatch_offers, rider_presence},
+  routing::routing_provider,
+  scheduling::{expire_unconfirmed_scheduled_trips, send_scheduled_trip_reminders},
+  shifts::{settle_rider_shifts, start_due_shifts},
+  trip_history::{
+    create_trip_history_partitions,
+    downsample_finished_trip_tracks,
+    purge_expired_trip_history,
+  },
+};
 use chrono::Utc;
 use clokwerk::{AsyncScheduler, TimeUnits as CTimeUnits};
 use diesel::{dsl::IntervalDsl, BoolExpressionMethods, ExpressionMethods, QueryDsl};
@@ -34,6 +48,134 @@ pub async fn setup(context: Data<FastJobContext>) -> FastJobResult<()> {
     }
   });
 
+  let context_1 = context.clone();
+  // Expire paid post boosts and clear their featured flags every 5 minutes
+  scheduler.every(CTimeUnits::minutes(5)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      expire_post_boosts(&mut context.pool())
+        .await
+        .inspect_err(|e| warn!("Failed to expire post boosts: {e}"))
+        .ok();
+    }
+  });
+
+  let context_1 = context.clone();
+  // Make rider payouts past their clearing period withdrawable every 10 minutes
+  scheduler.every(CTimeUnits::minutes(10)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      release_rider_earnings(&mut context.pool())
+        .await
+        .inspect_err(|e| warn!("Failed to release rider earnings: {e}"))
+        .ok();
+    }
+  });
+
+  let context_1 = context.clone();
+  // Send the next wave of dispatch offers once the current one times out
+  scheduler.every(CTimeUnits::seconds(10)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      advance_dispatches(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to advance dispatches: {e}"))
+        .ok();
+    }
+  });
+
+  let context_1 = context.clone();
+  // Remind riders of upcoming reservations and cancel scheduled trips nobody
+  // confirmed in time, every minute
+  scheduler.every(CTimeUnits::minutes(1)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      send_scheduled_trip_reminders(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to send scheduled trip reminders: {e}"))
+        .ok();
+      expire_unconfirmed_scheduled_trips(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to expire scheduled trips: {e}"))
+        .ok();
+    }
+  });
+
+  let context_1 = context.clone();
+  // Put riders online as their shifts start, mark no-shows and close shifts
+  // that are over, every minute
+  scheduler.every(CTimeUnits::minutes(1)).run(move || {
+    let context = context_1.clone();
+
+    async move {
+      start_due_shifts(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to start rider shifts: {e}"))
+        .ok();
+      settle_rider_shifts(&context)
+        .await
+        .inspect_err(|e| warn!("Failed to settle rider shifts: {e}"))
+        .ok();
+    }
+  });
+
+  let context_1 = context.clone();
+  // Warn riders of expiring licenses and suspend those that have expired, daily
+  scheduler
+    .every(CTimeUnits::day(1))
+    .at("03:00")
+    .run(move || {
+      let context = context_1.clone();
+
+      async move {
+        send_license_expiry_reminders(&context)
+          .await
+          .inspect_err(|e| warn!("Failed to send license expiry reminders: {e}"))
+          .ok();
+        suspend_riders_with_expired_licenses(&context)
+          .await
+          .inspect_err(|e| warn!("Failed to suspend riders with expired licenses: {e}"))
+          .ok();
+      }
+    });
+
+  let context_1 = context.clone();
+  // Create upcoming trip location partitions, thin out the tracks of finished
+  // trips and purge expired locations, daily
+  scheduler
+    .every(CTimeUnits::day(1))
+    .at("04:00")
+    .run(move || {
+      let context = context_1.clone();
+
+      async move {
+        create_trip_history_partitions(&context)
+          .await
+          .inspect_err(|e| warn!("Failed to create trip location partitions: {e}"))
+          .ok();
+        if let Ok(rows) = downsample_finished_trip_tracks(&context)
+          .await
+          .inspect_err(|e| warn!("Failed to downsample trip tracks: {e}"))
+        {
+          TRIP_HISTORY_PRUNED_ROWS
+            .with_label_values(&["downsample"])
+            .inc_by(rows as u64);
+        }
+        if let Ok(rows) = purge_expired_trip_history(&context)
+          .await
+          .inspect_err(|e| warn!("Failed to purge expired trip locations: {e}"))
+        {
+          TRIP_HISTORY_PRUNED_ROWS
+            .with_label_values(&["retention"])
+            .inc_by(rows as u64);
+        }
+      }
+    });
+
   // Manually run the scheduler in an event loop
   loop {
     scheduler.run_pending().await;
@@ -85,3 +227,34 @@ async fn update_expired_wallet_topups(pool: &mut DbPool<'_>) -> FastJobResult<()
 
   Ok(())
 }
+
+async fn expire_post_boosts(pool: &mut DbPool<'_>) -> FastJobResult<()> {
+  let expired = PostBoost::expire_due(pool).await?;
+  if expired > 0 {
+    info!("Expired {} post boost(s)", expired);
+  }
+  Ok(())
+}
+
+async fn release_rider_earnings(pool: &mut DbPool<'_>) -> FastJobResult<()> {
+  let released = RiderEarningHold::release_due(pool).await?;
+  if released > 0 {
+    info!("Released {} rider earning hold(s)", released);
+  }
+  Ok(())
+}
+
+async fn advance_dispatches(context: &FastJobContext) -> FastJobResult<()> {
+  let offers = DispatchRequest::advance_due(
+    &mut context.pool(),
+    &context.settings().dispatch,
+    &routing_provider(context),
+    &rider_presence(context),
+  )
+  .await?;
+  if !offers.is_empty() {
+    info!("Sent {} dispatch offer(s)", offers.len());
+    push_dispatch_offers(context, &offers).await;
+  }
+  Ok(())
+}
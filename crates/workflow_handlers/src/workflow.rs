@@ -1,6 +1,6 @@
 use crate::workflow_authz::{require_any_party, require_post_creator, require_role, WorkflowRole};
 use actix_web::web::{Data, Json, Query};
-use app_108jobs_api_utils::context::FastJobContext;
+use app_108jobs_api_utils::{context::FastJobContext, utils::reward_referrals};
 use app_108jobs_core::error::{FastJobErrorType, FastJobResult};
 use app_108jobs_db::{
   enums::{BillingStatus, WorkFlowStatus},
@@ -257,6 +257,14 @@ pub async fn approve_work(
     .approve_work_on(&mut context.pool(), coin_id, platform_wallet_id, billing_id)
     .await?;
 
+  reward_referrals(
+    &context,
+    &[billing.employer_id, billing.freelancer_id],
+    "billing",
+    billing_id.0,
+  )
+  .await;
+
   Ok(Json(WorkFlowOperationResponse {
     workflow_id: wf.data.workflow_id.into(),
     status: WorkFlowStatus::Completed,
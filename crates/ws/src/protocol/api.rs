@@ -1,7 +1,7 @@
 use actix::prelude::*;
 use app_108jobs_db::{
   enums::WorkFlowStatus,
-  newtypes::{ChatMessageRefId, ChatRoomId, LocalUserId},
+  newtypes::{ChatMessageRefId, ChatRoomId, DispatchOfferId, LocalUserId},
   source::chat_message::ChatMessageInsertForm,
 };
 use chrono::{DateTime, Utc};
@@ -59,6 +59,16 @@ pub enum ChatEvent {
   GlobalOnline,
   #[serde(rename = "globalOffline")]
   GlobalOffline,
+  #[serde(rename = "job:offer")]
+  JobOffer,
+  #[serde(rename = "job:taken")]
+  JobTaken,
+  #[serde(rename = "job:accept")]
+  JobAccept,
+  #[serde(rename = "job:decline")]
+  JobDecline,
+  #[serde(rename = "incident:opened")]
+  IncidentOpened,
   #[serde(other)]
   Unknown,
 }
@@ -113,6 +123,12 @@ pub struct ReadPayload {
 pub struct ActiveRoomPayload {
   pub room_id: ChatRoomId,
 }
+/// A rider accepting or declining a job offer pushed on `rider:{id}:offers`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferAnswerPayload {
+  pub offer_id: DispatchOfferId,
+}
 #[skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
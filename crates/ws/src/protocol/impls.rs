@@ -54,6 +54,10 @@ impl FromStr for ChatEvent {
       "chat:stop" => ChatEvent::TypingStop,
       "chat:update" => ChatEvent::Update,
       "chats:signal" => ChatEvent::ChatsSignal,
+
+      // Job offers to riders
+      "job:accept" => ChatEvent::JobAccept,
+      "job:decline" => ChatEvent::JobDecline,
       _ => ChatEvent::Unknown,
     })
   }
@@ -82,6 +86,15 @@ impl ChatEvent {
       ChatEvent::ChatsSignal => "chats:signal",
       ChatEvent::GlobalOnline => "globalOnline",
       ChatEvent::GlobalOffline => "globalOffline",
+
+      // Job offers to riders
+      ChatEvent::JobOffer => "job:offer",
+      ChatEvent::JobTaken => "job:taken",
+      ChatEvent::JobAccept => "job:accept",
+      ChatEvent::JobDecline => "job:decline",
+
+      // Incidents raised during trips, to admins
+      ChatEvent::IncidentOpened => "incident:opened",
       ChatEvent::Unknown => "unknown",
     }
   }
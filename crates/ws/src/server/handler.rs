@@ -113,12 +113,13 @@ pub async fn phoenix_ws(
   // Extract query parameters similar to chat_ws
   let auth_token = query.token.clone();
 
-  let (shared_key, local_user_id): (Option<String>, Option<LocalUserId>) =
+  let (shared_key, local_user_id, is_admin): (Option<String>, Option<LocalUserId>, bool) =
     if let Some(jwt_token) = auth_token {
       match local_user_view_from_jwt(&jwt_token, &context).await {
         Ok((local_user_view, _session)) => (
           local_user_view.person.shared_key,
           Some(local_user_view.local_user.id),
+          local_user_view.local_user.admin,
         ),
         Err(_) => {
           return Err(Error::from(FastJobError::from(
@@ -127,9 +128,14 @@ pub async fn phoenix_ws(
         }
       }
     } else {
-      (None, None)
+      (None, None, false)
     };
-  let ph_session = PhoenixSession::new(shared_key, local_user_id);
+  let ph_session = PhoenixSession::new(
+    shared_key,
+    local_user_id,
+    is_admin,
+    context.get_ref().clone(),
+  );
   ws::start(ph_session, &req, stream)
 }
 
@@ -1,19 +1,39 @@
 use crate::{
   bridge_message::{BridgeMessage, GlobalOffline, GlobalOnline, OutboundMessage},
   protocol::{
-    api::{ChatEvent, IncomingEvent},
+    api::{ChatEvent, IncomingEvent, OfferAnswerPayload},
     impls::AnyIncomingEvent,
     phx_helper::{is_base64_like, parse_phx, phx_push, phx_reply},
   },
 };
-use actix::{Actor, ActorContext, AsyncContext, Handler, StreamHandler};
+use actix::{
+  Actor,
+  ActorContext,
+  ActorFutureExt,
+  AsyncContext,
+  Handler,
+  StreamHandler,
+  WrapFuture,
+};
 use actix_broker::{BrokerIssue, BrokerSubscribe, SystemBroker};
 use actix_web_actors::ws;
-use app_108jobs_api_utils::context::FastJobContext;
-use app_108jobs_core::crypto;
-use app_108jobs_db::newtypes::{ChatRoomId, LocalUserId, PostId};
+use app_108jobs_api_utils::{context::FastJobContext, utils::get_active_rider_by_person};
+use app_108jobs_core::{
+  crypto,
+  error::FastJobResult,
+  utils::keys::{admin_incidents_topic, rider_offers_topic},
+};
+use app_108jobs_db::{
+  newtypes::{ChatRoomId, DispatchOfferId, LocalUserId, PostId},
+  source::dispatch::DispatchRequest,
+};
+use app_108jobs_db_views_local_user::LocalUserView;
+use app_108jobs_logistics::{
+  incidents::IncidentAlert,
+  offers::{accept_for_rider, RiderOfferPush, RiderOfferPushKind},
+};
 use chrono::Utc;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::time::Duration;
 use uuid::Uuid;
 
@@ -22,14 +42,24 @@ pub struct PhoenixSession {
   pub(crate) shared_key: Option<String>,
   pub(crate) local_user_id: Option<LocalUserId>,
   pub(crate) connection_id: String,
+  /// Admins are sent the incidents raised during trips
+  pub(crate) is_admin: bool,
+  context: FastJobContext,
 }
 
 impl PhoenixSession {
-  pub fn new(shared_key: Option<String>, local_user_id: Option<LocalUserId>) -> Self {
+  pub fn new(
+    shared_key: Option<String>,
+    local_user_id: Option<LocalUserId>,
+    is_admin: bool,
+    context: FastJobContext,
+  ) -> Self {
     Self {
       shared_key,
       local_user_id,
       connection_id: Uuid::new_v4().to_string(),
+      is_admin,
+      context,
     }
   }
 
@@ -66,6 +96,65 @@ impl PhoenixSession {
       .and_then(|v| v.as_bool())
       .unwrap_or(false)
   }
+
+  /// Accept or decline a job offer as the signed-in rider and reply with the
+  /// outcome once it is known. The rider is always the session's user, never
+  /// one named in the payload.
+  fn answer_offer(
+    &self,
+    jr: Option<String>,
+    mr: Option<String>,
+    incoming: IncomingEvent,
+    ctx: &mut ws::WebsocketContext<Self>,
+  ) {
+    let topic = incoming.topic.clone();
+    let answer = serde_json::from_value::<OfferAnswerPayload>(incoming.payload);
+    let (Some(local_user_id), Ok(answer)) = (self.local_user_id, answer) else {
+      ctx.text(phx_reply(
+        &jr,
+        &mr,
+        &topic,
+        "error",
+        json!({ "error": "invalidOfferAnswer" }),
+      ));
+      return;
+    };
+
+    let context = self.context.clone();
+    let accept = incoming.event == ChatEvent::JobAccept;
+    let reply = async move {
+      match answer_offer(&context, local_user_id, answer.offer_id, accept).await {
+        Ok(response) => phx_reply(&jr, &mr, &topic, "ok", response),
+        Err(e) => {
+          let error = serde_json::to_value(&e.error_type).unwrap_or(Value::Null);
+          phx_reply(&jr, &mr, &topic, "error", error)
+        }
+      }
+    };
+    ctx.spawn(
+      reply
+        .into_actor(self)
+        .map(|frame, _act, ctx| ctx.text(frame)),
+    );
+  }
+}
+
+async fn answer_offer(
+  context: &FastJobContext,
+  local_user_id: LocalUserId,
+  offer_id: DispatchOfferId,
+  accept: bool,
+) -> FastJobResult<Value> {
+  let user = LocalUserView::read(&mut context.pool(), local_user_id).await?;
+  let rider = get_active_rider_by_person(&mut context.pool(), user.person.id).await?;
+  let response = if accept {
+    serde_json::to_value(accept_for_rider(context, &rider, offer_id).await?)
+  } else {
+    serde_json::to_value(
+      DispatchRequest::decline_offer(&mut context.pool(), offer_id, rider.id).await?,
+    )
+  };
+  Ok(response.unwrap_or(Value::Null))
 }
 
 impl Actor for PhoenixSession {
@@ -73,6 +162,12 @@ impl Actor for PhoenixSession {
 
   fn started(&mut self, ctx: &mut Self::Context) {
     self.subscribe_system_sync::<OutboundMessage>(ctx);
+    if self.local_user_id.is_some() {
+      self.subscribe_system_sync::<RiderOfferPush>(ctx);
+    }
+    if self.is_admin {
+      self.subscribe_system_sync::<IncidentAlert>(ctx);
+    }
 
     // Emit GlobalOnline if user is authenticated
     if let Some(uid) = self.local_user_id {
@@ -141,11 +236,50 @@ impl Handler<OutboundMessage> for PhoenixSession {
   }
 }
 
+/// Job offer events reach only the sessions of the rider they are for.
+impl Handler<RiderOfferPush> for PhoenixSession {
+  type Result = ();
+
+  fn handle(&mut self, msg: RiderOfferPush, ctx: &mut Self::Context) {
+    if self.local_user_id != Some(msg.local_user_id) {
+      return;
+    }
+    let event = match msg.kind {
+      RiderOfferPushKind::Offer => ChatEvent::JobOffer,
+      RiderOfferPushKind::Taken => ChatEvent::JobTaken,
+    };
+    let topic = rider_offers_topic(msg.local_user_id.0);
+    ctx.text(phx_push(&topic, &event, msg.payload));
+  }
+}
+
+/// Incidents reach the sessions of admins only; they subscribe on start.
+impl Handler<IncidentAlert> for PhoenixSession {
+  type Result = ();
+
+  fn handle(&mut self, msg: IncidentAlert, ctx: &mut Self::Context) {
+    if !self.is_admin {
+      return;
+    }
+    ctx.text(phx_push(
+      &admin_incidents_topic(),
+      &ChatEvent::IncidentOpened,
+      msg.payload,
+    ));
+  }
+}
+
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for PhoenixSession {
   fn handle(&mut self, m: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
     match m {
       Ok(ws::Message::Text(txt)) => {
         if let Some((jr, mr, mut incoming)) = parse_phx(&txt) {
+          // Offer answers are handled here, where the rider is known
+          if matches!(incoming.event, ChatEvent::JobAccept | ChatEvent::JobDecline) {
+            self.answer_offer(jr, mr, incoming, ctx);
+            return;
+          }
+
           // Keep original payload for reply echo, decrypt for broker only
           let reply = incoming.payload.clone();
 
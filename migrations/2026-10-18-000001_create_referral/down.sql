@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS public.referral CASCADE;
+
+DROP TABLE IF EXISTS public.referral_code CASCADE;
+
+DROP TYPE IF EXISTS public.referral_status;
@@ -0,0 +1,69 @@
+CREATE TYPE public.referral_status AS ENUM (
+    'Pending',
+    'Rewarded',
+    'Rejected'
+);
+
+-- One invite code per user, created lazily the first time it is requested.
+CREATE TABLE public.referral_code (
+    local_user_id integer NOT NULL,
+    code character varying(16) NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+ALTER TABLE ONLY public.referral_code
+    ADD CONSTRAINT referral_code_pkey PRIMARY KEY (local_user_id);
+
+ALTER TABLE ONLY public.referral_code
+    ADD CONSTRAINT uq_referral_code_code UNIQUE (code);
+
+ALTER TABLE ONLY public.referral_code
+    ADD CONSTRAINT referral_code_local_user_id_fkey FOREIGN KEY (local_user_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- A referred user can only ever be attributed to a single referrer.
+CREATE TABLE public.referral (
+    id integer NOT NULL,
+    referrer_id integer NOT NULL,
+    referred_id integer NOT NULL,
+    code character varying(16) NOT NULL,
+    status public.referral_status DEFAULT 'Pending'::public.referral_status NOT NULL,
+    signup_ip text,
+    device_id text,
+    reward_coin integer,
+    reference_type character varying(32),
+    reference_id integer,
+    rejected_reason text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    rewarded_at timestamp with time zone,
+    CONSTRAINT referral_not_self CHECK ((referrer_id <> referred_id))
+);
+
+CREATE SEQUENCE public.referral_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.referral_id_seq OWNED BY public.referral.id;
+
+ALTER TABLE ONLY public.referral ALTER COLUMN id SET DEFAULT nextval('public.referral_id_seq'::regclass);
+
+ALTER TABLE ONLY public.referral
+    ADD CONSTRAINT referral_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.referral
+    ADD CONSTRAINT uq_referral_referred_id UNIQUE (referred_id);
+
+ALTER TABLE ONLY public.referral
+    ADD CONSTRAINT referral_referrer_id_fkey FOREIGN KEY (referrer_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.referral
+    ADD CONSTRAINT referral_referred_id_fkey FOREIGN KEY (referred_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_referral_referrer_id ON public.referral USING btree (referrer_id, created_at DESC);
+
+CREATE INDEX idx_referral_device_id ON public.referral USING btree (device_id);
+
+CREATE INDEX idx_referral_signup_ip ON public.referral USING btree (signup_ip);
@@ -0,0 +1,13 @@
+ALTER TABLE public.post
+    DROP COLUMN IF EXISTS boosted;
+
+ALTER TABLE public.search_combined
+    DROP COLUMN IF EXISTS boosted;
+
+DROP TABLE IF EXISTS public.post_boost CASCADE;
+
+DROP TABLE IF EXISTS public.post_boost_package CASCADE;
+
+DROP TYPE IF EXISTS public.post_boost_status;
+
+DROP TYPE IF EXISTS public.post_boost_kind;
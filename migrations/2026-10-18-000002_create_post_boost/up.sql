@@ -0,0 +1,101 @@
+CREATE TYPE public.post_boost_kind AS ENUM (
+    'FeaturedLocal',
+    'FeaturedCategory',
+    'TopOfSearch'
+);
+
+CREATE TYPE public.post_boost_status AS ENUM (
+    'Active',
+    'Expired',
+    'Refunded'
+);
+
+-- Purchasable boost packages, managed by admins.
+CREATE TABLE public.post_boost_package (
+    id integer NOT NULL,
+    name character varying(100) NOT NULL,
+    kind public.post_boost_kind NOT NULL,
+    duration_days integer NOT NULL,
+    price_coin integer NOT NULL,
+    is_active boolean DEFAULT true NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT post_boost_package_duration_positive CHECK ((duration_days > 0)),
+    CONSTRAINT post_boost_package_price_positive CHECK ((price_coin > 0))
+);
+
+CREATE SEQUENCE public.post_boost_package_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.post_boost_package_id_seq OWNED BY public.post_boost_package.id;
+
+ALTER TABLE ONLY public.post_boost_package ALTER COLUMN id SET DEFAULT nextval('public.post_boost_package_id_seq'::regclass);
+
+ALTER TABLE ONLY public.post_boost_package
+    ADD CONSTRAINT post_boost_package_pkey PRIMARY KEY (id);
+
+-- A boost bought for a single post. The package price and duration are
+-- copied so later package edits do not affect running boosts or refunds.
+CREATE TABLE public.post_boost (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    package_id integer NOT NULL,
+    local_user_id integer NOT NULL,
+    kind public.post_boost_kind NOT NULL,
+    price_coin integer NOT NULL,
+    status public.post_boost_status DEFAULT 'Active'::public.post_boost_status NOT NULL,
+    starts_at timestamp with time zone NOT NULL,
+    ends_at timestamp with time zone NOT NULL,
+    refunded_coin integer,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT post_boost_window CHECK ((ends_at > starts_at))
+);
+
+CREATE SEQUENCE public.post_boost_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.post_boost_id_seq OWNED BY public.post_boost.id;
+
+ALTER TABLE ONLY public.post_boost ALTER COLUMN id SET DEFAULT nextval('public.post_boost_id_seq'::regclass);
+
+ALTER TABLE ONLY public.post_boost
+    ADD CONSTRAINT post_boost_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.post_boost
+    ADD CONSTRAINT post_boost_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.post_boost
+    ADD CONSTRAINT post_boost_package_id_fkey FOREIGN KEY (package_id) REFERENCES public.post_boost_package(id) ON UPDATE CASCADE;
+
+ALTER TABLE ONLY public.post_boost
+    ADD CONSTRAINT post_boost_local_user_id_fkey FOREIGN KEY (local_user_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_post_boost_post_id ON public.post_boost USING btree (post_id, kind);
+
+CREATE INDEX idx_post_boost_active_ends_at ON public.post_boost USING btree (ends_at) WHERE (status = 'Active'::public.post_boost_status);
+
+-- Search ranks boosted posts ahead of the regular sort.
+ALTER TABLE public.search_combined
+    ADD COLUMN boosted boolean DEFAULT false NOT NULL;
+
+-- Hot and active post listings rank boosted posts first as well.
+ALTER TABLE public.post
+    ADD COLUMN boosted boolean DEFAULT false NOT NULL;
+
+INSERT INTO public.post_boost_package (name, kind, duration_days, price_coin)
+VALUES
+    ('Featured 3 days', 'FeaturedLocal', 3, 300),
+    ('Featured 7 days', 'FeaturedLocal', 7, 600),
+    ('Category featured 7 days', 'FeaturedCategory', 7, 400),
+    ('Top of search 7 days', 'TopOfSearch', 7, 250);
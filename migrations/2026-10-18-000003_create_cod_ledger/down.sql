@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS public.cod_settlement CASCADE;
+
+DROP TABLE IF EXISTS public.cod_collection CASCADE;
+
+DROP TYPE IF EXISTS public.cod_settlement_method;
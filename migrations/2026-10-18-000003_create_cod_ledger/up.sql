@@ -0,0 +1,88 @@
+CREATE TYPE public.cod_settlement_method AS ENUM (
+    'EarningsDeduction',
+    'WalletPayment',
+    'CashHandover'
+);
+
+-- Cash collected by a rider on a cash-on-delivery job. The rider owes
+-- `amount_coin - settled_coin` until it has been remitted to the employer.
+CREATE TABLE public.cod_collection (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    rider_id integer NOT NULL,
+    employer_id integer NOT NULL,
+    cod_amount double precision NOT NULL,
+    amount_coin integer NOT NULL,
+    settled_coin integer DEFAULT 0 NOT NULL,
+    collected_at timestamp with time zone DEFAULT now() NOT NULL,
+    settled_at timestamp with time zone,
+    CONSTRAINT cod_collection_amount_positive CHECK ((amount_coin > 0)),
+    CONSTRAINT cod_collection_settled_range CHECK (((settled_coin >= 0) AND (settled_coin <= amount_coin)))
+);
+
+CREATE SEQUENCE public.cod_collection_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.cod_collection_id_seq OWNED BY public.cod_collection.id;
+
+ALTER TABLE ONLY public.cod_collection ALTER COLUMN id SET DEFAULT nextval('public.cod_collection_id_seq'::regclass);
+
+ALTER TABLE ONLY public.cod_collection
+    ADD CONSTRAINT cod_collection_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.cod_collection
+    ADD CONSTRAINT uq_cod_collection_post_id UNIQUE (post_id);
+
+ALTER TABLE ONLY public.cod_collection
+    ADD CONSTRAINT cod_collection_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.cod_collection
+    ADD CONSTRAINT cod_collection_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.cod_collection
+    ADD CONSTRAINT cod_collection_employer_id_fkey FOREIGN KEY (employer_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_cod_collection_rider_open ON public.cod_collection USING btree (rider_id, collected_at) WHERE (settled_at IS NULL);
+
+CREATE INDEX idx_cod_collection_employer_id ON public.cod_collection USING btree (employer_id, collected_at DESC);
+
+-- A settlement paid by (or on behalf of) a rider, allocated oldest
+-- collection first.
+CREATE TABLE public.cod_settlement (
+    id integer NOT NULL,
+    rider_id integer NOT NULL,
+    method public.cod_settlement_method NOT NULL,
+    amount_coin integer NOT NULL,
+    recorded_by_person_id integer,
+    note text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT cod_settlement_amount_positive CHECK ((amount_coin > 0))
+);
+
+CREATE SEQUENCE public.cod_settlement_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.cod_settlement_id_seq OWNED BY public.cod_settlement.id;
+
+ALTER TABLE ONLY public.cod_settlement ALTER COLUMN id SET DEFAULT nextval('public.cod_settlement_id_seq'::regclass);
+
+ALTER TABLE ONLY public.cod_settlement
+    ADD CONSTRAINT cod_settlement_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.cod_settlement
+    ADD CONSTRAINT cod_settlement_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.cod_settlement
+    ADD CONSTRAINT cod_settlement_recorded_by_person_id_fkey FOREIGN KEY (recorded_by_person_id) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_cod_settlement_rider_id ON public.cod_settlement USING btree (rider_id, created_at DESC);
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS public.rider_earning_hold CASCADE;
+
+ALTER TABLE public.rider
+    ADD COLUMN total_earnings double precision DEFAULT 0 NOT NULL,
+    ADD COLUMN pending_earnings double precision DEFAULT 0 NOT NULL;
@@ -0,0 +1,46 @@
+-- Rider earnings are derived from the wallet ledger; the float columns were
+-- never kept in sync with delivery payouts.
+ALTER TABLE public.rider
+    DROP COLUMN total_earnings,
+    DROP COLUMN pending_earnings;
+
+-- A delivery payout reserved in the rider's wallet until its clearing period
+-- ends. While `released_at` is null the coins sit in `balance_outstanding`.
+CREATE TABLE public.rider_earning_hold (
+    id integer NOT NULL,
+    rider_id integer NOT NULL,
+    post_id integer NOT NULL,
+    amount_coin integer NOT NULL,
+    clears_at timestamp with time zone NOT NULL,
+    released_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT rider_earning_hold_amount_positive CHECK ((amount_coin > 0))
+);
+
+CREATE SEQUENCE public.rider_earning_hold_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.rider_earning_hold_id_seq OWNED BY public.rider_earning_hold.id;
+
+ALTER TABLE ONLY public.rider_earning_hold ALTER COLUMN id SET DEFAULT nextval('public.rider_earning_hold_id_seq'::regclass);
+
+ALTER TABLE ONLY public.rider_earning_hold
+    ADD CONSTRAINT rider_earning_hold_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.rider_earning_hold
+    ADD CONSTRAINT uq_rider_earning_hold_post_id UNIQUE (post_id);
+
+ALTER TABLE ONLY public.rider_earning_hold
+    ADD CONSTRAINT rider_earning_hold_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.rider_earning_hold
+    ADD CONSTRAINT rider_earning_hold_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_rider_earning_hold_due ON public.rider_earning_hold USING btree (clears_at) WHERE (released_at IS NULL);
+
+CREATE INDEX idx_rider_earning_hold_rider_open ON public.rider_earning_hold USING btree (rider_id) WHERE (released_at IS NULL);
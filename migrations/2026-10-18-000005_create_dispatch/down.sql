@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS public.dispatch_offer CASCADE;
+
+DROP TABLE IF EXISTS public.dispatch_request CASCADE;
+
+DROP TYPE IF EXISTS public.dispatch_offer_status;
+
+DROP TYPE IF EXISTS public.dispatch_status;
+
+DROP TYPE IF EXISTS public.dispatch_job_kind;
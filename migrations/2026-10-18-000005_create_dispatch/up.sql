@@ -0,0 +1,105 @@
+CREATE TYPE public.dispatch_job_kind AS ENUM (
+    'Delivery',
+    'Ride'
+);
+
+CREATE TYPE public.dispatch_status AS ENUM (
+    'Searching',
+    'Accepted',
+    'Exhausted',
+    'Cancelled'
+);
+
+CREATE TYPE public.dispatch_offer_status AS ENUM (
+    'Offered',
+    'Accepted',
+    'Declined',
+    'Expired',
+    'Superseded'
+);
+
+-- An automatic search for a rider on a delivery or ride post. Offers go out
+-- in waves; when the waves run out the job stays on the open list.
+CREATE TABLE public.dispatch_request (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    job_kind public.dispatch_job_kind NOT NULL,
+    vehicle_type public.vehicle_type,
+    pickup_lat double precision NOT NULL,
+    pickup_lng double precision NOT NULL,
+    status public.dispatch_status DEFAULT 'Searching'::public.dispatch_status NOT NULL,
+    wave integer DEFAULT 0 NOT NULL,
+    accepted_rider_id integer,
+    next_wave_at timestamp with time zone DEFAULT now() NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.dispatch_request_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.dispatch_request_id_seq OWNED BY public.dispatch_request.id;
+
+ALTER TABLE ONLY public.dispatch_request ALTER COLUMN id SET DEFAULT nextval('public.dispatch_request_id_seq'::regclass);
+
+ALTER TABLE ONLY public.dispatch_request
+    ADD CONSTRAINT dispatch_request_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.dispatch_request
+    ADD CONSTRAINT uq_dispatch_request_post_id UNIQUE (post_id);
+
+ALTER TABLE ONLY public.dispatch_request
+    ADD CONSTRAINT dispatch_request_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.dispatch_request
+    ADD CONSTRAINT dispatch_request_accepted_rider_id_fkey FOREIGN KEY (accepted_rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_dispatch_request_searching ON public.dispatch_request USING btree (next_wave_at) WHERE (status = 'Searching'::public.dispatch_status);
+
+-- A job offer sent to one rider in one wave.
+CREATE TABLE public.dispatch_offer (
+    id integer NOT NULL,
+    dispatch_request_id integer NOT NULL,
+    rider_id integer NOT NULL,
+    wave integer NOT NULL,
+    distance_km double precision NOT NULL,
+    score double precision NOT NULL,
+    status public.dispatch_offer_status DEFAULT 'Offered'::public.dispatch_offer_status NOT NULL,
+    offered_at timestamp with time zone DEFAULT now() NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    responded_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.dispatch_offer_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.dispatch_offer_id_seq OWNED BY public.dispatch_offer.id;
+
+ALTER TABLE ONLY public.dispatch_offer ALTER COLUMN id SET DEFAULT nextval('public.dispatch_offer_id_seq'::regclass);
+
+ALTER TABLE ONLY public.dispatch_offer
+    ADD CONSTRAINT dispatch_offer_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.dispatch_offer
+    ADD CONSTRAINT uq_dispatch_offer_request_rider UNIQUE (dispatch_request_id, rider_id);
+
+ALTER TABLE ONLY public.dispatch_offer
+    ADD CONSTRAINT dispatch_offer_dispatch_request_id_fkey FOREIGN KEY (dispatch_request_id) REFERENCES public.dispatch_request(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.dispatch_offer
+    ADD CONSTRAINT dispatch_offer_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_dispatch_offer_rider_open ON public.dispatch_offer USING btree (rider_id, expires_at) WHERE (status = 'Offered'::public.dispatch_offer_status);
+
+-- Acceptance rate lookups scan a rider's answered offers
+CREATE INDEX idx_dispatch_offer_rider_status ON public.dispatch_offer USING btree (rider_id, status);
@@ -0,0 +1,6 @@
+ALTER TABLE public.ride_session
+    DROP COLUMN IF EXISTS price_multiplier;
+
+DROP TABLE IF EXISTS public.pricing_rule CASCADE;
+
+DROP TYPE IF EXISTS public.pricing_rule_kind;
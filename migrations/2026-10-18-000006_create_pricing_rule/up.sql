@@ -0,0 +1,60 @@
+CREATE TYPE public.pricing_rule_kind AS ENUM (
+    'TimeWindow',
+    'DayOfWeek',
+    'Holiday',
+    'Surge'
+);
+
+-- A multiplier layered on a pricing config. Matching time, day and holiday
+-- rules stack; of the matching surge rules only the highest applies.
+CREATE TABLE public.pricing_rule (
+    id integer NOT NULL,
+    pricing_config_id integer NOT NULL,
+    name character varying(100) NOT NULL,
+    kind public.pricing_rule_kind NOT NULL,
+    multiplier double precision NOT NULL,
+    start_minute integer,
+    end_minute integer,
+    days_of_week integer[],
+    holiday_date date,
+    zone_lat double precision,
+    zone_lng double precision,
+    zone_radius_km double precision,
+    demand_ratio_threshold double precision,
+    is_active boolean DEFAULT true NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT pricing_rule_multiplier_positive CHECK (multiplier > 0),
+    CONSTRAINT pricing_rule_minutes_range CHECK (
+        (start_minute IS NULL OR start_minute BETWEEN 0 AND 1439)
+        AND (end_minute IS NULL OR end_minute BETWEEN 0 AND 1440)
+    ),
+    CONSTRAINT pricing_rule_zone_complete CHECK (
+        (zone_lat IS NULL) = (zone_lng IS NULL)
+        AND (zone_lat IS NULL) = (zone_radius_km IS NULL)
+    )
+);
+
+CREATE SEQUENCE public.pricing_rule_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.pricing_rule_id_seq OWNED BY public.pricing_rule.id;
+
+ALTER TABLE ONLY public.pricing_rule ALTER COLUMN id SET DEFAULT nextval('public.pricing_rule_id_seq'::regclass);
+
+ALTER TABLE ONLY public.pricing_rule
+    ADD CONSTRAINT pricing_rule_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.pricing_rule
+    ADD CONSTRAINT pricing_rule_pricing_config_id_fkey FOREIGN KEY (pricing_config_id) REFERENCES public.pricing_config(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_pricing_rule_config_active ON public.pricing_rule USING btree (pricing_config_id, is_active);
+
+-- Multiplier in force when the ride was requested; NULL until it is locked
+ALTER TABLE public.ride_session
+    ADD COLUMN price_multiplier double precision;
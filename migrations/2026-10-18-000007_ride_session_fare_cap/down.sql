@@ -0,0 +1,2 @@
+ALTER TABLE public.ride_session
+    DROP COLUMN IF EXISTS fare_cap_coin;
@@ -0,0 +1,4 @@
+-- Upper bound from a fare quote the employer accepted; the meter never
+-- charges more than this
+ALTER TABLE public.ride_session
+    ADD COLUMN fare_cap_coin integer;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS public.ride_meter_flag CASCADE;
+
+DROP TYPE IF EXISTS public.meter_check_stage;
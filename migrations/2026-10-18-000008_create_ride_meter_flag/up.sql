@@ -0,0 +1,48 @@
+CREATE TYPE public.meter_check_stage AS ENUM (
+    'Meter',
+    'Completion'
+);
+
+-- A rider-reported meter reading that diverged from the distance and time
+-- recomputed from GPS history. Kept for admin review.
+CREATE TABLE public.ride_meter_flag (
+    id integer NOT NULL,
+    ride_session_id integer NOT NULL,
+    stage public.meter_check_stage NOT NULL,
+    reported_distance_km double precision NOT NULL,
+    server_distance_km double precision NOT NULL,
+    reported_minutes integer NOT NULL,
+    server_minutes integer NOT NULL,
+    points_used integer NOT NULL,
+    points_dropped integer NOT NULL,
+    rejected boolean DEFAULT false NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    reviewed_at timestamp with time zone,
+    reviewed_by_person_id integer,
+    resolution_note text
+);
+
+CREATE SEQUENCE public.ride_meter_flag_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.ride_meter_flag_id_seq OWNED BY public.ride_meter_flag.id;
+
+ALTER TABLE ONLY public.ride_meter_flag ALTER COLUMN id SET DEFAULT nextval('public.ride_meter_flag_id_seq'::regclass);
+
+ALTER TABLE ONLY public.ride_meter_flag
+    ADD CONSTRAINT ride_meter_flag_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.ride_meter_flag
+    ADD CONSTRAINT ride_meter_flag_ride_session_id_fkey FOREIGN KEY (ride_session_id) REFERENCES public.ride_session(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.ride_meter_flag
+    ADD CONSTRAINT ride_meter_flag_reviewed_by_person_id_fkey FOREIGN KEY (reviewed_by_person_id) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_ride_meter_flag_unreviewed ON public.ride_meter_flag USING btree (created_at) WHERE (reviewed_at IS NULL);
+
+CREATE INDEX idx_ride_meter_flag_ride_session_id ON public.ride_meter_flag USING btree (ride_session_id);
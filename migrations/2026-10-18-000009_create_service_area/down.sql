@@ -0,0 +1,7 @@
+ALTER TABLE public.dispatch_request
+    DROP COLUMN IF EXISTS service_area_id;
+
+ALTER TABLE public.pricing_rule
+    DROP COLUMN IF EXISTS service_area_id;
+
+DROP TABLE IF EXISTS public.service_area CASCADE;
@@ -0,0 +1,51 @@
+-- An area we operate in, imported from GeoJSON. Trips must start and end
+-- inside an active area once any area exists.
+CREATE TABLE public.service_area (
+    id integer NOT NULL,
+    name character varying(255) NOT NULL,
+    geometry jsonb NOT NULL,
+    min_lat double precision NOT NULL,
+    max_lat double precision NOT NULL,
+    min_lng double precision NOT NULL,
+    max_lng double precision NOT NULL,
+    pricing_config_id integer,
+    allowed_vehicle_types public.vehicle_type[],
+    is_active boolean DEFAULT true NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT service_area_bbox_valid CHECK (min_lat <= max_lat AND min_lng <= max_lng)
+);
+
+CREATE SEQUENCE public.service_area_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.service_area_id_seq OWNED BY public.service_area.id;
+
+ALTER TABLE ONLY public.service_area ALTER COLUMN id SET DEFAULT nextval('public.service_area_id_seq'::regclass);
+
+ALTER TABLE ONLY public.service_area
+    ADD CONSTRAINT service_area_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.service_area
+    ADD CONSTRAINT service_area_pricing_config_id_fkey FOREIGN KEY (pricing_config_id) REFERENCES public.pricing_config(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_service_area_active_bbox ON public.service_area USING btree (min_lat, max_lat) WHERE is_active;
+
+-- Pricing rules can be limited to one service area instead of a circle
+ALTER TABLE public.pricing_rule
+    ADD COLUMN service_area_id integer;
+
+ALTER TABLE ONLY public.pricing_rule
+    ADD CONSTRAINT pricing_rule_service_area_id_fkey FOREIGN KEY (service_area_id) REFERENCES public.service_area(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- Dispatch only offers a job to riders inside the pickup's area
+ALTER TABLE public.dispatch_request
+    ADD COLUMN service_area_id integer;
+
+ALTER TABLE ONLY public.dispatch_request
+    ADD CONSTRAINT dispatch_request_service_area_id_fkey FOREIGN KEY (service_area_id) REFERENCES public.service_area(id) ON UPDATE CASCADE ON DELETE SET NULL;
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS public.delivery_proof CASCADE;
+
+ALTER TABLE public.delivery_details
+    DROP COLUMN IF EXISTS requires_photo,
+    DROP COLUMN IF EXISTS requires_otp;
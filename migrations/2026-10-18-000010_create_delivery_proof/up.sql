@@ -0,0 +1,46 @@
+-- Evidence the rider must capture when marking a delivery Delivered
+ALTER TABLE public.delivery_details
+    ADD COLUMN requires_photo boolean DEFAULT false NOT NULL,
+    ADD COLUMN requires_otp boolean DEFAULT false NOT NULL;
+
+-- Proof of delivery. The row is created with a receiver OTP when the parcel
+-- is picked up and completed when the rider marks the delivery Delivered.
+CREATE TABLE public.delivery_proof (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    rider_id integer NOT NULL,
+    photo_url text,
+    signature_url text,
+    otp_code character varying(4),
+    otp_attempts integer DEFAULT 0 NOT NULL,
+    otp_verified_at timestamp with time zone,
+    delivered_lat double precision,
+    delivered_lng double precision,
+    submitted_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.delivery_proof_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.delivery_proof_id_seq OWNED BY public.delivery_proof.id;
+
+ALTER TABLE ONLY public.delivery_proof ALTER COLUMN id SET DEFAULT nextval('public.delivery_proof_id_seq'::regclass);
+
+ALTER TABLE ONLY public.delivery_proof
+    ADD CONSTRAINT delivery_proof_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.delivery_proof
+    ADD CONSTRAINT delivery_proof_post_id_key UNIQUE (post_id);
+
+ALTER TABLE ONLY public.delivery_proof
+    ADD CONSTRAINT delivery_proof_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.delivery_proof
+    ADD CONSTRAINT delivery_proof_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
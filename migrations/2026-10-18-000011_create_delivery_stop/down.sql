@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS public.delivery_stop CASCADE;
+
+DROP TYPE IF EXISTS public.delivery_stop_status;
@@ -0,0 +1,55 @@
+DROP TYPE IF EXISTS public.delivery_stop_status;
+CREATE TYPE public.delivery_stop_status AS ENUM (
+    'Pending',
+    'Arrived',
+    'Completed',
+    'Skipped'
+);
+
+-- Ordered drop-offs of a multi-stop delivery. The delivery's own dropoff
+-- columns mirror the last stop. Each leg (previous stop -> this stop) is
+-- priced on its own.
+CREATE TABLE public.delivery_stop (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    stop_order integer NOT NULL,
+    address text NOT NULL,
+    lat double precision NOT NULL,
+    lng double precision NOT NULL,
+    contact_name character varying,
+    contact_phone character varying,
+    cod_amount double precision,
+    status public.delivery_stop_status DEFAULT 'Pending'::public.delivery_stop_status NOT NULL,
+    skip_reason text,
+    leg_distance_km double precision DEFAULT 0 NOT NULL,
+    leg_fee integer DEFAULT 0 NOT NULL,
+    photo_url text,
+    signature_url text,
+    completed_lat double precision,
+    completed_lng double precision,
+    arrived_at timestamp with time zone,
+    completed_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.delivery_stop_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.delivery_stop_id_seq OWNED BY public.delivery_stop.id;
+
+ALTER TABLE ONLY public.delivery_stop ALTER COLUMN id SET DEFAULT nextval('public.delivery_stop_id_seq'::regclass);
+
+ALTER TABLE ONLY public.delivery_stop
+    ADD CONSTRAINT delivery_stop_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.delivery_stop
+    ADD CONSTRAINT delivery_stop_post_id_stop_order_key UNIQUE (post_id, stop_order);
+
+ALTER TABLE ONLY public.delivery_stop
+    ADD CONSTRAINT delivery_stop_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
@@ -0,0 +1,8 @@
+ALTER TABLE public.delivery_details
+    DROP COLUMN IF EXISTS scheduled_pickup_at,
+    DROP COLUMN IF EXISTS rider_confirmed_at,
+    DROP COLUMN IF EXISTS reminder_sent_at;
+
+ALTER TABLE public.ride_session
+    DROP COLUMN IF EXISTS scheduled_pickup_at,
+    DROP COLUMN IF EXISTS reminder_sent_at;
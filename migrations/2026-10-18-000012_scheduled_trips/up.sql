@@ -0,0 +1,14 @@
+-- Rides and deliveries booked for a later pickup time. A rider may reserve
+-- one in advance and must confirm before the deadline, or it is cancelled.
+ALTER TABLE public.ride_session
+    ADD COLUMN scheduled_pickup_at timestamp with time zone,
+    ADD COLUMN reminder_sent_at timestamp with time zone;
+
+ALTER TABLE public.delivery_details
+    ADD COLUMN scheduled_pickup_at timestamp with time zone,
+    ADD COLUMN rider_confirmed_at timestamp with time zone,
+    ADD COLUMN reminder_sent_at timestamp with time zone;
+
+CREATE INDEX idx_ride_session_scheduled_pickup_at ON public.ride_session USING btree (scheduled_pickup_at) WHERE (scheduled_pickup_at IS NOT NULL);
+
+CREATE INDEX idx_delivery_details_scheduled_pickup_at ON public.delivery_details USING btree (scheduled_pickup_at) WHERE (scheduled_pickup_at IS NOT NULL);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_rider_license_expiry_date;
+
+ALTER TABLE public.rider
+    DROP COLUMN IF EXISTS license_reminder_days,
+    DROP COLUMN IF EXISTS documents_expired_at;
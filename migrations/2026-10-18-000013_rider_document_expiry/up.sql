@@ -0,0 +1,7 @@
+-- Track license expiry reminders and suspensions. A rider whose license
+-- expires is deactivated until new documents are approved.
+ALTER TABLE public.rider
+    ADD COLUMN license_reminder_days integer,
+    ADD COLUMN documents_expired_at timestamp with time zone;
+
+CREATE INDEX idx_rider_license_expiry_date ON public.rider USING btree (license_expiry_date) WHERE (license_expiry_date IS NOT NULL);
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS record_status_change ON public.delivery_details;
+
+DROP TRIGGER IF EXISTS record_status_change ON public.ride_session;
+
+DROP FUNCTION IF EXISTS public.record_trip_status_change();
+
+DROP TABLE IF EXISTS public.trip_status_history CASCADE;
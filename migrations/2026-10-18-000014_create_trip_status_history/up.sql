@@ -0,0 +1,59 @@
+-- Every status a delivery or ride has passed through, so an exported track
+-- can be annotated with when the trip was assigned, picked up, delivered and
+-- so on. Rows are written by triggers, which catches every code path that
+-- changes a status.
+CREATE TABLE public.trip_status_history (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    status public.trip_status NOT NULL,
+    reason text,
+    changed_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE SEQUENCE public.trip_status_history_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.trip_status_history_id_seq OWNED BY public.trip_status_history.id;
+
+ALTER TABLE ONLY public.trip_status_history ALTER COLUMN id SET DEFAULT nextval('public.trip_status_history_id_seq'::regclass);
+
+ALTER TABLE ONLY public.trip_status_history
+    ADD CONSTRAINT trip_status_history_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.trip_status_history
+    ADD CONSTRAINT trip_status_history_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_trip_status_history_post_changed ON public.trip_status_history USING btree (post_id, changed_at);
+
+CREATE FUNCTION public.record_trip_status_change() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    IF (TG_OP = 'UPDATE' AND NEW.status IS NOT DISTINCT FROM OLD.status) THEN
+        RETURN NEW;
+    END IF;
+    INSERT INTO public.trip_status_history (post_id, status, reason)
+        VALUES (NEW.post_id, NEW.status, CASE WHEN NEW.status = 'Cancelled' THEN NEW.cancellation_reason END);
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER record_status_change AFTER INSERT OR UPDATE OF status ON public.delivery_details
+    FOR EACH ROW EXECUTE PROCEDURE public.record_trip_status_change();
+
+CREATE TRIGGER record_status_change AFTER INSERT OR UPDATE OF status ON public.ride_session
+    FOR EACH ROW EXECUTE PROCEDURE public.record_trip_status_change();
+
+-- Seed the current status of existing trips so their exports are not empty
+INSERT INTO public.trip_status_history (post_id, status, reason, changed_at)
+SELECT post_id, status, cancellation_reason, COALESCE(updated_at, created_at)
+FROM public.delivery_details;
+
+INSERT INTO public.trip_status_history (post_id, status, reason, changed_at)
+SELECT post_id, status, cancellation_reason, COALESCE(updated_at, created_at)
+FROM public.ride_session;
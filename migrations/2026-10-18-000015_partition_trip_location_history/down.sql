@@ -0,0 +1,37 @@
+DROP TABLE IF EXISTS public.trip_location_downsample CASCADE;
+
+CREATE TABLE public.trip_location_history_unpartitioned (
+    id bigint DEFAULT nextval('public.trip_location_history_id_seq'::regclass) NOT NULL,
+    post_id integer NOT NULL,
+    rider_id integer NOT NULL,
+    lat double precision NOT NULL,
+    lng double precision NOT NULL,
+    heading double precision,
+    speed_kmh double precision,
+    accuracy_m double precision,
+    recorded_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+INSERT INTO public.trip_location_history_unpartitioned
+SELECT * FROM public.trip_location_history;
+
+ALTER SEQUENCE public.trip_location_history_id_seq OWNED BY public.trip_location_history_unpartitioned.id;
+
+DROP TABLE public.trip_location_history CASCADE;
+
+DROP FUNCTION IF EXISTS public.create_trip_location_history_partitions(timestamp with time zone, integer);
+
+DROP FUNCTION IF EXISTS public.drop_trip_location_history_partitions(timestamp with time zone);
+
+ALTER TABLE public.trip_location_history_unpartitioned RENAME TO trip_location_history;
+
+ALTER TABLE ONLY public.trip_location_history
+    ADD CONSTRAINT trip_location_history_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.trip_location_history
+    ADD CONSTRAINT trip_location_history_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.trip_location_history
+    ADD CONSTRAINT trip_location_history_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_trip_location_history_post_time ON public.trip_location_history USING btree (post_id, recorded_at DESC);
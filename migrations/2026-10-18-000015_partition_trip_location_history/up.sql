@@ -0,0 +1,114 @@
+-- Partition trip_location_history by month of recorded_at so old fixes can be
+-- dropped a whole month at a time. Partitions are created ahead of time by a
+-- maintenance job; anything outside them lands in the default partition.
+ALTER TABLE public.trip_location_history RENAME TO trip_location_history_unpartitioned;
+
+ALTER INDEX public.idx_trip_location_history_post_time RENAME TO idx_trip_location_history_unpartitioned_post_time;
+
+ALTER TABLE public.trip_location_history_unpartitioned RENAME CONSTRAINT trip_location_history_pkey TO trip_location_history_unpartitioned_pkey;
+
+CREATE TABLE public.trip_location_history (
+    id bigint DEFAULT nextval('public.trip_location_history_id_seq'::regclass) NOT NULL,
+    post_id integer NOT NULL,
+    rider_id integer NOT NULL,
+    lat double precision NOT NULL,
+    lng double precision NOT NULL,
+    heading double precision,
+    speed_kmh double precision,
+    accuracy_m double precision,
+    recorded_at timestamp with time zone DEFAULT now() NOT NULL
+)
+PARTITION BY RANGE (recorded_at);
+
+-- Keep the id sequence when the old table is dropped
+ALTER SEQUENCE public.trip_location_history_id_seq OWNED BY public.trip_location_history.id;
+
+ALTER TABLE public.trip_location_history
+    ADD CONSTRAINT trip_location_history_pkey PRIMARY KEY (id, recorded_at);
+
+ALTER TABLE public.trip_location_history
+    ADD CONSTRAINT trip_location_history_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON DELETE CASCADE;
+
+ALTER TABLE public.trip_location_history
+    ADD CONSTRAINT trip_location_history_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_trip_location_history_post_time ON public.trip_location_history USING btree (post_id, recorded_at DESC);
+
+CREATE INDEX idx_trip_location_history_rider_time ON public.trip_location_history USING btree (rider_id, recorded_at DESC);
+
+CREATE TABLE public.trip_location_history_default PARTITION OF public.trip_location_history DEFAULT;
+
+-- Create the monthly partitions for `_months` months starting with the month
+-- of `_from`. Returns how many were created.
+CREATE FUNCTION public.create_trip_location_history_partitions(_from timestamp with time zone, _months integer) RETURNS integer
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    _start timestamp;
+    _name text;
+    _created integer := 0;
+BEGIN
+    FOR i IN 0 .. _months - 1 LOOP
+        _start := date_trunc('month', _from AT TIME ZONE 'UTC') + make_interval(months => i);
+        _name := format('trip_location_history_p%s', to_char(_start, 'YYYYMM'));
+        IF to_regclass(format('public.%I', _name)) IS NULL THEN
+            EXECUTE format('CREATE TABLE public.%I PARTITION OF public.trip_location_history FOR VALUES FROM (%L) TO (%L)',
+                _name, _start AT TIME ZONE 'UTC', (_start + interval '1 month') AT TIME ZONE 'UTC');
+            _created := _created + 1;
+        END IF;
+    END LOOP;
+    RETURN _created;
+END;
+$$;
+
+-- Drop the monthly partitions that end on or before `_before`. Returns how
+-- many rows they held.
+CREATE FUNCTION public.drop_trip_location_history_partitions(_before timestamp with time zone) RETURNS bigint
+    LANGUAGE plpgsql
+    AS $$
+DECLARE
+    _partition record;
+    _rows bigint;
+    _dropped bigint := 0;
+BEGIN
+    FOR _partition IN
+        SELECT c.relname
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        WHERE i.inhparent = 'public.trip_location_history'::regclass
+          AND c.relname ~ '^trip_location_history_p[0-9]{6}$'
+    LOOP
+        IF (to_date(right(_partition.relname, 6), 'YYYYMM') + interval '1 month') AT TIME ZONE 'UTC' <= _before THEN
+            EXECUTE format('SELECT count(*) FROM public.%I', _partition.relname) INTO _rows;
+            EXECUTE format('DROP TABLE public.%I', _partition.relname);
+            _dropped := _dropped + _rows;
+        END IF;
+    END LOOP;
+    RETURN _dropped;
+END;
+$$;
+
+-- Partitions for every month with existing fixes, plus the next few
+SELECT public.create_trip_location_history_partitions(since, ((extract(year FROM age(now(), since)) * 12 + extract(month FROM age(now(), since)))::integer + 3))
+FROM (
+    SELECT date_trunc('month', COALESCE(min(recorded_at), now())) AS since
+    FROM public.trip_location_history_unpartitioned) AS existing;
+
+INSERT INTO public.trip_location_history
+SELECT * FROM public.trip_location_history_unpartitioned;
+
+DROP TABLE public.trip_location_history_unpartitioned;
+
+-- Finished trips whose fixes have been thinned out, so each is only done once
+CREATE TABLE public.trip_location_downsample (
+    post_id integer NOT NULL,
+    rows_before integer NOT NULL,
+    rows_after integer NOT NULL,
+    downsampled_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+ALTER TABLE ONLY public.trip_location_downsample
+    ADD CONSTRAINT trip_location_downsample_pkey PRIMARY KEY (post_id);
+
+ALTER TABLE ONLY public.trip_location_downsample
+    ADD CONSTRAINT trip_location_downsample_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS revoke_tracking_links ON public.delivery_details;
+
+DROP TRIGGER IF EXISTS revoke_tracking_links ON public.ride_session;
+
+DROP FUNCTION IF EXISTS public.revoke_tracking_links();
+
+DROP TABLE IF EXISTS public.tracking_link CASCADE;
@@ -0,0 +1,55 @@
+-- Public share links that let a receiver or passenger without an account
+-- follow a delivery or ride. A link stops working when it expires or when
+-- the trip is delivered or cancelled.
+CREATE TABLE public.tracking_link (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    job_kind public.dispatch_job_kind NOT NULL,
+    token character varying(32) NOT NULL,
+    expires_at timestamp with time zone NOT NULL,
+    revoked_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE SEQUENCE public.tracking_link_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.tracking_link_id_seq OWNED BY public.tracking_link.id;
+
+ALTER TABLE ONLY public.tracking_link ALTER COLUMN id SET DEFAULT nextval('public.tracking_link_id_seq'::regclass);
+
+ALTER TABLE ONLY public.tracking_link
+    ADD CONSTRAINT tracking_link_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.tracking_link
+    ADD CONSTRAINT tracking_link_token_key UNIQUE (token);
+
+ALTER TABLE ONLY public.tracking_link
+    ADD CONSTRAINT tracking_link_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_tracking_link_post_active ON public.tracking_link USING btree (post_id) WHERE (revoked_at IS NULL);
+
+CREATE FUNCTION public.revoke_tracking_links() RETURNS trigger
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE public.tracking_link SET revoked_at = now()
+    WHERE post_id = NEW.post_id AND revoked_at IS NULL;
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER revoke_tracking_links AFTER UPDATE OF status ON public.delivery_details
+    FOR EACH ROW
+    WHEN (NEW.status IN ('Delivered', 'Cancelled') AND OLD.status IS DISTINCT FROM NEW.status)
+    EXECUTE PROCEDURE public.revoke_tracking_links();
+
+CREATE TRIGGER revoke_tracking_links AFTER UPDATE OF status ON public.ride_session
+    FOR EACH ROW
+    WHEN (NEW.status IN ('Delivered', 'Cancelled') AND OLD.status IS DISTINCT FROM NEW.status)
+    EXECUTE PROCEDURE public.revoke_tracking_links();
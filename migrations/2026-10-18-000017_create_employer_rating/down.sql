@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS public.employer_reliability;
+
+DROP TABLE IF EXISTS public.employer_rating;
+
+DROP TYPE IF EXISTS public.employer_rating_tag;
@@ -0,0 +1,72 @@
+-- Riders rate the employer or passenger after a delivery or ride, with
+-- optional tags describing what went right or wrong.
+CREATE TYPE public.employer_rating_tag AS ENUM (
+    'NoShow',
+    'WrongAddress',
+    'Rude',
+    'Great'
+);
+
+CREATE TABLE public.employer_rating (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    job_kind public.dispatch_job_kind NOT NULL,
+    rider_id integer NOT NULL,
+    employer_id integer NOT NULL,
+    rating smallint NOT NULL,
+    tags public.employer_rating_tag[] DEFAULT '{}'::public.employer_rating_tag[] NOT NULL,
+    comment text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT employer_rating_rating_check CHECK (((rating >= 1) AND (rating <= 5)))
+);
+
+CREATE SEQUENCE public.employer_rating_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.employer_rating_id_seq OWNED BY public.employer_rating.id;
+
+ALTER TABLE ONLY public.employer_rating ALTER COLUMN id SET DEFAULT nextval('public.employer_rating_id_seq'::regclass);
+
+ALTER TABLE ONLY public.employer_rating
+    ADD CONSTRAINT employer_rating_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.employer_rating
+    ADD CONSTRAINT uq_employer_rating UNIQUE (post_id, rider_id);
+
+ALTER TABLE ONLY public.employer_rating
+    ADD CONSTRAINT employer_rating_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.employer_rating
+    ADD CONSTRAINT employer_rating_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.employer_rating
+    ADD CONSTRAINT employer_rating_employer_id_fkey FOREIGN KEY (employer_id) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_employer_rating_employer_id ON public.employer_rating USING btree (employer_id);
+
+-- Per-employer aggregate of the ratings above, refreshed on every rating.
+CREATE TABLE public.employer_reliability (
+    employer_id integer NOT NULL,
+    rating_count integer DEFAULT 0 NOT NULL,
+    average_rating double precision DEFAULT 0 NOT NULL,
+    no_show_count integer DEFAULT 0 NOT NULL,
+    wrong_address_count integer DEFAULT 0 NOT NULL,
+    rude_count integer DEFAULT 0 NOT NULL,
+    great_count integer DEFAULT 0 NOT NULL,
+    score double precision NOT NULL,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+ALTER TABLE ONLY public.employer_reliability
+    ADD CONSTRAINT employer_reliability_pkey PRIMARY KEY (employer_id);
+
+ALTER TABLE ONLY public.employer_reliability
+    ADD CONSTRAINT employer_reliability_employer_id_fkey FOREIGN KEY (employer_id) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_employer_reliability_score ON public.employer_reliability USING btree (score);
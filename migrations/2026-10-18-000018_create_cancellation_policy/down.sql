@@ -0,0 +1,10 @@
+ALTER TABLE public.rider
+    DROP COLUMN IF EXISTS accept_banned_until;
+
+DROP TABLE IF EXISTS public.trip_cancellation;
+
+DROP TABLE IF EXISTS public.cancellation_policy;
+
+DROP TYPE IF EXISTS public.cancellation_party;
+
+DROP TYPE IF EXISTS public.cancellation_fee_kind;
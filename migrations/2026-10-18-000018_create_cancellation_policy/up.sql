@@ -0,0 +1,100 @@
+-- Cancellation fees. A policy applies to a post kind, optionally narrowed to
+-- one pricing config; cancelling within `free_minutes` of assignment is free,
+-- after that the rider is paid a flat or distance-based fee.
+CREATE TYPE public.cancellation_fee_kind AS ENUM (
+    'Flat',
+    'Distance'
+);
+
+CREATE TYPE public.cancellation_party AS ENUM (
+    'Employer',
+    'Rider',
+    'Admin',
+    'System'
+);
+
+CREATE TABLE public.cancellation_policy (
+    id integer NOT NULL,
+    post_kind public.post_kind NOT NULL,
+    pricing_config_id integer,
+    free_minutes integer DEFAULT 5 NOT NULL,
+    fee_kind public.cancellation_fee_kind DEFAULT 'Flat'::public.cancellation_fee_kind NOT NULL,
+    flat_fee_coin integer DEFAULT 0 NOT NULL,
+    fee_per_km_coin integer DEFAULT 0 NOT NULL,
+    max_fee_coin integer,
+    is_active boolean DEFAULT true NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT cancellation_policy_free_minutes_check CHECK ((free_minutes >= 0)),
+    CONSTRAINT cancellation_policy_fee_check CHECK (((flat_fee_coin >= 0) AND (fee_per_km_coin >= 0) AND ((max_fee_coin IS NULL) OR (max_fee_coin >= 0))))
+);
+
+CREATE SEQUENCE public.cancellation_policy_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.cancellation_policy_id_seq OWNED BY public.cancellation_policy.id;
+
+ALTER TABLE ONLY public.cancellation_policy ALTER COLUMN id SET DEFAULT nextval('public.cancellation_policy_id_seq'::regclass);
+
+ALTER TABLE ONLY public.cancellation_policy
+    ADD CONSTRAINT cancellation_policy_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.cancellation_policy
+    ADD CONSTRAINT cancellation_policy_pricing_config_id_fkey FOREIGN KEY (pricing_config_id) REFERENCES public.pricing_config(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- One policy per post kind and pricing config, with at most one default
+-- (no pricing config) per post kind
+CREATE UNIQUE INDEX uq_cancellation_policy_scope ON public.cancellation_policy USING btree (post_kind, COALESCE(pricing_config_id, 0));
+
+-- Every cancellation of a trip that already had a rider, with the fee the
+-- rider was paid and who cancelled.
+CREATE TABLE public.trip_cancellation (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    job_kind public.dispatch_job_kind NOT NULL,
+    rider_id integer NOT NULL,
+    cancelled_by public.cancellation_party NOT NULL,
+    policy_id integer,
+    minutes_since_assigned integer NOT NULL,
+    distance_km double precision NOT NULL,
+    fee_coin integer DEFAULT 0 NOT NULL,
+    reason text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE SEQUENCE public.trip_cancellation_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.trip_cancellation_id_seq OWNED BY public.trip_cancellation.id;
+
+ALTER TABLE ONLY public.trip_cancellation ALTER COLUMN id SET DEFAULT nextval('public.trip_cancellation_id_seq'::regclass);
+
+ALTER TABLE ONLY public.trip_cancellation
+    ADD CONSTRAINT trip_cancellation_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.trip_cancellation
+    ADD CONSTRAINT trip_cancellation_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.trip_cancellation
+    ADD CONSTRAINT trip_cancellation_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.trip_cancellation
+    ADD CONSTRAINT trip_cancellation_policy_id_fkey FOREIGN KEY (policy_id) REFERENCES public.cancellation_policy(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_trip_cancellation_post_id ON public.trip_cancellation USING btree (post_id);
+
+CREATE INDEX idx_trip_cancellation_rider_offences ON public.trip_cancellation USING btree (rider_id, created_at) WHERE (cancelled_by = 'Rider'::public.cancellation_party);
+
+-- Riders who cancel too often may not accept new jobs until this time
+ALTER TABLE public.rider
+    ADD COLUMN accept_banned_until timestamp with time zone;
@@ -0,0 +1,44 @@
+-- Cargo tariffs. A tariff sits next to a pricing config and prices what a
+-- delivery carries: a fee per weight bracket and per size class, surcharges
+-- for fragile packages and signatures, and a multiplier per vehicle type.
+-- Brackets and size classes are JSON arrays, e.g.
+--   weight_brackets: [{"upToKg": 5, "feeCoin": 0}, {"upToKg": null, "feeCoin": 3000}]
+--   size_classes:    [{"name": "small", "feeCoin": 0}, {"name": "large", "feeCoin": 2000}]
+CREATE TABLE public.cargo_tariff (
+    id integer NOT NULL,
+    pricing_config_id integer NOT NULL,
+    weight_brackets jsonb DEFAULT '[]'::jsonb NOT NULL,
+    size_classes jsonb DEFAULT '[]'::jsonb NOT NULL,
+    fragile_surcharge_coin integer DEFAULT 0 NOT NULL,
+    signature_surcharge_coin integer DEFAULT 0 NOT NULL,
+    motorcycle_multiplier double precision DEFAULT 1.0 NOT NULL,
+    bicycle_multiplier double precision DEFAULT 1.0 NOT NULL,
+    car_multiplier double precision DEFAULT 1.0 NOT NULL,
+    is_active boolean DEFAULT true NOT NULL,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT cargo_tariff_surcharge_check CHECK (((fragile_surcharge_coin >= 0) AND (signature_surcharge_coin >= 0))),
+    CONSTRAINT cargo_tariff_multiplier_check CHECK (((motorcycle_multiplier > 0) AND (bicycle_multiplier > 0) AND (car_multiplier > 0)))
+);
+
+CREATE SEQUENCE public.cargo_tariff_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.cargo_tariff_id_seq OWNED BY public.cargo_tariff.id;
+
+ALTER TABLE ONLY public.cargo_tariff ALTER COLUMN id SET DEFAULT nextval('public.cargo_tariff_id_seq'::regclass);
+
+ALTER TABLE ONLY public.cargo_tariff
+    ADD CONSTRAINT cargo_tariff_pkey PRIMARY KEY (id);
+
+-- One tariff per pricing config
+ALTER TABLE ONLY public.cargo_tariff
+    ADD CONSTRAINT cargo_tariff_pricing_config_id_key UNIQUE (pricing_config_id);
+
+ALTER TABLE ONLY public.cargo_tariff
+    ADD CONSTRAINT cargo_tariff_pricing_config_id_fkey FOREIGN KEY (pricing_config_id) REFERENCES public.pricing_config(id) ON UPDATE CASCADE ON DELETE CASCADE;
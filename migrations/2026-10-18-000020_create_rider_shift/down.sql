@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS public.rider_shift;
+
+DROP TABLE IF EXISTS public.rider_shift_slot;
+
+DROP TYPE IF EXISTS public.rider_shift_status;
@@ -0,0 +1,83 @@
+-- Rider shifts. Ops open slots per service area and time window with a cap
+-- on riders; riders claim them, are put online when the shift starts, and
+-- are marked no-show if they do not show up within the grace period.
+CREATE TYPE public.rider_shift_status AS ENUM (
+    'Claimed',
+    'Started',
+    'Completed',
+    'NoShow',
+    'Released'
+);
+
+CREATE TABLE public.rider_shift_slot (
+    id integer NOT NULL,
+    service_area_id integer NOT NULL,
+    starts_at timestamp with time zone NOT NULL,
+    ends_at timestamp with time zone NOT NULL,
+    capacity integer NOT NULL,
+    note text,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT rider_shift_slot_window_check CHECK ((ends_at > starts_at)),
+    CONSTRAINT rider_shift_slot_capacity_check CHECK ((capacity > 0))
+);
+
+CREATE SEQUENCE public.rider_shift_slot_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.rider_shift_slot_id_seq OWNED BY public.rider_shift_slot.id;
+
+ALTER TABLE ONLY public.rider_shift_slot ALTER COLUMN id SET DEFAULT nextval('public.rider_shift_slot_id_seq'::regclass);
+
+ALTER TABLE ONLY public.rider_shift_slot
+    ADD CONSTRAINT rider_shift_slot_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.rider_shift_slot
+    ADD CONSTRAINT rider_shift_slot_service_area_id_fkey FOREIGN KEY (service_area_id) REFERENCES public.service_area(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX idx_rider_shift_slot_window ON public.rider_shift_slot USING btree (starts_at, ends_at);
+
+-- A rider's claim on a slot
+CREATE TABLE public.rider_shift (
+    id integer NOT NULL,
+    slot_id integer NOT NULL,
+    rider_id integer NOT NULL,
+    status public.rider_shift_status DEFAULT 'Claimed'::public.rider_shift_status NOT NULL,
+    started_at timestamp with time zone,
+    -- First sign of life from the rider after the shift started
+    checked_in_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.rider_shift_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.rider_shift_id_seq OWNED BY public.rider_shift.id;
+
+ALTER TABLE ONLY public.rider_shift ALTER COLUMN id SET DEFAULT nextval('public.rider_shift_id_seq'::regclass);
+
+ALTER TABLE ONLY public.rider_shift
+    ADD CONSTRAINT rider_shift_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.rider_shift
+    ADD CONSTRAINT rider_shift_slot_id_fkey FOREIGN KEY (slot_id) REFERENCES public.rider_shift_slot(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.rider_shift
+    ADD CONSTRAINT rider_shift_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- A rider holds at most one live claim per slot; released claims may be
+-- claimed again
+CREATE UNIQUE INDEX uq_rider_shift_slot_rider ON public.rider_shift USING btree (slot_id, rider_id) WHERE (status <> 'Released'::public.rider_shift_status);
+
+CREATE INDEX idx_rider_shift_rider_id ON public.rider_shift USING btree (rider_id, status);
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS public.trip_incident;
+
+DROP TYPE IF EXISTS public.incident_status;
+
+DROP TYPE IF EXISTS public.incident_kind;
+
+DROP TABLE IF EXISTS public.emergency_contact;
@@ -0,0 +1,90 @@
+-- Someone a user wants told, with a live tracking link, when they raise an
+-- incident during a trip. One per user.
+CREATE TABLE public.emergency_contact (
+    local_user_id integer NOT NULL,
+    name character varying(100) NOT NULL,
+    phone character varying(32) NOT NULL,
+    email character varying(255),
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+ALTER TABLE ONLY public.emergency_contact
+    ADD CONSTRAINT emergency_contact_pkey PRIMARY KEY (local_user_id);
+
+ALTER TABLE ONLY public.emergency_contact
+    ADD CONSTRAINT emergency_contact_local_user_id_fkey FOREIGN KEY (local_user_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE TYPE public.incident_kind AS ENUM (
+    'Sos',
+    'Accident',
+    'Harassment',
+    'Theft',
+    'Other'
+);
+
+CREATE TYPE public.incident_status AS ENUM (
+    'Open',
+    'Acknowledged',
+    'Resolved'
+);
+
+-- An accident, harassment, stolen package or other emergency raised by the
+-- rider or the employer during an active delivery or ride, with where the
+-- rider was at the time.
+CREATE TABLE public.trip_incident (
+    id integer NOT NULL,
+    post_id integer NOT NULL,
+    job_kind public.dispatch_job_kind NOT NULL,
+    reporter_id integer NOT NULL,
+    reported_by_rider boolean NOT NULL,
+    rider_id integer,
+    kind public.incident_kind NOT NULL,
+    status public.incident_status DEFAULT 'Open'::public.incident_status NOT NULL,
+    notes text,
+    photo_urls text[] DEFAULT '{}'::text[] NOT NULL,
+    lat double precision,
+    lng double precision,
+    located_at timestamp with time zone,
+    tracking_link_id integer,
+    contact_notified_at timestamp with time zone,
+    resolved_by integer,
+    resolution_note text,
+    resolved_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.trip_incident_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.trip_incident_id_seq OWNED BY public.trip_incident.id;
+
+ALTER TABLE ONLY public.trip_incident ALTER COLUMN id SET DEFAULT nextval('public.trip_incident_id_seq'::regclass);
+
+ALTER TABLE ONLY public.trip_incident
+    ADD CONSTRAINT trip_incident_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.trip_incident
+    ADD CONSTRAINT trip_incident_post_id_fkey FOREIGN KEY (post_id) REFERENCES public.post(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.trip_incident
+    ADD CONSTRAINT trip_incident_reporter_id_fkey FOREIGN KEY (reporter_id) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.trip_incident
+    ADD CONSTRAINT trip_incident_rider_id_fkey FOREIGN KEY (rider_id) REFERENCES public.rider(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE ONLY public.trip_incident
+    ADD CONSTRAINT trip_incident_tracking_link_id_fkey FOREIGN KEY (tracking_link_id) REFERENCES public.tracking_link(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+ALTER TABLE ONLY public.trip_incident
+    ADD CONSTRAINT trip_incident_resolved_by_fkey FOREIGN KEY (resolved_by) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_trip_incident_post_id ON public.trip_incident USING btree (post_id);
+
+CREATE INDEX idx_trip_incident_unresolved ON public.trip_incident USING btree (created_at) WHERE (status <> 'Resolved'::public.incident_status);
@@ -0,0 +1,10 @@
+DROP TABLE IF EXISTS public.fleet_document;
+
+DROP TYPE IF EXISTS public.fleet_document_status;
+
+DROP TYPE IF EXISTS public.fleet_document_kind;
+
+ALTER TABLE public.rider
+    DROP COLUMN IF EXISTS fleet_id;
+
+DROP TABLE IF EXISTS public.rider_fleet;
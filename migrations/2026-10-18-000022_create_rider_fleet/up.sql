@@ -0,0 +1,103 @@
+-- A courier company whose riders are managed together. The owner sees the
+-- riders' status and may have a share of their delivery payouts paid into
+-- their own wallet. Riders join with the fleet's join code.
+CREATE TABLE public.rider_fleet (
+    id integer NOT NULL,
+    owner_id integer NOT NULL,
+    name character varying(100) NOT NULL,
+    join_code character varying(32) NOT NULL,
+    route_earnings boolean DEFAULT false NOT NULL,
+    fleet_share_percent double precision DEFAULT 0 NOT NULL,
+    is_verified boolean DEFAULT false NOT NULL,
+    verified_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone,
+    CONSTRAINT rider_fleet_share_percent_range CHECK (((fleet_share_percent >= (0)::double precision) AND (fleet_share_percent <= (100)::double precision)))
+);
+
+CREATE SEQUENCE public.rider_fleet_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.rider_fleet_id_seq OWNED BY public.rider_fleet.id;
+
+ALTER TABLE ONLY public.rider_fleet ALTER COLUMN id SET DEFAULT nextval('public.rider_fleet_id_seq'::regclass);
+
+ALTER TABLE ONLY public.rider_fleet
+    ADD CONSTRAINT rider_fleet_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.rider_fleet
+    ADD CONSTRAINT uq_rider_fleet_owner_id UNIQUE (owner_id);
+
+ALTER TABLE ONLY public.rider_fleet
+    ADD CONSTRAINT uq_rider_fleet_join_code UNIQUE (join_code);
+
+ALTER TABLE ONLY public.rider_fleet
+    ADD CONSTRAINT rider_fleet_owner_id_fkey FOREIGN KEY (owner_id) REFERENCES public.local_user(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+-- The fleet a rider drives for
+ALTER TABLE public.rider
+    ADD COLUMN fleet_id integer;
+
+ALTER TABLE ONLY public.rider
+    ADD CONSTRAINT rider_fleet_id_fkey FOREIGN KEY (fleet_id) REFERENCES public.rider_fleet(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_rider_fleet_id ON public.rider USING btree (fleet_id) WHERE (fleet_id IS NOT NULL);
+
+CREATE TYPE public.fleet_document_kind AS ENUM (
+    'BusinessRegistration',
+    'TaxCertificate',
+    'Insurance',
+    'Other'
+);
+
+CREATE TYPE public.fleet_document_status AS ENUM (
+    'Pending',
+    'Approved',
+    'Rejected'
+);
+
+-- A company document uploaded by the fleet owner for admins to review. The
+-- fleet counts as verified while all of its documents are approved.
+CREATE TABLE public.fleet_document (
+    id integer NOT NULL,
+    fleet_id integer NOT NULL,
+    kind public.fleet_document_kind NOT NULL,
+    file_url text NOT NULL,
+    expires_at timestamp with time zone,
+    status public.fleet_document_status DEFAULT 'Pending'::public.fleet_document_status NOT NULL,
+    reviewed_by integer,
+    review_note text,
+    reviewed_at timestamp with time zone,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    updated_at timestamp with time zone
+);
+
+CREATE SEQUENCE public.fleet_document_id_seq
+    AS integer
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+ALTER SEQUENCE public.fleet_document_id_seq OWNED BY public.fleet_document.id;
+
+ALTER TABLE ONLY public.fleet_document ALTER COLUMN id SET DEFAULT nextval('public.fleet_document_id_seq'::regclass);
+
+ALTER TABLE ONLY public.fleet_document
+    ADD CONSTRAINT fleet_document_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY public.fleet_document
+    ADD CONSTRAINT fleet_document_fleet_id_fkey FOREIGN KEY (fleet_id) REFERENCES public.rider_fleet(id) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY public.fleet_document
+    ADD CONSTRAINT fleet_document_reviewed_by_fkey FOREIGN KEY (reviewed_by) REFERENCES public.person(id) ON UPDATE CASCADE ON DELETE SET NULL;
+
+CREATE INDEX idx_fleet_document_fleet_id ON public.fleet_document USING btree (fleet_id);
+
+CREATE INDEX idx_fleet_document_pending ON public.fleet_document USING btree (created_at) WHERE (status = 'Pending'::public.fleet_document_status);
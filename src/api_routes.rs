@@ -4,14 +4,23 @@ use app_108jobs_admin::{
   currency::{
     admin_create_currency,
     admin_create_pricing_config,
+    admin_create_pricing_rule,
+    admin_delete_pricing_rule,
     admin_get_currency,
     admin_get_pricing_config,
     admin_list_currencies,
     admin_list_pricing_configs,
+    admin_list_pricing_rules,
     admin_update_currency,
     admin_update_pricing_config,
+    admin_update_pricing_rule,
   },
   platform::{admin_get_platform_assets, admin_get_platform_balance},
+  post_boost::{
+    admin_create_post_boost_package,
+    admin_list_post_boost_packages,
+    admin_update_post_boost_package,
+  },
   site::{
     admin_allow_instance::admin_allow_instance,
     admin_block_instance::admin_block_instance,
@@ -127,6 +136,7 @@ use app_108jobs_jobs::{
     update::update_post,
   },
   handlers::{
+    boost::{list_post_boost_packages, list_post_boosts, purchase_post_boost},
     feature::feature_post,
     get_link_metadata::get_link_metadata,
     hide::hide_post,
@@ -150,7 +160,61 @@ use app_108jobs_logistics::{
   },
   handlers::{
     assign::assign_delivery_from_proposal,
+    batch::plan_batch,
+    cancellation::{
+      admin_create_cancellation_policy,
+      admin_delete_cancellation_policy,
+      admin_list_cancellation_policies,
+      admin_update_cancellation_policy,
+      cancel_delivery,
+    },
+    cargo_tariff::{
+      admin_create_cargo_tariff,
+      admin_delete_cargo_tariff,
+      admin_list_cargo_tariffs,
+      admin_update_cargo_tariff,
+    },
+    cod::{
+      admin_list_rider_cod_outstanding,
+      admin_record_cod_handover,
+      get_cod_remittance_report,
+      get_rider_cod_balance,
+      settle_rider_cod,
+    },
     confirm::confirm_delivery_completion,
+    dispatch::{
+      accept_dispatch_offer,
+      cancel_dispatch,
+      decline_dispatch_offer,
+      get_dispatch_status,
+      list_my_dispatch_offers,
+      start_delivery_dispatch,
+      start_ride_dispatch,
+    },
+    earnings::get_rider_earnings,
+    fleet::{
+      admin_list_fleet_documents,
+      admin_review_fleet_document,
+      create_fleet,
+      get_my_fleet,
+      get_my_fleet_membership,
+      join_fleet,
+      leave_fleet,
+      list_fleet_documents,
+      list_fleet_riders,
+      remove_fleet_rider,
+      submit_fleet_document,
+      update_my_fleet,
+    },
+    incident::{
+      admin_list_incidents,
+      admin_update_incident,
+      delete_emergency_contact,
+      get_emergency_contact,
+      report_delivery_incident,
+      report_ride_incident,
+      save_emergency_contact,
+    },
     list::{
       get_active_deliveries,
       get_cancelled_deliveries,
@@ -164,7 +228,10 @@ use app_108jobs_logistics::{
       post_location as post_trip_location,
       post_locations_bulk as post_trip_locations_bulk,
     },
-    rate::{get_rider_ratings, rate_rider},
+    meter_flag::{admin_list_ride_meter_flags, admin_review_ride_meter_flag},
+    proof::{get_delivery_proof, reissue_delivery_otp},
+    quote::{quote_delivery, quote_fare},
+    rate::{admin_list_flagged_employers, get_rider_ratings, rate_employer, rate_rider},
     ride::{
       cancel_ride_session,
       confirm_ride_assignment,
@@ -175,7 +242,39 @@ use app_108jobs_logistics::{
       update_ride_meter,
       update_ride_status,
     },
-    status::update_delivery_status,
+    schedule::{
+      confirm_scheduled_delivery,
+      list_scheduled_deliveries,
+      list_scheduled_rides,
+      reserve_scheduled_delivery,
+      reserve_scheduled_ride,
+    },
+    service_area::{
+      admin_delete_service_area,
+      admin_import_service_areas,
+      admin_list_service_areas,
+      admin_update_service_area,
+    },
+    shift::{
+      admin_create_shift_slot,
+      admin_delete_shift_slot,
+      admin_get_shift_coverage,
+      admin_list_shift_slots,
+      admin_update_shift_slot,
+      claim_shift_slot,
+      list_my_shifts,
+      list_shift_slots,
+      release_shift,
+    },
+    status::{get_delivery_status, update_delivery_status},
+    stop::{get_delivery_stops, set_delivery_stops, update_delivery_stop},
+    track::{export_delivery_track, export_ride_track},
+    tracking::{
+      create_delivery_tracking_link,
+      create_ride_tracking_link,
+      get_public_tracking,
+      stream_public_tracking,
+    },
   },
 };
 use app_108jobs_notifications::{
@@ -196,6 +295,7 @@ use app_108jobs_payments::{
     update_bank_account,
   },
   list_top_up_requests::list_top_up_requests,
+  referral::get_referral_dashboard,
   wallet::get_wallet,
   withdraw::{list_withdraw_requests, retract_withdraw, submit_withdraw},
 };
@@ -336,6 +436,9 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             .route("/hide", post().to(hide_post))
             .route("/lock", post().to(lock_post))
             .route("/feature", post().to(feature_post))
+            .route("/boost", post().to(purchase_post_boost))
+            .route("/boost/list", get().to(list_post_boosts))
+            .route("/boost/packages", get().to(list_post_boost_packages))
             .route("/list", get().to(list_posts))
             .route("/like", post().to(like_post))
             .route("/like/list", get().to(list_post_likes))
@@ -351,15 +454,40 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             .route("/active", get().to(get_active_deliveries))
             .route("/completed", get().to(get_completed_deliveries))
             .route("/cancelled", get().to(get_cancelled_deliveries))
+            .route("/scheduled", get().to(list_scheduled_deliveries))
+            .route("/quote", post().to(quote_delivery))
+            .route("/batch-plan", post().to(plan_batch))
+            .route("/cod/balance", get().to(get_rider_cod_balance))
+            .route("/cod/settle", post().to(settle_rider_cod))
+            .route("/cod/remittance", get().to(get_cod_remittance_report))
             .route("/{postId}/location", post().to(post_trip_location))
             .route("/{postId}/location", get().to(get_trip_location))
             .route(
               "/{postId}/locations/bulk",
               post().to(post_trip_locations_bulk),
             )
+            .route("/{postId}/status", get().to(get_delivery_status))
             .route("/{postId}/status", put().to(update_delivery_status))
+            .route("/{postId}/cancel", post().to(cancel_delivery))
+            .route("/{postId}/proof", get().to(get_delivery_proof))
+            .route("/{postId}/proof/otp", post().to(reissue_delivery_otp))
+            .route("/{postId}/track", get().to(export_delivery_track))
+            .route(
+              "/{postId}/tracking-link",
+              post().to(create_delivery_tracking_link),
+            )
+            .route("/{postId}/incidents", post().to(report_delivery_incident))
+            .route("/{postId}/stops", get().to(get_delivery_stops))
+            .route("/{postId}/stops", put().to(set_delivery_stops))
+            .route("/{postId}/stops/{stopId}", put().to(update_delivery_stop))
             .route("/{postId}/assign", post().to(assign_delivery_from_proposal))
             .route("/{postId}/confirm", post().to(confirm_delivery_completion))
+            .route("/{postId}/dispatch", post().to(start_delivery_dispatch))
+            .route("/{postId}/reserve", post().to(reserve_scheduled_delivery))
+            .route(
+              "/{postId}/reserve/confirm",
+              post().to(confirm_scheduled_delivery),
+            )
             // Registered last so the literal /active|/completed|/cancelled
             // routes above are matched before this single-segment dynamic one.
             .route("/{postId}", get().to(get_delivery)),
@@ -370,14 +498,36 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             .route("/create", post().to(create_ride_session))
             .route("/my-sessions", get().to(list_my_ride_sessions))
             .route("/available", get().to(list_available_rides))
+            .route("/scheduled", get().to(list_scheduled_rides))
+            .route("/quote", post().to(quote_fare))
             .route("/{sessionId}/confirm", post().to(confirm_ride_assignment))
+            .route("/{sessionId}/reserve", post().to(reserve_scheduled_ride))
             .route(
               "/{sessionId}/pricing-config",
               get().to(get_ride_pricing_config),
             )
             .route("/{sessionId}/meter", put().to(update_ride_meter))
+            .route("/{sessionId}/track", get().to(export_ride_track))
+            .route(
+              "/{sessionId}/tracking-link",
+              post().to(create_ride_tracking_link),
+            )
+            .route("/{sessionId}/incidents", post().to(report_ride_incident))
             .route("/{sessionId}/status", put().to(update_ride_status))
-            .route("/{sessionId}/cancel", post().to(cancel_ride_session)),
+            .route("/{sessionId}/cancel", post().to(cancel_ride_session))
+            .route("/{sessionId}/dispatch", post().to(start_ride_dispatch)),
+        )
+        // Public trip tracking for receivers and passengers, no login needed
+        .service(
+          scope("/track")
+            .route("/{token}", get().to(get_public_tracking))
+            .route("/{token}/events", get().to(stream_public_tracking)),
+        )
+        // Automatic dispatch status for deliveries and rides
+        .service(
+          scope("/dispatch")
+            .route("/{postId}", get().to(get_dispatch_status))
+            .route("/{postId}/cancel", post().to(cancel_dispatch)),
         )
         // Proposal
         .service(
@@ -452,6 +602,9 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             )
             .route("/inbox", get().to(list_inbox))
             .route("/delete", post().to(delete_account))
+            .route("/emergency-contact", get().to(get_emergency_contact))
+            .route("/emergency-contact", put().to(save_emergency_contact))
+            .route("/emergency-contact", delete().to(delete_emergency_contact))
             // upload file
             .service(
               scope("/files")
@@ -512,6 +665,8 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
                     .route("/{id}", delete().to(retract_withdraw)),
                 ),
             )
+            // Referral invite code and rewards
+            .route("/referral", get().to(get_referral_dashboard))
             // Bank account management scope
             .service(scope("/banks").route("", get().to(list_banks)))
             // Employer delivery list + single ride detail
@@ -605,7 +760,60 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             .service(
               scope("/riders")
                 .route("/list", get().to(list_riders))
-                .route("/verify", post().to(admin_verify_rider)),
+                .route("/verify", post().to(admin_verify_rider))
+                .route(
+                  "/cod/outstanding",
+                  get().to(admin_list_rider_cod_outstanding),
+                )
+                .route("/cod/handover", post().to(admin_record_cod_handover)),
+            )
+            .service(scope("/employers").route("/flagged", get().to(admin_list_flagged_employers)))
+            .service(
+              scope("/rides")
+                .route("/meter-flags", get().to(admin_list_ride_meter_flags))
+                .route(
+                  "/meter-flags/{flagId}/review",
+                  post().to(admin_review_ride_meter_flag),
+                ),
+            )
+            .service(
+              scope("/service-areas")
+                .route("", get().to(admin_list_service_areas))
+                .route("/import", post().to(admin_import_service_areas))
+                .route("/{areaId}", put().to(admin_update_service_area))
+                .route("/{areaId}", delete().to(admin_delete_service_area)),
+            )
+            .service(
+              scope("/cancellation-policies")
+                .route("", get().to(admin_list_cancellation_policies))
+                .route("", post().to(admin_create_cancellation_policy))
+                .route("/{policyId}", put().to(admin_update_cancellation_policy))
+                .route("/{policyId}", delete().to(admin_delete_cancellation_policy)),
+            )
+            .service(
+              scope("/shift-slots")
+                .route("", get().to(admin_list_shift_slots))
+                .route("", post().to(admin_create_shift_slot))
+                .route("/{slotId}", put().to(admin_update_shift_slot))
+                .route("/{slotId}", delete().to(admin_delete_shift_slot)),
+            )
+            .route("/shift-coverage", get().to(admin_get_shift_coverage))
+            .service(
+              scope("/incidents")
+                .route("", get().to(admin_list_incidents))
+                .route("/{incidentId}", put().to(admin_update_incident)),
+            )
+            .service(
+              scope("/fleet-documents")
+                .route("", get().to(admin_list_fleet_documents))
+                .route("/{documentId}", put().to(admin_review_fleet_document)),
+            )
+            .service(
+              scope("/cargo-tariffs")
+                .route("", get().to(admin_list_cargo_tariffs))
+                .route("", post().to(admin_create_cargo_tariff))
+                .route("/{tariffId}", put().to(admin_update_cargo_tariff))
+                .route("/{tariffId}", delete().to(admin_delete_cargo_tariff)),
             )
             .service(
               scope("/currency")
@@ -619,7 +827,17 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
                 .route("/list", post().to(admin_list_pricing_configs))
                 .route("", get().to(admin_get_pricing_config))
                 .route("", post().to(admin_create_pricing_config))
-                .route("", put().to(admin_update_pricing_config)),
+                .route("", put().to(admin_update_pricing_config))
+                .route("/rules/list", post().to(admin_list_pricing_rules))
+                .route("/rules", post().to(admin_create_pricing_rule))
+                .route("/rules", put().to(admin_update_pricing_rule))
+                .route("/rules", delete().to(admin_delete_pricing_rule)),
+            )
+            .service(
+              scope("/post-boost-package")
+                .route("/list", get().to(admin_list_post_boost_packages))
+                .route("", post().to(admin_create_post_boost_package))
+                .route("", put().to(admin_update_post_boost_package)),
             )
             .service(
               scope("/platform")
@@ -638,6 +856,16 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             .route("/unread-snapshot", get().to(get_unread_snapshot))
             .route("/presence-snapshot", get().to(get_presence_snapshot)),
         )
+        .service(
+          scope("/fleets")
+            .route("", post().to(create_fleet))
+            .route("/mine", get().to(get_my_fleet))
+            .route("/mine", put().to(update_my_fleet))
+            .route("/mine/riders", get().to(list_fleet_riders))
+            .route("/mine/riders/{riderId}", delete().to(remove_fleet_rider))
+            .route("/mine/documents", get().to(list_fleet_documents))
+            .route("/mine/documents", post().to(submit_fleet_document)),
+        )
         .service(
           scope("/reviews")
             .route("", post().to(submit_user_review))
@@ -651,8 +879,23 @@ pub fn config(cfg: &mut ServiceConfig, rate_limit: &RateLimit) {
             .route("/status/online", patch().to(set_online))
             .route("/status/accepting", patch().to(set_accepting))
             .route("/heartbeat", post().to(heartbeat))
+            .route("/shifts", get().to(list_my_shifts))
+            .route("/shifts/slots", get().to(list_shift_slots))
+            .route("/shifts/slots/{slotId}/claim", post().to(claim_shift_slot))
+            .route("/shifts/{shiftId}/release", post().to(release_shift))
+            .route("/earnings", get().to(get_rider_earnings))
+            .route("/offers", get().to(list_my_dispatch_offers))
+            .route("/offers/{offerId}/accept", post().to(accept_dispatch_offer))
+            .route(
+              "/offers/{offerId}/decline",
+              post().to(decline_dispatch_offer),
+            )
+            .route("/fleet", get().to(get_my_fleet_membership))
+            .route("/fleet/join", post().to(join_fleet))
+            .route("/fleet/leave", post().to(leave_fleet))
             .route("/profile/{id}", get().to(get_rider))
             .route("/rate", post().to(rate_rider))
+            .route("/rate-employer", post().to(rate_employer))
             .route("/{riderId}/ratings", get().to(get_rider_ratings)),
         )
         .service(